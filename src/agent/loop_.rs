@@ -1,10 +1,14 @@
 use crate::config::Config;
-use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
-use crate::providers::{self, ChatMessage, Provider};
+use crate::memory::{self, Memory, MemoryCategory, MemoryMetadata};
+use crate::observability::{self, Observer, ObserverEvent, ObserverMetric};
+use crate::providers::{
+    self, collect_stream, ChatMessage, ConversationMessage, ImageContent, Provider, ToolCall,
+    ToolResultMessage,
+};
 use crate::runtime;
+use crate::security::approval::{self, ApprovalRoute};
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, Tool, ToolSpec};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use std::fmt::Write;
@@ -19,11 +23,39 @@ pub const MAX_TOOL_ITERATIONS: usize = 10;
 /// When exceeded, the oldest messages are dropped (system prompt is always preserved).
 const MAX_HISTORY_MESSAGES: usize = 50;
 
+/// A history entry that `trim_history` knows how to classify.
+pub trait HistoryMessage {
+    /// Whether this is the system prompt (always preserved).
+    fn is_system(&self) -> bool;
+
+    /// Whether this is a tool result, which must never lead the trimmed
+    /// history because providers reject results without a preceding call.
+    fn is_tool_result(&self) -> bool {
+        false
+    }
+}
+
+impl HistoryMessage for ChatMessage {
+    fn is_system(&self) -> bool {
+        self.role == "system"
+    }
+}
+
+impl HistoryMessage for ConversationMessage {
+    fn is_system(&self) -> bool {
+        matches!(self, ConversationMessage::Chat(m) if m.role == "system")
+    }
+
+    fn is_tool_result(&self) -> bool {
+        matches!(self, ConversationMessage::ToolResult(_))
+    }
+}
+
 /// Trim conversation history to prevent unbounded growth.
 /// Preserves the system prompt (first message if role=system) and the most recent messages.
-pub fn trim_history(history: &mut Vec<ChatMessage>) {
+pub fn trim_history<M: HistoryMessage>(history: &mut Vec<M>) {
     // Nothing to trim if within limit
-    let has_system = history.first().map_or(false, |m| m.role == "system");
+    let has_system = history.first().is_some_and(HistoryMessage::is_system);
     let non_system_count = if has_system {
         history.len() - 1
     } else {
@@ -37,6 +69,14 @@ pub fn trim_history(history: &mut Vec<ChatMessage>) {
     let start = if has_system { 1 } else { 0 };
     let to_remove = non_system_count - MAX_HISTORY_MESSAGES;
     history.drain(start..start + to_remove);
+
+    // Drop tool results whose originating call was just trimmed away
+    while history
+        .get(start)
+        .is_some_and(HistoryMessage::is_tool_result)
+    {
+        history.remove(start);
+    }
 }
 
 /// Build context preamble by searching memory for relevant entries
@@ -71,10 +111,23 @@ pub fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn T
 /// </tool_call>
 /// ```
 ///
-/// Also supports JSON with `tool_calls` array from OpenAI-format responses.
+/// This is the fallback path for providers without native function calling;
+/// malformed blocks are logged and skipped. Use `parse_tool_calls_with_errors`
+/// to feed them back to the model instead.
 pub fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
+    let (text, calls, errors) = parse_tool_calls_with_errors(response);
+    for error in &errors {
+        tracing::warn!("{error}");
+    }
+    (text, calls)
+}
+
+/// Like `parse_tool_calls`, but also returns a description of every
+/// `<tool_call>` block that could not be turned into a call.
+pub fn parse_tool_calls_with_errors(response: &str) -> (String, Vec<ParsedToolCall>, Vec<String>) {
     let mut text_parts = Vec::new();
     let mut calls = Vec::new();
+    let mut errors = Vec::new();
     let mut remaining = response;
 
     while let Some(start) = remaining.find("<tool_call>") {
@@ -93,18 +146,26 @@ pub fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
                         .and_then(|v| v.as_str())
                         .unwrap_or("")
                         .to_string();
-                    let arguments = parsed
-                        .get("arguments")
-                        .cloned()
-                        .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
-                    calls.push(ParsedToolCall { name, arguments });
+                    if name.is_empty() {
+                        errors.push(format!(
+                            "Malformed <tool_call>: missing \"name\" in {}",
+                            truncate_with_ellipsis(inner.trim(), 200)
+                        ));
+                    } else {
+                        let arguments = parsed
+                            .get("arguments")
+                            .cloned()
+                            .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+                        calls.push(ParsedToolCall { name, arguments });
+                    }
                 }
                 Err(e) => {
-                    tracing::warn!("Malformed <tool_call> JSON: {e}");
+                    errors.push(format!("Malformed <tool_call> JSON: {e}"));
                 }
             }
             remaining = &remaining[start + end + 12..];
         } else {
+            errors.push("Unterminated <tool_call>: missing </tool_call>".to_string());
             break;
         }
     }
@@ -114,7 +175,7 @@ pub fn parse_tool_calls(response: &str) -> (String, Vec<ParsedToolCall>) {
         text_parts.push(remaining.trim().to_string());
     }
 
-    (text_parts.join("\n"), calls)
+    (text_parts.join("\n"), calls, errors)
 }
 
 #[derive(Debug)]
@@ -123,82 +184,188 @@ pub struct ParsedToolCall {
     pub arguments: serde_json::Value,
 }
 
-/// Execute a single turn of the agent loop: send messages, parse tool calls,
+/// Execute one tool call and render its outcome as text for the model,
+/// along with any images it returned. Images are only passed on when the
+/// model can `see` them; otherwise the text says they were left out.
+/// Calls are refused once the daily cost budget is spent.
+async fn execute_tool_call(
+    tools_registry: &[Box<dyn Tool>],
+    security: &SecurityPolicy,
+    name: &str,
+    arguments: serde_json::Value,
+    observer: &dyn Observer,
+    vision: bool,
+) -> (String, Vec<ImageContent>) {
+    if let Err(reason) = security.check_budget() {
+        return (format!("Error: {reason}"), Vec::new());
+    }
+    let Some(tool) = find_tool(tools_registry, name) else {
+        return (format!("Unknown tool: {name}"), Vec::new());
+    };
+
+    let start = Instant::now();
+    match tool.execute(arguments).await {
+        Ok(r) => {
+            observer.record_event(&ObserverEvent::ToolCall {
+                tool: name.to_string(),
+                duration: start.elapsed(),
+                success: r.success,
+            });
+            let mut output = if r.success {
+                r.output
+            } else {
+                format!("Error: {}", r.error.unwrap_or(r.output))
+            };
+            if vision || r.images.is_empty() {
+                return (output, r.images);
+            }
+            let _ = write!(
+                output,
+                "\n[{} image(s) not shown: the current model cannot view images]",
+                r.images.len()
+            );
+            (output, Vec::new())
+        }
+        Err(e) => {
+            observer.record_event(&ObserverEvent::ToolCall {
+                tool: name.to_string(),
+                duration: start.elapsed(),
+                success: false,
+            });
+            (format!("Error executing {name}: {e}"), Vec::new())
+        }
+    }
+}
+
+/// Record a malformed tool call and build the message fed back to the model.
+fn malformed_tool_call(observer: &dyn Observer, error: &str) -> String {
+    observer.record_event(&ObserverEvent::Error {
+        component: "agent".into(),
+        message: error.to_string(),
+    });
+    format!("{error}. The call was not executed; fix it and try again.")
+}
+
+/// Execute a single turn of the agent loop: send messages, collect tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
-async fn agent_turn(
+///
+/// Responses are streamed; `on_text` receives every text delta as it arrives.
+/// Native tool calls come back as structured `ChatResponse::tool_calls`; for
+/// providers without function calling the `<tool_call>` XML is parsed out of
+/// the text instead. Malformed calls in either form are reported back to the
+/// model so it can retry rather than being silently dropped.
+///
+/// Token usage from each response is charged to the daily budget in
+/// `security`; once it is spent, pending tool calls are refused and the turn
+/// fails before the next LLM call.
+#[allow(clippy::too_many_arguments)]
+pub async fn agent_turn(
     provider: &dyn Provider,
-    history: &mut Vec<ChatMessage>,
+    history: &mut Vec<ConversationMessage>,
     tools_registry: &[Box<dyn Tool>],
     observer: &dyn Observer,
+    security: &SecurityPolicy,
     model: &str,
     temperature: f64,
+    on_text: &mut (dyn FnMut(&str) + Send),
 ) -> Result<String> {
+    let tool_specs: Vec<ToolSpec> = tools_registry.iter().map(|t| t.spec()).collect();
+    let vision = provider.supports_vision();
+
     for _iteration in 0..MAX_TOOL_ITERATIONS {
-        let response = provider
-            .chat_with_history(history, model, temperature)
+        security.check_budget().map_err(anyhow::Error::msg)?;
+        let started = Instant::now();
+        let stream = provider
+            .chat_stream(history, &tool_specs, model, temperature)
             .await?;
+        let response = collect_stream(stream, &mut *on_text).await?;
+        observer.record_metric(&ObserverMetric::RequestLatency(started.elapsed()));
+        if let Some(usage) = response.usage {
+            observer.record_metric(&ObserverMetric::TokensUsed(usage.total()));
+            security.record_usage(&provider.resolved_model(model), usage);
+        }
 
-        let (text, tool_calls) = parse_tool_calls(&response);
-
-        if tool_calls.is_empty() {
-            // No tool calls — this is the final response
-            history.push(ChatMessage::assistant(&response));
-            return Ok(if text.is_empty() {
-                response
-            } else {
-                text
+        if !response.tool_calls.is_empty() {
+            let calls: Vec<ToolCall> = response.tool_calls;
+            history.push(ConversationMessage::AssistantToolCalls {
+                text: response.text,
+                tool_calls: calls.clone(),
             });
+
+            for call in calls {
+                let (content, images) =
+                    match serde_json::from_str::<serde_json::Value>(&call.arguments) {
+                        Ok(arguments) => {
+                            execute_tool_call(
+                                tools_registry,
+                                security,
+                                &call.name,
+                                arguments,
+                                observer,
+                                vision,
+                            )
+                            .await
+                        }
+                        Err(e) => (
+                            malformed_tool_call(
+                                observer,
+                                &format!("Malformed arguments for tool {}: {e}", call.name),
+                            ),
+                            Vec::new(),
+                        ),
+                    };
+                history.push(ConversationMessage::ToolResult(ToolResultMessage {
+                    tool_call_id: call.id,
+                    content,
+                    images,
+                }));
+            }
+            continue;
         }
 
-        // Print any text the LLM produced alongside tool calls
-        if !text.is_empty() {
-            print!("{text}");
-            let _ = std::io::stdout().flush();
+        // Text-mode fallback: look for <tool_call> XML in the response
+        let response = response.text.unwrap_or_default();
+        let (text, tool_calls, errors) = parse_tool_calls_with_errors(&response);
+
+        if tool_calls.is_empty() && errors.is_empty() {
+            // No tool calls — this is the final response
+            history.push(ConversationMessage::Chat(ChatMessage::assistant(&response)));
+            return Ok(if text.is_empty() { response } else { text });
         }
 
         // Execute each tool call and build results
         let mut tool_results = String::new();
-        for call in &tool_calls {
-            let start = Instant::now();
-            let result = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
-                    Ok(r) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: r.success,
-                        });
-                        if r.success {
-                            r.output
-                        } else {
-                            format!("Error: {}", r.error.unwrap_or_else(|| r.output))
-                        }
-                    }
-                    Err(e) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: false,
-                        });
-                        format!("Error executing {}: {e}", call.name)
-                    }
-                }
-            } else {
-                format!("Unknown tool: {}", call.name)
-            };
-
+        let mut tool_images = Vec::new();
+        for call in tool_calls {
+            let (result, images) = execute_tool_call(
+                tools_registry,
+                security,
+                &call.name,
+                call.arguments,
+                observer,
+                vision,
+            )
+            .await;
+            tool_images.extend(images);
             let _ = writeln!(
                 tool_results,
                 "<tool_result name=\"{}\">\n{}\n</tool_result>",
                 call.name, result
             );
         }
+        for error in &errors {
+            let _ = writeln!(
+                tool_results,
+                "<tool_error>\n{}\n</tool_error>",
+                malformed_tool_call(observer, error)
+            );
+        }
 
         // Add assistant message with tool calls + tool results to history
-        history.push(ChatMessage::assistant(&response));
-        history.push(ChatMessage::user(format!(
-            "[Tool results]\n{tool_results}"
-        )));
+        history.push(ConversationMessage::Chat(ChatMessage::assistant(&response)));
+        history.push(ConversationMessage::Chat(
+            ChatMessage::user(format!("[Tool results]\n{tool_results}")).with_images(tool_images),
+        ));
     }
 
     anyhow::bail!("Agent exceeded maximum tool iterations ({MAX_TOOL_ITERATIONS})")
@@ -229,6 +396,70 @@ pub fn build_tool_instructions(tools_registry: &[Box<dyn Tool>]) -> String {
     instructions
 }
 
+/// Name and usage guidance for every built-in tool enabled by `config`, as
+/// listed in the system prompt.
+pub fn tool_descriptions(config: &Config) -> Vec<(&'static str, &'static str)> {
+    let mut tool_descs: Vec<(&'static str, &'static str)> = vec![
+        (
+            "shell",
+            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
+        ),
+        (
+            "file_read",
+            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
+        ),
+        (
+            "file_write",
+            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
+        ),
+        (
+            "memory_store",
+            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
+        ),
+        (
+            "memory_recall",
+            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
+        ),
+        (
+            "memory_forget",
+            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
+        ),
+        (
+            "schedule",
+            "List, create, update or cancel scheduled jobs for this conversation. Use when: the user asks for a reminder, a recurring report or a timed task. Don't use when: the work should happen now.",
+        ),
+        (
+            "send_file",
+            "Send workspace files to the user in this conversation. Use when: sharing a screenshot, chart, report or generated file. Don't use when: the content fits in the text reply.",
+        ),
+        (
+            "memory_ingest",
+            "Chunk workspace documents (Markdown, text, source) into memory. Use when: the user points you at runbooks, ADRs or docs to remember. Don't use when: a single fact is enough (use memory_store).",
+        ),
+    ];
+    tool_descs.push((
+        "screenshot",
+        "Capture a screenshot of the current screen. Returns the file path and attaches the image. Use when: visual verification, UI inspection, debugging displays.",
+    ));
+    tool_descs.push((
+        "image_info",
+        "Read image file metadata (format, dimensions, size) and optionally attach the image to look at it. Use when: inspecting images, preparing visual data for analysis.",
+    ));
+    if config.browser.enabled {
+        tool_descs.push((
+            "browser_open",
+            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
+        ));
+    }
+    if config.composio.enabled {
+        tool_descs.push((
+            "composio",
+            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run, 'connect' to OAuth.",
+        ));
+    }
+    tool_descs
+}
+
 #[allow(clippy::too_many_lines)]
 pub async fn run(
     config: Config,
@@ -256,17 +487,13 @@ pub async fn run(
     tracing::info!(backend = mem.name(), "Memory initialized");
 
     // ── Tools (including memory tools) ────────────────────────────
-    let composio_key = if config.composio.enabled {
-        config.composio.api_key.as_deref()
-    } else {
-        None
-    };
-    let tools_registry = tools::all_tools_with_runtime(
+    let skills = crate::skills::load_skills(&config.workspace_dir);
+    let tools_registry = tools::agent_tools(
+        &Arc::new(config.clone()),
         &security,
-        runtime,
+        &runtime,
         mem.clone(),
-        composio_key,
-        &config.browser,
+        &skills,
     );
 
     // ── Resolve provider ─────────────────────────────────────────
@@ -294,53 +521,7 @@ pub async fn run(
     });
 
     // ── Build system prompt from workspace MD files (OpenClaw framework) ──
-    let skills = crate::skills::load_skills(&config.workspace_dir);
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-    tool_descs.push((
-        "screenshot",
-        "Capture a screenshot of the current screen. Returns file path and base64-encoded PNG. Use when: visual verification, UI inspection, debugging displays.",
-    ));
-    tool_descs.push((
-        "image_info",
-        "Read image file metadata (format, dimensions, size) and optionally base64-encode it. Use when: inspecting images, preparing visual data for analysis.",
-    ));
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
-    }
-    if config.composio.enabled {
-        tool_descs.push((
-            "composio",
-            "Execute actions on 1000+ apps via Composio (Gmail, Notion, GitHub, Slack, etc.). Use action='list' to discover, 'execute' to run, 'connect' to OAuth.",
-        ));
-    }
+    let tool_descs = tool_descriptions(&config);
     let mut system_prompt = crate::channels::build_system_prompt(
         &config.workspace_dir,
         model_name,
@@ -349,17 +530,28 @@ pub async fn run(
         Some(&config.identity),
     );
 
-    // Append structured tool-use instructions with schemas
-    system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    // Providers without native function calling get the XML protocol instead
+    if !provider.supports_native_tools() {
+        system_prompt.push_str(&build_tool_instructions(&tools_registry));
+    }
 
     // ── Execute ──────────────────────────────────────────────────
     let start = Instant::now();
+    let mut print_delta = |delta: &str| {
+        print!("{delta}");
+        let _ = std::io::stdout().flush();
+    };
 
     if let Some(msg) = message {
         // Auto-save user message to memory
         if config.memory.auto_save {
             let _ = mem
-                .store("user_msg", &msg, MemoryCategory::Conversation)
+                .store_with_metadata(
+                    "user_msg",
+                    &msg,
+                    MemoryCategory::Conversation,
+                    &MemoryMetadata::with_source("cli"),
+                )
                 .await;
         }
 
@@ -372,20 +564,23 @@ pub async fn run(
         };
 
         let mut history = vec![
-            ChatMessage::system(&system_prompt),
-            ChatMessage::user(&enriched),
+            ConversationMessage::Chat(ChatMessage::system(&system_prompt)),
+            ConversationMessage::Chat(ChatMessage::user(&enriched)),
         ];
 
-        let response = agent_turn(
-            provider.as_ref(),
-            &mut history,
-            &tools_registry,
-            observer.as_ref(),
-            model_name,
-            temperature,
-        )
-        .await?;
-        println!("{response}");
+        let response = ApprovalRoute::new("cli", "user")
+            .scope(agent_turn(
+                provider.as_ref(),
+                &mut history,
+                &tools_registry,
+                observer.as_ref(),
+                &security,
+                model_name,
+                temperature,
+                &mut print_delta,
+            ))
+            .await?;
+        println!();
 
         // Auto-save assistant response to daily log
         if config.memory.auto_save {
@@ -398,22 +593,32 @@ pub async fn run(
         println!("🦀 ViziClaw Interactive Mode");
         println!("Type /quit to exit.\n");
 
-        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
-        let cli = crate::channels::CliChannel::new();
+        let (tx, rx) = tokio::sync::mpsc::channel(32);
+        let cli = Arc::new(crate::channels::CliChannel::new());
+        approval::broker().register_channel(cli.clone());
 
         // Spawn listener
         let listen_handle = tokio::spawn(async move {
-            let _ = crate::channels::Channel::listen(&cli, tx).await;
+            let _ = crate::channels::Channel::listen(cli.as_ref(), tx).await;
         });
+        // Approval replies are typed while a turn is still running
+        let mut rx = approval::intercept_replies(rx);
 
         // Persistent conversation history across turns
-        let mut history = vec![ChatMessage::system(&system_prompt)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::system(
+            &system_prompt,
+        ))];
 
         while let Some(msg) = rx.recv().await {
             // Auto-save conversation turns
             if config.memory.auto_save {
                 let _ = mem
-                    .store("user_msg", &msg.content, MemoryCategory::Conversation)
+                    .store_with_metadata(
+                        "user_msg",
+                        &msg.content,
+                        MemoryCategory::Conversation,
+                        &MemoryMetadata::with_source("cli"),
+                    )
                     .await;
             }
 
@@ -425,17 +630,21 @@ pub async fn run(
                 format!("{context}{}", msg.content)
             };
 
-            history.push(ChatMessage::user(&enriched));
-
-            let response = match agent_turn(
-                provider.as_ref(),
-                &mut history,
-                &tools_registry,
-                observer.as_ref(),
-                model_name,
-                temperature,
-            )
-            .await
+            history.push(ConversationMessage::Chat(ChatMessage::user(&enriched)));
+
+            println!();
+            let response = match ApprovalRoute::new("cli", "user")
+                .scope(agent_turn(
+                    provider.as_ref(),
+                    &mut history,
+                    &tools_registry,
+                    observer.as_ref(),
+                    &security,
+                    model_name,
+                    temperature,
+                    &mut print_delta,
+                ))
+                .await
             {
                 Ok(resp) => resp,
                 Err(e) => {
@@ -443,7 +652,7 @@ pub async fn run(
                     continue;
                 }
             };
-            println!("\n{response}\n");
+            println!("\n");
 
             // Prevent unbounded history growth in long interactive sessions
             trim_history(&mut history);
@@ -462,7 +671,7 @@ pub async fn run(
     let duration = start.elapsed();
     observer.record_event(&ObserverEvent::AgentEnd {
         duration,
-        tokens_used: None,
+        tokens_used: Some(security.cost_ledger.session_usage().total()),
     });
 
     Ok(())
@@ -471,6 +680,7 @@ pub async fn run(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::traits::ChatResponse;
 
     #[test]
     fn parse_tool_calls_extracts_single_call() {
@@ -589,4 +799,332 @@ After text."#;
         trim_history(&mut history);
         assert_eq!(history.len(), 3);
     }
+
+    #[test]
+    fn parse_tool_calls_with_errors_reports_malformed_blocks() {
+        let response = r#"<tool_call>
+not valid json
+</tool_call>
+<tool_call>
+{"arguments": {"path": "a.txt"}}
+</tool_call>
+<tool_call>
+{"name": "file_read", "arguments": {"path": "b.txt"}}
+</tool_call>
+<tool_call>
+{"name": "shell""#;
+
+        let (_, calls, errors) = parse_tool_calls_with_errors(response);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].name, "file_read");
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].contains("Malformed <tool_call> JSON"));
+        assert!(errors[1].contains("missing \"name\""));
+        assert!(errors[2].contains("Unterminated"));
+    }
+
+    #[test]
+    fn trim_history_drops_orphaned_tool_results() {
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::system("sys"))];
+        history.push(ConversationMessage::AssistantToolCalls {
+            text: None,
+            tool_calls: vec![ToolCall {
+                id: "call_0".into(),
+                name: "shell".into(),
+                arguments: "{}".into(),
+            }],
+        });
+        history.push(ConversationMessage::ToolResult(ToolResultMessage {
+            tool_call_id: "call_0".into(),
+            content: "ok".into(),
+            images: Vec::new(),
+        }));
+        for i in 0..MAX_HISTORY_MESSAGES - 1 {
+            history.push(ConversationMessage::Chat(ChatMessage::user(format!(
+                "msg {i}"
+            ))));
+        }
+
+        trim_history(&mut history);
+
+        assert!(history[0].is_system());
+        assert!(matches!(&history[1], ConversationMessage::Chat(m) if m.content == "msg 0"));
+        assert_eq!(history.len(), MAX_HISTORY_MESSAGES);
+    }
+
+    struct ScriptedProvider {
+        responses: std::sync::Mutex<Vec<ChatResponse>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(mut responses: Vec<ChatResponse>) -> Self {
+            responses.reverse();
+            Self {
+                responses: std::sync::Mutex::new(responses),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("text chat not scripted")
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        async fn chat_with_tools(
+            &self,
+            _messages: &[ConversationMessage],
+            _tools: &[ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.responses
+                .lock()
+                .unwrap()
+                .pop()
+                .ok_or_else(|| anyhow::anyhow!("script exhausted"))
+        }
+    }
+
+    struct EchoTool;
+
+    #[async_trait::async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the input"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {"text": {"type": "string"}}})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> anyhow::Result<tools::ToolResult> {
+            Ok(tools::ToolResult {
+                success: true,
+                output: args["text"].as_str().unwrap_or_default().to_string(),
+                error: None,
+                images: Vec::new(),
+            })
+        }
+    }
+
+    fn text_response(text: &str) -> ChatResponse {
+        ChatResponse {
+            text: Some(text.into()),
+            tool_calls: vec![],
+            usage: None,
+        }
+    }
+
+    fn native_call(id: &str, arguments: &str) -> ChatResponse {
+        ChatResponse {
+            text: None,
+            tool_calls: vec![ToolCall {
+                id: id.into(),
+                name: "echo".into(),
+                arguments: arguments.into(),
+            }],
+            usage: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn agent_turn_executes_native_tool_calls() {
+        let provider = ScriptedProvider::new(vec![
+            native_call("call_1", r#"{"text":"pong"}"#),
+            text_response("done"),
+        ]);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+
+        let reply = agent_turn(
+            &provider,
+            &mut history,
+            &tools,
+            &observability::NoopObserver,
+            &SecurityPolicy::default(),
+            "test",
+            0.0,
+            &mut |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "done");
+        assert!(matches!(
+            &history[2],
+            ConversationMessage::ToolResult(r) if r.tool_call_id == "call_1" && r.content == "pong"
+        ));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_feeds_back_malformed_native_arguments() {
+        let provider = ScriptedProvider::new(vec![
+            native_call("call_1", "{not json"),
+            text_response("retried"),
+        ]);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+
+        let reply = agent_turn(
+            &provider,
+            &mut history,
+            &tools,
+            &observability::NoopObserver,
+            &SecurityPolicy::default(),
+            "test",
+            0.0,
+            &mut |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "retried");
+        assert!(matches!(
+            &history[2],
+            ConversationMessage::ToolResult(r) if r.content.contains("Malformed arguments for tool echo")
+        ));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_feeds_back_malformed_xml_calls() {
+        let provider = ScriptedProvider::new(vec![
+            text_response("<tool_call>\nnot json\n</tool_call>"),
+            text_response("ok"),
+        ]);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+
+        let reply = agent_turn(
+            &provider,
+            &mut history,
+            &tools,
+            &observability::NoopObserver,
+            &SecurityPolicy::default(),
+            "test",
+            0.0,
+            &mut |_| {},
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "ok");
+        assert!(matches!(
+            &history[2],
+            ConversationMessage::Chat(m) if m.role == "user" && m.content.contains("<tool_error>")
+        ));
+    }
+
+    #[tokio::test]
+    async fn agent_turn_streams_text_deltas() {
+        let provider = ScriptedProvider::new(vec![
+            ChatResponse {
+                text: Some("Checking".into()),
+                tool_calls: native_call("call_1", r#"{"text":"x"}"#).tool_calls,
+                usage: None,
+            },
+            text_response("All done"),
+        ]);
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+        let mut streamed = Vec::new();
+
+        let reply = agent_turn(
+            &provider,
+            &mut history,
+            &tools,
+            &observability::NoopObserver,
+            &SecurityPolicy::default(),
+            "test",
+            0.0,
+            &mut |delta| streamed.push(delta.to_string()),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "All done");
+        assert_eq!(streamed, vec!["Checking", "All done"]);
+    }
+
+    fn with_usage(mut response: ChatResponse) -> ChatResponse {
+        // 2k in + 500 out on gpt-4o = 1 cent
+        response.usage = Some(crate::providers::traits::TokenUsage {
+            prompt_tokens: 2_000,
+            completion_tokens: 500,
+        });
+        response
+    }
+
+    #[tokio::test]
+    async fn agent_turn_charges_usage_to_budget() {
+        let provider = ScriptedProvider::new(vec![with_usage(text_response("hi"))]);
+        let security = SecurityPolicy::default();
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+
+        agent_turn(
+            &provider,
+            &mut history,
+            &[],
+            &observability::NoopObserver,
+            &security,
+            "gpt-4o",
+            0.0,
+            &mut |_| {},
+        )
+        .await
+        .unwrap();
+
+        let spent = security.cost_ledger.today();
+        assert_eq!(spent.requests, 1);
+        assert!((spent.cost_cents - 1.0).abs() < 1e-9);
+        assert_eq!(security.cost_ledger.session_usage().total(), 2_500);
+    }
+
+    #[tokio::test]
+    async fn agent_turn_stops_once_budget_is_spent() {
+        let provider = ScriptedProvider::new(vec![
+            with_usage(native_call("call_1", r#"{"text":"pong"}"#)),
+            text_response("never sent"),
+        ]);
+        let security = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            ..SecurityPolicy::default()
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::user("ping"))];
+
+        let err = agent_turn(
+            &provider,
+            &mut history,
+            &tools,
+            &observability::NoopObserver,
+            &security,
+            "gpt-4o",
+            0.0,
+            &mut |_| {},
+        )
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("budget exhausted"));
+        // The pending call is answered with a refusal so history stays valid.
+        assert!(matches!(
+            &history[2],
+            ConversationMessage::ToolResult(r) if r.content.contains("budget exhausted")
+        ));
+    }
 }
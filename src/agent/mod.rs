@@ -1,7 +1,10 @@
 pub mod loop_;
+pub mod task;
 
 pub use loop_::run;
 pub use loop_::{
-    build_context, build_tool_instructions, find_tool, parse_tool_calls, trim_history,
+    agent_turn, build_context, build_tool_instructions, find_tool, parse_tool_calls,
+    parse_tool_calls_with_errors, tool_descriptions, trim_history, HistoryMessage,
     ParsedToolCall, MAX_TOOL_ITERATIONS,
 };
+pub use task::TaskAgent;
@@ -0,0 +1,111 @@
+use super::loop_::{agent_turn, build_context, build_tool_instructions, tool_descriptions};
+use crate::config::Config;
+use crate::memory::{self, Memory};
+use crate::observability::Observer;
+use crate::providers::{self, ChatMessage, ConversationMessage, Provider};
+use crate::runtime;
+use crate::security::approval::ApprovalRoute;
+use crate::security::SecurityPolicy;
+use crate::tools::{self, Tool};
+use anyhow::Result;
+use std::sync::Arc;
+
+/// Agent for unattended work (heartbeat tasks, scheduled prompts): the
+/// workspace's provider, tools, memory and system prompt, answering one
+/// standalone prompt at a time with no conversation carried between runs.
+pub struct TaskAgent {
+    pub(crate) provider: Box<dyn Provider>,
+    pub(crate) tools: Vec<Box<dyn Tool>>,
+    pub(crate) security: Arc<SecurityPolicy>,
+    pub(crate) mem: Arc<dyn Memory>,
+    pub(crate) observer: Arc<dyn Observer>,
+    pub(crate) system_prompt: String,
+    pub(crate) model: String,
+    pub(crate) temperature: f64,
+}
+
+impl TaskAgent {
+    /// Build the agent from `config` the same way `viziclaw agent` does.
+    pub fn from_config(config: &Config, observer: Arc<dyn Observer>) -> Result<Self> {
+        let runtime: Arc<dyn runtime::RuntimeAdapter> =
+            Arc::from(runtime::create_runtime(&config.runtime)?);
+        let security = Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        ));
+        let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
+            &config.memory,
+            &config.workspace_dir,
+            config.api_key.as_deref(),
+        )?);
+        let skills = crate::skills::load_skills(&config.workspace_dir);
+        let tools = tools::agent_tools(
+            &Arc::new(config.clone()),
+            &security,
+            &runtime,
+            mem.clone(),
+            &skills,
+        );
+
+        let model = config
+            .default_model
+            .clone()
+            .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
+        let provider = providers::create_routed_provider(
+            config.default_provider.as_deref().unwrap_or("openrouter"),
+            config.api_key.as_deref(),
+            &config.reliability,
+            &config.model_routes,
+            &model,
+        )?;
+
+        let mut system_prompt = crate::channels::build_system_prompt(
+            &config.workspace_dir,
+            &model,
+            &tool_descriptions(config),
+            &skills,
+            Some(&config.identity),
+        );
+        if !provider.supports_native_tools() {
+            system_prompt.push_str(&build_tool_instructions(&tools));
+        }
+
+        Ok(Self {
+            provider,
+            tools,
+            security,
+            mem,
+            observer,
+            system_prompt,
+            model,
+            temperature: config.default_temperature,
+        })
+    }
+
+    pub fn memory(&self) -> &Arc<dyn Memory> {
+        &self.mem
+    }
+
+    /// Run `prompt` through a fresh agent turn and return the final reply.
+    /// Commands that need approval are routed according to `route`.
+    pub async fn run(&self, prompt: &str, route: ApprovalRoute) -> Result<String> {
+        let context = build_context(self.mem.as_ref(), prompt).await;
+        let mut history = vec![
+            ConversationMessage::Chat(ChatMessage::system(&self.system_prompt)),
+            ConversationMessage::Chat(ChatMessage::user(format!("{context}{prompt}"))),
+        ];
+
+        route
+            .scope(agent_turn(
+                self.provider.as_ref(),
+                &mut history,
+                &self.tools,
+                self.observer.as_ref(),
+                &self.security,
+                &self.model,
+                self.temperature,
+                &mut |_| {},
+            ))
+            .await
+    }
+}
@@ -40,12 +40,14 @@ impl Channel for CliChannel {
             let msg = ChannelMessage {
                 id: Uuid::new_v4().to_string(),
                 sender: "user".to_string(),
+                author: "user".to_string(),
                 content: line,
                 channel: "cli".to_string(),
                 timestamp: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
                     .as_secs(),
+                attachments: Vec::new(),
             };
 
             if tx.send(msg).await.is_err() {
@@ -90,9 +92,11 @@ mod tests {
         let msg = ChannelMessage {
             id: "test-id".into(),
             sender: "user".into(),
+            author: "user".into(),
             content: "hello".into(),
             channel: "cli".into(),
             timestamp: 1_234_567_890,
+            attachments: Vec::new(),
         };
         assert_eq!(msg.id, "test-id");
         assert_eq!(msg.sender, "user");
@@ -106,9 +110,11 @@ mod tests {
         let msg = ChannelMessage {
             id: "id".into(),
             sender: "s".into(),
+            author: "s".into(),
             content: "c".into(),
             channel: "ch".into(),
             timestamp: 0,
+            attachments: Vec::new(),
         };
         let cloned = msg.clone();
         assert_eq!(cloned.id, msg.id);
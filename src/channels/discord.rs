@@ -1,7 +1,10 @@
-use super::traits::{Channel, ChannelMessage};
+use super::inbox::{self, FileInfo, Inbox};
+use super::traits::{Attachment, Channel, ChannelMessage, OutgoingMessage};
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
+use reqwest::multipart::{Form, Part};
 use serde_json::json;
+use std::sync::Arc;
 use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
@@ -10,6 +13,7 @@ pub struct DiscordChannel {
     bot_token: String,
     guild_id: Option<String>,
     allowed_users: Vec<String>,
+    inbox: Option<Arc<Inbox>>,
     client: reqwest::Client,
 }
 
@@ -19,10 +23,18 @@ impl DiscordChannel {
             bot_token,
             guild_id,
             allowed_users,
+            inbox: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Save files attached to incoming messages into `inbox`.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
     /// Check if a Discord user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
@@ -35,6 +47,92 @@ impl DiscordChannel {
         let part = token.split('.').next()?;
         base64_decode(part)
     }
+
+    /// Turn a `MESSAGE_CREATE` payload into a channel message, downloading
+    /// its attachments. `None` for messages with neither text nor files.
+    async fn incoming_message(&self, d: &serde_json::Value) -> Option<ChannelMessage> {
+        let mut content = d
+            .get("content")
+            .and_then(|c| c.as_str())
+            .unwrap_or("")
+            .to_string();
+        let files = message_files(d);
+        if content.is_empty() && files.is_empty() {
+            return None;
+        }
+
+        let mut attachments = Vec::new();
+        for (url, info) in files {
+            let saved = match &self.inbox {
+                Some(inbox) => inbox.download("discord", &info, self.client.get(url)).await,
+                None => Err(anyhow::anyhow!("attachments are disabled")),
+            };
+            match saved {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => inbox::note_skipped("discord", &mut content, &info.filename, &e),
+            }
+        }
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: d
+                .get("channel_id")
+                .and_then(|c| c.as_str())
+                .unwrap_or("")
+                .to_string(),
+            author: d
+                .get("author")
+                .and_then(|a| a.get("id"))
+                .and_then(|i| i.as_str())
+                .unwrap_or("")
+                .to_string(),
+            content,
+            channel: "discord".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+        })
+    }
+
+    /// Post one message with up to [`DISCORD_MAX_FILES`] attachments.
+    async fn send_files(
+        &self,
+        channel_id: &str,
+        content: &str,
+        files: &[Attachment],
+    ) -> anyhow::Result<()> {
+        let mut form = Form::new().text(
+            "payload_json",
+            attachment_payload(content, files).to_string(),
+        );
+        for (i, attachment) in files.iter().enumerate() {
+            let part = Part::bytes(attachment.bytes().await?)
+                .file_name(attachment.filename.clone())
+                .mime_str(&attachment.mime_type)?;
+            form = form.part(format!("files[{i}]"), part);
+        }
+
+        let url = format!("https://discord.com/api/v10/channels/{channel_id}/messages");
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord file upload failed ({status}): {err}");
+        }
+        Ok(())
+    }
 }
 
 const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -42,6 +140,52 @@ const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstu
 /// Discord's maximum message length for regular messages
 const DISCORD_MAX_MESSAGE_LENGTH: usize = 4000;
 
+/// Discord's maximum number of files per message
+const DISCORD_MAX_FILES: usize = 10;
+
+/// `payload_json` for a message carrying `files`. Discord has no per-file
+/// captions, so captions become lines of the message and the file's alt text.
+fn attachment_payload(content: &str, files: &[Attachment]) -> serde_json::Value {
+    let mut lines: Vec<&str> = Vec::new();
+    if !content.is_empty() {
+        lines.push(content);
+    }
+    lines.extend(files.iter().filter_map(|a| a.caption.as_deref()));
+
+    let attachments: Vec<serde_json::Value> = files
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let mut entry = json!({ "id": i, "filename": a.filename });
+            if let Some(caption) = &a.caption {
+                entry["description"] = json!(caption);
+            }
+            entry
+        })
+        .collect();
+
+    json!({ "content": lines.join("\n"), "attachments": attachments })
+}
+
+/// Attachments of a message, as `(cdn_url, info)`.
+fn message_files(d: &serde_json::Value) -> Vec<(String, FileInfo)> {
+    d.get("attachments")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|a| {
+            let url = a.get("url")?.as_str()?;
+            let filename = a.get("filename").and_then(|f| f.as_str()).unwrap_or("file");
+            let mime = a
+                .get("content_type")
+                .and_then(|c| c.as_str())
+                .unwrap_or("application/octet-stream");
+            let size = a.get("size").and_then(serde_json::Value::as_u64);
+            Some((url.to_string(), FileInfo::new(filename, mime, size)))
+        })
+        .collect()
+}
+
 /// Split a message into chunks that respect Discord's 4000 character limit.
 /// Tries to split at word boundaries when possible, and adds continuation markers.
 fn split_message_for_discord(message: &str) -> Vec<String> {
@@ -160,6 +304,24 @@ impl Channel for DiscordChannel {
         Ok(())
     }
 
+    async fn send_rich(&self, message: &OutgoingMessage, channel_id: &str) -> anyhow::Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(&message.text, channel_id).await;
+        }
+
+        // Text that fits rides along with the first batch of files
+        let mut content = message.text.trim();
+        if content.len() > DISCORD_MAX_MESSAGE_LENGTH {
+            self.send(content, channel_id).await?;
+            content = "";
+        }
+        for batch in message.attachments.chunks(DISCORD_MAX_FILES) {
+            self.send_files(channel_id, content, batch).await?;
+            content = "";
+        }
+        Ok(())
+    }
+
     #[allow(clippy::too_many_lines)]
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let bot_user_id = Self::bot_user_id_from_token(&self.bot_token).unwrap_or_default();
@@ -320,22 +482,8 @@ impl Channel for DiscordChannel {
                         }
                     }
 
-                    let content = d.get("content").and_then(|c| c.as_str()).unwrap_or("");
-                    if content.is_empty() {
+                    let Some(channel_msg) = self.incoming_message(d).await else {
                         continue;
-                    }
-
-                    let channel_id = d.get("channel_id").and_then(|c| c.as_str()).unwrap_or("").to_string();
-
-                    let channel_msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        sender: channel_id,
-                        content: content.to_string(),
-                        channel: "discord".to_string(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
                     };
 
                     if tx.send(channel_msg).await.is_err() {
@@ -581,4 +729,59 @@ mod tests {
         let reconstructed = chunks.concat();
         assert_eq!(reconstructed, msg);
     }
+
+    #[test]
+    fn discord_attachment_payload_carries_captions() {
+        let files = vec![
+            Attachment::from_bytes(vec![1], "shot.png", "image/png").with_caption("Login page"),
+            Attachment::from_bytes(vec![2], "report.csv", "text/csv"),
+        ];
+        let payload = attachment_payload("Done", &files);
+        assert_eq!(payload["content"], "Done\nLogin page");
+        assert_eq!(payload["attachments"][0]["id"], 0);
+        assert_eq!(payload["attachments"][0]["filename"], "shot.png");
+        assert_eq!(payload["attachments"][0]["description"], "Login page");
+        assert_eq!(payload["attachments"][1]["filename"], "report.csv");
+        assert!(payload["attachments"][1].get("description").is_none());
+    }
+
+    #[tokio::test]
+    async fn discord_attachments_are_saved_to_the_inbox() {
+        use axum::{routing::get, Router};
+
+        let app = Router::new().route("/attachments/1/note.txt", get(|| async { "hi there" }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ch = DiscordChannel::new("fake".into(), None, vec!["*".into()])
+            .with_inbox(Some(Arc::new(Inbox::new(tmp.path(), 1024))));
+        let d = json!({
+            "channel_id": "C1",
+            "content": "",
+            "attachments": [
+                {"url": format!("{base}/attachments/1/note.txt"), "filename": "note.txt",
+                 "content_type": "text/plain; charset=utf-8", "size": 8},
+                {"url": format!("{base}/missing"), "filename": "gone.png", "content_type": "image/png"}
+            ]
+        });
+
+        let msg = ch.incoming_message(&d).await.unwrap();
+        assert_eq!(msg.sender, "C1");
+        assert!(msg
+            .content
+            .starts_with("[Attachment gone.png was not saved"));
+        assert_eq!(msg.attachments.len(), 1);
+        assert_eq!(msg.attachments[0].filename, "note.txt");
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(&msg.attachments[0].path)).unwrap(),
+            "hi there"
+        );
+
+        assert!(ch
+            .incoming_message(&json!({"channel_id": "C1", "content": ""}))
+            .await
+            .is_none());
+    }
 }
@@ -0,0 +1,344 @@
+use super::session::session_key;
+use super::traits::ChannelMessage;
+use crate::observability::{Observer, ObserverMetric};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinSet;
+
+/// Messages received but not yet being handled. A conversation has an entry
+/// exactly while a worker is serving it, even once its queue has drained.
+#[derive(Default)]
+struct Queues {
+    conversations: HashMap<String, VecDeque<ChannelMessage>>,
+    depth: u64,
+}
+
+/// What became of a message handed to [`Queues::push`].
+enum Pushed {
+    /// Queued, and its conversation needs a worker.
+    StartWorker,
+    /// Queued behind its conversation's running worker.
+    Queued,
+    /// Refused: the conversation already has `max_queued` messages waiting.
+    Full(ChannelMessage),
+}
+
+impl Queues {
+    /// Queue `msg` unless its conversation already has `max_queued`
+    /// messages waiting.
+    fn push(&mut self, key: String, msg: ChannelMessage, max_queued: usize) -> Pushed {
+        let start_worker = !self.conversations.contains_key(&key);
+        let queue = self.conversations.entry(key).or_default();
+        if queue.len() >= max_queued {
+            return Pushed::Full(msg);
+        }
+        queue.push_back(msg);
+        self.depth += 1;
+        if start_worker {
+            Pushed::StartWorker
+        } else {
+            Pushed::Queued
+        }
+    }
+
+    /// Next message for `key`, or `None` (retiring the conversation's
+    /// worker) when it has caught up.
+    fn pop(&mut self, key: &str) -> Option<ChannelMessage> {
+        let msg = self.conversations.get_mut(key)?.pop_front();
+        if msg.is_some() {
+            self.depth -= 1;
+        } else {
+            self.conversations.remove(key);
+        }
+        msg
+    }
+}
+
+/// Run `handler` on every message from `rx` with at most `concurrency`
+/// messages in flight. Each conversation (channel + sender) is served by one
+/// worker at a time, so its messages are handled in the order received while
+/// other conversations proceed in parallel. The number of messages waiting
+/// is reported as `ObserverMetric::QueueDepth`. A conversation may have at
+/// most `max_queued` messages waiting; further ones are passed to
+/// `on_overflow` and dropped.
+pub async fn dispatch<F, Fut, O>(
+    mut rx: mpsc::Receiver<ChannelMessage>,
+    concurrency: usize,
+    max_queued: usize,
+    observer: Arc<dyn Observer>,
+    handler: F,
+    on_overflow: O,
+) where
+    F: Fn(ChannelMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+    O: Fn(ChannelMessage),
+{
+    let handler = Arc::new(handler);
+    let permits = Arc::new(Semaphore::new(concurrency.max(1)));
+    let queues = Arc::new(Mutex::new(Queues::default()));
+    let mut workers = JoinSet::new();
+
+    while let Some(msg) = rx.recv().await {
+        while workers.try_join_next().is_some() {}
+
+        let key = session_key(&msg);
+        let pushed = {
+            let mut q = lock(&queues);
+            let pushed = q.push(key.clone(), msg, max_queued.max(1));
+            observer.record_metric(&ObserverMetric::QueueDepth(q.depth));
+            pushed
+        };
+        match pushed {
+            Pushed::StartWorker => {}
+            Pushed::Queued => continue,
+            Pushed::Full(msg) => {
+                tracing::warn!("Dropping message from {key}: {max_queued} already waiting");
+                on_overflow(msg);
+                continue;
+            }
+        }
+
+        let handler = Arc::clone(&handler);
+        let permits = Arc::clone(&permits);
+        let queues = Arc::clone(&queues);
+        let observer = Arc::clone(&observer);
+        workers.spawn(async move {
+            loop {
+                let Ok(_permit) = permits.acquire().await else {
+                    return;
+                };
+                let msg = {
+                    let mut q = lock(&queues);
+                    let msg = q.pop(&key);
+                    if msg.is_some() {
+                        observer.record_metric(&ObserverMetric::QueueDepth(q.depth));
+                    }
+                    msg
+                };
+                let Some(msg) = msg else {
+                    return;
+                };
+                // A panicking handler must not retire the worker while its
+                // conversation is still registered, or that sender is
+                // never served again
+                if let Err(e) = tokio::spawn(handler(msg)).await {
+                    tracing::error!("Channel message handler failed: {e}");
+                }
+            }
+        });
+    }
+
+    while workers.join_next().await.is_some() {}
+}
+
+fn lock(queues: &Mutex<Queues>) -> std::sync::MutexGuard<'_, Queues> {
+    queues
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observability::{NoopObserver, ObserverEvent};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn message(sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: content.into(),
+            sender: sender.into(),
+            author: sender.into(),
+            content: content.into(),
+            channel: "test".into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[derive(Default)]
+    struct DepthRecorder {
+        depths: Mutex<Vec<u64>>,
+    }
+
+    impl Observer for DepthRecorder {
+        fn record_event(&self, _event: &ObserverEvent) {}
+
+        fn record_metric(&self, metric: &ObserverMetric) {
+            if let ObserverMetric::QueueDepth(d) = metric {
+                self.depths.lock().unwrap().push(*d);
+            }
+        }
+
+        fn name(&self) -> &str {
+            "depth-recorder"
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_conversation_does_not_block_others() {
+        let (tx, rx) = mpsc::channel(16);
+        let done = Arc::new(Mutex::new(Vec::new()));
+        let finished = Arc::clone(&done);
+
+        tx.send(message("slow", "slow-1")).await.unwrap();
+        tx.send(message("fast", "fast-1")).await.unwrap();
+        drop(tx);
+
+        dispatch(
+            rx,
+            2,
+            100,
+            Arc::new(NoopObserver),
+            move |msg| {
+                let finished = Arc::clone(&finished);
+                async move {
+                    if msg.sender == "slow" {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                    finished.lock().unwrap().push(msg.content);
+                }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(*done.lock().unwrap(), vec!["fast-1", "slow-1"]);
+    }
+
+    #[tokio::test]
+    async fn messages_in_one_conversation_stay_in_order() {
+        let (tx, rx) = mpsc::channel(16);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&seen);
+
+        for i in 0..5 {
+            tx.send(message("alice", &format!("m{i}"))).await.unwrap();
+        }
+        drop(tx);
+
+        dispatch(
+            rx,
+            4,
+            100,
+            Arc::new(NoopObserver),
+            move |msg| {
+                let record = Arc::clone(&record);
+                async move {
+                    // Later messages finish faster; ordering must still hold
+                    let delay = 50 - 10 * msg.content[1..].parse::<u64>().unwrap();
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                    record.lock().unwrap().push(msg.content);
+                }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(*seen.lock().unwrap(), vec!["m0", "m1", "m2", "m3", "m4"]);
+    }
+
+    #[tokio::test]
+    async fn concurrency_is_bounded_and_depth_reported() {
+        let (tx, rx) = mpsc::channel(16);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let observer = Arc::new(DepthRecorder::default());
+
+        for i in 0..6 {
+            tx.send(message(&format!("user{i}"), "hi")).await.unwrap();
+        }
+        drop(tx);
+
+        let (current, max) = (Arc::clone(&in_flight), Arc::clone(&peak));
+        dispatch(
+            rx,
+            2,
+            100,
+            observer.clone(),
+            move |_msg| {
+                let (current, max) = (Arc::clone(&current), Arc::clone(&max));
+                async move {
+                    let now = current.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    current.fetch_sub(1, Ordering::SeqCst);
+                }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let depths = observer.depths.lock().unwrap();
+        assert!(depths.iter().any(|d| *d > 1));
+        assert_eq!(depths.last(), Some(&0));
+    }
+
+    #[tokio::test]
+    async fn panicking_handler_does_not_silence_conversation() {
+        let (tx, rx) = mpsc::channel(16);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = Arc::clone(&seen);
+        let observer = Arc::new(DepthRecorder::default());
+
+        for content in ["boom", "after-1", "after-2"] {
+            tx.send(message("alice", content)).await.unwrap();
+        }
+        drop(tx);
+
+        dispatch(
+            rx,
+            1,
+            100,
+            observer.clone(),
+            move |msg| {
+                let record = Arc::clone(&record);
+                async move {
+                    assert_ne!(msg.content, "boom", "handler panicked");
+                    record.lock().unwrap().push(msg.content);
+                }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(*seen.lock().unwrap(), vec!["after-1", "after-2"]);
+        assert_eq!(observer.depths.lock().unwrap().last(), Some(&0));
+    }
+
+    #[tokio::test]
+    async fn queue_per_conversation_is_capped() {
+        let (tx, rx) = mpsc::channel(16);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let (record, overflow) = (Arc::clone(&seen), Arc::clone(&dropped));
+
+        // Everything is queued before the first worker gets to run
+        for i in 0..5 {
+            tx.send(message("alice", &format!("m{i}"))).await.unwrap();
+        }
+        tx.send(message("bob", "b0")).await.unwrap();
+        drop(tx);
+
+        dispatch(
+            rx,
+            1,
+            2,
+            Arc::new(NoopObserver),
+            move |msg| {
+                let record = Arc::clone(&record);
+                async move { record.lock().unwrap().push(msg.content) }
+            },
+            move |msg| overflow.lock().unwrap().push(msg.content),
+        )
+        .await;
+
+        let mut seen = seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, vec!["b0", "m0", "m1"]);
+        assert_eq!(*dropped.lock().unwrap(), vec!["m2", "m3", "m4"]);
+    }
+}
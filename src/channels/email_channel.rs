@@ -10,11 +10,14 @@
 
 use anyhow::{anyhow, Result};
 use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as MailAttachment, MultiPart, SinglePart};
 use lettre::transport::smtp::authentication::Credentials;
 use lettre::{Message, SmtpTransport, Transport};
 use mail_parser::{MessageParser, MimeHeaders};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
 use std::io::Write as IoWrite;
 use std::net::TcpStream;
 use std::sync::Mutex;
@@ -24,7 +27,8 @@ use tokio::time::{interval, sleep};
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use super::traits::{Channel, ChannelMessage};
+use super::inbox::{self, FileInfo, Inbox};
+use super::traits::{Channel, ChannelMessage, OutgoingMessage};
 
 /// Email channel configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,16 +101,28 @@ impl Default for EmailConfig {
 pub struct EmailChannel {
     pub config: EmailConfig,
     seen_messages: Mutex<HashSet<String>>,
+    inbox: Option<std::sync::Arc<Inbox>>,
 }
 
+/// An email fetched over IMAP: id, sender, content, timestamp and attachments
+type FetchedEmail = (String, String, String, u64, Vec<(FileInfo, Vec<u8>)>);
+
 impl EmailChannel {
     pub fn new(config: EmailConfig) -> Self {
         Self {
             config,
             seen_messages: Mutex::new(HashSet::new()),
+            inbox: None,
         }
     }
 
+    /// Save email attachments into `inbox`.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<std::sync::Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
     /// Check if a sender email is in the allowlist
     pub fn is_sender_allowed(&self, email: &str) -> bool {
         if self.config.allowed_senders.is_empty() {
@@ -177,8 +193,30 @@ impl EmailChannel {
         "(no readable content)".to_string()
     }
 
+    /// Attached files of a parsed email, with their contents
+    fn extract_files(parsed: &mail_parser::Message) -> Vec<(FileInfo, Vec<u8>)> {
+        parsed
+            .attachments()
+            .enumerate()
+            .map(|(i, part)| {
+                let mime = MimeHeaders::content_type(part)
+                    .map(|ct| match ct.subtype() {
+                        Some(sub) => format!("{}/{}", ct.ctype(), sub),
+                        None => ct.ctype().to_string(),
+                    })
+                    .unwrap_or_else(|| "application/octet-stream".into());
+                let name = MimeHeaders::attachment_name(part)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("attachment-{}", i + 1));
+                let contents = part.contents().to_vec();
+                let info = FileInfo::new(name, mime, Some(contents.len() as u64));
+                (info, contents)
+            })
+            .collect()
+    }
+
     /// Fetch unseen emails via IMAP (blocking, run in spawn_blocking)
-    fn fetch_unseen_imap(config: &EmailConfig) -> Result<Vec<(String, String, String, u64)>> {
+    fn fetch_unseen_imap(config: &EmailConfig) -> Result<Vec<FetchedEmail>> {
         use rustls::ClientConfig as TlsConfig;
         use rustls_pki_types::ServerName;
         use std::sync::Arc;
@@ -319,7 +357,8 @@ impl EmailChannel {
                             .unwrap_or(0)
                     });
 
-                results.push((msg_id, sender, content, ts));
+                let files = Self::extract_files(&parsed);
+                results.push((msg_id, sender, content, ts, files));
             }
 
             // Mark as seen with unique tag
@@ -339,6 +378,38 @@ impl EmailChannel {
         Ok(results)
     }
 
+    /// Build a `multipart/mixed` email: the text (captions appended) followed
+    /// by one attachment per entry of `files`, matching `message.attachments`.
+    fn rich_email(
+        &self,
+        message: &OutgoingMessage,
+        files: Vec<Vec<u8>>,
+        recipient: &str,
+    ) -> Result<Message> {
+        let (subject, body) = split_subject(&message.text);
+        let mut text = body.to_string();
+        for attachment in &message.attachments {
+            if let Some(caption) = &attachment.caption {
+                let _ = write!(text, "\n\n{}: {}", attachment.filename, caption);
+            }
+        }
+
+        let mut parts = MultiPart::mixed().singlepart(SinglePart::plain(text));
+        for (attachment, content) in message.attachments.iter().zip(files) {
+            let content_type = ContentType::parse(&attachment.mime_type)
+                .unwrap_or_else(|_| ContentType::parse("application/octet-stream").unwrap());
+            parts = parts.singlepart(
+                MailAttachment::new(attachment.filename.clone()).body(content, content_type),
+            );
+        }
+
+        Ok(Message::builder()
+            .from(self.config.from_address.parse()?)
+            .to(recipient.parse()?)
+            .subject(subject)
+            .multipart(parts)?)
+    }
+
     fn create_smtp_transport(&self) -> Result<SmtpTransport> {
         let creds = Credentials::new(self.config.username.clone(), self.config.password.clone());
         let transport = if self.config.smtp_tls {
@@ -356,6 +427,19 @@ impl EmailChannel {
     }
 }
 
+/// Split a leading `Subject: ...` line off `message`.
+fn split_subject(message: &str) -> (&str, &str) {
+    if message.starts_with("Subject: ") {
+        if let Some(pos) = message.find('\n') {
+            (&message[9..pos], message[pos + 1..].trim())
+        } else {
+            ("ViziClaw Message", message)
+        }
+    } else {
+        ("ViziClaw Message", message)
+    }
+}
+
 #[async_trait]
 impl Channel for EmailChannel {
     fn name(&self) -> &str {
@@ -363,15 +447,7 @@ impl Channel for EmailChannel {
     }
 
     async fn send(&self, message: &str, recipient: &str) -> Result<()> {
-        let (subject, body) = if message.starts_with("Subject: ") {
-            if let Some(pos) = message.find('\n') {
-                (&message[9..pos], message[pos + 1..].trim())
-            } else {
-                ("ViziClaw Message", message)
-            }
-        } else {
-            ("ViziClaw Message", message)
-        };
+        let (subject, body) = split_subject(message);
 
         let email = Message::builder()
             .from(self.config.from_address.parse()?)
@@ -385,6 +461,23 @@ impl Channel for EmailChannel {
         Ok(())
     }
 
+    async fn send_rich(&self, message: &OutgoingMessage, recipient: &str) -> Result<()> {
+        let mut files = Vec::with_capacity(message.attachments.len());
+        for attachment in &message.attachments {
+            files.push(attachment.bytes().await?);
+        }
+        let email = self.rich_email(message, files, recipient)?;
+
+        let transport = self.create_smtp_transport()?;
+        transport.send(&email)?;
+        info!(
+            "Email with {} attachment(s) sent to {}",
+            message.attachments.len(),
+            recipient
+        );
+        Ok(())
+    }
+
     async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> Result<()> {
         info!(
             "Email polling every {}s on {}",
@@ -398,7 +491,7 @@ impl Channel for EmailChannel {
             let cfg = config.clone();
             match tokio::task::spawn_blocking(move || Self::fetch_unseen_imap(&cfg)).await {
                 Ok(Ok(messages)) => {
-                    for (id, sender, content, ts) in messages {
+                    for (id, sender, mut content, ts, files) in messages {
                         {
                             let mut seen = self.seen_messages.lock().unwrap();
                             if seen.contains(&id) {
@@ -410,12 +503,27 @@ impl Channel for EmailChannel {
                             }
                             seen.insert(id.clone());
                         } // MutexGuard dropped before await
+                        let mut attachments = Vec::new();
+                        for (info, data) in files {
+                            let saved = match &self.inbox {
+                                Some(inbox) => inbox.save("email", &info, &data).await,
+                                None => Err(anyhow!("attachments are disabled")),
+                            };
+                            match saved {
+                                Ok(attachment) => attachments.push(attachment),
+                                Err(e) => {
+                                    inbox::note_skipped("email", &mut content, &info.filename, &e);
+                                }
+                            }
+                        }
                         let msg = ChannelMessage {
                             id,
+                            author: sender.clone(),
                             sender,
                             content,
                             channel: "email".to_string(),
                             timestamp: ts,
+                            attachments,
                         };
                         if tx.send(msg).await.is_err() {
                             return Ok(());
@@ -444,3 +552,55 @@ impl Channel for EmailChannel {
         .unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::Attachment;
+
+    #[test]
+    fn rich_email_attaches_files() {
+        let channel = EmailChannel::new(EmailConfig {
+            from_address: "bot@example.com".into(),
+            ..EmailConfig::default()
+        });
+        let message = OutgoingMessage::text("Subject: Weekly report\nNumbers attached.")
+            .with_attachment(
+                Attachment::from_bytes(b"a,b".to_vec(), "report.csv", "text/csv")
+                    .with_caption("This week"),
+            );
+
+        let email = channel
+            .rich_email(&message, vec![b"a,b".to_vec()], "ops@example.com")
+            .unwrap();
+        let raw = String::from_utf8(email.formatted()).unwrap();
+        assert!(raw.contains("Subject: Weekly report"));
+        assert!(raw.contains("multipart/mixed"));
+        assert!(raw.contains("Numbers attached.\r\n\r\nreport.csv: This week"));
+        assert!(raw.contains("Content-Disposition: attachment; filename=\"report.csv\""));
+        assert!(raw.contains("Content-Type: text/csv"));
+    }
+
+    #[test]
+    fn attachments_are_extracted_from_received_email() {
+        let channel = EmailChannel::new(EmailConfig {
+            from_address: "bot@example.com".into(),
+            ..EmailConfig::default()
+        });
+        let message = OutgoingMessage::text("Subject: Scan\nSee attached.").with_attachment(
+            Attachment::from_bytes(b"%PDF".to_vec(), "scan.pdf", "application/pdf"),
+        );
+        let raw = channel
+            .rich_email(&message, vec![b"%PDF".to_vec()], "ops@example.com")
+            .unwrap()
+            .formatted();
+
+        let parsed = MessageParser::default().parse(&raw).unwrap();
+        assert!(EmailChannel::extract_text(&parsed).contains("See attached."));
+        let files = EmailChannel::extract_files(&parsed);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].0.filename, "scan.pdf");
+        assert_eq!(files[0].0.mime_type, "application/pdf");
+        assert_eq!(files[0].1, b"%PDF");
+    }
+}
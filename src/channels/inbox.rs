@@ -0,0 +1,258 @@
+//! Files received on channels.
+//!
+//! Attachments are downloaded into `<workspace>/inbox/<channel>/`, so the
+//! agent can open them with `file_read` or `image_info` like any other
+//! workspace file. Files over the configured size limit are not downloaded.
+
+use super::traits::{media_kind, InboundAttachment};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// What a channel knows about a file before downloading it.
+#[derive(Debug, Clone)]
+pub struct FileInfo {
+    pub filename: String,
+    pub mime_type: String,
+    /// Size announced by the platform, if any
+    pub size: Option<u64>,
+}
+
+impl FileInfo {
+    pub fn new(
+        filename: impl Into<String>,
+        mime_type: impl Into<String>,
+        size: Option<u64>,
+    ) -> Self {
+        Self {
+            filename: filename.into(),
+            mime_type: mime_type.into(),
+            size,
+        }
+    }
+}
+
+/// Where received files are stored, and how large they may be.
+#[derive(Debug)]
+pub struct Inbox {
+    workspace_dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl Inbox {
+    pub fn new(workspace_dir: impl Into<PathBuf>, max_bytes: u64) -> Self {
+        Self {
+            workspace_dir: workspace_dir.into(),
+            max_bytes,
+        }
+    }
+
+    /// The inbox for `config`, or `None` when attachments are disabled
+    /// (`channels_config.max_attachment_mb = 0`).
+    pub fn from_config(config: &Config) -> Option<Arc<Self>> {
+        let max_mb = config.channels_config.max_attachment_mb;
+        (max_mb > 0).then(|| Arc::new(Self::new(&config.workspace_dir, max_mb * 1024 * 1024)))
+    }
+
+    fn check_size(&self, filename: &str, size: u64) -> Result<()> {
+        if size > self.max_bytes {
+            anyhow::bail!(
+                "{filename} is {size} bytes, over the {} byte attachment limit",
+                self.max_bytes
+            );
+        }
+        Ok(())
+    }
+
+    /// Download the body of `request`, stopping as soon as it grows past the
+    /// size limit.
+    pub async fn fetch(
+        &self,
+        info: &FileInfo,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Vec<u8>> {
+        if let Some(size) = info.size {
+            self.check_size(&info.filename, size)?;
+        }
+        let mut resp = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to download {}", info.filename))?;
+        if !resp.status().is_success() {
+            anyhow::bail!(
+                "Failed to download {}: HTTP {}",
+                info.filename,
+                resp.status()
+            );
+        }
+        if let Some(length) = resp.content_length() {
+            self.check_size(&info.filename, length)?;
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = resp.chunk().await? {
+            data.extend_from_slice(&chunk);
+            self.check_size(&info.filename, data.len() as u64)?;
+        }
+        Ok(data)
+    }
+
+    /// Save `data` under `inbox/<channel>/` with a unique, sanitized name.
+    pub async fn save(
+        &self,
+        channel: &str,
+        info: &FileInfo,
+        data: &[u8],
+    ) -> Result<InboundAttachment> {
+        self.check_size(&info.filename, data.len() as u64)?;
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let name = format!(
+            "{}-{}-{}",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            &id[..8],
+            sanitize_filename(&info.filename)
+        );
+        let relative = PathBuf::from("inbox")
+            .join(sanitize_filename(channel))
+            .join(name);
+        let path = self.workspace_dir.join(&relative);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("Failed to save {}", path.display()))?;
+
+        Ok(InboundAttachment {
+            kind: media_kind(&info.mime_type),
+            filename: info.filename.clone(),
+            mime_type: info.mime_type.clone(),
+            path: relative,
+            size: data.len() as u64,
+        })
+    }
+
+    /// Download and save a file in one go.
+    pub async fn download(
+        &self,
+        channel: &str,
+        info: &FileInfo,
+        request: reqwest::RequestBuilder,
+    ) -> Result<InboundAttachment> {
+        let data = self.fetch(info, request).await?;
+        self.save(channel, info, &data).await
+    }
+}
+
+/// Record a file that could not be saved: log it and tell the agent in the
+/// message text, so it isn't silently lost.
+pub fn note_skipped(channel: &str, content: &mut String, filename: &str, error: &anyhow::Error) {
+    tracing::warn!("{channel}: attachment {filename} not saved: {error:#}");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    let _ = write!(content, "[Attachment {filename} was not saved: {error}]");
+}
+
+/// Keep a file name safe to use as a single path component.
+fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(100)
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+    if cleaned.is_empty() {
+        "file".into()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn filenames_are_sanitized() {
+        assert_eq!(sanitize_filename("report 2024.pdf"), "report_2024.pdf");
+        assert_eq!(sanitize_filename("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(sanitize_filename("..."), "file");
+        assert_eq!(sanitize_filename(""), "file");
+        assert_eq!(sanitize_filename(&"a".repeat(300)).len(), 100);
+    }
+
+    #[tokio::test]
+    async fn saves_into_the_channel_inbox() {
+        let tmp = TempDir::new().unwrap();
+        let inbox = Inbox::new(tmp.path(), 16);
+
+        let info = FileInfo::new("photo.jpg", "image/jpeg", None);
+        let saved = inbox.save("telegram", &info, b"jpeg").await.unwrap();
+        assert_eq!(saved.kind, "image");
+        assert_eq!(saved.size, 4);
+        assert!(saved.path.starts_with("inbox/telegram"));
+        assert!(saved.path.to_string_lossy().ends_with("-photo.jpg"));
+        assert_eq!(
+            std::fs::read(tmp.path().join(&saved.path)).unwrap(),
+            b"jpeg"
+        );
+
+        let err = inbox
+            .save(
+                "telegram",
+                &FileInfo::new("big.bin", "application/octet-stream", None),
+                &[0; 17],
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("attachment limit"));
+    }
+
+    #[tokio::test]
+    async fn downloads_stop_at_the_size_limit() {
+        use axum::{routing::get, Router};
+
+        let app = Router::new()
+            .route("/small", get(|| async { "hello" }))
+            .route("/large", get(|| async { "x".repeat(64) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let tmp = TempDir::new().unwrap();
+        let inbox = Inbox::new(tmp.path(), 16);
+        let client = reqwest::Client::new();
+        let info = FileInfo::new("note.txt", "text/plain", None);
+
+        let saved = inbox
+            .download("slack", &info, client.get(format!("{base}/small")))
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read(tmp.path().join(saved.path)).unwrap(),
+            b"hello"
+        );
+
+        assert!(inbox
+            .download("slack", &info, client.get(format!("{base}/large")))
+            .await
+            .is_err());
+        let announced = FileInfo::new("huge.bin", "application/octet-stream", Some(1 << 30));
+        assert!(inbox
+            .download("slack", &announced, client.get(format!("{base}/small")))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,1009 @@
+// End-to-end encryption for the Matrix channel (Olm + Megolm via vodozemac).
+//
+// The state of this device — Olm account, pairwise Olm sessions, inbound and
+// outbound Megolm sessions and the sync token — is kept in one JSON file,
+// encrypted with the secret store when `secrets.encrypt` is on. Everything in
+// here is pure crypto and bookkeeping; the channel does the HTTP calls.
+
+use crate::security::SecretStore;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::Aes256;
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use vodozemac::megolm::{
+    GroupSession, GroupSessionPickle, InboundGroupSession, InboundGroupSessionPickle,
+    MegolmMessage, SessionConfig as MegolmConfig, SessionKey,
+};
+use vodozemac::olm::{Account, AccountPickle, OlmMessage, Session, SessionConfig, SessionPickle};
+use vodozemac::{Curve25519PublicKey, Ed25519PublicKey, Ed25519Signature};
+
+pub const OLM_ALGORITHM: &str = "m.olm.v1.curve25519-aes-sha2";
+pub const MEGOLM_ALGORITHM: &str = "m.megolm.v1.aes-sha2";
+
+/// Outbound Megolm sessions are replaced after this many messages or this age,
+/// matching the defaults of `m.room.encryption`.
+const ROTATION_MESSAGES: u32 = 100;
+const ROTATION_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// A device of some user, with keys taken from a validly self-signed
+/// `/keys/query` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    pub user_id: String,
+    pub device_id: String,
+    pub curve25519: String,
+    pub ed25519: String,
+}
+
+impl Device {
+    /// Parse and verify one device entry from a `/keys/query` response.
+    pub fn from_keys(user_id: &str, device_id: &str, keys: &Value) -> Option<Self> {
+        if keys.get("user_id")?.as_str()? != user_id
+            || keys.get("device_id")?.as_str()? != device_id
+        {
+            return None;
+        }
+        let key = |algorithm: &str| {
+            keys.get("keys")?
+                .get(format!("{algorithm}:{device_id}"))?
+                .as_str()
+                .map(String::from)
+        };
+        let device = Self {
+            user_id: user_id.to_string(),
+            device_id: device_id.to_string(),
+            curve25519: key("curve25519")?,
+            ed25519: key("ed25519")?,
+        };
+        device.verify(keys).then_some(device)
+    }
+
+    /// Whether `value` carries a valid signature by this device.
+    pub fn verify(&self, value: &Value) -> bool {
+        let Ok(key) = Ed25519PublicKey::from_base64(&self.ed25519) else {
+            return false;
+        };
+        let Some(signature) = value
+            .get("signatures")
+            .and_then(|s| s.get(&self.user_id))
+            .and_then(|s| s.get(format!("ed25519:{}", self.device_id)))
+            .and_then(Value::as_str)
+            .and_then(|s| Ed25519Signature::from_base64(s).ok())
+        else {
+            return false;
+        };
+        key.verify(canonical_json(value).as_bytes(), &signature)
+            .is_ok()
+    }
+
+    fn key(&self) -> String {
+        format!("{}|{}", self.user_id, self.device_id)
+    }
+}
+
+/// Fields of an `m.room.encrypted` event's content.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EncryptedContent {
+    #[serde(default)]
+    pub algorithm: Option<String>,
+    #[serde(default)]
+    pub ciphertext: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+/// A room event decrypted with an inbound Megolm session, along with the
+/// keys of the device that shared the session.
+#[derive(Debug)]
+pub struct DecryptedEvent {
+    pub event_type: String,
+    pub content: Value,
+    pub sender_key: String,
+    pub signing_key: String,
+}
+
+struct InboundSession {
+    room_id: String,
+    sender: String,
+    sender_key: String,
+    signing_key: String,
+    session: InboundGroupSession,
+}
+
+struct OutboundSession {
+    session: GroupSession,
+    created_at: i64,
+    shared_with: HashSet<String>,
+}
+
+pub struct Crypto {
+    pub user_id: String,
+    pub device_id: String,
+    pub since: Option<String>,
+    account: Account,
+    device_keys_uploaded: bool,
+    olm_sessions: HashMap<String, Vec<Session>>,
+    inbound: HashMap<String, InboundSession>,
+    outbound: HashMap<String, OutboundSession>,
+    /// Verified device keys per user; refreshed when the server reports a change
+    devices: HashMap<String, Vec<Device>>,
+    /// Megolm message indices already decrypted, to spot replayed ciphertext
+    seen_indices: HashMap<(String, u32), String>,
+    path: PathBuf,
+    secrets: SecretStore,
+}
+
+// ── Persistence ─────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    user_id: String,
+    device_id: String,
+    #[serde(default)]
+    since: Option<String>,
+    account: AccountPickle,
+    device_keys_uploaded: bool,
+    olm_sessions: HashMap<String, Vec<SessionPickle>>,
+    inbound: Vec<InboundRecord>,
+    outbound: HashMap<String, OutboundRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct InboundRecord {
+    room_id: String,
+    sender: String,
+    sender_key: String,
+    signing_key: String,
+    session: InboundGroupSessionPickle,
+}
+
+#[derive(Serialize, Deserialize)]
+struct OutboundRecord {
+    session: GroupSessionPickle,
+    created_at: i64,
+    shared_with: HashSet<String>,
+}
+
+type SharedCrypto = Arc<tokio::sync::Mutex<Option<Crypto>>>;
+
+/// The crypto state for a store file, shared by every channel instance in the
+/// process so a listener and a one-off sender never overwrite each other.
+pub fn shared_state(path: &Path) -> SharedCrypto {
+    static STATES: OnceLock<Mutex<HashMap<PathBuf, SharedCrypto>>> = OnceLock::new();
+    let mut states = STATES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    Arc::clone(states.entry(path.to_path_buf()).or_default())
+}
+
+impl Crypto {
+    /// Load the store at `path`, or start a new Olm account for this device
+    /// when there is none or it belongs to a different user or device.
+    pub fn load(path: &Path, secrets: SecretStore, user_id: &str, device_id: &str) -> Result<Self> {
+        let mut crypto = Self {
+            user_id: user_id.to_string(),
+            device_id: device_id.to_string(),
+            since: None,
+            account: Account::new(),
+            device_keys_uploaded: false,
+            olm_sessions: HashMap::new(),
+            inbound: HashMap::new(),
+            outbound: HashMap::new(),
+            devices: HashMap::new(),
+            seen_indices: HashMap::new(),
+            path: path.to_path_buf(),
+            secrets,
+        };
+        if !path.exists() {
+            return Ok(crypto);
+        }
+
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read Matrix crypto store {}", path.display()))?;
+        let store: StoreFile = serde_json::from_str(&crypto.secrets.decrypt(&raw)?)
+            .context("Matrix crypto store is corrupt")?;
+        if store.user_id != user_id || store.device_id != device_id {
+            tracing::warn!(
+                "Matrix: crypto store belongs to {} ({}), starting fresh for {user_id} ({device_id})",
+                store.user_id,
+                store.device_id
+            );
+            return Ok(crypto);
+        }
+
+        crypto.since = store.since;
+        crypto.account = Account::from_pickle(store.account);
+        crypto.device_keys_uploaded = store.device_keys_uploaded;
+        crypto.olm_sessions = store
+            .olm_sessions
+            .into_iter()
+            .map(|(key, sessions)| {
+                (
+                    key,
+                    sessions.into_iter().map(Session::from_pickle).collect(),
+                )
+            })
+            .collect();
+        crypto.inbound = store
+            .inbound
+            .into_iter()
+            .map(|r| {
+                let session = InboundGroupSession::from_pickle(r.session);
+                let inbound = InboundSession {
+                    room_id: r.room_id,
+                    sender: r.sender,
+                    sender_key: r.sender_key,
+                    signing_key: r.signing_key,
+                    session,
+                };
+                (inbound.session.session_id(), inbound)
+            })
+            .collect();
+        crypto.outbound = store
+            .outbound
+            .into_iter()
+            .map(|(room, r)| {
+                let outbound = OutboundSession {
+                    session: GroupSession::from_pickle(r.session),
+                    created_at: r.created_at,
+                    shared_with: r.shared_with,
+                };
+                (room, outbound)
+            })
+            .collect();
+        Ok(crypto)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let store = StoreFile {
+            user_id: self.user_id.clone(),
+            device_id: self.device_id.clone(),
+            since: self.since.clone(),
+            account: self.account.pickle(),
+            device_keys_uploaded: self.device_keys_uploaded,
+            olm_sessions: self
+                .olm_sessions
+                .iter()
+                .map(|(key, sessions)| {
+                    (key.clone(), sessions.iter().map(Session::pickle).collect())
+                })
+                .collect(),
+            inbound: self
+                .inbound
+                .values()
+                .map(|s| InboundRecord {
+                    room_id: s.room_id.clone(),
+                    sender: s.sender.clone(),
+                    sender_key: s.sender_key.clone(),
+                    signing_key: s.signing_key.clone(),
+                    session: s.session.pickle(),
+                })
+                .collect(),
+            outbound: self
+                .outbound
+                .iter()
+                .map(|(room, s)| {
+                    let record = OutboundRecord {
+                        session: s.session.pickle(),
+                        created_at: s.created_at,
+                        shared_with: s.shared_with.clone(),
+                    };
+                    (room.clone(), record)
+                })
+                .collect(),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = self.secrets.encrypt(&serde_json::to_string(&store)?)?;
+        std::fs::write(&self.path, contents)
+            .with_context(|| format!("Failed to write {}", self.path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    // ── Keys ────────────────────────────────────────────────────
+
+    /// Fingerprint (Ed25519 key) users compare when verifying this device.
+    pub fn fingerprint(&self) -> String {
+        self.account.ed25519_key().to_base64()
+    }
+
+    /// This device as it appears to others.
+    pub fn own_device(&self) -> Device {
+        Device {
+            user_id: self.user_id.clone(),
+            device_id: self.device_id.clone(),
+            curve25519: self.account.curve25519_key().to_base64(),
+            ed25519: self.fingerprint(),
+        }
+    }
+
+    /// Body for `/keys/upload`: device keys until they have been published,
+    /// plus enough one-time keys to bring `published` (the server's count) up
+    /// to the number the account wants on the server.
+    pub fn keys_to_upload(&mut self, published: Option<u64>) -> Option<Value> {
+        let mut body = serde_json::Map::new();
+        if !self.device_keys_uploaded {
+            let own = self.own_device();
+            let mut device_keys = json!({
+                "user_id": own.user_id,
+                "device_id": own.device_id,
+                "algorithms": [OLM_ALGORITHM, MEGOLM_ALGORITHM],
+                "keys": {
+                    format!("curve25519:{}", own.device_id): own.curve25519,
+                    format!("ed25519:{}", own.device_id): own.ed25519,
+                },
+            });
+            self.sign(&mut device_keys);
+            body.insert("device_keys".into(), device_keys);
+            if self.account.fallback_key().is_empty() {
+                self.account.generate_fallback_key();
+            }
+        }
+
+        if let Some(published) = published {
+            let target = self.account.max_number_of_one_time_keys();
+            let pending = self.account.one_time_keys().len();
+            let published = usize::try_from(published).unwrap_or(usize::MAX);
+            let missing = target.saturating_sub(published + pending);
+            if missing > 0 {
+                self.account.generate_one_time_keys(missing);
+            }
+        }
+
+        let one_time_keys = self.signed_keys(self.account.one_time_keys(), false);
+        if !one_time_keys.is_empty() {
+            body.insert("one_time_keys".into(), Value::Object(one_time_keys));
+        }
+        let fallback_keys = self.signed_keys(self.account.fallback_key(), true);
+        if !fallback_keys.is_empty() {
+            body.insert("fallback_keys".into(), Value::Object(fallback_keys));
+        }
+        (!body.is_empty()).then_some(Value::Object(body))
+    }
+
+    /// Record a successful `/keys/upload` of the body from `keys_to_upload`.
+    pub fn mark_keys_published(&mut self) {
+        self.device_keys_uploaded = true;
+        self.account.mark_keys_as_published();
+    }
+
+    fn signed_keys(
+        &self,
+        keys: HashMap<vodozemac::KeyId, Curve25519PublicKey>,
+        fallback: bool,
+    ) -> serde_json::Map<String, Value> {
+        keys.into_iter()
+            .map(|(id, key)| {
+                let mut entry = json!({ "key": key.to_base64() });
+                if fallback {
+                    entry["fallback"] = json!(true);
+                }
+                self.sign(&mut entry);
+                (format!("signed_curve25519:{}", id.to_base64()), entry)
+            })
+            .collect()
+    }
+
+    /// Add this device's signature to `value` (Matrix signed JSON).
+    fn sign(&self, value: &mut Value) {
+        let signature = self.account.sign(canonical_json(value)).to_base64();
+        value["signatures"] = json!({
+            &self.user_id: { format!("ed25519:{}", self.device_id): signature }
+        });
+    }
+
+    // ── Devices ─────────────────────────────────────────────────
+
+    pub fn cached_devices(&self, user_id: &str) -> Option<&[Device]> {
+        self.devices.get(user_id).map(Vec::as_slice)
+    }
+
+    pub fn cache_devices(&mut self, user_id: &str, devices: Vec<Device>) {
+        self.devices.insert(user_id.to_string(), devices);
+    }
+
+    pub fn forget_devices(&mut self, user_id: &str) {
+        self.devices.remove(user_id);
+    }
+
+    // ── Receiving ───────────────────────────────────────────────
+
+    /// Decrypt an Olm-encrypted to-device event and keep any Megolm room key
+    /// it carries. Returns the room key's session ID.
+    pub fn receive_to_device(&mut self, event: &Value) -> Result<Option<String>> {
+        let sender = event
+            .get("sender")
+            .and_then(Value::as_str)
+            .context("to-device event has no sender")?;
+        let content = event
+            .get("content")
+            .context("to-device event has no content")?;
+        if content.get("algorithm").and_then(Value::as_str) != Some(OLM_ALGORITHM) {
+            return Ok(None);
+        }
+        let sender_key = content
+            .get("sender_key")
+            .and_then(Value::as_str)
+            .context("Olm message has no sender_key")?;
+        let own_key = self.account.curve25519_key().to_base64();
+        let Some(ciphertext) = content.get("ciphertext").and_then(|c| c.get(&own_key)) else {
+            return Ok(None);
+        };
+        let message: OlmMessage = serde_json::from_value(ciphertext.clone())?;
+        let plaintext: Value = serde_json::from_slice(&self.olm_decrypt(sender_key, &message)?)?;
+
+        // The payload must be addressed to us and come from the event's sender
+        let field = |name: &str| plaintext.get(name).and_then(Value::as_str);
+        if field("sender") != Some(sender)
+            || field("recipient") != Some(self.user_id.as_str())
+            || plaintext
+                .get("recipient_keys")
+                .and_then(|k| k.get("ed25519"))
+                .and_then(Value::as_str)
+                != Some(self.fingerprint().as_str())
+        {
+            anyhow::bail!("Olm payload from {sender} is not addressed to this device");
+        }
+        if field("type") != Some("m.room_key") {
+            return Ok(None);
+        }
+
+        let key = plaintext
+            .get("content")
+            .context("m.room_key has no content")?;
+        let key_field = |name: &str| key.get(name).and_then(Value::as_str);
+        if key_field("algorithm") != Some(MEGOLM_ALGORITHM) {
+            return Ok(None);
+        }
+        let (Some(room_id), Some(session_key)) = (key_field("room_id"), key_field("session_key"))
+        else {
+            anyhow::bail!("m.room_key from {sender} is incomplete");
+        };
+        let signing_key = plaintext
+            .get("keys")
+            .and_then(|k| k.get("ed25519"))
+            .and_then(Value::as_str)
+            .context("Olm payload has no sender ed25519 key")?;
+
+        let session = InboundGroupSession::new(
+            &SessionKey::from_base64(session_key)?,
+            MegolmConfig::version_1(),
+        );
+        let session_id = session.session_id();
+        self.inbound
+            .entry(session_id.clone())
+            .or_insert_with(|| InboundSession {
+                room_id: room_id.to_string(),
+                sender: sender.to_string(),
+                sender_key: sender_key.to_string(),
+                signing_key: signing_key.to_string(),
+                session,
+            });
+        Ok(Some(session_id))
+    }
+
+    fn olm_decrypt(&mut self, sender_key: &str, message: &OlmMessage) -> Result<Vec<u8>> {
+        let sessions = self.olm_sessions.entry(sender_key.to_string()).or_default();
+        for session in sessions.iter_mut() {
+            if let Ok(plaintext) = session.decrypt(message) {
+                return Ok(plaintext);
+            }
+        }
+        let OlmMessage::PreKey(pre_key) = message else {
+            anyhow::bail!("No Olm session with {sender_key} can decrypt this message");
+        };
+        let identity = Curve25519PublicKey::from_base64(sender_key)?;
+        let created = self.account.create_inbound_session(identity, pre_key)?;
+        self.olm_sessions
+            .entry(sender_key.to_string())
+            .or_default()
+            .push(created.session);
+        Ok(created.plaintext)
+    }
+
+    /// Decrypt a Megolm `m.room.encrypted` event `sender` sent to `room_id`.
+    pub fn decrypt_room_event(
+        &mut self,
+        room_id: &str,
+        sender: &str,
+        event_id: &str,
+        content: &EncryptedContent,
+    ) -> Result<DecryptedEvent> {
+        if content.algorithm.as_deref() != Some(MEGOLM_ALGORITHM) {
+            anyhow::bail!("Unsupported room encryption algorithm");
+        }
+        let (Some(session_id), Some(ciphertext)) = (&content.session_id, &content.ciphertext)
+        else {
+            anyhow::bail!("Encrypted event is missing its session_id or ciphertext");
+        };
+
+        let inbound = self
+            .inbound
+            .get_mut(session_id)
+            .with_context(|| format!("No room key for Megolm session {session_id}"))?;
+        if inbound.room_id != room_id || inbound.sender != sender {
+            anyhow::bail!("Megolm session {session_id} belongs to another room or sender");
+        }
+        let decrypted = inbound
+            .session
+            .decrypt(&MegolmMessage::from_base64(ciphertext)?)?;
+
+        let seen = self
+            .seen_indices
+            .entry((session_id.clone(), decrypted.message_index))
+            .or_insert_with(|| event_id.to_string());
+        if seen != event_id {
+            anyhow::bail!("Replayed Megolm message index in {room_id}");
+        }
+
+        let plaintext: Value = serde_json::from_slice(&decrypted.plaintext)?;
+        if plaintext.get("room_id").and_then(Value::as_str) != Some(room_id) {
+            anyhow::bail!("Encrypted event was meant for another room");
+        }
+        Ok(DecryptedEvent {
+            event_type: plaintext
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            content: plaintext.get("content").cloned().unwrap_or_default(),
+            sender_key: inbound.sender_key.clone(),
+            signing_key: inbound.signing_key.clone(),
+        })
+    }
+
+    // ── Sending ─────────────────────────────────────────────────
+
+    /// Devices in `devices` still missing the room's current Megolm key. The
+    /// session is replaced when it is worn out or a device has left the room.
+    pub fn room_key_recipients(&mut self, room_id: &str, devices: &[Device]) -> Vec<Device> {
+        let now = chrono::Utc::now().timestamp();
+        let current: HashSet<String> = devices.iter().map(Device::key).collect();
+        let stale = self.outbound.get(room_id).is_none_or(|s| {
+            s.session.message_index() >= ROTATION_MESSAGES
+                || now - s.created_at >= ROTATION_SECS
+                || !s.shared_with.is_subset(&current)
+        });
+        if stale {
+            self.outbound.insert(
+                room_id.to_string(),
+                OutboundSession {
+                    session: GroupSession::new(MegolmConfig::version_1()),
+                    created_at: now,
+                    shared_with: HashSet::new(),
+                },
+            );
+        }
+        let shared = &self.outbound[room_id].shared_with;
+        devices
+            .iter()
+            .filter(|d| !shared.contains(&d.key()))
+            .cloned()
+            .collect()
+    }
+
+    /// Devices we have no Olm session with yet; claim a one-time key for each.
+    pub fn devices_without_session<'a>(&self, devices: &'a [Device]) -> Vec<&'a Device> {
+        devices
+            .iter()
+            .filter(|d| {
+                self.olm_sessions
+                    .get(&d.curve25519)
+                    .is_none_or(Vec::is_empty)
+            })
+            .collect()
+    }
+
+    /// Start an Olm session with `device` from a claimed, signed one-time key.
+    pub fn add_outbound_session(&mut self, device: &Device, signed_key: &Value) -> Result<()> {
+        if !device.verify(signed_key) {
+            anyhow::bail!(
+                "One-time key of {} is not signed by the device",
+                device.device_id
+            );
+        }
+        let one_time_key = signed_key
+            .get("key")
+            .and_then(Value::as_str)
+            .context("Claimed one-time key has no key")?;
+        let session = self.account.create_outbound_session(
+            SessionConfig::version_1(),
+            Curve25519PublicKey::from_base64(&device.curve25519)?,
+            Curve25519PublicKey::from_base64(one_time_key)?,
+        );
+        self.olm_sessions
+            .entry(device.curve25519.clone())
+            .or_default()
+            .push(session);
+        Ok(())
+    }
+
+    /// `/sendToDevice` messages carrying the room's Megolm key to `devices`.
+    /// Devices without an Olm session are skipped and asked again next time.
+    pub fn room_key_messages(&mut self, room_id: &str, devices: &[Device]) -> Value {
+        let own = self.own_device();
+        let Some(outbound) = self.outbound.get_mut(room_id) else {
+            return json!({});
+        };
+        let room_key = json!({
+            "algorithm": MEGOLM_ALGORITHM,
+            "room_id": room_id,
+            "session_id": outbound.session.session_id(),
+            "session_key": outbound.session.session_key().to_base64(),
+        });
+
+        let mut messages = serde_json::Map::new();
+        for device in devices {
+            let Some(session) = self
+                .olm_sessions
+                .get_mut(&device.curve25519)
+                .and_then(|s| s.last_mut())
+            else {
+                tracing::warn!(
+                    "Matrix: no Olm session with {}, key not shared",
+                    device.device_id
+                );
+                continue;
+            };
+            let payload = json!({
+                "type": "m.room_key",
+                "content": room_key,
+                "sender": own.user_id,
+                "sender_device": own.device_id,
+                "keys": { "ed25519": own.ed25519 },
+                "recipient": device.user_id,
+                "recipient_keys": { "ed25519": device.ed25519 },
+            });
+            let message = session.encrypt(payload.to_string());
+            let content = json!({
+                "algorithm": OLM_ALGORITHM,
+                "sender_key": own.curve25519,
+                "ciphertext": { &device.curve25519: message },
+            });
+            messages
+                .entry(device.user_id.clone())
+                .or_insert_with(|| json!({}))[&device.device_id] = content;
+            outbound.shared_with.insert(device.key());
+        }
+        Value::Object(messages)
+    }
+
+    /// Drop the room's outbound session, e.g. when sharing its key failed.
+    pub fn discard_outbound(&mut self, room_id: &str) {
+        self.outbound.remove(room_id);
+    }
+
+    /// Content of an `m.room.encrypted` event wrapping `content`.
+    pub fn encrypt_room_event(
+        &mut self,
+        room_id: &str,
+        event_type: &str,
+        content: &Value,
+    ) -> Result<Value> {
+        let sender_key = self.account.curve25519_key().to_base64();
+        let outbound = self
+            .outbound
+            .get_mut(room_id)
+            .with_context(|| format!("No Megolm session for {room_id}"))?;
+        let plaintext = json!({ "type": event_type, "content": content, "room_id": room_id });
+        let message = outbound.session.encrypt(plaintext.to_string());
+        Ok(json!({
+            "algorithm": MEGOLM_ALGORITHM,
+            "sender_key": sender_key,
+            "ciphertext": message.to_base64(),
+            "session_id": outbound.session.session_id(),
+            "device_id": self.device_id,
+        }))
+    }
+}
+
+/// Canonical JSON of a signed object: the `signatures` and `unsigned` fields
+/// removed, keys sorted and no whitespace. `serde_json` maps are ordered, so
+/// compact output is already sorted.
+pub fn canonical_json(value: &Value) -> String {
+    let mut value = value.clone();
+    if let Some(object) = value.as_object_mut() {
+        object.remove("signatures");
+        object.remove("unsigned");
+    }
+    value.to_string()
+}
+
+/// Encrypt an attachment for an encrypted room (AES-256-CTR with a fresh
+/// key). Returns the ciphertext to upload and the `file` object for the
+/// event content, still missing its `url`.
+pub fn encrypt_attachment(data: &[u8]) -> (Vec<u8>, Value) {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    // Random high half, zero counter: the counter can't wrap into the IV
+    let mut iv = [0u8; 16];
+    OsRng.fill_bytes(&mut iv[..8]);
+
+    let mut ciphertext = data.to_vec();
+    aes_ctr(&key, &iv, &mut ciphertext);
+    let hash = Sha256::digest(&ciphertext);
+
+    let file = json!({
+        "v": "v2",
+        "key": {
+            "kty": "oct",
+            "key_ops": ["encrypt", "decrypt"],
+            "alg": "A256CTR",
+            "k": URL_SAFE_NO_PAD.encode(key),
+            "ext": true
+        },
+        "iv": STANDARD_NO_PAD.encode(iv),
+        "hashes": { "sha256": STANDARD_NO_PAD.encode(hash) }
+    });
+    (ciphertext, file)
+}
+
+/// Decrypt a downloaded attachment described by the `file` object of an
+/// encrypted event, checking its SHA-256 hash first.
+pub fn decrypt_attachment(ciphertext: &[u8], file: &Value) -> Result<Vec<u8>> {
+    // Senders differ on padding; accept both
+    let decode = |engine: &base64::engine::GeneralPurpose, field: Option<&str>, what: &str| {
+        let field = field.with_context(|| format!("encrypted file has no {what}"))?;
+        engine
+            .decode(field.trim_end_matches('='))
+            .with_context(|| format!("invalid {what} in encrypted file"))
+    };
+    let key = decode(&URL_SAFE_NO_PAD, file["key"]["k"].as_str(), "key")?;
+    let iv = decode(&STANDARD_NO_PAD, file["iv"].as_str(), "iv")?;
+    let hash = decode(&STANDARD_NO_PAD, file["hashes"]["sha256"].as_str(), "hash")?;
+
+    let key: [u8; 32] = key
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("encrypted file key is not 256 bits"))?;
+    let iv: [u8; 16] = iv
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("encrypted file iv is not 128 bits"))?;
+    if Sha256::digest(ciphertext).as_slice() != hash.as_slice() {
+        anyhow::bail!("encrypted file hash mismatch");
+    }
+
+    let mut data = ciphertext.to_vec();
+    aes_ctr(&key, &iv, &mut data);
+    Ok(data)
+}
+
+/// AES-256 in CTR mode with a 128-bit big-endian counter, in place. The same
+/// call encrypts and decrypts.
+fn aes_ctr(key: &[u8; 32], iv: &[u8; 16], data: &mut [u8]) {
+    let cipher = Aes256::new(key.into());
+    let mut counter = u128::from_be_bytes(*iv);
+    for chunk in data.chunks_mut(16) {
+        let mut block = counter.to_be_bytes().into();
+        cipher.encrypt_block(&mut block);
+        for (byte, pad) in chunk.iter_mut().zip(block.iter()) {
+            *byte ^= pad;
+        }
+        counter = counter.wrapping_add(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn crypto(tmp: &TempDir, user: &str, device: &str) -> Crypto {
+        let path = tmp.path().join(format!("{device}.json"));
+        Crypto::load(&path, SecretStore::new(tmp.path(), true), user, device).unwrap()
+    }
+
+    /// What a homeserver would hand out for `/keys/query` and `/keys/claim`.
+    fn published(crypto: &mut Crypto) -> (Device, Value) {
+        let body = crypto.keys_to_upload(Some(0)).unwrap();
+        crypto.mark_keys_published();
+        let device =
+            Device::from_keys(&crypto.user_id, &crypto.device_id, &body["device_keys"]).unwrap();
+        let one_time_key = body["one_time_keys"]
+            .as_object()
+            .unwrap()
+            .values()
+            .next()
+            .unwrap()
+            .clone();
+        (device, one_time_key)
+    }
+
+    #[test]
+    fn uploaded_keys_are_self_signed() {
+        let tmp = TempDir::new().unwrap();
+        let mut bot = crypto(&tmp, "@bot:m", "BOT");
+        let body = bot.keys_to_upload(Some(0)).unwrap();
+        let device = Device::from_keys("@bot:m", "BOT", &body["device_keys"]).unwrap();
+        assert_eq!(device, bot.own_device());
+        assert_eq!(body["one_time_keys"].as_object().unwrap().len(), 50);
+        assert!(body["one_time_keys"]
+            .as_object()
+            .unwrap()
+            .values()
+            .all(|k| device.verify(k)));
+
+        let mut tampered = body["device_keys"].clone();
+        tampered["keys"]["curve25519:BOT"] = json!("AAAA");
+        assert!(Device::from_keys("@bot:m", "BOT", &tampered).is_none());
+
+        bot.mark_keys_published();
+        assert!(bot.keys_to_upload(Some(50)).is_none());
+    }
+
+    #[test]
+    fn room_messages_round_trip_between_devices() {
+        let tmp = TempDir::new().unwrap();
+        let mut alice = crypto(&tmp, "@alice:m", "ALICE");
+        let mut bot = crypto(&tmp, "@bot:m", "BOT");
+        let (alice_device, _) = published(&mut alice);
+        let (bot_device, bot_otk) = published(&mut bot);
+
+        // Alice shares a room key with the bot and sends an encrypted message
+        let recipients = alice.room_key_recipients("!r:m", std::slice::from_ref(&bot_device));
+        assert_eq!(alice.devices_without_session(&recipients).len(), 1);
+        alice.add_outbound_session(&bot_device, &bot_otk).unwrap();
+        let to_device = alice.room_key_messages("!r:m", &recipients);
+        let content = alice
+            .encrypt_room_event(
+                "!r:m",
+                "m.room.message",
+                &json!({"msgtype": "m.text", "body": "hi"}),
+            )
+            .unwrap();
+
+        let key_event = json!({"sender": "@alice:m", "content": to_device["@bot:m"]["BOT"]});
+        assert!(bot.receive_to_device(&key_event).unwrap().is_some());
+        let content: EncryptedContent = serde_json::from_value(content).unwrap();
+        let decrypted = bot
+            .decrypt_room_event("!r:m", "@alice:m", "$1", &content)
+            .unwrap();
+        assert_eq!(decrypted.event_type, "m.room.message");
+        assert_eq!(decrypted.content["body"], "hi");
+        assert_eq!(decrypted.signing_key, alice_device.ed25519);
+        assert_eq!(decrypted.sender_key, alice_device.curve25519);
+
+        // Same ciphertext under a new event ID is a replay; other rooms are refused
+        assert!(bot
+            .decrypt_room_event("!r:m", "@alice:m", "$2", &content)
+            .is_err());
+        assert!(bot
+            .decrypt_room_event("!other:m", "@alice:m", "$1", &content)
+            .is_err());
+        assert!(bot
+            .decrypt_room_event("!r:m", "@mallory:m", "$1", &content)
+            .is_err());
+
+        // The key is shared once per session, and again after a device leaves
+        assert!(alice
+            .room_key_recipients("!r:m", std::slice::from_ref(&bot_device))
+            .is_empty());
+        assert_eq!(alice.room_key_recipients("!r:m", &[]).len(), 0);
+        assert_eq!(
+            alice
+                .room_key_recipients("!r:m", std::slice::from_ref(&bot_device))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn store_survives_restart_and_is_encrypted() {
+        let tmp = TempDir::new().unwrap();
+        let mut alice = crypto(&tmp, "@alice:m", "ALICE");
+        let mut bot = crypto(&tmp, "@bot:m", "BOT");
+        published(&mut alice);
+        let (bot_device, bot_otk) = published(&mut bot);
+
+        let recipients = alice.room_key_recipients("!r:m", std::slice::from_ref(&bot_device));
+        alice.add_outbound_session(&bot_device, &bot_otk).unwrap();
+        let to_device = alice.room_key_messages("!r:m", &recipients);
+        bot.receive_to_device(
+            &json!({"sender": "@alice:m", "content": to_device["@bot:m"]["BOT"]}),
+        )
+        .unwrap();
+        bot.since = Some("s42".into());
+        bot.save().unwrap();
+
+        let raw = std::fs::read_to_string(tmp.path().join("BOT.json")).unwrap();
+        assert!(raw.starts_with("enc2:"));
+
+        let mut restarted = crypto(&tmp, "@bot:m", "BOT");
+        assert_eq!(restarted.since.as_deref(), Some("s42"));
+        assert_eq!(restarted.fingerprint(), bot.fingerprint());
+        let content = alice
+            .encrypt_room_event("!r:m", "m.room.message", &json!({"body": "later"}))
+            .unwrap();
+        let content: EncryptedContent = serde_json::from_value(content).unwrap();
+        let decrypted = restarted
+            .decrypt_room_event("!r:m", "@alice:m", "$9", &content)
+            .unwrap();
+        assert_eq!(decrypted.content["body"], "later");
+
+        // A store written for another device is not reused
+        let path = tmp.path().join("BOT.json");
+        let other =
+            Crypto::load(&path, SecretStore::new(tmp.path(), true), "@bot:m", "OTHER").unwrap();
+        assert_ne!(other.fingerprint(), bot.fingerprint());
+        assert!(other.since.is_none());
+    }
+
+    #[test]
+    fn attachments_round_trip_through_aes_ctr() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let (mut ciphertext, file) = encrypt_attachment(&data);
+        assert_ne!(ciphertext, data);
+        assert_eq!(file["key"]["alg"], "A256CTR");
+
+        let hash = STANDARD_NO_PAD
+            .decode(file["hashes"]["sha256"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(hash, Sha256::digest(&ciphertext).to_vec());
+
+        let key: [u8; 32] = URL_SAFE_NO_PAD
+            .decode(file["key"]["k"].as_str().unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let iv: [u8; 16] = STANDARD_NO_PAD
+            .decode(file["iv"].as_str().unwrap())
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(&iv[8..], &[0; 8]);
+        aes_ctr(&key, &iv, &mut ciphertext);
+        assert_eq!(ciphertext, data);
+    }
+
+    #[test]
+    fn decrypt_attachment_checks_the_hash() {
+        let (mut ciphertext, file) = encrypt_attachment(b"secret photo");
+        assert_eq!(
+            decrypt_attachment(&ciphertext, &file).unwrap(),
+            b"secret photo"
+        );
+
+        ciphertext[0] ^= 1;
+        let err = decrypt_attachment(&ciphertext, &file).unwrap_err();
+        assert!(err.to_string().contains("hash mismatch"));
+        assert!(decrypt_attachment(&ciphertext, &json!({})).is_err());
+    }
+
+    #[test]
+    fn aes_ctr_matches_nist_vector() {
+        // NIST SP 800-38A F.5.5 (CTR-AES256.Encrypt), first block
+        let key: [u8; 32] =
+            hex::decode("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let iv: [u8; 16] = hex::decode("f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let mut block =
+            hex::decode("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51")
+                .unwrap();
+        aes_ctr(&key, &iv, &mut block);
+        assert_eq!(
+            hex::encode(block),
+            "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5"
+        );
+    }
+}
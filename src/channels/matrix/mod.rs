@@ -0,0 +1,1694 @@
+use crate::channels::inbox::{self, FileInfo, Inbox};
+use crate::channels::traits::{
+    Attachment, Channel, ChannelMessage, InboundAttachment, OutgoingMessage,
+};
+use crate::security::SecretStore;
+use anyhow::Context;
+use async_trait::async_trait;
+use reqwest::{Client, Method};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{mpsc, MappedMutexGuard, MutexGuard};
+
+mod crypto;
+
+use crypto::{Crypto, DecryptedEvent, Device, EncryptedContent};
+
+/// Matrix channel using the Client-Server API (no SDK needed).
+/// Connects to any Matrix homeserver (Element, Synapse, etc.).
+///
+/// Serves every room the bot has joined: messages are answered in the room
+/// they came from, and `room_id` is only the fallback target for sends
+/// without one. Encrypted rooms work once E2EE is enabled.
+#[derive(Clone)]
+pub struct MatrixChannel {
+    homeserver: String,
+    access_token: String,
+    room_id: String,
+    allowed_users: Vec<String>,
+    room_allowed_users: HashMap<String, Vec<String>>,
+    auto_join: bool,
+    e2ee: Option<E2ee>,
+    encrypted_rooms: Arc<std::sync::Mutex<HashMap<String, bool>>>,
+    inbox: Option<Arc<Inbox>>,
+    client: Client,
+}
+
+/// Message types whose file is downloaded
+const MEDIA_MSGTYPES: [&str; 4] = ["m.image", "m.file", "m.audio", "m.video"];
+
+/// End-to-end encryption settings and the device state they share.
+#[derive(Clone)]
+struct E2ee {
+    store_path: PathBuf,
+    secrets: SecretStore,
+    verified_devices: Vec<String>,
+    state: Arc<tokio::sync::Mutex<Option<Crypto>>>,
+}
+
+impl E2ee {
+    /// With no verified fingerprints configured, any device with validly
+    /// self-signed keys is trusted.
+    fn trusts(&self, device: &Device) -> bool {
+        self.verified_devices.is_empty() || self.verified_devices.contains(&device.ed25519)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SyncResponse {
+    next_batch: String,
+    #[serde(default)]
+    rooms: Rooms,
+    #[serde(default)]
+    to_device: ToDevice,
+    #[serde(default)]
+    device_lists: DeviceLists,
+    #[serde(default)]
+    device_one_time_keys_count: HashMap<String, u64>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Rooms {
+    #[serde(default)]
+    join: std::collections::HashMap<String, JoinedRoom>,
+    #[serde(default)]
+    invite: std::collections::HashMap<String, InvitedRoom>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JoinedRoom {
+    #[serde(default)]
+    state: Timeline,
+    #[serde(default)]
+    timeline: Timeline,
+}
+
+#[derive(Debug, Deserialize)]
+struct InvitedRoom {
+    #[serde(default)]
+    invite_state: Timeline,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Timeline {
+    #[serde(default)]
+    events: Vec<TimelineEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TimelineEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    sender: String,
+    #[serde(default)]
+    event_id: String,
+    #[serde(default)]
+    state_key: Option<String>,
+    #[serde(default)]
+    content: EventContent,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct EventContent {
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    msgtype: Option<String>,
+    #[serde(default)]
+    membership: Option<String>,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    /// Location and keys of a file in an encrypted room
+    #[serde(default)]
+    file: Option<Value>,
+    #[serde(default)]
+    info: Option<Value>,
+    #[serde(flatten)]
+    encrypted: EncryptedContent,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ToDevice {
+    #[serde(default)]
+    events: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct DeviceLists {
+    #[serde(default)]
+    changed: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WhoAmIResponse {
+    user_id: String,
+    #[serde(default)]
+    device_id: Option<String>,
+}
+
+impl MatrixChannel {
+    pub fn new(
+        homeserver: String,
+        access_token: String,
+        room_id: String,
+        allowed_users: Vec<String>,
+    ) -> Self {
+        let homeserver = if homeserver.ends_with('/') {
+            homeserver[..homeserver.len() - 1].to_string()
+        } else {
+            homeserver
+        };
+        Self {
+            homeserver,
+            access_token,
+            room_id,
+            allowed_users,
+            room_allowed_users: HashMap::new(),
+            auto_join: false,
+            e2ee: None,
+            encrypted_rooms: Arc::default(),
+            inbox: None,
+            client: Client::new(),
+        }
+    }
+
+    /// Per-room allowlists, used instead of `allowed_users` in those rooms,
+    /// and whether to join rooms an allowed user invites the bot to.
+    pub fn with_rooms(
+        mut self,
+        room_allowed_users: HashMap<String, Vec<String>>,
+        auto_join: bool,
+    ) -> Self {
+        self.room_allowed_users = room_allowed_users;
+        self.auto_join = auto_join;
+        self
+    }
+
+    /// Read and write end-to-end encrypted rooms, keeping this device's keys
+    /// and sessions in `store_path`. Non-empty `verified_devices` (Ed25519
+    /// fingerprints) limits which devices the bot talks to.
+    pub fn with_e2ee(
+        mut self,
+        store_path: PathBuf,
+        secrets: SecretStore,
+        verified_devices: Vec<String>,
+    ) -> Self {
+        self.e2ee = Some(E2ee {
+            state: crypto::shared_state(&store_path),
+            store_path,
+            secrets,
+            verified_devices,
+        });
+        self
+    }
+
+    /// Save images, audio, video and files sent to the bot into `inbox`,
+    /// decrypting them in encrypted rooms.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
+    fn is_user_allowed(&self, sender: &str) -> bool {
+        allowlist_contains(&self.allowed_users, sender)
+    }
+
+    fn is_user_allowed_in(&self, room_id: &str, sender: &str) -> bool {
+        let allowed = self
+            .room_allowed_users
+            .get(room_id)
+            .unwrap_or(&self.allowed_users);
+        allowlist_contains(allowed, sender)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> anyhow::Result<Value> {
+        let url = format!("{}/_matrix/client/v3{path}", self.homeserver);
+        let mut req = self
+            .client
+            .request(method, &url)
+            .header("Authorization", format!("Bearer {}", self.access_token));
+        if let Some(body) = body {
+            req = req.json(body);
+        }
+        let resp = req.send().await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            let endpoint = path.split('?').next().unwrap_or(path);
+            anyhow::bail!("Matrix {endpoint} failed: {err}");
+        }
+        Ok(resp.json().await?)
+    }
+
+    /// Upload `data` to the media repository and return its `mxc://` URI.
+    async fn upload_media(
+        &self,
+        data: Vec<u8>,
+        filename: &str,
+        content_type: &str,
+    ) -> anyhow::Result<String> {
+        let url = format!(
+            "{}/_matrix/media/v3/upload?filename={}",
+            self.homeserver,
+            encode(filename)
+        );
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", content_type)
+            .body(data)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix media upload failed: {err}");
+        }
+        let body: Value = resp.json().await?;
+        body.get("content_uri")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+            .context("Matrix media upload returned no content_uri")
+    }
+
+    /// `m.room.message` content for an attachment. Files for encrypted rooms
+    /// are encrypted before upload and referenced through `file` instead of
+    /// `url`.
+    async fn attachment_content(
+        &self,
+        attachment: &Attachment,
+        encrypted: bool,
+    ) -> anyhow::Result<Value> {
+        let data = attachment.bytes().await?;
+        let msgtype = match attachment.kind() {
+            "file" => "m.file".to_string(),
+            kind => format!("m.{kind}"),
+        };
+        let mut content = json!({
+            "msgtype": msgtype,
+            "body": attachment.caption.as_ref().unwrap_or(&attachment.filename),
+            "filename": attachment.filename,
+            "info": { "mimetype": attachment.mime_type, "size": data.len() },
+        });
+
+        if encrypted {
+            let (ciphertext, mut file) = crypto::encrypt_attachment(&data);
+            file["url"] = json!(
+                self.upload_media(ciphertext, &attachment.filename, "application/octet-stream")
+                    .await?
+            );
+            content["file"] = file;
+        } else {
+            content["url"] = json!(
+                self.upload_media(data, &attachment.filename, &attachment.mime_type)
+                    .await?
+            );
+        }
+        Ok(content)
+    }
+
+    /// Send an `m.room.message` with `content`, encrypting it in encrypted
+    /// rooms.
+    async fn send_content(&self, room_id: &str, content: Value) -> anyhow::Result<()> {
+        let (event_type, content) = match &self.e2ee {
+            Some(e2ee) if self.is_encrypted(room_id).await? => (
+                "m.room.encrypted",
+                self.encrypt_for_room(e2ee, room_id, &content).await?,
+            ),
+            _ => ("m.room.message", content),
+        };
+
+        let path = format!("/rooms/{}/send/{event_type}/{}", encode(room_id), txn_id());
+        self.request(Method::PUT, &path, Some(&content))
+            .await
+            .context("Matrix send failed")?;
+        Ok(())
+    }
+
+    fn target_room<'a>(&'a self, target: &'a str) -> &'a str {
+        if target.is_empty() {
+            self.room_id.as_str()
+        } else {
+            target
+        }
+    }
+
+    async fn whoami(&self) -> anyhow::Result<WhoAmIResponse> {
+        let who = self.request(Method::GET, "/account/whoami", None).await?;
+        Ok(serde_json::from_value(who)?)
+    }
+
+    async fn sync(&self, since: Option<&str>) -> anyhow::Result<SyncResponse> {
+        let path = match since {
+            Some(since) => format!("/sync?since={}&timeout=30000", encode(since)),
+            // First sync: only the latest event per room, just to get a token
+            None => format!(
+                "/sync?timeout=30000&filter={}",
+                encode(r#"{"room":{"timeline":{"limit":1}}}"#)
+            ),
+        };
+        Ok(serde_json::from_value(
+            self.request(Method::GET, &path, None).await?,
+        )?)
+    }
+
+    /// Handle one sync batch; timeline messages are only forwarded when
+    /// `deliver` is set. Returns `false` once the receiver is gone.
+    async fn handle_sync(
+        &self,
+        user_id: &str,
+        sync: &SyncResponse,
+        deliver: bool,
+        tx: &mpsc::Sender<ChannelMessage>,
+    ) -> anyhow::Result<bool> {
+        if let Some(e2ee) = &self.e2ee {
+            self.sync_crypto(e2ee, sync).await?;
+        }
+        if self.auto_join {
+            self.join_invites(user_id, &sync.rooms.invite).await;
+        }
+
+        for (room_id, room) in &sync.rooms.join {
+            if room
+                .state
+                .events
+                .iter()
+                .chain(&room.timeline.events)
+                .any(|e| e.event_type == "m.room.encryption")
+            {
+                self.set_encrypted(room_id, true);
+            }
+            if !deliver {
+                continue;
+            }
+            for event in &room.timeline.events {
+                let Some(msg) = self.timeline_message(user_id, room_id, event).await else {
+                    continue;
+                };
+                if tx.send(msg).await.is_err() {
+                    return Ok(false);
+                }
+            }
+        }
+
+        if let Some(e2ee) = &self.e2ee {
+            let mut crypto = self.crypto(e2ee).await?;
+            crypto.since = Some(sync.next_batch.clone());
+            if let Err(e) = crypto.save() {
+                tracing::warn!("Matrix: failed to save crypto store: {e}");
+            }
+        }
+        Ok(true)
+    }
+
+    /// The text message in `event`, decrypted if needed, when the sender is
+    /// allowed in the room.
+    async fn timeline_message(
+        &self,
+        user_id: &str,
+        room_id: &str,
+        event: &TimelineEvent,
+    ) -> Option<ChannelMessage> {
+        // Skip our own messages
+        if event.sender == user_id {
+            return None;
+        }
+
+        if !self.is_user_allowed_in(room_id, &event.sender) {
+            return None;
+        }
+
+        let decrypted_content: EventContent;
+        let (event_type, content) = if event.event_type == "m.room.encrypted" {
+            let Some(e2ee) = &self.e2ee else {
+                tracing::warn!("Matrix: encrypted message in {room_id}, but e2ee is disabled");
+                return None;
+            };
+            let decrypted = match self.decrypt(e2ee, room_id, event).await {
+                Ok(d) => d,
+                Err(e) => {
+                    tracing::warn!("Matrix: cannot decrypt message in {room_id}: {e}");
+                    return None;
+                }
+            };
+            decrypted_content = serde_json::from_value(decrypted.content).ok()?;
+            (decrypted.event_type, &decrypted_content)
+        } else {
+            (event.event_type.clone(), &event.content)
+        };
+
+        if event_type != "m.room.message" {
+            return None;
+        }
+        let msgtype = content.msgtype.as_deref()?;
+        let (body, attachments) = if msgtype == "m.text" {
+            (content.body.clone()?, Vec::new())
+        } else if MEDIA_MSGTYPES.contains(&msgtype) {
+            // `body` is the file name, or a caption when `filename` is set
+            let filename = content
+                .filename
+                .clone()
+                .or_else(|| content.body.clone())
+                .unwrap_or_else(|| "file".into());
+            let mut caption = match (&content.filename, &content.body) {
+                (Some(name), Some(body)) if name != body => body.clone(),
+                _ => String::new(),
+            };
+            let mut attachments = Vec::new();
+            match self.download_media(content, &filename).await {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => inbox::note_skipped("matrix", &mut caption, &filename, &e),
+            }
+            (caption, attachments)
+        } else {
+            return None;
+        };
+
+        Some(ChannelMessage {
+            id: format!("mx_{}", chrono::Utc::now().timestamp_millis()),
+            sender: room_id.to_string(),
+            author: event.sender.clone(),
+            content: body,
+            channel: "matrix".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+        })
+    }
+
+    /// Download the file of a media message into the inbox, decrypting it
+    /// if it came from an encrypted room.
+    async fn download_media(
+        &self,
+        content: &EventContent,
+        filename: &str,
+    ) -> anyhow::Result<InboundAttachment> {
+        let inbox = self
+            .inbox
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("attachments are disabled"))?;
+        let mxc = content
+            .file
+            .as_ref()
+            .and_then(|f| f["url"].as_str())
+            .or(content.url.as_deref())
+            .context("media message has no url")?;
+        let media = mxc
+            .strip_prefix("mxc://")
+            .filter(|m| m.contains('/'))
+            .with_context(|| format!("invalid media uri {mxc}"))?;
+        let info = content.info.as_ref();
+        let file = FileInfo::new(
+            filename,
+            info.and_then(|i| i["mimetype"].as_str())
+                .unwrap_or("application/octet-stream"),
+            info.and_then(|i| i["size"].as_u64()),
+        );
+
+        // Authenticated media first, then the legacy endpoint for older servers
+        let request = |path: &str| {
+            self.client
+                .get(format!(
+                    "{}/_matrix/{path}/download/{media}",
+                    self.homeserver
+                ))
+                .bearer_auth(&self.access_token)
+        };
+        let data = match inbox.fetch(&file, request("client/v1/media")).await {
+            Ok(data) => data,
+            Err(e) => inbox
+                .fetch(&file, request("media/v3"))
+                .await
+                .map_err(|_| e)?,
+        };
+        let data = match &content.file {
+            Some(encrypted) => crypto::decrypt_attachment(&data, encrypted)?,
+            None => data,
+        };
+        inbox.save("matrix", &file, &data).await
+    }
+
+    async fn join_invites(&self, user_id: &str, invites: &HashMap<String, InvitedRoom>) {
+        for (room_id, room) in invites {
+            let membership = room.invite_state.events.iter().find(|e| {
+                e.event_type == "m.room.member"
+                    && e.state_key.as_deref() == Some(user_id)
+                    && e.content.membership.as_deref() == Some("invite")
+            });
+            match membership {
+                Some(invite) if self.is_user_allowed_in(room_id, &invite.sender) => {
+                    let path = format!("/join/{}", encode(room_id));
+                    match self.request(Method::POST, &path, Some(&json!({}))).await {
+                        Ok(_) => tracing::info!(
+                            "Matrix: joined {room_id} (invited by {})",
+                            invite.sender
+                        ),
+                        Err(e) => tracing::warn!("Matrix: failed to join {room_id}: {e}"),
+                    }
+                }
+                _ => tracing::info!("Matrix: ignoring invite to {room_id}"),
+            }
+        }
+    }
+
+    fn set_encrypted(&self, room_id: &str, encrypted: bool) {
+        self.encrypted_rooms
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(room_id.to_string(), encrypted);
+    }
+
+    async fn is_encrypted(&self, room_id: &str) -> anyhow::Result<bool> {
+        let known = self
+            .encrypted_rooms
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(room_id)
+            .copied();
+        if let Some(encrypted) = known {
+            return Ok(encrypted);
+        }
+
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/state/m.room.encryption/",
+            self.homeserver,
+            encode(room_id)
+        );
+        let resp = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .send()
+            .await?;
+        let encrypted = match resp.status() {
+            s if s.is_success() => true,
+            reqwest::StatusCode::NOT_FOUND => false,
+            _ => {
+                let err = resp.text().await?;
+                anyhow::bail!("Matrix room state lookup failed: {err}");
+            }
+        };
+        self.set_encrypted(room_id, encrypted);
+        Ok(encrypted)
+    }
+
+    // ── End-to-end encryption ───────────────────────────────────
+
+    /// This device's crypto state, loaded and its keys published on first use.
+    async fn crypto<'a>(&'a self, e2ee: &'a E2ee) -> anyhow::Result<MappedMutexGuard<'a, Crypto>> {
+        let mut state = e2ee.state.lock().await;
+        if state.is_none() {
+            let who = self.whoami().await?;
+            let device_id = who
+                .device_id
+                .context("Matrix whoami returned no device_id, which e2ee needs")?;
+            let mut crypto = Crypto::load(
+                &e2ee.store_path,
+                e2ee.secrets.clone(),
+                &who.user_id,
+                &device_id,
+            )?;
+            self.upload_keys(&mut crypto, None).await?;
+            tracing::info!(
+                "Matrix: e2ee device {device_id} has fingerprint {}; verify it from your client",
+                crypto.fingerprint()
+            );
+            *state = Some(crypto);
+        }
+        Ok(MutexGuard::map(state, |s| {
+            s.as_mut().expect("crypto state was just initialized")
+        }))
+    }
+
+    /// Publish device keys and top up one-time keys. `published` is the
+    /// server's one-time key count, when known.
+    async fn upload_keys(&self, crypto: &mut Crypto, published: Option<u64>) -> anyhow::Result<()> {
+        let mut published = published;
+        for _ in 0..2 {
+            let Some(body) = crypto.keys_to_upload(published) else {
+                break;
+            };
+            let resp = self
+                .request(Method::POST, "/keys/upload", Some(&body))
+                .await
+                .context("Key upload rejected; the access token's device may already have keys from another client")?;
+            crypto.mark_keys_published();
+            crypto.save()?;
+            if published.is_some() {
+                break;
+            }
+            published = Some(
+                resp.pointer("/one_time_key_counts/signed_curve25519")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0),
+            );
+        }
+        Ok(())
+    }
+
+    async fn sync_crypto(&self, e2ee: &E2ee, sync: &SyncResponse) -> anyhow::Result<()> {
+        let mut crypto = self.crypto(e2ee).await?;
+        for event in &sync.to_device.events {
+            if event.get("type").and_then(Value::as_str) != Some("m.room.encrypted") {
+                continue;
+            }
+            if let Err(e) = crypto.receive_to_device(event) {
+                tracing::warn!("Matrix: cannot decrypt to-device message: {e}");
+            }
+        }
+        for user_id in &sync.device_lists.changed {
+            crypto.forget_devices(user_id);
+        }
+        if let Some(&count) = sync.device_one_time_keys_count.get("signed_curve25519") {
+            if let Err(e) = self.upload_keys(&mut crypto, Some(count)).await {
+                tracing::warn!("Matrix: one-time key upload failed: {e}");
+            }
+        }
+        Ok(())
+    }
+
+    async fn decrypt(
+        &self,
+        e2ee: &E2ee,
+        room_id: &str,
+        event: &TimelineEvent,
+    ) -> anyhow::Result<DecryptedEvent> {
+        let mut crypto = self.crypto(e2ee).await?;
+        let decrypted = crypto.decrypt_room_event(
+            room_id,
+            &event.sender,
+            &event.event_id,
+            &event.content.encrypted,
+        )?;
+
+        // The room key must have come from a trusted device of the sender
+        let devices = self
+            .devices(e2ee, &mut crypto, std::slice::from_ref(&event.sender))
+            .await?;
+        if !devices
+            .iter()
+            .any(|d| d.curve25519 == decrypted.sender_key && d.ed25519 == decrypted.signing_key)
+        {
+            anyhow::bail!("sent from an unverified device of {}", event.sender);
+        }
+        Ok(decrypted)
+    }
+
+    /// Trusted devices of `users`, other than our own.
+    async fn devices(
+        &self,
+        e2ee: &E2ee,
+        crypto: &mut Crypto,
+        users: &[String],
+    ) -> anyhow::Result<Vec<Device>> {
+        let unknown: Vec<&String> = users
+            .iter()
+            .filter(|u| crypto.cached_devices(u).is_none())
+            .collect();
+        if !unknown.is_empty() {
+            let query: serde_json::Map<String, Value> =
+                unknown.iter().map(|u| ((*u).clone(), json!([]))).collect();
+            let resp = self
+                .request(
+                    Method::POST,
+                    "/keys/query",
+                    Some(&json!({ "device_keys": query })),
+                )
+                .await?;
+            for user_id in unknown {
+                let devices = resp
+                    .get("device_keys")
+                    .and_then(|k| k.get(user_id))
+                    .and_then(Value::as_object)
+                    .map(|devices| {
+                        devices
+                            .iter()
+                            .filter_map(|(id, keys)| Device::from_keys(user_id, id, keys))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                crypto.cache_devices(user_id, devices);
+            }
+        }
+
+        let own = crypto.own_device();
+        Ok(users
+            .iter()
+            .filter_map(|u| crypto.cached_devices(u))
+            .flatten()
+            .filter(|d| **d != own && e2ee.trusts(d))
+            .cloned()
+            .collect())
+    }
+
+    /// Megolm-encrypt `content` for `room_id`, sharing the room key with any
+    /// member device that doesn't have it yet.
+    async fn encrypt_for_room(
+        &self,
+        e2ee: &E2ee,
+        room_id: &str,
+        content: &Value,
+    ) -> anyhow::Result<Value> {
+        let mut crypto = self.crypto(e2ee).await?;
+        let members = self
+            .request(
+                Method::GET,
+                &format!("/rooms/{}/joined_members", encode(room_id)),
+                None,
+            )
+            .await?;
+        let members: Vec<String> = members
+            .get("joined")
+            .and_then(Value::as_object)
+            .map(|joined| joined.keys().cloned().collect())
+            .unwrap_or_default();
+        let devices = self.devices(e2ee, &mut crypto, &members).await?;
+
+        let recipients = crypto.room_key_recipients(room_id, &devices);
+        if !recipients.is_empty() {
+            self.claim_sessions(&mut crypto, &recipients).await?;
+            let messages = crypto.room_key_messages(room_id, &recipients);
+            let path = format!("/sendToDevice/m.room.encrypted/{}", txn_id());
+            if let Err(e) = self
+                .request(Method::PUT, &path, Some(&json!({ "messages": messages })))
+                .await
+            {
+                crypto.discard_outbound(room_id);
+                return Err(e);
+            }
+        }
+
+        let encrypted = crypto.encrypt_room_event(room_id, "m.room.message", content)?;
+        crypto.save()?;
+        Ok(encrypted)
+    }
+
+    /// Open Olm sessions with `devices` we have none with yet.
+    async fn claim_sessions(&self, crypto: &mut Crypto, devices: &[Device]) -> anyhow::Result<()> {
+        let missing = crypto.devices_without_session(devices);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let mut query = serde_json::Map::new();
+        for device in &missing {
+            query
+                .entry(device.user_id.clone())
+                .or_insert_with(|| json!({}))[&device.device_id] = json!("signed_curve25519");
+        }
+        let resp = self
+            .request(
+                Method::POST,
+                "/keys/claim",
+                Some(&json!({ "one_time_keys": query })),
+            )
+            .await?;
+
+        for device in missing {
+            let key = resp
+                .get("one_time_keys")
+                .and_then(|k| k.get(&device.user_id))
+                .and_then(|k| k.get(&device.device_id))
+                .and_then(Value::as_object)
+                .and_then(|keys| keys.values().next());
+            let result = match key {
+                Some(key) => crypto.add_outbound_session(device, key),
+                None => Err(anyhow::anyhow!("no one-time key available")),
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Matrix: no Olm session with device {}: {e}",
+                    device.device_id
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn allowlist_contains(allowed: &[String], sender: &str) -> bool {
+    if allowed.iter().any(|u| u == "*") {
+        return true;
+    }
+    allowed.iter().any(|u| u.eq_ignore_ascii_case(sender))
+}
+
+/// Percent-encode a path segment or query value (room IDs contain `!` and `:`).
+fn encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+fn txn_id() -> String {
+    format!("zc_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[async_trait]
+impl Channel for MatrixChannel {
+    fn name(&self) -> &str {
+        "matrix"
+    }
+
+    async fn send(&self, message: &str, target: &str) -> anyhow::Result<()> {
+        let content = json!({
+            "msgtype": "m.text",
+            "body": message
+        });
+        self.send_content(self.target_room(target), content).await
+    }
+
+    async fn send_rich(&self, message: &OutgoingMessage, target: &str) -> anyhow::Result<()> {
+        let room_id = self.target_room(target);
+        if !message.text.trim().is_empty() {
+            self.send(&message.text, room_id).await?;
+        }
+        if message.attachments.is_empty() {
+            return Ok(());
+        }
+
+        let encrypted = self.e2ee.is_some() && self.is_encrypted(room_id).await?;
+        for attachment in &message.attachments {
+            let content = self.attachment_content(attachment, encrypted).await?;
+            self.send_content(room_id, content).await?;
+        }
+        Ok(())
+    }
+
+    async fn listen(&self, tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let my_user_id = self.whoami().await?.user_id;
+        tracing::info!("Matrix channel listening as {my_user_id}...");
+
+        // Resume from the stored sync token so no room keys are missed; the
+        // first batch only updates state, as before the bot was started
+        let mut since = match &self.e2ee {
+            Some(e2ee) => self.crypto(e2ee).await?.since.clone(),
+            None => None,
+        };
+        let mut deliver = false;
+
+        // Long-poll loop
+        loop {
+            let sync = match self.sync(since.as_deref()).await {
+                Ok(sync) => sync,
+                Err(e) => {
+                    tracing::warn!("Matrix sync error: {e}, retrying...");
+                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                    continue;
+                }
+            };
+            if !self.handle_sync(&my_user_id, &sync, deliver, &tx).await? {
+                return Ok(());
+            }
+            since = Some(sync.next_batch);
+            deliver = true;
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        let url = format!("{}/_matrix/client/v3/account/whoami", self.homeserver);
+        let Ok(resp) = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .send()
+            .await
+        else {
+            return false;
+        };
+
+        resp.status().is_success()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_channel() -> MatrixChannel {
+        MatrixChannel::new(
+            "https://matrix.org".to_string(),
+            "syt_test_token".to_string(),
+            "!room:matrix.org".to_string(),
+            vec!["@user:matrix.org".to_string()],
+        )
+    }
+
+    #[test]
+    fn creates_with_correct_fields() {
+        let ch = make_channel();
+        assert_eq!(ch.homeserver, "https://matrix.org");
+        assert_eq!(ch.access_token, "syt_test_token");
+        assert_eq!(ch.room_id, "!room:matrix.org");
+        assert_eq!(ch.allowed_users.len(), 1);
+    }
+
+    #[test]
+    fn strips_trailing_slash() {
+        let ch = MatrixChannel::new(
+            "https://matrix.org/".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec![],
+        );
+        assert_eq!(ch.homeserver, "https://matrix.org");
+    }
+
+    #[test]
+    fn no_trailing_slash_unchanged() {
+        let ch = MatrixChannel::new(
+            "https://matrix.org".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec![],
+        );
+        assert_eq!(ch.homeserver, "https://matrix.org");
+    }
+
+    #[test]
+    fn multiple_trailing_slashes_strips_one() {
+        let ch = MatrixChannel::new(
+            "https://matrix.org//".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec![],
+        );
+        assert_eq!(ch.homeserver, "https://matrix.org/");
+    }
+
+    #[test]
+    fn wildcard_allows_anyone() {
+        let ch = MatrixChannel::new(
+            "https://m.org".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec!["*".to_string()],
+        );
+        assert!(ch.is_user_allowed("@anyone:matrix.org"));
+        assert!(ch.is_user_allowed("@hacker:evil.org"));
+    }
+
+    #[test]
+    fn specific_user_allowed() {
+        let ch = make_channel();
+        assert!(ch.is_user_allowed("@user:matrix.org"));
+    }
+
+    #[test]
+    fn unknown_user_denied() {
+        let ch = make_channel();
+        assert!(!ch.is_user_allowed("@stranger:matrix.org"));
+        assert!(!ch.is_user_allowed("@evil:hacker.org"));
+    }
+
+    #[test]
+    fn user_case_insensitive() {
+        let ch = MatrixChannel::new(
+            "https://m.org".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec!["@User:Matrix.org".to_string()],
+        );
+        assert!(ch.is_user_allowed("@user:matrix.org"));
+        assert!(ch.is_user_allowed("@USER:MATRIX.ORG"));
+    }
+
+    #[test]
+    fn empty_allowlist_denies_all() {
+        let ch = MatrixChannel::new(
+            "https://m.org".to_string(),
+            "tok".to_string(),
+            "!r:m".to_string(),
+            vec![],
+        );
+        assert!(!ch.is_user_allowed("@anyone:matrix.org"));
+    }
+
+    #[test]
+    fn name_returns_matrix() {
+        let ch = make_channel();
+        assert_eq!(ch.name(), "matrix");
+    }
+
+    #[test]
+    fn sync_response_deserializes_empty() {
+        let json = r#"{"next_batch":"s123","rooms":{"join":{}}}"#;
+        let resp: SyncResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.next_batch, "s123");
+        assert!(resp.rooms.join.is_empty());
+    }
+
+    #[test]
+    fn sync_response_deserializes_with_events() {
+        let json = r#"{
+            "next_batch": "s456",
+            "rooms": {
+                "join": {
+                    "!room:matrix.org": {
+                        "timeline": {
+                            "events": [
+                                {
+                                    "type": "m.room.message",
+                                    "sender": "@user:matrix.org",
+                                    "content": {
+                                        "msgtype": "m.text",
+                                        "body": "Hello!"
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                }
+            }
+        }"#;
+        let resp: SyncResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.next_batch, "s456");
+        let room = resp.rooms.join.get("!room:matrix.org").unwrap();
+        assert_eq!(room.timeline.events.len(), 1);
+        assert_eq!(room.timeline.events[0].sender, "@user:matrix.org");
+        assert_eq!(
+            room.timeline.events[0].content.body.as_deref(),
+            Some("Hello!")
+        );
+        assert_eq!(
+            room.timeline.events[0].content.msgtype.as_deref(),
+            Some("m.text")
+        );
+    }
+
+    #[test]
+    fn sync_response_ignores_non_text_events() {
+        let json = r#"{
+            "next_batch": "s789",
+            "rooms": {
+                "join": {
+                    "!room:m": {
+                        "timeline": {
+                            "events": [
+                                {
+                                    "type": "m.room.member",
+                                    "sender": "@user:m",
+                                    "content": {}
+                                }
+                            ]
+                        }
+                    }
+                }
+            }
+        }"#;
+        let resp: SyncResponse = serde_json::from_str(json).unwrap();
+        let room = resp.rooms.join.get("!room:m").unwrap();
+        assert_eq!(room.timeline.events[0].event_type, "m.room.member");
+        assert!(room.timeline.events[0].content.body.is_none());
+    }
+
+    #[test]
+    fn whoami_response_deserializes() {
+        let json = r#"{"user_id":"@bot:matrix.org"}"#;
+        let resp: WhoAmIResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.user_id, "@bot:matrix.org");
+    }
+
+    #[test]
+    fn event_content_defaults() {
+        let json = r#"{"type":"m.room.message","sender":"@u:m","content":{}}"#;
+        let event: TimelineEvent = serde_json::from_str(json).unwrap();
+        assert!(event.content.body.is_none());
+        assert!(event.content.msgtype.is_none());
+    }
+
+    #[test]
+    fn sync_response_missing_rooms_defaults() {
+        let json = r#"{"next_batch":"s0"}"#;
+        let resp: SyncResponse = serde_json::from_str(json).unwrap();
+        assert!(resp.rooms.join.is_empty());
+    }
+
+    #[test]
+    fn room_allowlists_override_the_global_one() {
+        let ch = make_channel().with_rooms(
+            HashMap::from([("!ops:m".to_string(), vec!["@oncall:m".to_string()])]),
+            true,
+        );
+        assert!(ch.is_user_allowed_in("!other:m", "@user:matrix.org"));
+        assert!(!ch.is_user_allowed_in("!other:m", "@oncall:m"));
+        assert!(ch.is_user_allowed_in("!ops:m", "@oncall:m"));
+        assert!(!ch.is_user_allowed_in("!ops:m", "@user:matrix.org"));
+    }
+
+    #[test]
+    fn room_ids_are_percent_encoded() {
+        assert_eq!(encode("!abc:matrix.org"), "%21abc%3Amatrix.org");
+        assert_eq!(encode("s72_5-x.y~"), "s72_5-x.y~");
+    }
+
+    // ── Mock homeserver ─────────────────────────────────────────
+
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::routing::{get, post, put};
+    use axum::{Json, Router};
+    use std::collections::{HashSet, VecDeque};
+    use std::sync::Mutex;
+
+    /// Just enough of a homeserver for one bot (`@bot:m`, device `BOT`).
+    #[derive(Default)]
+    struct Homeserver {
+        syncs: Mutex<VecDeque<Value>>,
+        device_keys: Mutex<HashMap<String, serde_json::Map<String, Value>>>,
+        one_time_keys: Mutex<HashMap<String, serde_json::Map<String, Value>>>,
+        encrypted_rooms: Mutex<HashSet<String>>,
+        members: Mutex<Vec<String>>,
+        joined: Mutex<Vec<String>>,
+        to_device: Mutex<Vec<Value>>,
+        sent: Mutex<Vec<(String, String, Value)>>,
+        uploads: Mutex<Vec<(String, Vec<u8>)>>,
+    }
+
+    impl Homeserver {
+        /// Store a `/keys/upload` body for `user`'s `device`.
+        fn publish(&self, user: &str, device: &str, body: &Value) -> usize {
+            if let Some(keys) = body.get("device_keys") {
+                self.device_keys
+                    .lock()
+                    .unwrap()
+                    .entry(user.into())
+                    .or_default()
+                    .insert(device.into(), keys.clone());
+            }
+            let mut one_time_keys = self.one_time_keys.lock().unwrap();
+            let stored = one_time_keys.entry(format!("{user}|{device}")).or_default();
+            if let Some(keys) = body.get("one_time_keys").and_then(Value::as_object) {
+                stored.extend(keys.clone());
+            }
+            stored.len()
+        }
+
+        fn claim(&self, user: &str, device: &str) -> Option<(String, Value)> {
+            let mut one_time_keys = self.one_time_keys.lock().unwrap();
+            let stored = one_time_keys.get_mut(&format!("{user}|{device}"))?;
+            let id = stored.keys().next()?.clone();
+            stored.remove(&id).map(|key| (id, key))
+        }
+
+        fn device(&self, user: &str, device: &str) -> Option<Device> {
+            let keys = self.device_keys.lock().unwrap();
+            Device::from_keys(user, device, keys.get(user)?.get(device)?)
+        }
+    }
+
+    type Hs = State<Arc<Homeserver>>;
+
+    /// Login, sync and room membership.
+    fn client_routes() -> Router<Arc<Homeserver>> {
+        Router::new()
+            .route(
+                "/_matrix/client/v3/account/whoami",
+                get(|| async { Json(json!({"user_id": "@bot:m", "device_id": "BOT"})) }),
+            )
+            .route(
+                "/_matrix/client/v3/sync",
+                get(|State(hs): Hs| async move {
+                    for _ in 0..10 {
+                        if let Some(sync) = hs.syncs.lock().unwrap().pop_front() {
+                            return Json(sync);
+                        }
+                        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+                    }
+                    Json(json!({"next_batch": "idle"}))
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/join/:room",
+                post(|State(hs): Hs, Path(room): Path<String>| async move {
+                    hs.joined.lock().unwrap().push(room.clone());
+                    Json(json!({ "room_id": room }))
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/rooms/:room/joined_members",
+                get(|State(hs): Hs| async move {
+                    let joined: serde_json::Map<String, Value> = hs
+                        .members
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .map(|m| (m.clone(), json!({})))
+                        .collect();
+                    Json(json!({ "joined": joined }))
+                }),
+            )
+    }
+
+    /// Device keys and to-device messages for end-to-end encryption.
+    fn key_routes() -> Router<Arc<Homeserver>> {
+        Router::new()
+            .route(
+                "/_matrix/client/v3/keys/upload",
+                post(|State(hs): Hs, Json(body): Json<Value>| async move {
+                    let count = hs.publish("@bot:m", "BOT", &body);
+                    Json(json!({"one_time_key_counts": {"signed_curve25519": count}}))
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/keys/query",
+                post(|State(hs): Hs, Json(body): Json<Value>| async move {
+                    let known = hs.device_keys.lock().unwrap();
+                    let device_keys: serde_json::Map<String, Value> = body["device_keys"]
+                        .as_object()
+                        .unwrap()
+                        .keys()
+                        .map(|user| {
+                            let devices = known.get(user).cloned().unwrap_or_default();
+                            (user.clone(), Value::Object(devices))
+                        })
+                        .collect();
+                    Json(json!({ "device_keys": device_keys }))
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/keys/claim",
+                post(|State(hs): Hs, Json(body): Json<Value>| async move {
+                    let mut claimed = json!({});
+                    for (user, devices) in body["one_time_keys"].as_object().unwrap() {
+                        for device in devices.as_object().unwrap().keys() {
+                            if let Some((id, key)) = hs.claim(user, device) {
+                                claimed[user][device] = json!({ id: key });
+                            }
+                        }
+                    }
+                    Json(json!({ "one_time_keys": claimed }))
+                }),
+            )
+            .route(
+                "/_matrix/client/v3/sendToDevice/:event_type/:txn",
+                put(|State(hs): Hs, Json(body): Json<Value>| async move {
+                    hs.to_device.lock().unwrap().push(body);
+                    Json(json!({}))
+                }),
+            )
+    }
+
+    /// Room events and media.
+    fn room_routes() -> Router<Arc<Homeserver>> {
+        Router::new()
+            .route(
+                "/_matrix/client/v3/rooms/:room/send/:event_type/:txn",
+                put(
+                    |State(hs): Hs,
+                     Path((room, event_type, _txn)): Path<(String, String, String)>,
+                     Json(body): Json<Value>| async move {
+                        hs.sent.lock().unwrap().push((room, event_type, body));
+                        Json(json!({"event_id": "$sent"}))
+                    },
+                ),
+            )
+            .route(
+                "/_matrix/client/v3/rooms/:room/state/m.room.encryption/",
+                get(|State(hs): Hs, Path(room): Path<String>| async move {
+                    if hs.encrypted_rooms.lock().unwrap().contains(&room) {
+                        (
+                            StatusCode::OK,
+                            Json(json!({"algorithm": crypto::MEGOLM_ALGORITHM})),
+                        )
+                    } else {
+                        (
+                            StatusCode::NOT_FOUND,
+                            Json(json!({"errcode": "M_NOT_FOUND"})),
+                        )
+                    }
+                }),
+            )
+            .route(
+                "/_matrix/media/v3/upload",
+                post(
+                    |State(hs): Hs, headers: axum::http::HeaderMap, body: axum::body::Bytes| async move {
+                        let content_type = headers
+                            .get("content-type")
+                            .and_then(|v| v.to_str().ok())
+                            .unwrap_or_default()
+                            .to_string();
+                        let mut uploads = hs.uploads.lock().unwrap();
+                        uploads.push((content_type, body.to_vec()));
+                        Json(json!({ "content_uri": format!("mxc://m/{}", uploads.len()) }))
+                    },
+                ),
+            )
+            .route(
+                "/_matrix/client/v1/media/download/:server/:id",
+                get(
+                    |State(hs): Hs, Path((_, id)): Path<(String, usize)>, headers: axum::http::HeaderMap| async move {
+                        assert_eq!(headers["authorization"], "Bearer tok");
+                        let uploads = hs.uploads.lock().unwrap();
+                        match id.checked_sub(1).and_then(|i| uploads.get(i)) {
+                            Some((_, data)) => Ok(data.clone()),
+                            None => Err(StatusCode::NOT_FOUND),
+                        }
+                    },
+                ),
+            )
+    }
+
+    async fn mock_homeserver(server: Arc<Homeserver>) -> String {
+        let app = client_routes()
+            .merge(key_routes())
+            .merge(room_routes())
+            .with_state(server);
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        base
+    }
+
+    fn text(sender: &str, event_id: &str, body: &str) -> Value {
+        json!({
+            "type": "m.room.message",
+            "sender": sender,
+            "event_id": event_id,
+            "content": {"msgtype": "m.text", "body": body}
+        })
+    }
+
+    fn invite(inviter: &str) -> Value {
+        json!({"invite_state": {"events": [{
+            "type": "m.room.member",
+            "sender": inviter,
+            "state_key": "@bot:m",
+            "content": {"membership": "invite"}
+        }]}})
+    }
+
+    /// Alice's own client, with its keys already on the homeserver.
+    fn alice_client(dir: &std::path::Path, server: &Homeserver) -> Crypto {
+        let mut alice = Crypto::load(
+            &dir.join("alice.json"),
+            SecretStore::new(dir, false),
+            "@alice:m",
+            "ALICE",
+        )
+        .unwrap();
+        server.publish("@alice:m", "ALICE", &alice.keys_to_upload(Some(0)).unwrap());
+        alice.mark_keys_published();
+        alice
+    }
+
+    /// Waits until the device has uploaded its identity and one-time keys,
+    /// then claims one of them.
+    async fn published_keys(server: &Homeserver, user: &str, device: &str) -> (Device, Value) {
+        for _ in 0..100 {
+            let published = server
+                .device(user, device)
+                .and_then(|d| Some((d, server.claim(user, device)?.1)));
+            if let Some(published) = published {
+                return published;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("{user} never published its keys");
+    }
+
+    /// Checks the three replies sent to the encrypted room, the plain room and
+    /// the default room, decrypting the first as Alice.
+    fn check_replies(server: &Homeserver, alice: &mut Crypto) {
+        let to_device = server.to_device.lock().unwrap().pop().unwrap();
+        alice
+            .receive_to_device(&json!({
+                "sender": "@bot:m",
+                "content": to_device["messages"]["@alice:m"]["ALICE"]
+            }))
+            .unwrap();
+        let sent = server.sent.lock().unwrap().clone();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(
+            (sent[0].0.as_str(), sent[0].1.as_str()),
+            ("!enc:m", "m.room.encrypted")
+        );
+        let content: EncryptedContent = serde_json::from_value(sent[0].2.clone()).unwrap();
+        let reply = alice
+            .decrypt_room_event("!enc:m", "@bot:m", "$r", &content)
+            .unwrap();
+        assert_eq!(reply.content["body"], "secret reply");
+        assert_eq!(
+            (sent[1].0.as_str(), sent[1].1.as_str()),
+            ("!plain:m", "m.room.message")
+        );
+        assert_eq!(sent[1].2["body"], "plain reply");
+        assert_eq!(sent[2].0, "!default:m");
+    }
+
+    /// Sends an image to the encrypted room and a file to the plain one, and
+    /// checks what reached the homeserver. Expects three earlier sends.
+    async fn send_attachments_and_check_uploads(
+        channel: &MatrixChannel,
+        server: &Homeserver,
+        alice: &mut Crypto,
+    ) {
+        let photo = Attachment::from_bytes(b"png bytes".to_vec(), "shot.png", "image/png")
+            .with_caption("Login page");
+        let report = Attachment::from_bytes(b"a,b".to_vec(), "report.csv", "text/csv");
+        channel
+            .send_rich(&OutgoingMessage::default().with_attachment(photo), "!enc:m")
+            .await
+            .unwrap();
+        channel
+            .send_rich(
+                &OutgoingMessage::default().with_attachment(report),
+                "!plain:m",
+            )
+            .await
+            .unwrap();
+
+        let uploads = server.uploads.lock().unwrap().clone();
+        assert_eq!(uploads[0].0, "application/octet-stream");
+        assert_ne!(uploads[0].1, b"png bytes");
+        assert_eq!(uploads[1], ("text/csv".to_string(), b"a,b".to_vec()));
+
+        let sent = server.sent.lock().unwrap().clone();
+        assert_eq!(sent[3].1, "m.room.encrypted");
+        let content: EncryptedContent = serde_json::from_value(sent[3].2.clone()).unwrap();
+        let image = alice
+            .decrypt_room_event("!enc:m", "@bot:m", "$i", &content)
+            .unwrap()
+            .content;
+        assert_eq!(image["msgtype"], "m.image");
+        assert_eq!(image["body"], "Login page");
+        assert_eq!(image["filename"], "shot.png");
+        assert_eq!(image["file"]["url"], "mxc://m/1");
+        assert!(image.get("url").is_none());
+        assert_eq!(
+            (sent[4].0.as_str(), sent[4].1.as_str()),
+            ("!plain:m", "m.room.message")
+        );
+        assert_eq!(sent[4].2["msgtype"], "m.file");
+        assert_eq!(sent[4].2["url"], "mxc://m/2");
+        assert_eq!(sent[4].2["info"]["size"], 3);
+    }
+
+    #[tokio::test]
+    async fn serves_encrypted_and_plain_rooms_on_mock_homeserver() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let server = Arc::new(Homeserver::default());
+        server
+            .encrypted_rooms
+            .lock()
+            .unwrap()
+            .insert("!enc:m".into());
+        let base = mock_homeserver(Arc::clone(&server)).await;
+
+        let mut alice = alice_client(tmp.path(), &server);
+
+        let channel = MatrixChannel::new(
+            base,
+            "tok".into(),
+            "!default:m".into(),
+            vec!["@alice:m".into()],
+        )
+        .with_rooms(
+            HashMap::from([(
+                "!plain:m".to_string(),
+                vec!["@alice:m".to_string(), "@carol:m".to_string()],
+            )]),
+            true,
+        )
+        .with_e2ee(
+            tmp.path().join("bot.json"),
+            SecretStore::new(tmp.path(), true),
+            vec![],
+        );
+
+        // The first sync only catches up: invites are handled, old messages are not
+        server.syncs.lock().unwrap().push_back(json!({
+            "next_batch": "s1",
+            "rooms": {
+                "invite": {"!new:m": invite("@alice:m"), "!spam:m": invite("@mallory:m")},
+                "join": {"!plain:m": {"timeline": {"events": [text("@alice:m", "$0", "old")]}}}
+            }
+        }));
+        let (tx, mut rx) = mpsc::channel(8);
+        let listener = tokio::spawn({
+            let channel = channel.clone();
+            async move { channel.listen(tx).await }
+        });
+
+        let (bot, bot_key) = published_keys(&server, "@bot:m", "BOT").await;
+
+        // Alice shares a room key with the bot and writes in the encrypted room
+        alice.room_key_recipients("!enc:m", std::slice::from_ref(&bot));
+        alice.add_outbound_session(&bot, &bot_key).unwrap();
+        let room_keys = alice.room_key_messages("!enc:m", std::slice::from_ref(&bot));
+        let secret = alice
+            .encrypt_room_event(
+                "!enc:m",
+                "m.room.message",
+                &json!({"msgtype": "m.text", "body": "secret hello"}),
+            )
+            .unwrap();
+        server.syncs.lock().unwrap().push_back(json!({
+            "next_batch": "s2",
+            "to_device": {"events": [{
+                "type": "m.room.encrypted",
+                "sender": "@alice:m",
+                "content": room_keys["@bot:m"]["BOT"]
+            }]},
+            "rooms": {"join": {
+                "!enc:m": {"timeline": {"events": [{
+                    "type": "m.room.encrypted",
+                    "sender": "@alice:m",
+                    "event_id": "$1",
+                    "content": secret
+                }]}},
+                "!plain:m": {"timeline": {"events": [
+                    text("@mallory:m", "$2", "ignored"),
+                    text("@carol:m", "$3", "plain hello")
+                ]}}
+            }}
+        }));
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            received.push((msg.sender, msg.content));
+        }
+        received.sort();
+        assert_eq!(
+            received,
+            vec![
+                ("!enc:m".to_string(), "secret hello".to_string()),
+                ("!plain:m".to_string(), "plain hello".to_string()),
+            ]
+        );
+        assert_eq!(*server.joined.lock().unwrap(), vec!["!new:m".to_string()]);
+
+        // Replies go to the target room, encrypted where the room is
+        *server.members.lock().unwrap() = vec!["@alice:m".into(), "@bot:m".into()];
+        channel.send("secret reply", "!enc:m").await.unwrap();
+        channel.send("plain reply", "!plain:m").await.unwrap();
+        channel.send("fallback", "").await.unwrap();
+        listener.abort();
+
+        check_replies(&server, &mut alice);
+        assert!(tmp.path().join("bot.json").exists());
+
+        // Attachments are uploaded, encrypted first in encrypted rooms
+        send_attachments_and_check_uploads(&channel, &server, &mut alice).await;
+    }
+
+    #[tokio::test]
+    async fn media_messages_are_downloaded_and_decrypted_into_the_inbox() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let server = Arc::new(Homeserver::default());
+        let (ciphertext, mut file) = crypto::encrypt_attachment(b"secret scan");
+        file["url"] = json!("mxc://m/2");
+        server.uploads.lock().unwrap().extend([
+            ("image/png".to_string(), b"png bytes".to_vec()),
+            ("application/octet-stream".to_string(), ciphertext),
+        ]);
+        let base = mock_homeserver(Arc::clone(&server)).await;
+        let channel = MatrixChannel::new(base, "tok".into(), "!r:m".into(), vec!["*".into()])
+            .with_inbox(Some(Arc::new(Inbox::new(tmp.path(), 1024))));
+
+        let event = |content: Value| -> TimelineEvent {
+            serde_json::from_value(json!({
+                "type": "m.room.message",
+                "sender": "@alice:m",
+                "content": content
+            }))
+            .unwrap()
+        };
+
+        let photo = channel
+            .timeline_message(
+                "@bot:m",
+                "!r:m",
+                &event(json!({
+                    "msgtype": "m.image",
+                    "body": "the login page",
+                    "filename": "shot.png",
+                    "url": "mxc://m/1",
+                    "info": {"mimetype": "image/png", "size": 9}
+                })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(photo.content, "the login page");
+        let saved = &photo.attachments[0];
+        assert_eq!((saved.kind, saved.filename.as_str()), ("image", "shot.png"));
+        assert_eq!(
+            std::fs::read(tmp.path().join(&saved.path)).unwrap(),
+            b"png bytes"
+        );
+
+        let scan = channel
+            .timeline_message(
+                "@bot:m",
+                "!r:m",
+                &event(json!({
+                    "msgtype": "m.file",
+                    "body": "scan.pdf",
+                    "file": file,
+                    "info": {"mimetype": "application/pdf"}
+                })),
+            )
+            .await
+            .unwrap();
+        assert_eq!(scan.content, "");
+        assert_eq!(
+            std::fs::read(tmp.path().join(&scan.attachments[0].path)).unwrap(),
+            b"secret scan"
+        );
+
+        let missing = channel
+            .timeline_message(
+                "@bot:m",
+                "!r:m",
+                &event(json!({"msgtype": "m.video", "body": "clip.mp4", "url": "mxc://m/9"})),
+            )
+            .await
+            .unwrap();
+        assert!(missing.attachments.is_empty());
+        assert!(missing.content.contains("clip.mp4 was not saved"));
+    }
+}
@@ -1,9 +1,12 @@
 pub mod cli;
 pub mod discord;
+pub mod dispatch;
 pub mod email_channel;
 pub mod imessage;
+pub mod inbox;
 pub mod irc;
 pub mod matrix;
+pub mod session;
 pub mod slack;
 pub mod telegram;
 pub mod traits;
@@ -15,17 +18,26 @@ pub use email_channel::EmailChannel;
 pub use imessage::IMessageChannel;
 pub use irc::IrcChannel;
 pub use matrix::MatrixChannel;
+pub use session::SessionStore;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
 pub use traits::Channel;
 pub use whatsapp::WhatsAppChannel;
 
-use crate::config::Config;
+use crate::agent::{agent_turn, build_context, build_tool_instructions, tool_descriptions};
+use crate::config::{Config, MatrixConfig};
 use crate::identity;
 use crate::memory::{self, Memory};
-use crate::providers::{self, Provider};
+use crate::observability::{self, Observer, ObserverEvent};
+use crate::providers::{self, ChatMessage, ConversationMessage, ImageContent, Provider};
+use crate::runtime;
+use crate::security::approval::{self, ApprovalRoute};
+use crate::security::{SecretStore, SecurityPolicy};
+use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use inbox::Inbox;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -34,7 +46,201 @@ const BOOTSTRAP_MAX_CHARS: usize = 20_000;
 
 const DEFAULT_CHANNEL_INITIAL_BACKOFF_SECS: u64 = 2;
 const DEFAULT_CHANNEL_MAX_BACKOFF_SECS: u64 = 60;
-const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 90;
+/// Budget for one whole agent turn, including tool calls and any time spent
+/// waiting on the operator to approve a command.
+const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 300;
+/// Messages a conversation may have waiting behind the one being answered.
+const MAX_QUEUED_MESSAGES_PER_CONVERSATION: usize = 20;
+const SESSION_PRUNE_INTERVAL_SECS: u64 = 600;
+
+/// Policy and tools a channel's messages are handled with. Each channel gets
+/// its own `SecurityPolicy`, so one busy channel cannot use up another's
+/// hourly action allowance.
+struct ChannelAgent {
+    security: Arc<SecurityPolicy>,
+    tools: Vec<Box<dyn Tool>>,
+}
+
+/// Everything needed to answer a channel message with the agent loop.
+struct ChannelContext {
+    provider: Arc<dyn Provider>,
+    mem: Arc<dyn Memory>,
+    observer: Arc<dyn Observer>,
+    system_prompt: String,
+    model: String,
+    temperature: f64,
+    agents: HashMap<String, ChannelAgent>,
+    sessions: Arc<SessionStore>,
+    /// Channels replies are sent back on, matched by name.
+    channels: Vec<Arc<dyn Channel>>,
+    auto_save: bool,
+}
+
+impl ChannelContext {
+    /// Answer `msg` with one agent turn on its channel's tools and policy,
+    /// continuing the sender's session. Relevant memories are prepended to
+    /// the message, and approval prompts for held commands go back to the
+    /// sender. `/new` and `/reset` start the session over instead.
+    async fn answer(&self, msg: &traits::ChannelMessage) -> Result<String> {
+        let key = session::session_key(msg);
+        if session::is_reset_command(&msg.content) {
+            self.sessions.reset(&key);
+            return Ok("🆕 Started a new conversation.".into());
+        }
+
+        let agent = self
+            .agents
+            .get(&msg.channel)
+            .ok_or_else(|| anyhow::anyhow!("No agent configured for channel {}", msg.channel))?;
+
+        let prompt = msg.prompt_text();
+        let context = build_context(self.mem.as_ref(), &msg.content).await;
+        let enriched = if context.is_empty() {
+            prompt.clone()
+        } else {
+            format!("{context}{prompt}")
+        };
+        let mut history = vec![ConversationMessage::Chat(ChatMessage::system(
+            &self.system_prompt,
+        ))];
+        history.extend(self.sessions.history(&key));
+        let user_index = history.len();
+        let images: Vec<ImageContent> = if self.provider.supports_vision() {
+            msg.attachments
+                .iter()
+                .filter(|a| a.kind == "image")
+                .map(|a| ImageContent::file(agent.security.workspace_dir.join(&a.path)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        history.push(ConversationMessage::Chat(
+            ChatMessage::user(&enriched).with_images(images.clone()),
+        ));
+
+        let reply = ApprovalRoute::new(&msg.channel, &msg.sender)
+            .approved_by(&msg.author)
+            .scope(agent_turn(
+                self.provider.as_ref(),
+                &mut history,
+                &agent.tools,
+                self.observer.as_ref(),
+                &agent.security,
+                &self.model,
+                self.temperature,
+                &mut |_| {},
+            ))
+            .await?;
+
+        // Keep the message as sent; memory context is looked up fresh each turn
+        history[user_index] =
+            ConversationMessage::Chat(ChatMessage::user(&prompt).with_images(images));
+        self.sessions.save(&key, history.split_off(1));
+        Ok(reply)
+    }
+
+    /// Handle one incoming message end to end: log it, auto-save it to
+    /// memory, run the agent and send the reply (or error) back.
+    async fn process(&self, msg: traits::ChannelMessage) {
+        println!(
+            "  💬 [{}] from {}: {}",
+            msg.channel,
+            msg.sender,
+            truncate_with_ellipsis(&msg.content, 80)
+        );
+        self.observer.record_event(&ObserverEvent::ChannelMessage {
+            channel: msg.channel.clone(),
+            direction: "inbound".into(),
+        });
+
+        // Auto-save to memory
+        if self.auto_save {
+            let session = session::session_key(&msg);
+            let _ = self
+                .mem
+                .store_with_metadata(
+                    &format!("{}_{}", msg.channel, msg.sender),
+                    &msg.content,
+                    crate::memory::MemoryCategory::Conversation,
+                    &memory::MemoryMetadata::with_source(session.clone()).with_session(session),
+                )
+                .await;
+        }
+
+        // Run the full tool loop (identity + soul + tools in the system prompt)
+        println!("  ⏳ Processing message...");
+        let started_at = Instant::now();
+
+        let llm_result = tokio::time::timeout(
+            Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
+            self.answer(&msg),
+        )
+        .await;
+
+        match llm_result {
+            Ok(Ok(response)) => {
+                println!(
+                    "  🤖 Reply ({}ms): {}",
+                    started_at.elapsed().as_millis(),
+                    truncate_with_ellipsis(&response, 80)
+                );
+                // Find the channel that sent this message and reply
+                for ch in &self.channels {
+                    if ch.name() == msg.channel {
+                        match ch.send(&response, &msg.sender).await {
+                            Ok(()) => self.observer.record_event(&ObserverEvent::ChannelMessage {
+                                channel: msg.channel.clone(),
+                                direction: "outbound".into(),
+                            }),
+                            Err(e) => eprintln!("  ❌ Failed to reply on {}: {e}", ch.name()),
+                        }
+                        break;
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                eprintln!(
+                    "  ❌ LLM error after {}ms: {e}",
+                    started_at.elapsed().as_millis()
+                );
+                self.observer.record_event(&ObserverEvent::Error {
+                    component: "channels".into(),
+                    message: e.to_string(),
+                });
+                for ch in &self.channels {
+                    if ch.name() == msg.channel {
+                        let _ = ch.send(&format!("⚠️ Error: {e}"), &msg.sender).await;
+                        break;
+                    }
+                }
+            }
+            Err(_) => {
+                let timeout_msg =
+                    format!("LLM response timed out after {CHANNEL_MESSAGE_TIMEOUT_SECS}s");
+                eprintln!(
+                    "  ❌ {} (elapsed: {}ms)",
+                    timeout_msg,
+                    started_at.elapsed().as_millis()
+                );
+                self.observer.record_event(&ObserverEvent::Error {
+                    component: "channels".into(),
+                    message: timeout_msg,
+                });
+                for ch in &self.channels {
+                    if ch.name() == msg.channel {
+                        let _ = ch
+                            .send(
+                                "⚠️ Request timed out while waiting for the model. Please try again.",
+                                &msg.sender,
+                            )
+                            .await;
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
 
 fn spawn_supervised_listener(
     ch: Arc<dyn Channel>,
@@ -369,11 +575,14 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     if let Some(ref sl) = config.channels_config.slack {
         channels.push((
             "Slack",
-            Arc::new(SlackChannel::new(
-                sl.bot_token.clone(),
-                sl.channel_id.clone(),
-                sl.allowed_users.clone(),
-            )),
+            Arc::new(
+                SlackChannel::new(
+                    sl.bot_token.clone(),
+                    sl.channel_id.clone(),
+                    sl.allowed_users.clone(),
+                )
+                .with_app_token(sl.app_token.clone()),
+            ),
         ));
     }
 
@@ -387,12 +596,7 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     if let Some(ref mx) = config.channels_config.matrix {
         channels.push((
             "Matrix",
-            Arc::new(MatrixChannel::new(
-                mx.homeserver.clone(),
-                mx.access_token.clone(),
-                mx.room_id.clone(),
-                mx.allowed_users.clone(),
-            )),
+            Arc::new(matrix_channel(&config, mx)),
         ));
     }
 
@@ -471,6 +675,117 @@ pub async fn doctor_channels(config: Config) -> Result<()> {
     Ok(())
 }
 
+fn matrix_channel(config: &Config, mx: &MatrixConfig) -> MatrixChannel {
+    let channel = MatrixChannel::new(
+        mx.homeserver.clone(),
+        mx.access_token.clone(),
+        mx.room_id.clone(),
+        mx.allowed_users.clone(),
+    )
+    .with_rooms(mx.room_allowed_users.clone(), mx.auto_join);
+    if !mx.e2ee {
+        return channel;
+    }
+    let secrets_dir = config
+        .config_path
+        .parent()
+        .unwrap_or(&config.workspace_dir);
+    channel.with_e2ee(
+        config.workspace_dir.join("matrix").join("crypto.json"),
+        SecretStore::new(secrets_dir, config.secrets.encrypt),
+        mx.verified_devices.clone(),
+    )
+}
+
+/// Every channel with a configuration section, ready to listen or send.
+pub fn configured_channels(config: &Config) -> Vec<Arc<dyn Channel>> {
+    let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
+    let inbox = Inbox::from_config(config);
+
+    if let Some(ref tg) = config.channels_config.telegram {
+        channels.push(Arc::new(
+            TelegramChannel::new(tg.bot_token.clone(), tg.allowed_users.clone())
+                .with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref dc) = config.channels_config.discord {
+        channels.push(Arc::new(
+            DiscordChannel::new(
+                dc.bot_token.clone(),
+                dc.guild_id.clone(),
+                dc.allowed_users.clone(),
+            )
+            .with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref sl) = config.channels_config.slack {
+        channels.push(Arc::new(
+            SlackChannel::new(
+                sl.bot_token.clone(),
+                sl.channel_id.clone(),
+                sl.allowed_users.clone(),
+            )
+            .with_app_token(sl.app_token.clone())
+            .with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref im) = config.channels_config.imessage {
+        channels.push(Arc::new(IMessageChannel::new(im.allowed_contacts.clone())));
+    }
+
+    if let Some(ref mx) = config.channels_config.matrix {
+        channels.push(Arc::new(
+            matrix_channel(config, mx).with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref wa) = config.channels_config.whatsapp {
+        channels.push(Arc::new(
+            WhatsAppChannel::new(
+                wa.access_token.clone(),
+                wa.phone_number_id.clone(),
+                wa.verify_token.clone(),
+                wa.allowed_numbers.clone(),
+            )
+            .with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref email_cfg) = config.channels_config.email {
+        channels.push(Arc::new(
+            EmailChannel::new(email_cfg.clone()).with_inbox(inbox.clone()),
+        ));
+    }
+
+    if let Some(ref irc) = config.channels_config.irc {
+        channels.push(Arc::new(IrcChannel::new(
+            irc.server.clone(),
+            irc.port,
+            irc.nickname.clone(),
+            irc.username.clone(),
+            irc.channels.clone(),
+            irc.allowed_users.clone(),
+            irc.server_password.clone(),
+            irc.nickserv_password.clone(),
+            irc.sasl_password.clone(),
+            irc.verify_tls.unwrap_or(true),
+        )));
+    }
+
+    channels
+}
+
+/// The configured channel called `name`, e.g. for delivering background
+/// task results.
+pub fn configured_channel(config: &Config, name: &str) -> Option<Arc<dyn Channel>> {
+    configured_channels(config)
+        .into_iter()
+        .find(|ch| ch.name() == name)
+}
+
 /// Start all configured channels and route messages to the agent
 #[allow(clippy::too_many_lines)]
 pub async fn start_channels(config: Config) -> Result<()> {
@@ -496,47 +811,17 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.workspace_dir,
         config.api_key.as_deref(),
     )?);
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let runtime: Arc<dyn runtime::RuntimeAdapter> =
+        Arc::from(runtime::create_runtime(&config.runtime)?);
 
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
     let skills = crate::skills::load_skills(&workspace);
 
-    // Collect tool descriptions for the prompt
-    let mut tool_descs: Vec<(&str, &str)> = vec![
-        (
-            "shell",
-            "Execute terminal commands. Use when: running local checks, build/test commands, diagnostics. Don't use when: a safer dedicated tool exists, or command is destructive without approval.",
-        ),
-        (
-            "file_read",
-            "Read file contents. Use when: inspecting project files, configs, logs. Don't use when: a targeted search is enough.",
-        ),
-        (
-            "file_write",
-            "Write file contents. Use when: applying focused edits, scaffolding files, updating docs/code. Don't use when: side effects are unclear or file ownership is uncertain.",
-        ),
-        (
-            "memory_store",
-            "Save to memory. Use when: preserving durable preferences, decisions, key context. Don't use when: information is transient/noisy/sensitive without need.",
-        ),
-        (
-            "memory_recall",
-            "Search memory. Use when: retrieving prior decisions, user preferences, historical context. Don't use when: answer is already in current context.",
-        ),
-        (
-            "memory_forget",
-            "Delete a memory entry. Use when: memory is incorrect/stale or explicitly requested for removal. Don't use when: impact is uncertain.",
-        ),
-    ];
-
-    if config.browser.enabled {
-        tool_descs.push((
-            "browser_open",
-            "Open approved HTTPS URLs in Brave Browser (allowlist-only, no scraping)",
-        ));
-    }
-
-    let system_prompt = build_system_prompt(
+    let tool_descs = tool_descriptions(&config);
+    let mut system_prompt = build_system_prompt(
         &workspace,
         &model,
         &tool_descs,
@@ -555,77 +840,48 @@ pub async fn start_channels(config: Config) -> Result<()> {
         );
     }
 
-    // Collect active channels
-    let mut channels: Vec<Arc<dyn Channel>> = Vec::new();
-
-    if let Some(ref tg) = config.channels_config.telegram {
-        channels.push(Arc::new(TelegramChannel::new(
-            tg.bot_token.clone(),
-            tg.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref dc) = config.channels_config.discord {
-        channels.push(Arc::new(DiscordChannel::new(
-            dc.bot_token.clone(),
-            dc.guild_id.clone(),
-            dc.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref sl) = config.channels_config.slack {
-        channels.push(Arc::new(SlackChannel::new(
-            sl.bot_token.clone(),
-            sl.channel_id.clone(),
-            sl.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref im) = config.channels_config.imessage {
-        channels.push(Arc::new(IMessageChannel::new(im.allowed_contacts.clone())));
-    }
-
-    if let Some(ref mx) = config.channels_config.matrix {
-        channels.push(Arc::new(MatrixChannel::new(
-            mx.homeserver.clone(),
-            mx.access_token.clone(),
-            mx.room_id.clone(),
-            mx.allowed_users.clone(),
-        )));
-    }
-
-    if let Some(ref wa) = config.channels_config.whatsapp {
-        channels.push(Arc::new(WhatsAppChannel::new(
-            wa.access_token.clone(),
-            wa.phone_number_id.clone(),
-            wa.verify_token.clone(),
-            wa.allowed_numbers.clone(),
-        )));
+    let channels = configured_channels(&config);
+    if channels.is_empty() {
+        println!("No channels configured. Run `viziclaw onboard` to set up channels.");
+        return Ok(());
     }
 
-    if let Some(ref email_cfg) = config.channels_config.email {
-        channels.push(Arc::new(EmailChannel::new(email_cfg.clone())));
-    }
+    let shared_config = Arc::new(config.clone());
+    let agents: HashMap<String, ChannelAgent> = channels
+        .iter()
+        .map(|ch| {
+            let security = Arc::new(SecurityPolicy::from_config(
+                &config.autonomy,
+                &config.workspace_dir,
+            ));
+            let tools =
+                tools::agent_tools(&shared_config, &security, &runtime, mem.clone(), &skills);
+            (ch.name().to_string(), ChannelAgent { security, tools })
+        })
+        .collect();
 
-    if let Some(ref irc) = config.channels_config.irc {
-        channels.push(Arc::new(IrcChannel::new(
-            irc.server.clone(),
-            irc.port,
-            irc.nickname.clone(),
-            irc.username.clone(),
-            irc.channels.clone(),
-            irc.allowed_users.clone(),
-            irc.server_password.clone(),
-            irc.nickserv_password.clone(),
-            irc.sasl_password.clone(),
-            irc.verify_tls.unwrap_or(true),
-        )));
+    // Providers without native function calling get the XML protocol instead
+    if !provider.supports_native_tools() {
+        if let Some(agent) = agents.values().next() {
+            system_prompt.push_str(&build_tool_instructions(&agent.tools));
+        }
     }
 
-    if channels.is_empty() {
-        println!("No channels configured. Run `viziclaw onboard` to set up channels.");
-        return Ok(());
-    }
+    let ctx = ChannelContext {
+        provider,
+        mem: mem.clone(),
+        observer,
+        system_prompt,
+        model: model.clone(),
+        temperature,
+        agents,
+        sessions: Arc::new(SessionStore::open(
+            &config.workspace_dir,
+            config.channels_config.session_idle_minutes,
+        )),
+        channels: channels.clone(),
+        auto_save: config.memory.auto_save,
+    };
 
     println!("🦀 ViziClaw Channel Server");
     println!("  🤖 Model:    {model}");
@@ -642,6 +898,10 @@ pub async fn start_channels(config: Config) -> Result<()> {
             .collect::<Vec<_>>()
             .join(", ")
     );
+    println!(
+        "  ⚙️  Workers:  {} concurrent message(s)",
+        config.channels_config.max_concurrent_messages.max(1)
+    );
     println!();
     println!("  Listening for messages... (Ctrl+C to stop)");
     println!();
@@ -658,7 +918,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .max(DEFAULT_CHANNEL_MAX_BACKOFF_SECS);
 
     // Single message bus — all channels send messages here
-    let (tx, mut rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
+    let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(100);
 
     // Spawn a listener for each channel
     let mut handles = Vec::new();
@@ -672,94 +932,59 @@ pub async fn start_channels(config: Config) -> Result<()> {
     }
     drop(tx); // Drop our copy so rx closes when all channels stop
 
-    // Process incoming messages — call the LLM and reply
-    while let Some(msg) = rx.recv().await {
-        println!(
-            "  💬 [{}] from {}: {}",
-            msg.channel,
-            msg.sender,
-            truncate_with_ellipsis(&msg.content, 80)
-        );
-
-        // Auto-save to memory
-        if config.memory.auto_save {
-            let _ = mem
-                .store(
-                    &format!("{}_{}", msg.channel, msg.sender),
-                    &msg.content,
-                    crate::memory::MemoryCategory::Conversation,
-                )
-                .await;
-        }
-
-        // Call the LLM with system prompt (identity + soul + tools)
-        println!("  ⏳ Processing message...");
-        let started_at = Instant::now();
-
-        let llm_result = tokio::time::timeout(
-            Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
-            provider.chat_with_system(Some(&system_prompt), &msg.content, &model, temperature),
-        )
-        .await;
+    // Approval prompts go out on the channel a turn came from, and replies
+    // are pulled off the bus before they reach the LLM.
+    for ch in &channels {
+        approval::broker().register_channel(ch.clone());
+    }
+    let rx = approval::intercept_replies(rx);
 
-        match llm_result {
-            Ok(Ok(response)) => {
-                println!(
-                    "  🤖 Reply ({}ms): {}",
-                    started_at.elapsed().as_millis(),
-                    truncate_with_ellipsis(&response, 80)
-                );
-                // Find the channel that sent this message and reply
-                for ch in &channels {
-                    if ch.name() == msg.channel {
-                        if let Err(e) = ch.send(&response, &msg.sender).await {
-                            eprintln!("  ❌ Failed to reply on {}: {e}", ch.name());
-                        }
-                        break;
-                    }
-                }
-            }
-            Ok(Err(e)) => {
-                eprintln!(
-                    "  ❌ LLM error after {}ms: {e}",
-                    started_at.elapsed().as_millis()
-                );
-                for ch in &channels {
-                    if ch.name() == msg.channel {
-                        let _ = ch.send(&format!("⚠️ Error: {e}"), &msg.sender).await;
-                        break;
-                    }
-                }
-            }
-            Err(_) => {
-                let timeout_msg = format!(
-                    "LLM response timed out after {}s",
-                    CHANNEL_MESSAGE_TIMEOUT_SECS
-                );
-                eprintln!(
-                    "  ❌ {} (elapsed: {}ms)",
-                    timeout_msg,
-                    started_at.elapsed().as_millis()
-                );
-                for ch in &channels {
-                    if ch.name() == msg.channel {
-                        let _ = ch
-                            .send(
-                                "⚠️ Request timed out while waiting for the model. Please try again.",
-                                &msg.sender,
-                            )
-                            .await;
-                        break;
-                    }
-                }
+    // Idle sessions also expire on their next message; this clears the rest
+    let sessions = Arc::clone(&ctx.sessions);
+    let prune_handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SESSION_PRUNE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let pruned = sessions.prune_expired();
+            if pruned > 0 {
+                tracing::info!("Expired {pruned} idle channel session(s)");
             }
         }
-    }
+    });
+
+    // Process incoming messages — call the LLM and reply, several
+    // conversations at a time
+    let max_concurrent = config.channels_config.max_concurrent_messages;
+    let observer = Arc::clone(&ctx.observer);
+    let ctx = Arc::new(ctx);
+    let notices = Arc::clone(&ctx);
+    dispatch::dispatch(
+        rx,
+        max_concurrent,
+        MAX_QUEUED_MESSAGES_PER_CONVERSATION,
+        observer,
+        move |msg| {
+            let ctx = Arc::clone(&ctx);
+            async move { ctx.process(msg).await }
+        },
+        move |msg| {
+            let Some(ch) = notices.channels.iter().find(|ch| ch.name() == msg.channel) else {
+                return;
+            };
+            let ch = Arc::clone(ch);
+            tokio::spawn(async move {
+                let notice = "⚠️ Too many messages are waiting for a reply; this one was dropped. Please resend it once I've caught up.";
+                let _ = ch.send(notice, &msg.sender).await;
+            });
+        },
+    )
+    .await;
 
     // Wait for all channel tasks
     for h in handles {
         let _ = h.await;
     }
+    prune_handle.abort();
 
     Ok(())
 }
@@ -1213,4 +1438,232 @@ mod tests {
             .contains("listen boom"));
         assert!(calls.load(Ordering::SeqCst) >= 1);
     }
+
+    /// Asks for the `probe` tool once, then answers with what it saw.
+    struct ToolCallingProvider {
+        seen: std::sync::Mutex<Vec<Vec<ConversationMessage>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ToolCallingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("channels use the tool loop")
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        async fn chat_with_tools(
+            &self,
+            messages: &[ConversationMessage],
+            _tools: &[crate::tools::ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<crate::providers::traits::ChatResponse> {
+            let mut seen = self.seen.lock().unwrap();
+            seen.push(messages.to_vec());
+            let tool_result = messages.iter().find_map(|m| match m {
+                ConversationMessage::ToolResult(r) => Some(r.content.clone()),
+                _ => None,
+            });
+            Ok(match tool_result {
+                Some(output) => crate::providers::traits::ChatResponse {
+                    text: Some(format!("probe said {output}")),
+                    tool_calls: vec![],
+                    usage: None,
+                },
+                None => crate::providers::traits::ChatResponse {
+                    text: None,
+                    tool_calls: vec![crate::providers::ToolCall {
+                        id: "call_1".into(),
+                        name: "probe".into(),
+                        arguments: "{}".into(),
+                    }],
+                    usage: None,
+                },
+            })
+        }
+    }
+
+    struct ProbeTool;
+
+    #[async_trait::async_trait]
+    impl Tool for ProbeTool {
+        fn name(&self) -> &str {
+            "probe"
+        }
+
+        fn description(&self) -> &str {
+            "Report that tools run from channels"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object", "properties": {}})
+        }
+
+        async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<tools::ToolResult> {
+            Ok(tools::ToolResult {
+                success: true,
+                output: "hello from telegram".into(),
+                error: None,
+                images: Vec::new(),
+            })
+        }
+    }
+
+    fn channel_message(channel: &str, content: &str) -> traits::ChannelMessage {
+        traits::ChannelMessage {
+            id: "m1".into(),
+            sender: "42".into(),
+            author: "42".into(),
+            content: content.into(),
+            channel: channel.into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_messages_run_the_tool_loop_with_memory_context() {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(crate::memory::MarkdownMemory::new(tmp.path()));
+        mem.store(
+            "deploy_day",
+            "We deploy on Tuesdays",
+            crate::memory::MemoryCategory::Core,
+        )
+        .await
+        .unwrap();
+
+        let provider = Arc::new(ToolCallingProvider {
+            seen: std::sync::Mutex::new(Vec::new()),
+        });
+        let mut agents = HashMap::new();
+        agents.insert(
+            "telegram".to_string(),
+            ChannelAgent {
+                security: Arc::new(SecurityPolicy::default()),
+                tools: vec![Box::new(ProbeTool)],
+            },
+        );
+        let ctx = ChannelContext {
+            provider: provider.clone(),
+            mem,
+            observer: Arc::new(crate::observability::NoopObserver),
+            system_prompt: "system".into(),
+            model: "test-model".into(),
+            temperature: 0.0,
+            agents,
+            sessions: Arc::new(SessionStore::in_memory(60)),
+            channels: vec![],
+            auto_save: false,
+        };
+
+        let reply = ctx
+            .answer(&channel_message("telegram", "when do we deploy"))
+            .await
+            .unwrap();
+        assert_eq!(reply, "probe said hello from telegram");
+
+        let seen = provider.seen.lock().unwrap().clone();
+        assert_eq!(seen.len(), 2);
+        let ConversationMessage::Chat(user) = &seen[0][1] else {
+            panic!("expected the user message after the system prompt");
+        };
+        assert!(user.content.contains("[Memory context]"));
+        assert!(user.content.contains("Tuesdays"));
+        assert!(user.content.ends_with("when do we deploy"));
+
+        let err = ctx
+            .answer(&channel_message("slack", "hi"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("No agent configured"));
+    }
+
+    /// Replies with how many user messages the conversation holds so far.
+    struct TurnCountingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for TurnCountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("channels use the tool loop")
+        }
+
+        async fn chat_with_tools(
+            &self,
+            messages: &[ConversationMessage],
+            _tools: &[crate::tools::ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<crate::providers::traits::ChatResponse> {
+            let turns = messages
+                .iter()
+                .filter(|m| matches!(m, ConversationMessage::Chat(c) if c.role == "user"))
+                .count();
+            Ok(crate::providers::traits::ChatResponse {
+                text: Some(format!("turn {turns}")),
+                tool_calls: vec![],
+                usage: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn channel_sessions_carry_history_until_reset() {
+        let tmp = TempDir::new().unwrap();
+        let mut agents = HashMap::new();
+        agents.insert(
+            "matrix".to_string(),
+            ChannelAgent {
+                security: Arc::new(SecurityPolicy::default()),
+                tools: vec![],
+            },
+        );
+        let ctx = ChannelContext {
+            provider: Arc::new(TurnCountingProvider),
+            mem: Arc::new(crate::memory::MarkdownMemory::new(tmp.path())),
+            observer: Arc::new(crate::observability::NoopObserver),
+            system_prompt: "system".into(),
+            model: "test-model".into(),
+            temperature: 0.0,
+            agents,
+            sessions: Arc::new(SessionStore::open(tmp.path(), 60)),
+            channels: vec![],
+            auto_save: false,
+        };
+
+        let first = channel_message("matrix", "hello");
+        assert_eq!(ctx.answer(&first).await.unwrap(), "turn 1");
+        assert_eq!(ctx.answer(&first).await.unwrap(), "turn 2");
+
+        // Other senders get their own conversation
+        let mut other = channel_message("matrix", "hi");
+        other.sender = "99".into();
+        assert_eq!(ctx.answer(&other).await.unwrap(), "turn 1");
+
+        let saved = SessionStore::open(tmp.path(), 60).history("matrix:42");
+        assert_eq!(saved.len(), 4);
+        assert!(matches!(&saved[0], ConversationMessage::Chat(m) if m.content == "hello"));
+
+        let reset = ctx
+            .answer(&channel_message("matrix", "/new"))
+            .await
+            .unwrap();
+        assert!(reset.contains("new conversation"));
+        assert_eq!(ctx.answer(&first).await.unwrap(), "turn 1");
+    }
 }
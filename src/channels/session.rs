@@ -0,0 +1,273 @@
+use super::traits::ChannelMessage;
+use crate::agent::trim_history;
+use crate::providers::ConversationMessage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Conversation key for a channel message: one session per sender (chat,
+/// room or thread id, depending on the channel) on each channel.
+pub fn session_key(msg: &ChannelMessage) -> String {
+    format!("{}:{}", msg.channel, msg.sender)
+}
+
+/// Whether `content` asks to start over (`/new` or `/reset`).
+pub fn is_reset_command(content: &str) -> bool {
+    matches!(content.trim(), "/new" | "/reset")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Session {
+    key: String,
+    updated_at: DateTime<Utc>,
+    /// Conversation without the system prompt, which is rebuilt every turn.
+    history: Vec<ConversationMessage>,
+}
+
+/// Per-conversation message history for channels. Each session is persisted
+/// as `<workspace>/sessions/<hash>.json` so conversations survive restarts;
+/// sessions idle for longer than the timeout are dropped.
+pub struct SessionStore {
+    dir: Option<PathBuf>,
+    idle_timeout: Duration,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SessionStore {
+    /// Store backed by `<workspace>/sessions/`, loading any sessions that
+    /// have not expired yet.
+    pub fn open(workspace_dir: &Path, idle_minutes: u64) -> Self {
+        let store = Self {
+            dir: Some(workspace_dir.join("sessions")),
+            idle_timeout: idle_duration(idle_minutes),
+            sessions: Mutex::new(HashMap::new()),
+        };
+        store.load();
+        store
+    }
+
+    /// Store that keeps sessions in memory only.
+    #[cfg(test)]
+    pub fn in_memory(idle_minutes: u64) -> Self {
+        Self {
+            dir: None,
+            idle_timeout: idle_duration(idle_minutes),
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// History for `key`, or empty when there is no live session.
+    pub fn history(&self, key: &str) -> Vec<ConversationMessage> {
+        let mut sessions = self.lock();
+        let Some(session) = sessions.get(key) else {
+            return Vec::new();
+        };
+        if self.is_expired(session, Utc::now()) {
+            sessions.remove(key);
+            drop(sessions);
+            self.delete_file(key);
+            return Vec::new();
+        }
+        session.history.clone()
+    }
+
+    /// Replace the history for `key`, trimmed to the agent's history limit.
+    pub fn save(&self, key: &str, mut history: Vec<ConversationMessage>) {
+        trim_history(&mut history);
+        let session = Session {
+            key: key.to_string(),
+            updated_at: Utc::now(),
+            history,
+        };
+        if let Err(e) = self.write_file(&session) {
+            tracing::warn!("Failed to persist session {key}: {e}");
+        }
+        self.lock().insert(key.to_string(), session);
+    }
+
+    /// Forget the session for `key`. Returns whether one existed.
+    pub fn reset(&self, key: &str) -> bool {
+        let existed = self.lock().remove(key).is_some();
+        self.delete_file(key);
+        existed
+    }
+
+    /// Drop every idle session and return how many were removed.
+    pub fn prune_expired(&self) -> usize {
+        let now = Utc::now();
+        let expired: Vec<String> = self
+            .lock()
+            .values()
+            .filter(|s| self.is_expired(s, now))
+            .map(|s| s.key.clone())
+            .collect();
+        for key in &expired {
+            self.lock().remove(key);
+            self.delete_file(key);
+        }
+        expired.len()
+    }
+
+    fn is_expired(&self, session: &Session, now: DateTime<Utc>) -> bool {
+        now - session.updated_at > self.idle_timeout
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Session>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn load(&self) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        let now = Utc::now();
+        let mut sessions = self.lock();
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let session: Session = match std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|raw| serde_json::from_str(&raw).map_err(anyhow::Error::from))
+            {
+                Ok(session) => session,
+                Err(e) => {
+                    tracing::warn!("Ignoring unreadable session {}: {e}", path.display());
+                    continue;
+                }
+            };
+            if self.is_expired(&session, now) {
+                let _ = std::fs::remove_file(&path);
+            } else {
+                sessions.insert(session.key.clone(), session);
+            }
+        }
+    }
+
+    fn file_path(&self, key: &str) -> Option<PathBuf> {
+        let digest = Sha256::digest(key.as_bytes());
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", hex::encode(&digest[..16]))))
+    }
+
+    fn write_file(&self, session: &Session) -> Result<()> {
+        let Some(path) = self.file_path(&session.key) else {
+            return Ok(());
+        };
+        let dir = path.parent().context("Session path has no parent")?;
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let temp_path = dir.join(format!(".session.tmp-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&temp_path, serde_json::to_vec(session)?)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    fn delete_file(&self, key: &str) {
+        if let Some(path) = self.file_path(key) {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => tracing::warn!("Failed to remove session {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+fn idle_duration(minutes: u64) -> Duration {
+    i64::try_from(minutes)
+        .ok()
+        .and_then(Duration::try_minutes)
+        .unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatMessage;
+    use tempfile::TempDir;
+
+    fn turn(user: &str, assistant: &str) -> Vec<ConversationMessage> {
+        vec![
+            ConversationMessage::Chat(ChatMessage::user(user)),
+            ConversationMessage::Chat(ChatMessage::assistant(assistant)),
+        ]
+    }
+
+    #[test]
+    fn reset_commands() {
+        assert!(is_reset_command("/new"));
+        assert!(is_reset_command(" /reset \n"));
+        assert!(!is_reset_command("/new chat please"));
+        assert!(!is_reset_command("new"));
+    }
+
+    #[test]
+    fn sessions_survive_restart() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path(), 60);
+        store.save("telegram:42", turn("hi", "hello"));
+        store.save("telegram:7", turn("yo", "hey"));
+
+        let reopened = SessionStore::open(tmp.path(), 60);
+        assert_eq!(reopened.history("telegram:42").len(), 2);
+        assert_eq!(reopened.history("telegram:7").len(), 2);
+        assert!(reopened.history("slack:42").is_empty());
+    }
+
+    #[test]
+    fn reset_forgets_history_on_disk() {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::open(tmp.path(), 60);
+        store.save("matrix:!room", turn("hi", "hello"));
+
+        assert!(store.reset("matrix:!room"));
+        assert!(!store.reset("matrix:!room"));
+        assert!(store.history("matrix:!room").is_empty());
+        assert!(SessionStore::open(tmp.path(), 60)
+            .history("matrix:!room")
+            .is_empty());
+    }
+
+    #[test]
+    fn idle_sessions_expire() {
+        let store = SessionStore::in_memory(0);
+        store.save("telegram:42", turn("hi", "hello"));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(store.history("telegram:42").is_empty());
+
+        store.save("telegram:1", turn("a", "b"));
+        store.save("telegram:2", turn("c", "d"));
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert_eq!(store.prune_expired(), 2);
+    }
+
+    #[test]
+    fn saved_history_is_trimmed() {
+        let store = SessionStore::in_memory(60);
+        let history: Vec<ConversationMessage> = (0..200)
+            .map(|i| ConversationMessage::Chat(ChatMessage::user(format!("msg {i}"))))
+            .collect();
+        store.save("irc:nick", history);
+
+        let kept = store.history("irc:nick");
+        assert!(kept.len() < 200);
+        assert!(
+            matches!(kept.last(), Some(ConversationMessage::Chat(m)) if m.content == "msg 199")
+        );
+    }
+}
@@ -1,12 +1,30 @@
-use super::traits::{Channel, ChannelMessage};
+use super::inbox::{self, FileInfo, Inbox};
+use super::traits::{Attachment, Channel, ChannelMessage, OutgoingMessage};
 use async_trait::async_trait;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tokio_tungstenite::tungstenite::Message;
 use uuid::Uuid;
 
-/// Slack channel — polls conversations.history via Web API
+const SLACK_API: &str = "https://slack.com/api";
+/// Recently handled messages, so `message` + `app_mention` pairs and
+/// redelivered envelopes are answered once.
+const SEEN_MESSAGES: usize = 256;
+
+/// Slack channel — receives events over Socket Mode when an app token is
+/// configured, otherwise polls `conversations.history` for `channel_id`.
+///
+/// Conversations are addressed as `<channel>` or `<channel>:<thread_ts>`;
+/// replies to a thread recipient are posted in that thread.
 pub struct SlackChannel {
     bot_token: String,
+    app_token: Option<String>,
     channel_id: Option<String>,
     allowed_users: Vec<String>,
+    api_base: String,
+    inbox: Option<Arc<Inbox>>,
     client: reqwest::Client,
 }
 
@@ -14,15 +32,32 @@ impl SlackChannel {
     pub fn new(bot_token: String, channel_id: Option<String>, allowed_users: Vec<String>) -> Self {
         Self {
             bot_token,
+            app_token: None,
             channel_id,
             allowed_users,
+            api_base: SLACK_API.into(),
+            inbox: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Listen over Socket Mode with this app-level token (`xapp-...`).
+    pub fn with_app_token(mut self, app_token: Option<String>) -> Self {
+        self.app_token = app_token.filter(|t| !t.trim().is_empty());
+        self
+    }
+
     /// Check if a Slack user ID is in the allowlist.
     /// Empty list means deny everyone until explicitly configured.
     /// `"*"` means allow everyone.
+    /// Save files shared in conversations into `inbox`. Needs the
+    /// `files:read` scope.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
     fn is_user_allowed(&self, user_id: &str) -> bool {
         self.allowed_users.iter().any(|u| u == "*" || u == user_id)
     }
@@ -31,7 +66,7 @@ impl SlackChannel {
     async fn get_bot_user_id(&self) -> Option<String> {
         let resp: serde_json::Value = self
             .client
-            .get("https://slack.com/api/auth.test")
+            .get(format!("{}/auth.test", self.api_base))
             .bearer_auth(&self.bot_token)
             .send()
             .await
@@ -44,61 +79,259 @@ impl SlackChannel {
             .and_then(|u| u.as_str())
             .map(String::from)
     }
-}
-
-#[async_trait]
-impl Channel for SlackChannel {
-    fn name(&self) -> &str {
-        "slack"
-    }
-
-    async fn send(&self, message: &str, channel: &str) -> anyhow::Result<()> {
-        let body = serde_json::json!({
-            "channel": channel,
-            "text": message
-        });
 
-        let resp = self
+    /// Ask Slack for a fresh Socket Mode WebSocket URL.
+    async fn open_socket(&self, app_token: &str) -> anyhow::Result<String> {
+        let resp: serde_json::Value = self
             .client
-            .post("https://slack.com/api/chat.postMessage")
-            .bearer_auth(&self.bot_token)
-            .json(&body)
+            .post(format!("{}/apps.connections.open", self.api_base))
+            .bearer_auth(app_token)
             .send()
+            .await?
+            .json()
             .await?;
 
+        if resp.get("ok") != Some(&serde_json::Value::Bool(true)) {
+            let err = resp
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack apps.connections.open failed: {err}");
+        }
+        resp.get("url")
+            .and_then(|u| u.as_str())
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Slack apps.connections.open returned no url"))
+    }
+
+    /// Serve one Socket Mode connection. Returns `Ok(true)` when Slack asks
+    /// for a reconnect and `Ok(false)` once the receiver is gone.
+    async fn run_socket(
+        &self,
+        url: &str,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+        bot_user_id: &str,
+        seen: &mut VecDeque<String>,
+    ) -> anyhow::Result<bool> {
+        let (ws_stream, _) = tokio_tungstenite::connect_async(url).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        while let Some(frame) = read.next().await {
+            let text = match frame? {
+                Message::Text(t) => t,
+                Message::Ping(data) => {
+                    write.send(Message::Pong(data)).await?;
+                    continue;
+                }
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let Ok(envelope) = serde_json::from_str::<serde_json::Value>(&text) else {
+                continue;
+            };
+
+            // Every envelope must be acknowledged or Slack redelivers it
+            if let Some(id) = envelope.get("envelope_id").and_then(|i| i.as_str()) {
+                write
+                    .send(Message::Text(json!({ "envelope_id": id }).to_string()))
+                    .await?;
+            }
+
+            match envelope.get("type").and_then(|t| t.as_str()) {
+                Some("hello") => tracing::info!("Slack: connected via Socket Mode"),
+                Some("disconnect") => {
+                    let reason = envelope
+                        .get("reason")
+                        .and_then(|r| r.as_str())
+                        .unwrap_or("unknown");
+                    tracing::info!("Slack: Socket Mode disconnect requested ({reason})");
+                    return Ok(true);
+                }
+                Some("events_api") => {
+                    let Some(event) = envelope.get("payload").and_then(|p| p.get("event")) else {
+                        continue;
+                    };
+                    let Some(mut msg) = self.event_to_message(event, bot_user_id, seen) else {
+                        continue;
+                    };
+                    self.attach_files(event, &mut msg).await;
+                    if tx.send(msg).await.is_err() {
+                        return Ok(false);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        anyhow::bail!("Slack Socket Mode connection closed")
+    }
+
+    /// Call a Web API method and return its body, failing on HTTP errors and
+    /// on `"ok": false`.
+    async fn api_result(
+        method: &str,
+        request: reqwest::RequestBuilder,
+    ) -> anyhow::Result<serde_json::Value> {
+        let resp = request.send().await?;
         let status = resp.status();
         let body = resp
             .text()
             .await
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
-
         if !status.is_success() {
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {body}");
+            anyhow::bail!("Slack {method} failed ({status}): {body}");
         }
-
-        // Slack returns 200 for most app-level errors; check JSON "ok" field
         let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
-        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+        if parsed.get("ok") != Some(&serde_json::Value::Bool(true)) {
             let err = parsed
                 .get("error")
                 .and_then(|e| e.as_str())
                 .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
+            anyhow::bail!("Slack {method} failed: {err}");
         }
+        Ok(parsed)
+    }
 
-        Ok(())
+    /// Upload one file's content with `files.getUploadURLExternal` and return
+    /// its file id, ready to be shared by `files.completeUploadExternal`.
+    async fn upload_file(&self, attachment: &Attachment) -> anyhow::Result<String> {
+        let bytes = attachment.bytes().await?;
+        let ticket = Self::api_result(
+            "files.getUploadURLExternal",
+            self.client
+                .post(format!("{}/files.getUploadURLExternal", self.api_base))
+                .bearer_auth(&self.bot_token)
+                .form(&[
+                    ("filename", attachment.filename.clone()),
+                    ("length", bytes.len().to_string()),
+                ]),
+        )
+        .await?;
+        let (Some(upload_url), Some(file_id)) = (
+            ticket.get("upload_url").and_then(|u| u.as_str()),
+            ticket.get("file_id").and_then(|f| f.as_str()),
+        ) else {
+            anyhow::bail!("Slack files.getUploadURLExternal returned no upload URL");
+        };
+
+        let resp = self
+            .client
+            .post(upload_url)
+            .header("Content-Type", attachment.mime_type.as_str())
+            .body(bytes)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!(
+                "Slack upload of {} failed ({})",
+                attachment.filename,
+                resp.status()
+            );
+        }
+        Ok(file_id.to_string())
     }
 
-    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
-        let channel_id = self
-            .channel_id
-            .clone()
-            .ok_or_else(|| anyhow::anyhow!("Slack channel_id required for listening"))?;
+    /// Turn a `message` or `app_mention` event into a channel message,
+    /// skipping bots, edits, other channels, disallowed users and repeats.
+    /// Shared files are downloaded separately by [`Self::attach_files`].
+    fn event_to_message(
+        &self,
+        event: &serde_json::Value,
+        bot_user_id: &str,
+        seen: &mut VecDeque<String>,
+    ) -> Option<ChannelMessage> {
+        let field = |name: &str| event.get(name).and_then(|v| v.as_str());
+
+        if !matches!(field("type"), Some("message" | "app_mention"))
+            || !matches!(field("subtype"), None | Some("file_share"))
+            || field("bot_id").is_some()
+        {
+            return None;
+        }
+        let user = field("user")?;
+        let channel = field("channel")?;
+        let ts = field("ts")?;
+        let text = field("text").unwrap_or("");
+        let has_files = event
+            .get("files")
+            .and_then(|f| f.as_array())
+            .is_some_and(|f| !f.is_empty());
+        if user == bot_user_id || (text.is_empty() && !has_files) {
+            return None;
+        }
 
-        let bot_user_id = self.get_bot_user_id().await.unwrap_or_default();
+        let is_dm = field("channel_type") == Some("im");
+        if let Some(only) = &self.channel_id {
+            if !is_dm && channel != only {
+                return None;
+            }
+        }
+        if !self.is_user_allowed(user) {
+            tracing::warn!("Slack: ignoring message from unauthorized user: {user}");
+            return None;
+        }
+
+        let key = format!("{channel}:{ts}");
+        if seen.contains(&key) {
+            return None;
+        }
+        if seen.len() == SEEN_MESSAGES {
+            seen.pop_front();
+        }
+        seen.push_back(key);
+
+        // Channel messages are answered in a thread; DMs only when already in one
+        let sender = match field("thread_ts") {
+            Some(thread) => format!("{channel}:{thread}"),
+            None if is_dm => channel.to_string(),
+            None => format!("{channel}:{ts}"),
+        };
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender,
+            author: user.to_string(),
+            content: text.to_string(),
+            channel: "slack".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments: Vec::new(),
+        })
+    }
+
+    /// Download the files shared with `event` into the inbox and attach them
+    /// to `msg`.
+    async fn attach_files(&self, event: &serde_json::Value, msg: &mut ChannelMessage) {
+        for (url, info) in shared_files(event) {
+            let saved = match &self.inbox {
+                Some(inbox) => {
+                    let request = self.client.get(url).bearer_auth(&self.bot_token);
+                    inbox.download("slack", &info, request).await
+                }
+                None => Err(anyhow::anyhow!("attachments are disabled")),
+            };
+            match saved {
+                Ok(attachment) => msg.attachments.push(attachment),
+                Err(e) => inbox::note_skipped("slack", &mut msg.content, &info.filename, &e),
+            }
+        }
+    }
+
+    async fn poll_history(
+        &self,
+        tx: &tokio::sync::mpsc::Sender<ChannelMessage>,
+        bot_user_id: &str,
+    ) -> anyhow::Result<()> {
+        let channel_id = self.channel_id.clone().ok_or_else(|| {
+            anyhow::anyhow!("Slack needs app_token (Socket Mode) or channel_id for listening")
+        })?;
         let mut last_ts = String::new();
 
-        tracing::info!("Slack channel listening on #{channel_id}...");
+        tracing::warn!(
+            "Slack: no app_token configured, polling #{channel_id}; set app_token to use Socket Mode"
+        );
 
         loop {
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
@@ -110,7 +343,7 @@ impl Channel for SlackChannel {
 
             let resp = match self
                 .client
-                .get("https://slack.com/api/conversations.history")
+                .get(format!("{}/conversations.history", self.api_base))
                 .bearer_auth(&self.bot_token)
                 .query(&params)
                 .send()
@@ -153,22 +386,26 @@ impl Channel for SlackChannel {
                     }
 
                     // Skip empty or already-seen
-                    if text.is_empty() || ts <= last_ts.as_str() {
+                    let has_files = msg.get("files").is_some_and(serde_json::Value::is_array);
+                    if (text.is_empty() && !has_files) || ts <= last_ts.as_str() {
                         continue;
                     }
 
                     last_ts = ts.to_string();
 
-                    let channel_msg = ChannelMessage {
+                    let mut channel_msg = ChannelMessage {
                         id: Uuid::new_v4().to_string(),
                         sender: channel_id.clone(),
+                        author: user.to_string(),
                         content: text.to_string(),
                         channel: "slack".to_string(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
                             .as_secs(),
+                        attachments: Vec::new(),
                     };
+                    self.attach_files(msg, &mut channel_msg).await;
 
                     if tx.send(channel_msg).await.is_err() {
                         return Ok(());
@@ -177,10 +414,130 @@ impl Channel for SlackChannel {
             }
         }
     }
+}
+
+/// Files shared with a message, as `(download_url, info)`.
+fn shared_files(event: &serde_json::Value) -> Vec<(String, FileInfo)> {
+    event
+        .get("files")
+        .and_then(|f| f.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|file| {
+            let url = file
+                .get("url_private_download")
+                .or_else(|| file.get("url_private"))?
+                .as_str()?;
+            let name = file.get("name").and_then(|n| n.as_str()).unwrap_or("file");
+            let mime = file
+                .get("mimetype")
+                .and_then(|m| m.as_str())
+                .unwrap_or("application/octet-stream");
+            let size = file.get("size").and_then(serde_json::Value::as_u64);
+            Some((url.to_string(), FileInfo::new(name, mime, size)))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Channel for SlackChannel {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
+        let mut body = json!({
+            "channel": recipient,
+            "text": message
+        });
+        if let Some((channel, thread_ts)) = recipient.split_once(':') {
+            body["channel"] = json!(channel);
+            body["thread_ts"] = json!(thread_ts);
+        }
+
+        let resp = self
+            .client
+            .post(format!("{}/chat.postMessage", self.api_base))
+            .bearer_auth(&self.bot_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        let body = resp
+            .text()
+            .await
+            .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+
+        if !status.is_success() {
+            anyhow::bail!("Slack chat.postMessage failed ({status}): {body}");
+        }
+
+        // Slack returns 200 for most app-level errors; check JSON "ok" field
+        let parsed: serde_json::Value = serde_json::from_str(&body).unwrap_or_default();
+        if parsed.get("ok") == Some(&serde_json::Value::Bool(false)) {
+            let err = parsed
+                .get("error")
+                .and_then(|e| e.as_str())
+                .unwrap_or("unknown");
+            anyhow::bail!("Slack chat.postMessage failed: {err}");
+        }
+
+        Ok(())
+    }
+
+    async fn send_rich(&self, message: &OutgoingMessage, recipient: &str) -> anyhow::Result<()> {
+        if message.attachments.is_empty() {
+            return self.send(&message.text, recipient).await;
+        }
+
+        let mut files = Vec::new();
+        for attachment in &message.attachments {
+            let id = self.upload_file(attachment).await?;
+            let title = attachment.caption.as_ref().unwrap_or(&attachment.filename);
+            files.push(json!({"id": id, "title": title}));
+        }
+
+        let (channel, thread_ts) = match recipient.split_once(':') {
+            Some((channel, thread_ts)) => (channel, Some(thread_ts)),
+            None => (recipient, None),
+        };
+        let mut body = json!({"files": files, "channel_id": channel});
+        if let Some(thread_ts) = thread_ts {
+            body["thread_ts"] = json!(thread_ts);
+        }
+        if !message.text.trim().is_empty() {
+            body["initial_comment"] = json!(message.text);
+        }
+        Self::api_result(
+            "files.completeUploadExternal",
+            self.client
+                .post(format!("{}/files.completeUploadExternal", self.api_base))
+                .bearer_auth(&self.bot_token)
+                .json(&body),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        let bot_user_id = self.get_bot_user_id().await.unwrap_or_default();
+        let Some(app_token) = &self.app_token else {
+            return self.poll_history(&tx, &bot_user_id).await;
+        };
+
+        let mut seen = VecDeque::with_capacity(SEEN_MESSAGES);
+        loop {
+            let url = self.open_socket(app_token).await?;
+            if !self.run_socket(&url, &tx, &bot_user_id, &mut seen).await? {
+                return Ok(());
+            }
+        }
+    }
 
     async fn health_check(&self) -> bool {
         self.client
-            .get("https://slack.com/api/auth.test")
+            .get(format!("{}/auth.test", self.api_base))
             .bearer_auth(&self.bot_token)
             .send()
             .await
@@ -192,6 +549,7 @@ impl Channel for SlackChannel {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn slack_channel_name() {
@@ -252,4 +610,301 @@ mod tests {
         assert!(ch.is_user_allowed("U111"));
         assert!(ch.is_user_allowed("anyone"));
     }
+
+    #[test]
+    fn blank_app_token_keeps_polling() {
+        let ch =
+            SlackChannel::new("xoxb-fake".into(), None, vec![]).with_app_token(Some(" ".into()));
+        assert!(ch.app_token.is_none());
+    }
+
+    #[test]
+    fn events_are_filtered_and_threaded() {
+        let ch = SlackChannel::new("xoxb-fake".into(), Some("C1".into()), vec!["U1".into()]);
+        let mut seen = VecDeque::new();
+        let mut convert = |event: serde_json::Value| {
+            ch.event_to_message(&event, "UBOT", &mut seen)
+                .map(|m| m.sender)
+        };
+
+        let top_level =
+            json!({"type": "message", "channel": "C1", "user": "U1", "ts": "1.0", "text": "hi"});
+        assert_eq!(convert(top_level.clone()), Some("C1:1.0".into()));
+        // The matching app_mention is the same message
+        let mention = json!({"type": "app_mention", "channel": "C1", "user": "U1", "ts": "1.0", "text": "hi"});
+        assert_eq!(convert(mention), None);
+        assert_eq!(convert(top_level), None);
+
+        let in_thread = json!({"type": "message", "channel": "C1", "user": "U1", "ts": "2.0", "thread_ts": "1.0", "text": "more"});
+        assert_eq!(convert(in_thread), Some("C1:1.0".into()));
+        let dm = json!({"type": "message", "channel": "D9", "channel_type": "im", "user": "U1", "ts": "3.0", "text": "psst"});
+        assert_eq!(convert(dm), Some("D9".into()));
+
+        for skipped in [
+            json!({"type": "message", "channel": "C2", "user": "U1", "ts": "4.0", "text": "other channel"}),
+            json!({"type": "message", "channel": "C1", "user": "U2", "ts": "5.0", "text": "stranger"}),
+            json!({"type": "message", "channel": "C1", "user": "UBOT", "ts": "6.0", "text": "myself"}),
+            json!({"type": "message", "subtype": "message_changed", "channel": "C1", "user": "U1", "ts": "7.0", "text": "edit"}),
+            json!({"type": "message", "bot_id": "B1", "channel": "C1", "user": "U1", "ts": "8.0", "text": "bot"}),
+            json!({"type": "reaction_added", "channel": "C1", "user": "U1", "ts": "9.0", "text": "x"}),
+        ] {
+            assert_eq!(convert(skipped), None);
+        }
+    }
+
+    /// Mock Slack Web API: `apps.connections.open` hands out `ws_url`;
+    /// `chat.postMessage` and `files.completeUploadExternal` bodies and
+    /// uploaded file contents are recorded.
+    async fn mock_web_api(ws_url: String) -> (String, Arc<Mutex<Vec<serde_json::Value>>>) {
+        use axum::{extract::Path, routing::post, Json, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let posted = Arc::new(Mutex::new(Vec::new()));
+        let (messages, completions, uploads) = (
+            Arc::clone(&posted),
+            Arc::clone(&posted),
+            Arc::clone(&posted),
+        );
+        let upload_base = base.clone();
+        let app = Router::new()
+            .route(
+                "/auth.test",
+                axum::routing::get(|| async { Json(json!({"ok": true, "user_id": "UBOT"})) }),
+            )
+            .route(
+                "/apps.connections.open",
+                post(move || {
+                    let url = ws_url.clone();
+                    async move { Json(json!({"ok": true, "url": url})) }
+                }),
+            )
+            .route(
+                "/chat.postMessage",
+                post(move |Json(body): Json<serde_json::Value>| {
+                    messages.lock().unwrap().push(body);
+                    async { Json(json!({"ok": true})) }
+                }),
+            )
+            .route(
+                "/files.getUploadURLExternal",
+                post(move |form: String| {
+                    let filename = form
+                        .split('&')
+                        .find_map(|pair| pair.strip_prefix("filename="))
+                        .unwrap_or_default();
+                    let id = format!("F-{filename}");
+                    let url = format!("{upload_base}/upload/{id}");
+                    async move { Json(json!({"ok": true, "upload_url": url, "file_id": id})) }
+                }),
+            )
+            .route(
+                "/upload/:id",
+                post(move |Path(id): Path<String>, body: axum::body::Bytes| {
+                    uploads.lock().unwrap().push(json!({
+                        "uploaded": id,
+                        "content": String::from_utf8_lossy(&body),
+                    }));
+                    async { "OK" }
+                }),
+            )
+            .route(
+                "/files/:name",
+                axum::routing::get(
+                    |headers: axum::http::HeaderMap, Path(name): Path<String>| async move {
+                        let authorized = headers
+                            .get("authorization")
+                            .is_some_and(|v| v == "Bearer xoxb-fake");
+                        if authorized {
+                            (axum::http::StatusCode::OK, format!("content of {name}"))
+                        } else {
+                            (axum::http::StatusCode::UNAUTHORIZED, String::new())
+                        }
+                    },
+                ),
+            )
+            .route(
+                "/files.completeUploadExternal",
+                post(move |Json(body): Json<serde_json::Value>| {
+                    completions.lock().unwrap().push(body);
+                    async { Json(json!({"ok": true})) }
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (base, posted)
+    }
+
+    fn envelope(id: &str, event: &serde_json::Value) -> Message {
+        Message::Text(
+            json!({
+                "envelope_id": id,
+                "type": "events_api",
+                "payload": {"type": "event_callback", "event": event}
+            })
+            .to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn socket_mode_receives_acks_and_reconnects() {
+        let ws_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_url = format!("ws://{}", ws_listener.local_addr().unwrap());
+        let acks = Arc::new(Mutex::new(Vec::new()));
+        let server_acks = Arc::clone(&acks);
+        tokio::spawn(async move {
+            for connection in 0..2 {
+                let (socket, _) = ws_listener.accept().await.unwrap();
+                let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+                ws.send(Message::Text(json!({"type": "hello"}).to_string()))
+                    .await
+                    .unwrap();
+                let events = if connection == 0 {
+                    vec![
+                        envelope(
+                            "e1",
+                            &json!({"type": "message", "channel": "C1", "user": "U1", "ts": "1.0", "text": "hello bot"}),
+                        ),
+                        envelope(
+                            "e2",
+                            &json!({"type": "app_mention", "channel": "C1", "user": "U1", "ts": "1.0", "text": "hello bot"}),
+                        ),
+                        envelope(
+                            "e3",
+                            &json!({"type": "message", "channel": "D1", "channel_type": "im", "user": "U1", "ts": "2.0", "text": "a dm"}),
+                        ),
+                    ]
+                } else {
+                    vec![envelope(
+                        "e4",
+                        &json!({"type": "message", "channel": "C1", "user": "U1", "ts": "3.0", "thread_ts": "1.0", "text": "follow-up"}),
+                    )]
+                };
+                let expected = events.len();
+                for event in events {
+                    ws.send(event).await.unwrap();
+                }
+                let mut received = 0;
+                while received < expected {
+                    if let Some(Ok(Message::Text(ack))) = ws.next().await {
+                        server_acks.lock().unwrap().push(ack);
+                        received += 1;
+                    }
+                }
+                if connection == 0 {
+                    ws.send(Message::Text(
+                        json!({"type": "disconnect", "reason": "refresh_requested"}).to_string(),
+                    ))
+                    .await
+                    .unwrap();
+                } else {
+                    // Keep the second connection open until the test ends
+                    std::future::pending::<()>().await;
+                }
+            }
+        });
+
+        let (api_base, _) = mock_web_api(ws_url).await;
+        let mut ch = SlackChannel::new("xoxb-fake".into(), None, vec!["*".into()])
+            .with_app_token(Some("xapp-fake".into()));
+        ch.api_base = api_base;
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+        let listener = tokio::spawn(async move { ch.listen(tx).await });
+
+        let mut messages = Vec::new();
+        for _ in 0..3 {
+            let msg = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            messages.push((msg.sender, msg.content));
+        }
+        listener.abort();
+
+        assert_eq!(
+            messages,
+            vec![
+                ("C1:1.0".to_string(), "hello bot".to_string()),
+                ("D1".to_string(), "a dm".to_string()),
+                ("C1:1.0".to_string(), "follow-up".to_string()),
+            ]
+        );
+        let acks = acks.lock().unwrap();
+        for id in ["e1", "e2", "e3"] {
+            assert!(acks.iter().any(|a| a.contains(id)), "missing ack for {id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn replies_go_to_the_thread() {
+        let (api_base, posted) = mock_web_api(String::new()).await;
+        let mut ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        ch.api_base = api_base;
+
+        ch.send("in thread", "C1:1.0").await.unwrap();
+        ch.send("direct", "D1").await.unwrap();
+
+        let posted = posted.lock().unwrap();
+        assert_eq!(posted[0]["channel"], "C1");
+        assert_eq!(posted[0]["thread_ts"], "1.0");
+        assert_eq!(posted[1]["channel"], "D1");
+        assert!(posted[1].get("thread_ts").is_none());
+    }
+
+    #[tokio::test]
+    async fn files_are_uploaded_into_the_thread() {
+        let (api_base, posted) = mock_web_api(String::new()).await;
+        let mut ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        ch.api_base = api_base;
+
+        let message = OutgoingMessage::text("Here is the report").with_attachment(
+            Attachment::from_bytes(b"a,b\n1,2".to_vec(), "report.csv", "text/csv")
+                .with_caption("Weekly numbers"),
+        );
+        ch.send_rich(&message, "C1:1.0").await.unwrap();
+
+        let posted = posted.lock().unwrap();
+        assert_eq!(posted[0]["uploaded"], "F-report.csv");
+        assert_eq!(posted[0]["content"], "a,b\n1,2");
+        assert_eq!(
+            posted[1],
+            json!({
+                "files": [{"id": "F-report.csv", "title": "Weekly numbers"}],
+                "channel_id": "C1",
+                "thread_ts": "1.0",
+                "initial_comment": "Here is the report",
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn shared_files_are_downloaded_into_the_inbox() {
+        let (api_base, _) = mock_web_api(String::new()).await;
+        let tmp = tempfile::TempDir::new().unwrap();
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec!["*".into()])
+            .with_inbox(Some(Arc::new(Inbox::new(tmp.path(), 1024))));
+
+        let event = json!({
+            "type": "message", "subtype": "file_share", "channel": "D1", "channel_type": "im",
+            "user": "U1", "ts": "1.0", "text": "",
+            "files": [
+                {"name": "notes.md", "mimetype": "text/markdown", "size": 16,
+                 "url_private_download": format!("{api_base}/files/notes.md")},
+                {"name": "big.mov", "mimetype": "video/quicktime", "size": 1_000_000,
+                 "url_private_download": format!("{api_base}/files/big.mov")}
+            ]
+        });
+        let mut msg = ch
+            .event_to_message(&event, "UBOT", &mut VecDeque::new())
+            .unwrap();
+        ch.attach_files(&event, &mut msg).await;
+
+        assert_eq!(msg.attachments.len(), 1);
+        assert_eq!(msg.attachments[0].filename, "notes.md");
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(&msg.attachments[0].path)).unwrap(),
+            "content of notes.md"
+        );
+        assert!(msg.content.starts_with("[Attachment big.mov was not saved"));
+    }
 }
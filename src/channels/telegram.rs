@@ -1,13 +1,20 @@
-use super::traits::{Channel, ChannelMessage};
+use super::inbox::{self, FileInfo, Inbox};
+use super::traits::{Attachment, Channel, ChannelMessage, InboundAttachment, OutgoingMessage};
+use crate::security::approval::ApprovalRequest;
 use async_trait::async_trait;
 use reqwest::multipart::{Form, Part};
 use std::path::Path;
+use std::sync::Arc;
 use uuid::Uuid;
 
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
 /// Telegram channel — long-polls the Bot API for updates
 pub struct TelegramChannel {
     bot_token: String,
     allowed_users: Vec<String>,
+    api_base: String,
+    inbox: Option<Arc<Inbox>>,
     client: reqwest::Client,
 }
 
@@ -16,12 +23,130 @@ impl TelegramChannel {
         Self {
             bot_token,
             allowed_users,
+            api_base: TELEGRAM_API.into(),
+            inbox: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Save photos, voice notes, documents and other media sent to the bot
+    /// into `inbox`.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
     fn api_url(&self, method: &str) -> String {
-        format!("https://api.telegram.org/bot{}/{method}", self.bot_token)
+        format!("{}/bot{}/{method}", self.api_base, self.bot_token)
+    }
+
+    /// Download a file through `getFile` into the inbox.
+    async fn download_file(
+        &self,
+        file_id: &str,
+        info: &FileInfo,
+    ) -> anyhow::Result<InboundAttachment> {
+        let inbox = self
+            .inbox
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("attachments are disabled"))?;
+        let resp: serde_json::Value = self
+            .client
+            .post(self.api_url("getFile"))
+            .json(&serde_json::json!({ "file_id": file_id }))
+            .send()
+            .await?
+            .json()
+            .await?;
+        let file_path = resp
+            .get("result")
+            .and_then(|r| r.get("file_path"))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Telegram getFile returned no file_path: {resp}"))?;
+        let url = format!("{}/file/bot{}/{file_path}", self.api_base, self.bot_token);
+        inbox.download("telegram", info, self.client.get(url)).await
+    }
+
+    /// Turn an incoming `message` update into a channel message, downloading
+    /// its media. `None` for unauthorized senders and empty messages.
+    async fn incoming_message(&self, message: &serde_json::Value) -> Option<ChannelMessage> {
+        let username_opt = message
+            .get("from")
+            .and_then(|f| f.get("username"))
+            .and_then(|u| u.as_str());
+        let username = username_opt.unwrap_or("unknown");
+
+        let user_id = message
+            .get("from")
+            .and_then(|f| f.get("id"))
+            .and_then(serde_json::Value::as_i64);
+        let user_id_str = user_id.map(|id| id.to_string());
+
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id_str {
+            identities.push(id.as_str());
+        }
+
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            tracing::warn!(
+                "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
+Allowlist Telegram @username or numeric user ID, then run `viziclaw onboard --channels-only`.",
+                user_id_str.as_deref().unwrap_or("unknown")
+            );
+            return None;
+        }
+
+        let mut content = message
+            .get("text")
+            .or_else(|| message.get("caption"))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let files = message_files(message);
+        if content.is_empty() && files.is_empty() {
+            return None;
+        }
+
+        let chat_id = message
+            .get("chat")
+            .and_then(|c| c.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())
+            .unwrap_or_default();
+
+        // Send "typing" indicator immediately when we receive a message
+        let typing_body = serde_json::json!({
+            "chat_id": &chat_id,
+            "action": "typing"
+        });
+        let _ = self
+            .client
+            .post(self.api_url("sendChatAction"))
+            .json(&typing_body)
+            .send()
+            .await; // Ignore errors for typing indicator
+
+        let mut attachments = Vec::new();
+        for (file_id, info) in files {
+            match self.download_file(&file_id, &info).await {
+                Ok(attachment) => attachments.push(attachment),
+                Err(e) => inbox::note_skipped("telegram", &mut content, &info.filename, &e),
+            }
+        }
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id,
+            author: user_id_str.unwrap_or_else(|| username.to_string()),
+            content,
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments,
+        })
     }
 
     fn is_user_allowed(&self, username: &str) -> bool {
@@ -35,6 +160,52 @@ impl TelegramChannel {
         identities.into_iter().any(|id| self.is_user_allowed(id))
     }
 
+    /// Turn an inline-button press into a message carrying the button's
+    /// `callback_data`, as if the user had typed it.
+    fn callback_query_message(&self, callback: &serde_json::Value) -> Option<ChannelMessage> {
+        let data = callback.get("data").and_then(serde_json::Value::as_str)?;
+        let from = callback.get("from");
+        let username = from
+            .and_then(|f| f.get("username"))
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown");
+        let user_id = from
+            .and_then(|f| f.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string());
+
+        let mut identities = vec![username];
+        if let Some(ref id) = user_id {
+            identities.push(id.as_str());
+        }
+        if !self.is_any_user_allowed(identities.iter().copied()) {
+            tracing::warn!(
+                "Telegram: ignoring button press from unauthorized user: username={username}, user_id={}",
+                user_id.as_deref().unwrap_or("unknown")
+            );
+            return None;
+        }
+
+        let chat_id = callback
+            .get("message")
+            .and_then(|m| m.get("chat"))
+            .and_then(|c| c.get("id"))
+            .and_then(serde_json::Value::as_i64)?;
+
+        Some(ChannelMessage {
+            id: Uuid::new_v4().to_string(),
+            sender: chat_id.to_string(),
+            author: user_id.unwrap_or_else(|| username.to_string()),
+            content: data.to_string(),
+            channel: "telegram".to_string(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            attachments: Vec::new(),
+        })
+    }
+
     /// Send a document/file to a Telegram chat
     pub async fn send_document(
         &self,
@@ -298,6 +469,74 @@ impl TelegramChannel {
         Ok(())
     }
 
+    /// Upload `file_bytes` through `method` (e.g. `sendPhoto`) as the `field`
+    /// form part.
+    async fn send_media_bytes(
+        &self,
+        chat_id: &str,
+        method: &str,
+        field: &str,
+        file_bytes: Vec<u8>,
+        file_name: &str,
+        caption: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let part = Part::bytes(file_bytes).file_name(file_name.to_string());
+
+        let mut form = Form::new()
+            .text("chat_id", chat_id.to_string())
+            .part(field.to_string(), part);
+
+        if let Some(cap) = caption {
+            form = form.text("caption", cap.to_string());
+        }
+
+        let resp = self
+            .client
+            .post(self.api_url(method))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram {method} failed: {err}");
+        }
+
+        tracing::info!("Telegram {field} sent to {chat_id}: {file_name}");
+        Ok(())
+    }
+
+    /// Send one attachment with the Bot API method matching its media kind.
+    /// Photos Telegram refuses to process (too large, odd dimensions) are
+    /// retried as documents.
+    async fn send_attachment(&self, chat_id: &str, attachment: &Attachment) -> anyhow::Result<()> {
+        let bytes = attachment.bytes().await?;
+        let caption = attachment.caption.as_deref();
+        let (method, field) = media_method(attachment);
+        if method != "sendPhoto" {
+            return self
+                .send_media_bytes(chat_id, method, field, bytes, &attachment.filename, caption)
+                .await;
+        }
+
+        if let Err(e) = self
+            .send_media_bytes(
+                chat_id,
+                method,
+                field,
+                bytes.clone(),
+                &attachment.filename,
+                caption,
+            )
+            .await
+        {
+            tracing::warn!("{e}; sending {} as a document", attachment.filename);
+            self.send_document_bytes(chat_id, bytes, &attachment.filename, caption)
+                .await?;
+        }
+        Ok(())
+    }
+
     /// Send a file by URL (Telegram will download it)
     pub async fn send_document_by_url(
         &self,
@@ -421,6 +660,49 @@ impl Channel for TelegramChannel {
         Ok(())
     }
 
+    async fn send_rich(&self, message: &OutgoingMessage, chat_id: &str) -> anyhow::Result<()> {
+        if !message.text.trim().is_empty() {
+            self.send(&message.text, chat_id).await?;
+        }
+        for attachment in &message.attachments {
+            self.send_attachment(chat_id, attachment).await?;
+        }
+        Ok(())
+    }
+
+    async fn send_approval_request(
+        &self,
+        request: &ApprovalRequest,
+        chat_id: &str,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "text": format!(
+                "⚠️ Approval needed for a {}-risk command:\n\n{}",
+                request.risk, request.command
+            ),
+            "reply_markup": {
+                "inline_keyboard": [[
+                    { "text": "✅ Approve", "callback_data": format!("/approve {}", request.id) },
+                    { "text": "❌ Deny", "callback_data": format!("/deny {}", request.id) }
+                ]]
+            }
+        });
+
+        let resp = self
+            .client
+            .post(self.api_url("sendMessage"))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram sendMessage failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
         let mut offset: i64 = 0;
 
@@ -431,7 +713,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.client.post(&url).json(&body).send().await {
@@ -459,67 +741,30 @@ impl Channel for TelegramChannel {
                         offset = uid + 1;
                     }
 
-                    let Some(message) = update.get("message") else {
+                    if let Some(callback) = update.get("callback_query") {
+                        // Stop the button's loading spinner whether or not we act on it
+                        if let Some(id) = callback.get("id").and_then(serde_json::Value::as_str) {
+                            let _ = self
+                                .client
+                                .post(self.api_url("answerCallbackQuery"))
+                                .json(&serde_json::json!({ "callback_query_id": id }))
+                                .send()
+                                .await;
+                        }
+                        if let Some(msg) = self.callback_query_message(callback) {
+                            if tx.send(msg).await.is_err() {
+                                return Ok(());
+                            }
+                        }
                         continue;
-                    };
+                    }
 
-                    let Some(text) = message.get("text").and_then(serde_json::Value::as_str) else {
+                    let Some(message) = update.get("message") else {
                         continue;
                     };
 
-                    let username_opt = message
-                        .get("from")
-                        .and_then(|f| f.get("username"))
-                        .and_then(|u| u.as_str());
-                    let username = username_opt.unwrap_or("unknown");
-
-                    let user_id = message
-                        .get("from")
-                        .and_then(|f| f.get("id"))
-                        .and_then(serde_json::Value::as_i64);
-                    let user_id_str = user_id.map(|id| id.to_string());
-
-                    let mut identities = vec![username];
-                    if let Some(ref id) = user_id_str {
-                        identities.push(id.as_str());
-                    }
-
-                    if !self.is_any_user_allowed(identities.iter().copied()) {
-                        tracing::warn!(
-                            "Telegram: ignoring message from unauthorized user: username={username}, user_id={}. \
-Allowlist Telegram @username or numeric user ID, then run `viziclaw onboard --channels-only`.",
-                            user_id_str.as_deref().unwrap_or("unknown")
-                        );
+                    let Some(msg) = self.incoming_message(message).await else {
                         continue;
-                    }
-
-                    let chat_id = message
-                        .get("chat")
-                        .and_then(|c| c.get("id"))
-                        .and_then(serde_json::Value::as_i64)
-                        .map(|id| id.to_string())
-                        .unwrap_or_default();
-
-                    // Send "typing" indicator immediately when we receive a message
-                    let typing_body = serde_json::json!({
-                        "chat_id": &chat_id,
-                        "action": "typing"
-                    });
-                    let _ = self.client
-                        .post(self.api_url("sendChatAction"))
-                        .json(&typing_body)
-                        .send()
-                        .await; // Ignore errors for typing indicator
-
-                    let msg = ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        sender: chat_id,
-                        content: text.to_string(),
-                        channel: "telegram".to_string(),
-                        timestamp: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_secs(),
                     };
 
                     if tx.send(msg).await.is_err() {
@@ -540,6 +785,66 @@ Allowlist Telegram @username or numeric user ID, then run `viziclaw onboard --ch
     }
 }
 
+/// Downloadable media in a message, as `(file_id, info)`. Of a photo's
+/// sizes only the largest is taken.
+fn message_files(message: &serde_json::Value) -> Vec<(String, FileInfo)> {
+    let mut files = Vec::new();
+    let str_field = |v: &serde_json::Value, key: &str| {
+        v.get(key)
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string)
+    };
+
+    if let Some(photo) = message
+        .get("photo")
+        .and_then(serde_json::Value::as_array)
+        .and_then(|sizes| sizes.last())
+    {
+        if let Some(id) = str_field(photo, "file_id") {
+            let name = format!(
+                "photo_{}.jpg",
+                str_field(photo, "file_unique_id").unwrap_or_else(|| id.clone())
+            );
+            let size = photo.get("file_size").and_then(serde_json::Value::as_u64);
+            files.push((id, FileInfo::new(name, "image/jpeg", size)));
+        }
+    }
+
+    for (field, default_mime, extension) in [
+        ("document", "application/octet-stream", "bin"),
+        ("voice", "audio/ogg", "ogg"),
+        ("audio", "audio/mpeg", "mp3"),
+        ("video", "video/mp4", "mp4"),
+        ("video_note", "video/mp4", "mp4"),
+    ] {
+        let Some(media) = message.get(field) else {
+            continue;
+        };
+        let Some(id) = str_field(media, "file_id") else {
+            continue;
+        };
+        let name = str_field(media, "file_name").unwrap_or_else(|| {
+            let unique = str_field(media, "file_unique_id").unwrap_or_else(|| id.clone());
+            format!("{field}_{unique}.{extension}")
+        });
+        let mime = str_field(media, "mime_type").unwrap_or_else(|| default_mime.to_string());
+        let size = media.get("file_size").and_then(serde_json::Value::as_u64);
+        files.push((id, FileInfo::new(name, mime, size)));
+    }
+    files
+}
+
+/// Bot API method and form field for an attachment's media kind.
+fn media_method(attachment: &Attachment) -> (&'static str, &'static str) {
+    match attachment.kind() {
+        "image" if attachment.mime_type != "image/svg+xml" => ("sendPhoto", "photo"),
+        "video" => ("sendVideo", "video"),
+        "audio" if attachment.mime_type == "audio/ogg" => ("sendVoice", "voice"),
+        "audio" => ("sendAudio", "audio"),
+        _ => ("sendDocument", "document"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -622,6 +927,29 @@ mod tests {
 
     // ── File sending API URL tests ──────────────────────────────────
 
+    #[test]
+    fn telegram_callback_query_becomes_message() {
+        let ch = TelegramChannel::new("t".into(), vec!["alice".into()]);
+        let callback = serde_json::json!({
+            "id": "cb1",
+            "from": { "id": 42, "username": "alice" },
+            "message": { "chat": { "id": 1001 } },
+            "data": "/approve ab12cd34"
+        });
+        let msg = ch.callback_query_message(&callback).unwrap();
+        assert_eq!(msg.sender, "1001");
+        assert_eq!(msg.content, "/approve ab12cd34");
+        assert_eq!(msg.channel, "telegram");
+
+        let stranger = serde_json::json!({
+            "id": "cb2",
+            "from": { "id": 7, "username": "mallory" },
+            "message": { "chat": { "id": 1001 } },
+            "data": "/approve ab12cd34"
+        });
+        assert!(ch.callback_query_message(&stranger).is_none());
+    }
+
     #[test]
     fn telegram_api_url_send_document() {
         let ch = TelegramChannel::new("123:ABC".into(), vec![]);
@@ -865,4 +1193,96 @@ mod tests {
         // Should not panic
         assert!(result.is_err());
     }
+
+    #[test]
+    fn telegram_media_method_follows_mime_type() {
+        let method =
+            |name: &str, mime: &str| media_method(&Attachment::from_bytes(vec![], name, mime));
+        assert_eq!(method("a.png", "image/png"), ("sendPhoto", "photo"));
+        assert_eq!(
+            method("a.svg", "image/svg+xml"),
+            ("sendDocument", "document")
+        );
+        assert_eq!(method("a.mp4", "video/mp4"), ("sendVideo", "video"));
+        assert_eq!(method("a.ogg", "audio/ogg"), ("sendVoice", "voice"));
+        assert_eq!(method("a.mp3", "audio/mpeg"), ("sendAudio", "audio"));
+        assert_eq!(
+            method("a.pdf", "application/pdf"),
+            ("sendDocument", "document")
+        );
+    }
+
+    #[tokio::test]
+    async fn telegram_media_is_downloaded_into_the_inbox() {
+        use axum::{extract::Path, routing::get, routing::post, Json, Router};
+        use serde_json::json;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let delivered = Arc::new(AtomicBool::new(false));
+        let app = Router::new()
+            .route(
+                "/botT/getUpdates",
+                post(move || {
+                    let first = !delivered.swap(true, Ordering::SeqCst);
+                    async move {
+                        let result = if first {
+                            json!([{"update_id": 1, "message": {
+                                "from": {"id": 7, "username": "alice"},
+                                "chat": {"id": 42},
+                                "caption": "what is this?",
+                                "photo": [
+                                    {"file_id": "small", "file_unique_id": "p1", "file_size": 3},
+                                    {"file_id": "large", "file_unique_id": "p2", "file_size": 5}
+                                ],
+                                "document": {"file_id": "doc", "file_name": "huge.zip", "file_size": 1_000_000}
+                            }}])
+                        } else {
+                            tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                            json!([])
+                        };
+                        Json(json!({"ok": true, "result": result}))
+                    }
+                }),
+            )
+            .route("/botT/sendChatAction", post(|| async { Json(json!({"ok": true})) }))
+            .route(
+                "/botT/getFile",
+                post(|Json(body): Json<serde_json::Value>| async move {
+                    let path = format!("photos/{}.jpg", body["file_id"].as_str().unwrap());
+                    Json(json!({"ok": true, "result": {"file_path": path}}))
+                }),
+            )
+            .route(
+                "/file/botT/photos/:name",
+                get(|Path(name): Path<String>| async move { format!("bytes of {name}") }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut ch = TelegramChannel::new("T".into(), vec!["alice".into()])
+            .with_inbox(Some(Arc::new(Inbox::new(tmp.path(), 1024))));
+        ch.api_base = base;
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let listener = tokio::spawn(async move { ch.listen(tx).await });
+        let msg = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        listener.abort();
+
+        assert_eq!(msg.sender, "42");
+        assert!(msg
+            .content
+            .starts_with("what is this?\n[Attachment huge.zip was not saved"));
+        assert_eq!(msg.attachments.len(), 1);
+        let photo = &msg.attachments[0];
+        assert_eq!((photo.kind, photo.filename.as_str()), ("image", "photo_p2.jpg"));
+        assert_eq!(
+            std::fs::read_to_string(tmp.path().join(&photo.path)).unwrap(),
+            "bytes of large.jpg"
+        );
+    }
 }
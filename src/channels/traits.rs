@@ -1,13 +1,184 @@
+use crate::security::approval::ApprovalRequest;
 use async_trait::async_trait;
+use std::fmt::Write;
+use std::path::PathBuf;
 
 /// A message received from or sent to a channel
 #[derive(Debug, Clone)]
 pub struct ChannelMessage {
     pub id: String,
+    /// Where replies go: a user for direct messages, or a room, channel or
+    /// thread in group chats
     pub sender: String,
+    /// Who wrote the message, as the platform identifies them. Equal to
+    /// `sender` in direct messages.
+    pub author: String,
     pub content: String,
     pub channel: String,
     pub timestamp: u64,
+    /// Files that came with the message, already saved to the inbox
+    pub attachments: Vec<InboundAttachment>,
+}
+
+impl ChannelMessage {
+    /// The text the agent sees: the content followed by one line per
+    /// attachment, naming the workspace path to open it with.
+    pub fn prompt_text(&self) -> String {
+        let mut text = self.content.clone();
+        if self.attachments.is_empty() {
+            return text;
+        }
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str("[Attachments]");
+        for a in &self.attachments {
+            let _ = write!(
+                text,
+                "\n- {} {} ({}, {}, {} bytes)",
+                a.kind,
+                a.path.display(),
+                a.filename,
+                a.mime_type,
+                a.size
+            );
+        }
+        text
+    }
+}
+
+/// A file received with a message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundAttachment {
+    /// Broad media kind: image, video, audio or file
+    pub kind: &'static str,
+    /// Name the sender gave the file
+    pub filename: String,
+    pub mime_type: String,
+    /// Where the file was saved, relative to the workspace
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Broad media kind of a MIME type: image, video, audio or file.
+pub fn media_kind(mime_type: &str) -> &'static str {
+    match mime_type.split('/').next().unwrap_or_default() {
+        "image" => "image",
+        "video" => "video",
+        "audio" => "audio",
+        _ => "file",
+    }
+}
+
+/// Where an attachment's content comes from
+#[derive(Debug, Clone)]
+pub enum AttachmentSource {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+/// A file sent along with an outgoing message
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub source: AttachmentSource,
+    pub filename: String,
+    pub mime_type: String,
+    pub caption: Option<String>,
+}
+
+impl Attachment {
+    /// Attach the file at `path`, guessing the MIME type from its extension.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let filename = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("file")
+            .to_string();
+        let mime_type = mime_guess::from_path(&path)
+            .first_or_octet_stream()
+            .essence_str()
+            .to_string();
+        Self {
+            source: AttachmentSource::Path(path),
+            filename,
+            mime_type,
+            caption: None,
+        }
+    }
+
+    /// Attach in-memory content, e.g. a generated report or a screenshot.
+    pub fn from_bytes(
+        bytes: Vec<u8>,
+        filename: impl Into<String>,
+        mime_type: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: AttachmentSource::Bytes(bytes),
+            filename: filename.into(),
+            mime_type: mime_type.into(),
+            caption: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Content of the attachment, reading it from disk if needed.
+    pub async fn bytes(&self) -> anyhow::Result<Vec<u8>> {
+        match &self.source {
+            AttachmentSource::Bytes(bytes) => Ok(bytes.clone()),
+            AttachmentSource::Path(path) => tokio::fs::read(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to read attachment {}: {e}", path.display())),
+        }
+    }
+
+    /// Broad media kind from the MIME type: image, video, audio or file.
+    pub fn kind(&self) -> &'static str {
+        media_kind(&self.mime_type)
+    }
+}
+
+/// A reply with optional attachments
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingMessage {
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+}
+
+impl OutgoingMessage {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            attachments: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_attachment(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    /// Text-only rendering for channels that can't carry files: the message
+    /// followed by one line per attachment.
+    pub fn fallback_text(&self) -> String {
+        let mut lines = Vec::new();
+        if !self.text.trim().is_empty() {
+            lines.push(self.text.clone());
+        }
+        for attachment in &self.attachments {
+            lines.push(match &attachment.caption {
+                Some(caption) => format!("📎 {} — {caption}", attachment.filename),
+                None => format!("📎 {}", attachment.filename),
+            });
+        }
+        lines.join("\n")
+    }
 }
 
 /// Core channel trait — implement for any messaging platform
@@ -19,11 +190,102 @@ pub trait Channel: Send + Sync {
     /// Send a message through this channel
     async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()>;
 
+    /// Send text with attachments. The default sends the text and lists the
+    /// attachments by name; channels that can upload files override it.
+    async fn send_rich(&self, message: &OutgoingMessage, recipient: &str) -> anyhow::Result<()> {
+        self.send(&message.fallback_text(), recipient).await
+    }
+
     /// Start listening for incoming messages (long-running)
     async fn listen(&self, tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()>;
 
+    /// Ask `recipient` to approve or deny a held shell command. The default
+    /// sends the prompt as text; channels with buttons override it.
+    async fn send_approval_request(
+        &self,
+        request: &ApprovalRequest,
+        recipient: &str,
+    ) -> anyhow::Result<()> {
+        self.send(&request.prompt(), recipient).await
+    }
+
     /// Check if channel is healthy
     async fn health_check(&self) -> bool {
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_from_path_guesses_mime_type() {
+        let png = Attachment::from_path("/tmp/shot.png");
+        assert_eq!(png.filename, "shot.png");
+        assert_eq!(png.mime_type, "image/png");
+        assert_eq!(png.kind(), "image");
+
+        let unknown = Attachment::from_path("/tmp/data.unknownext");
+        assert_eq!(unknown.mime_type, "application/octet-stream");
+        assert_eq!(unknown.kind(), "file");
+    }
+
+    #[test]
+    fn fallback_text_lists_attachments() {
+        let message = OutgoingMessage::text("Here you go")
+            .with_attachment(Attachment::from_bytes(
+                b"a,b".to_vec(),
+                "report.csv",
+                "text/csv",
+            ))
+            .with_attachment(
+                Attachment::from_bytes(vec![], "shot.png", "image/png").with_caption("Login page"),
+            );
+        assert_eq!(
+            message.fallback_text(),
+            "Here you go\n📎 report.csv\n📎 shot.png — Login page"
+        );
+    }
+
+    #[test]
+    fn prompt_text_lists_inbound_attachments() {
+        let mut msg = ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            author: "alice".into(),
+            content: "what is this?".into(),
+            channel: "telegram".into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+        };
+        assert_eq!(msg.prompt_text(), "what is this?");
+
+        msg.attachments.push(InboundAttachment {
+            kind: "image",
+            filename: "photo.jpg".into(),
+            mime_type: "image/jpeg".into(),
+            path: PathBuf::from("inbox/telegram/photo.jpg"),
+            size: 1024,
+        });
+        assert_eq!(
+            msg.prompt_text(),
+            "what is this?\n\n[Attachments]\n- image inbox/telegram/photo.jpg (photo.jpg, image/jpeg, 1024 bytes)"
+        );
+    }
+
+    #[tokio::test]
+    async fn attachment_bytes_reads_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.txt");
+        std::fs::write(&path, "hello").unwrap();
+        assert_eq!(
+            Attachment::from_path(&path).bytes().await.unwrap(),
+            b"hello"
+        );
+        assert!(Attachment::from_path(dir.path().join("missing.txt"))
+            .bytes()
+            .await
+            .is_err());
+    }
+}
@@ -1,5 +1,8 @@
-use super::traits::{Channel, ChannelMessage};
+use super::inbox::{self, FileInfo, Inbox};
+use super::traits::{Attachment, Channel, ChannelMessage, InboundAttachment, OutgoingMessage};
 use async_trait::async_trait;
+use reqwest::multipart::{Form, Part};
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// `WhatsApp` channel — uses `WhatsApp` Business Cloud API
@@ -13,9 +16,16 @@ pub struct WhatsAppChannel {
     phone_number_id: String,
     verify_token: String,
     allowed_numbers: Vec<String>,
+    api_base: String,
+    inbox: Option<Arc<Inbox>>,
     client: reqwest::Client,
 }
 
+const GRAPH_API: &str = "https://graph.facebook.com/v18.0";
+
+/// Message types whose media is downloaded
+const MEDIA_TYPES: [&str; 5] = ["image", "document", "audio", "video", "sticker"];
+
 impl WhatsAppChannel {
     pub fn new(
         access_token: String,
@@ -28,10 +38,19 @@ impl WhatsAppChannel {
             phone_number_id,
             verify_token,
             allowed_numbers,
+            api_base: GRAPH_API.into(),
+            inbox: None,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Save images, voice notes, documents and other media into `inbox`.
+    #[must_use]
+    pub fn with_inbox(mut self, inbox: Option<Arc<Inbox>>) -> Self {
+        self.inbox = inbox;
+        self
+    }
+
     /// Check if a phone number is allowed (E.164 format: +1234567890)
     fn is_number_allowed(&self, phone: &str) -> bool {
         self.allowed_numbers.iter().any(|n| n == "*" || n == phone)
@@ -42,8 +61,69 @@ impl WhatsAppChannel {
         &self.verify_token
     }
 
-    /// Parse an incoming webhook payload from Meta and extract messages
-    pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+    fn api_url(&self, endpoint: &str) -> String {
+        format!("{}/{}/{endpoint}", self.api_base, self.phone_number_id)
+    }
+
+    /// POST a message object to the Cloud API
+    async fn post_message(&self, body: &serde_json::Value) -> anyhow::Result<()> {
+        let resp = self
+            .client
+            .post(self.api_url("messages"))
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .json(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp send failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp API error: {status}");
+        }
+
+        Ok(())
+    }
+
+    /// Upload an attachment to the Cloud API media store and return its id
+    async fn upload_media(&self, attachment: &Attachment) -> anyhow::Result<String> {
+        let part = Part::bytes(attachment.bytes().await?)
+            .file_name(attachment.filename.clone())
+            .mime_str(&attachment.mime_type)?;
+        let form = Form::new()
+            .text("messaging_product", "whatsapp")
+            .text("type", attachment.mime_type.clone())
+            .part("file", part);
+
+        let resp = self
+            .client
+            .post(self.api_url("media"))
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .multipart(form)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let error_body = resp.text().await.unwrap_or_default();
+            tracing::error!("WhatsApp media upload failed: {status} — {error_body}");
+            anyhow::bail!("WhatsApp media upload error: {status}");
+        }
+
+        let body: serde_json::Value = resp.json().await?;
+        body.get("id")
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string)
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media upload returned no id"))
+    }
+
+    /// Messages of a webhook payload from allowed numbers, as
+    /// `(sender, message, timestamp)`.
+    fn webhook_messages<'a>(
+        &self,
+        payload: &'a serde_json::Value,
+    ) -> Vec<(String, &'a serde_json::Value, u64)> {
         let mut messages = Vec::new();
 
         // WhatsApp Cloud API webhook structure:
@@ -88,23 +168,6 @@ impl WhatsAppChannel {
                         continue;
                     }
 
-                    // Extract text content (support text messages only for now)
-                    let content = if let Some(text_obj) = msg.get("text") {
-                        text_obj
-                            .get("body")
-                            .and_then(|b| b.as_str())
-                            .unwrap_or("")
-                            .to_string()
-                    } else {
-                        // Could be image, audio, etc. — skip for now
-                        tracing::debug!("WhatsApp: skipping non-text message from {from}");
-                        continue;
-                    };
-
-                    if content.is_empty() {
-                        continue;
-                    }
-
                     // Get timestamp
                     let timestamp = msg
                         .get("timestamp")
@@ -117,19 +180,133 @@ impl WhatsAppChannel {
                                 .as_secs()
                         });
 
-                    messages.push(ChannelMessage {
-                        id: Uuid::new_v4().to_string(),
-                        sender: normalized_from,
-                        content,
-                        channel: "whatsapp".to_string(),
-                        timestamp,
-                    });
+                    messages.push((normalized_from, msg, timestamp));
                 }
             }
         }
 
         messages
     }
+
+    /// Parse an incoming webhook payload from Meta and extract its text
+    /// messages. [`Self::receive_webhook`] also downloads media messages.
+    pub fn parse_webhook_payload(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        self.webhook_messages(payload)
+            .into_iter()
+            .filter_map(|(sender, msg, timestamp)| {
+                let content = msg.get("text")?.get("body")?.as_str()?;
+                if content.is_empty() {
+                    return None;
+                }
+                Some(ChannelMessage {
+                    id: Uuid::new_v4().to_string(),
+                    author: sender.clone(),
+                    sender,
+                    content: content.to_string(),
+                    channel: "whatsapp".to_string(),
+                    timestamp,
+                    attachments: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
+    /// Extract the messages of a webhook payload, downloading the media of
+    /// image, audio, video, document and sticker messages into the inbox.
+    pub async fn receive_webhook(&self, payload: &serde_json::Value) -> Vec<ChannelMessage> {
+        let mut messages = Vec::new();
+        for (sender, msg, timestamp) in self.webhook_messages(payload) {
+            let kind = msg.get("type").and_then(|t| t.as_str()).unwrap_or("text");
+            let media = MEDIA_TYPES.contains(&kind).then(|| msg.get(kind)).flatten();
+            let mut content = msg
+                .get("text")
+                .and_then(|t| t.get("body"))
+                .or_else(|| media.and_then(|m| m.get("caption")))
+                .and_then(|b| b.as_str())
+                .unwrap_or_default()
+                .to_string();
+
+            let mut attachments = Vec::new();
+            if let Some(media) = media {
+                let filename = media_filename(kind, media);
+                match self.download_media(media, &filename).await {
+                    Ok(attachment) => attachments.push(attachment),
+                    Err(e) => inbox::note_skipped("whatsapp", &mut content, &filename, &e),
+                }
+            } else if content.is_empty() {
+                tracing::debug!("WhatsApp: skipping {kind} message from {sender}");
+                continue;
+            }
+
+            messages.push(ChannelMessage {
+                id: Uuid::new_v4().to_string(),
+                author: sender.clone(),
+                sender,
+                content,
+                channel: "whatsapp".to_string(),
+                timestamp,
+                attachments,
+            });
+        }
+        messages
+    }
+
+    /// Look up a media object's download URL and fetch it into the inbox.
+    async fn download_media(
+        &self,
+        media: &serde_json::Value,
+        filename: &str,
+    ) -> anyhow::Result<InboundAttachment> {
+        let inbox = self
+            .inbox
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("attachments are disabled"))?;
+        let id = media
+            .get("id")
+            .and_then(|i| i.as_str())
+            .ok_or_else(|| anyhow::anyhow!("media has no id"))?;
+
+        let resp = self
+            .client
+            .get(format!("{}/{id}", self.api_base))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("WhatsApp media lookup failed: {}", resp.status());
+        }
+        let meta: serde_json::Value = resp.json().await?;
+        let url = meta
+            .get("url")
+            .and_then(|u| u.as_str())
+            .ok_or_else(|| anyhow::anyhow!("WhatsApp media lookup returned no url"))?;
+        let mime = meta
+            .get("mime_type")
+            .or_else(|| media.get("mime_type"))
+            .and_then(|m| m.as_str())
+            .unwrap_or("application/octet-stream");
+        let size = meta.get("file_size").and_then(serde_json::Value::as_u64);
+
+        let info = FileInfo::new(filename, mime, size);
+        let request = self.client.get(url).bearer_auth(&self.access_token);
+        inbox.download("whatsapp", &info, request).await
+    }
+}
+
+/// Name for a received media file: the sender's name for documents,
+/// otherwise derived from the media id and MIME type.
+fn media_filename(kind: &str, media: &serde_json::Value) -> String {
+    if let Some(name) = media.get("filename").and_then(|f| f.as_str()) {
+        return name.to_string();
+    }
+    let id = media.get("id").and_then(|i| i.as_str()).unwrap_or("media");
+    let extension = media
+        .get("mime_type")
+        .and_then(|m| m.as_str())
+        .and_then(|m| mime_guess::get_mime_extensions_str(m.split(';').next().unwrap_or(m)))
+        .and_then(|exts| exts.first())
+        .unwrap_or(&"bin");
+    format!("{kind}_{id}.{extension}")
 }
 
 #[async_trait]
@@ -140,11 +317,6 @@ impl Channel for WhatsAppChannel {
 
     async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
         // WhatsApp Cloud API: POST to /v18.0/{phone_number_id}/messages
-        let url = format!(
-            "https://graph.facebook.com/v18.0/{}/messages",
-            self.phone_number_id
-        );
-
         // Normalize recipient (remove leading + if present for API)
         let to = recipient.strip_prefix('+').unwrap_or(recipient);
 
@@ -159,22 +331,23 @@ impl Channel for WhatsAppChannel {
             }
         });
 
-        let resp = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.access_token))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
+        self.post_message(&body).await
+    }
 
-        if !resp.status().is_success() {
-            let status = resp.status();
-            let error_body = resp.text().await.unwrap_or_default();
-            tracing::error!("WhatsApp send failed: {status} — {error_body}");
-            anyhow::bail!("WhatsApp API error: {status}");
+    async fn send_rich(&self, message: &OutgoingMessage, recipient: &str) -> anyhow::Result<()> {
+        if !message.text.trim().is_empty() {
+            self.send(&message.text, recipient).await?;
+        }
+        let to = recipient.strip_prefix('+').unwrap_or(recipient);
+        for attachment in &message.attachments {
+            let media_id = self.upload_media(attachment).await?;
+            self.post_message(&media_message(to, &media_id, attachment))
+                .await?;
+            // Audio messages can't carry a caption
+            if let (Some(caption), "audio") = (&attachment.caption, attachment.kind()) {
+                self.send(caption, recipient).await?;
+            }
         }
-
         Ok(())
     }
 
@@ -195,7 +368,7 @@ impl Channel for WhatsAppChannel {
 
     async fn health_check(&self) -> bool {
         // Check if we can reach the WhatsApp API
-        let url = format!("https://graph.facebook.com/v18.0/{}", self.phone_number_id);
+        let url = format!("{}/{}", self.api_base, self.phone_number_id);
 
         self.client
             .get(&url)
@@ -207,6 +380,29 @@ impl Channel for WhatsAppChannel {
     }
 }
 
+/// Cloud API message object sending uploaded media `media_id` to `to`.
+/// Files that aren't images, video or audio go out as documents.
+fn media_message(to: &str, media_id: &str, attachment: &Attachment) -> serde_json::Value {
+    let kind = match attachment.kind() {
+        "file" => "document",
+        kind => kind,
+    };
+    let mut media = serde_json::json!({ "id": media_id });
+    if kind == "document" {
+        media["filename"] = serde_json::json!(attachment.filename);
+    }
+    if let (Some(caption), true) = (&attachment.caption, kind != "audio") {
+        media["caption"] = serde_json::json!(caption);
+    }
+    serde_json::json!({
+        "messaging_product": "whatsapp",
+        "recipient_type": "individual",
+        "to": to,
+        "type": kind,
+        kind: media
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,6 +416,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn whatsapp_media_message_types() {
+        let photo =
+            Attachment::from_bytes(vec![], "shot.png", "image/png").with_caption("Login page");
+        let msg = media_message("1234567890", "M1", &photo);
+        assert_eq!(msg["type"], "image");
+        assert_eq!(msg["image"]["id"], "M1");
+        assert_eq!(msg["image"]["caption"], "Login page");
+
+        let pdf = Attachment::from_bytes(vec![], "report.pdf", "application/pdf");
+        let msg = media_message("1234567890", "M2", &pdf);
+        assert_eq!(msg["type"], "document");
+        assert_eq!(msg["document"]["filename"], "report.pdf");
+
+        let voice = Attachment::from_bytes(vec![], "note.ogg", "audio/ogg").with_caption("ignored");
+        let msg = media_message("1234567890", "M3", &voice);
+        assert_eq!(msg["type"], "audio");
+        assert!(msg["audio"].get("caption").is_none());
+    }
+
     #[test]
     fn whatsapp_channel_name() {
         let ch = make_channel();
@@ -1107,4 +1323,79 @@ mod tests {
             "<script>alert('xss')</script> & \"quotes\" 'apostrophe'"
         );
     }
+
+    #[tokio::test]
+    async fn whatsapp_media_is_downloaded_into_the_inbox() {
+        use axum::{extract::Path, http::HeaderMap, routing::get, Json, Router};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let media_base = base.clone();
+        let app = Router::new()
+            .route(
+                "/:id",
+                get(move |Path(id): Path<String>| {
+                    let base = media_base.clone();
+                    async move {
+                        Json(serde_json::json!({
+                            "url": format!("{base}/files/{id}"),
+                            "mime_type": "image/jpeg",
+                            "file_size": if id == "BIG" { 1u64 << 30 } else { 4 },
+                        }))
+                    }
+                }),
+            )
+            .route(
+                "/files/:id",
+                get(|headers: HeaderMap| async move {
+                    assert_eq!(headers["authorization"], "Bearer tok");
+                    "jpeg"
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let mut ch =
+            WhatsAppChannel::new("tok".into(), "123".into(), "ver".into(), vec!["*".into()])
+                .with_inbox(Some(Arc::new(Inbox::new(tmp.path(), 1024))));
+        ch.api_base = base;
+
+        let payload = serde_json::json!({
+            "entry": [{
+                "changes": [{
+                    "value": {
+                        "messages": [
+                            {
+                                "from": "111",
+                                "timestamp": "1",
+                                "type": "image",
+                                "image": { "id": "IMG", "mime_type": "image/jpeg", "caption": "my cat" }
+                            },
+                            {
+                                "from": "111",
+                                "timestamp": "2",
+                                "type": "document",
+                                "document": { "id": "BIG", "filename": "huge.pdf" }
+                            },
+                            { "from": "111", "timestamp": "3", "type": "location", "location": {} }
+                        ]
+                    }
+                }]
+            }]
+        });
+        let msgs = ch.receive_webhook(&payload).await;
+        assert_eq!(msgs.len(), 2);
+
+        assert_eq!(msgs[0].content, "my cat");
+        let photo = &msgs[0].attachments[0];
+        assert_eq!(photo.kind, "image");
+        assert!(photo.filename.starts_with("image_IMG."));
+        assert_eq!(
+            std::fs::read(tmp.path().join(&photo.path)).unwrap(),
+            b"jpeg"
+        );
+
+        assert!(msgs[1].attachments.is_empty());
+        assert!(msgs[1].content.contains("huge.pdf was not saved"));
+    }
 }
@@ -245,7 +245,7 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "custom:URL" | "ollama" | "ollama:URL" | "local:PATH"
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
     /// Embedding model name (e.g. "text-embedding-3-small")
@@ -266,6 +266,12 @@ pub struct MemoryConfig {
     /// Max tokens per chunk for document splitting
     #[serde(default = "default_chunk_size")]
     pub chunk_max_tokens: usize,
+    /// Vector search strategy: "hnsw" (approximate index) | "exact" (linear scan)
+    #[serde(default = "default_vector_index")]
+    pub vector_index: String,
+    /// Store int8-quantized vectors in the HNSW index (4x smaller, slightly less precise)
+    #[serde(default)]
+    pub vector_index_quantized: bool,
 }
 
 fn default_embedding_provider() -> String {
@@ -301,6 +307,9 @@ fn default_cache_size() -> usize {
 fn default_chunk_size() -> usize {
     512
 }
+fn default_vector_index() -> String {
+    "hnsw".into()
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -318,6 +327,8 @@ impl Default for MemoryConfig {
             keyword_weight: default_keyword_weight(),
             embedding_cache_size: default_cache_size(),
             chunk_max_tokens: default_chunk_size(),
+            vector_index: default_vector_index(),
+            vector_index_quantized: false,
         }
     }
 }
@@ -357,6 +368,8 @@ pub struct AutonomyConfig {
     pub allowed_commands: Vec<String>,
     pub forbidden_paths: Vec<String>,
     pub max_actions_per_hour: u32,
+    /// Daily LLM spend budget in US cents, reset at 00:00 UTC. `0` disables
+    /// the limit.
     pub max_cost_per_day_cents: u32,
 
     /// Require explicit approval for medium-risk shell commands.
@@ -366,6 +379,24 @@ pub struct AutonomyConfig {
     /// Block high-risk shell commands even if allowlisted.
     #[serde(default = "default_true")]
     pub block_high_risk_commands: bool,
+
+    /// How long a risky shell command waits for the operator's decision
+    /// before it is denied.
+    #[serde(default = "default_approval_timeout_secs")]
+    pub approval_timeout_secs: u64,
+
+    /// Most jobs each conversation may have scheduled at once through the
+    /// agent's `schedule` tool. `0` disables the tool's create action.
+    #[serde(default = "default_max_scheduled_jobs")]
+    pub max_scheduled_jobs: u32,
+}
+
+fn default_approval_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_scheduled_jobs() -> u32 {
+    20
 }
 
 impl Default for AutonomyConfig {
@@ -411,6 +442,8 @@ impl Default for AutonomyConfig {
             max_cost_per_day_cents: 500,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            approval_timeout_secs: default_approval_timeout_secs(),
+            max_scheduled_jobs: default_max_scheduled_jobs(),
         }
     }
 }
@@ -603,6 +636,13 @@ pub struct ModelRouteConfig {
 pub struct HeartbeatConfig {
     pub enabled: bool,
     pub interval_minutes: u32,
+    /// Channel to send task results to (e.g. "telegram"); results are only
+    /// logged and saved to memory when unset.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Recipient on `channel` (chat id, user id, address...)
+    #[serde(default)]
+    pub recipient: Option<String>,
 }
 
 impl Default for HeartbeatConfig {
@@ -610,6 +650,8 @@ impl Default for HeartbeatConfig {
         Self {
             enabled: false,
             interval_minutes: 30,
+            channel: None,
+            recipient: None,
         }
     }
 }
@@ -694,6 +736,30 @@ pub struct ChannelsConfig {
     pub whatsapp: Option<WhatsAppConfig>,
     pub email: Option<crate::channels::email_channel::EmailConfig>,
     pub irc: Option<IrcConfig>,
+    /// Minutes a channel conversation may sit idle before its history is
+    /// dropped and the next message starts a new session.
+    #[serde(default = "default_session_idle_minutes")]
+    pub session_idle_minutes: u64,
+    /// How many channel messages are handled at once. Messages from the same
+    /// conversation are always handled one after another.
+    #[serde(default = "default_max_concurrent_messages")]
+    pub max_concurrent_messages: usize,
+    /// Largest attachment, in MiB, downloaded into `<workspace>/inbox`.
+    /// 0 disables attachment downloads.
+    #[serde(default = "default_max_attachment_mb")]
+    pub max_attachment_mb: u64,
+}
+
+fn default_session_idle_minutes() -> u64 {
+    24 * 60
+}
+
+fn default_max_concurrent_messages() -> usize {
+    4
+}
+
+fn default_max_attachment_mb() -> u64 {
+    20
 }
 
 impl Default for ChannelsConfig {
@@ -709,6 +775,9 @@ impl Default for ChannelsConfig {
             whatsapp: None,
             email: None,
             irc: None,
+            session_idle_minutes: default_session_idle_minutes(),
+            max_concurrent_messages: default_max_concurrent_messages(),
+            max_attachment_mb: default_max_attachment_mb(),
         }
     }
 }
@@ -730,7 +799,9 @@ pub struct DiscordConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlackConfig {
     pub bot_token: String,
+    /// App-level token (`xapp-...`); enables Socket Mode event delivery
     pub app_token: Option<String>,
+    /// Only handle messages from this channel (DMs are always handled)
     pub channel_id: Option<String>,
     #[serde(default)]
     pub allowed_users: Vec<String>,
@@ -751,8 +822,24 @@ pub struct IMessageConfig {
 pub struct MatrixConfig {
     pub homeserver: String,
     pub access_token: String,
+    /// Room for messages sent without a target; the bot answers in every
+    /// room it has joined
     pub room_id: String,
     pub allowed_users: Vec<String>,
+    /// Per-room allowlists, used instead of `allowed_users` in those rooms
+    #[serde(default)]
+    pub room_allowed_users: std::collections::HashMap<String, Vec<String>>,
+    /// Join rooms when an allowed user invites the bot
+    #[serde(default = "default_true")]
+    pub auto_join: bool,
+    /// Read and send end-to-end encrypted messages (needs a device-bound
+    /// access token whose device has no keys from another client)
+    #[serde(default = "default_true")]
+    pub e2ee: bool,
+    /// Ed25519 fingerprints of the only devices to exchange encrypted
+    /// messages with; empty trusts every device with valid self-signed keys
+    #[serde(default)]
+    pub verified_devices: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1109,6 +1196,8 @@ mod tests {
                 max_cost_per_day_cents: 1000,
                 require_approval_for_medium_risk: false,
                 block_high_risk_commands: true,
+                approval_timeout_secs: 60,
+                max_scheduled_jobs: 5,
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
@@ -1119,6 +1208,8 @@ mod tests {
             heartbeat: HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 15,
+                channel: Some("telegram".into()),
+                recipient: Some("42".into()),
             },
             channels_config: ChannelsConfig {
                 cli: true,
@@ -1134,6 +1225,9 @@ mod tests {
                 whatsapp: None,
                 email: None,
                 irc: None,
+                session_idle_minutes: 60,
+                max_concurrent_messages: 2,
+                max_attachment_mb: 20,
             },
             memory: MemoryConfig::default(),
             tunnel: TunnelConfig::default(),
@@ -1157,6 +1251,8 @@ mod tests {
         assert_eq!(parsed.runtime.kind, "docker");
         assert!(parsed.heartbeat.enabled);
         assert_eq!(parsed.heartbeat.interval_minutes, 15);
+        assert_eq!(parsed.heartbeat.channel.as_deref(), Some("telegram"));
+        assert_eq!(parsed.heartbeat.recipient.as_deref(), Some("42"));
         assert!(parsed.channels_config.telegram.is_some());
         assert_eq!(
             parsed.channels_config.telegram.unwrap().bot_token,
@@ -1337,6 +1433,10 @@ default_temperature = 0.7
             access_token: "syt_token_abc".into(),
             room_id: "!room123:matrix.org".into(),
             allowed_users: vec!["@user:matrix.org".into()],
+            room_allowed_users: std::collections::HashMap::new(),
+            auto_join: true,
+            e2ee: true,
+            verified_devices: vec![],
         };
         let json = serde_json::to_string(&mc).unwrap();
         let parsed: MatrixConfig = serde_json::from_str(&json).unwrap();
@@ -1353,11 +1453,24 @@ default_temperature = 0.7
             access_token: "tok".into(),
             room_id: "!abc:synapse.local".into(),
             allowed_users: vec!["@admin:synapse.local".into(), "*".into()],
+            room_allowed_users: std::collections::HashMap::from([(
+                "!ops:synapse.local".into(),
+                vec!["@oncall:synapse.local".into()],
+            )]),
+            auto_join: false,
+            e2ee: true,
+            verified_devices: vec!["fingerprint".into()],
         };
         let toml_str = toml::to_string(&mc).unwrap();
         let parsed: MatrixConfig = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.homeserver, "https://synapse.local:8448");
         assert_eq!(parsed.allowed_users.len(), 2);
+        assert_eq!(
+            parsed.room_allowed_users["!ops:synapse.local"],
+            vec!["@oncall:synapse.local"]
+        );
+        assert!(!parsed.auto_join);
+        assert_eq!(parsed.verified_devices, vec!["fingerprint"]);
     }
 
     #[test]
@@ -1376,10 +1489,17 @@ default_temperature = 0.7
                 access_token: "tok".into(),
                 room_id: "!r:m".into(),
                 allowed_users: vec!["@u:m".into()],
+                room_allowed_users: std::collections::HashMap::new(),
+                auto_join: true,
+                e2ee: true,
+                verified_devices: vec![],
             }),
             whatsapp: None,
             email: None,
             irc: None,
+            session_idle_minutes: 60,
+            max_concurrent_messages: 2,
+            max_attachment_mb: 20,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -1538,6 +1658,9 @@ channel_id = "C123"
             }),
             email: None,
             irc: None,
+            session_idle_minutes: 60,
+            max_concurrent_messages: 2,
+            max_attachment_mb: 20,
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
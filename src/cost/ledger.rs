@@ -0,0 +1,281 @@
+use crate::providers::traits::TokenUsage;
+use anyhow::{Context, Result};
+use chrono::{Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// How many days of history the ledger keeps on disk.
+const RETENTION_DAYS: i64 = 90;
+
+/// Ledgers handed out by `CostLedger::shared`, keyed by file path.
+static SHARED: OnceLock<Mutex<HashMap<PathBuf, Arc<CostLedger>>>> = OnceLock::new();
+
+/// Spend recorded for one UTC day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailySpend {
+    pub cost_cents: f64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub requests: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LedgerFile {
+    #[serde(default)]
+    days: BTreeMap<NaiveDate, DailySpend>,
+}
+
+/// Daily LLM spend, persisted as JSON under the workspace so every process
+/// sharing the workspace (daemon, CLI agent, cron) draws from one budget.
+#[derive(Debug)]
+pub struct CostLedger {
+    path: Option<PathBuf>,
+    state: Mutex<LedgerState>,
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    file: LedgerFile,
+    /// Usage recorded through this handle since it was opened.
+    session: TokenUsage,
+}
+
+impl CostLedger {
+    /// Ledger backed by `<workspace>/costs/ledger.json`.
+    pub fn open(workspace_dir: &Path) -> Self {
+        Self {
+            path: Some(workspace_dir.join("costs").join("ledger.json")),
+            state: Mutex::new(LedgerState::default()),
+        }
+    }
+
+    /// The process-wide ledger for `workspace_dir`. Every policy built for
+    /// the same workspace (gateway, channels, heartbeat, cron) records
+    /// through this one handle, so their updates are serialized.
+    pub fn shared(workspace_dir: &Path) -> Arc<Self> {
+        let ledger = Self::open(workspace_dir);
+        let path = ledger.path.clone().unwrap_or_default();
+        let mut shared = SHARED
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Arc::clone(shared.entry(path).or_insert_with(|| Arc::new(ledger)))
+    }
+
+    /// Ledger that lives only as long as this value (tests, throwaway policies).
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            state: Mutex::new(LedgerState::default()),
+        }
+    }
+
+    /// Record one LLM call and return the updated total for today.
+    pub fn record(&self, usage: TokenUsage, cost_cents: f64) -> DailySpend {
+        self.record_on(Utc::now().date_naive(), usage, cost_cents)
+    }
+
+    /// Spend recorded so far today (UTC).
+    pub fn today(&self) -> DailySpend {
+        let today = Utc::now().date_naive();
+        let mut state = self.lock();
+        self.reload(&mut state);
+        state.file.days.get(&today).copied().unwrap_or_default()
+    }
+
+    /// Tokens recorded through this handle since it was opened.
+    pub fn session_usage(&self) -> TokenUsage {
+        self.lock().session
+    }
+
+    fn record_on(&self, date: NaiveDate, usage: TokenUsage, cost_cents: f64) -> DailySpend {
+        let mut state = self.lock();
+        // Hold the file lock across re-read and save so concurrent processes
+        // add to, rather than overwrite, each other's totals.
+        let mut file_lock = self.file_lock();
+        let _guard = file_lock.as_mut().and_then(|lock| match lock.write() {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                tracing::warn!("Failed to lock cost ledger: {e}");
+                None
+            }
+        });
+        self.reload(&mut state);
+
+        let day = state.file.days.entry(date).or_default();
+        day.cost_cents += cost_cents;
+        day.prompt_tokens += usage.prompt_tokens;
+        day.completion_tokens += usage.completion_tokens;
+        day.requests += 1;
+        let total = *day;
+
+        let cutoff = date - Duration::days(RETENTION_DAYS);
+        state.file.days.retain(|d, _| *d > cutoff);
+        state.session.prompt_tokens += usage.prompt_tokens;
+        state.session.completion_tokens += usage.completion_tokens;
+
+        if let Err(e) = self.save(&state.file) {
+            tracing::warn!("Failed to persist cost ledger: {e}");
+        }
+        total
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, LedgerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Lock file guarding writers across processes. The ledger itself is
+    /// replaced by rename on every save, so it cannot carry the lock.
+    fn file_lock(&self) -> Option<fd_lock::RwLock<std::fs::File>> {
+        let path = self.path.as_ref()?.with_extension("lock");
+        let parent = path.parent()?;
+        let file = std::fs::create_dir_all(parent).and_then(|()| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+        });
+        match file {
+            Ok(file) => Some(fd_lock::RwLock::new(file)),
+            Err(e) => {
+                tracing::warn!("Failed to open {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    fn reload(&self, state: &mut LedgerState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        match std::fs::read_to_string(path) {
+            Ok(raw) => match serde_json::from_str(&raw) {
+                Ok(file) => state.file = file,
+                Err(e) => tracing::warn!("Ignoring unreadable cost ledger {}: {e}", path.display()),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => tracing::warn!("Failed to read cost ledger {}: {e}", path.display()),
+        }
+    }
+
+    fn save(&self, file: &LedgerFile) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let parent = path.parent().context("Cost ledger path has no parent")?;
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+
+        let temp_path = parent.join(format!(".ledger.json.tmp-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&temp_path, serde_json::to_vec_pretty(file)?)
+            .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+        std::fs::rename(&temp_path, path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn usage(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn accumulates_todays_spend() {
+        let ledger = CostLedger::in_memory();
+        ledger.record(usage(100, 20), 1.5);
+        let total = ledger.record(usage(50, 10), 2.0);
+
+        assert!((total.cost_cents - 3.5).abs() < 1e-9);
+        assert_eq!(total.prompt_tokens, 150);
+        assert_eq!(total.completion_tokens, 30);
+        assert_eq!(total.requests, 2);
+        assert_eq!(ledger.today(), total);
+        assert_eq!(ledger.session_usage().total(), 180);
+    }
+
+    #[test]
+    fn persists_across_handles() {
+        let tmp = TempDir::new().unwrap();
+        let first = CostLedger::open(tmp.path());
+        first.record(usage(10, 10), 4.0);
+
+        let second = CostLedger::open(tmp.path());
+        assert!((second.today().cost_cents - 4.0).abs() < 1e-9);
+        second.record(usage(10, 10), 1.0);
+
+        // The first handle sees the second one's write instead of clobbering it.
+        let total = first.record(usage(0, 0), 0.0);
+        assert!((total.cost_cents - 5.0).abs() < 1e-9);
+        assert_eq!(first.session_usage().total(), 20);
+        assert!(tmp.path().join("costs/ledger.json").exists());
+    }
+
+    #[test]
+    fn concurrent_writers_do_not_lose_spend() {
+        let tmp = TempDir::new().unwrap();
+        let ledger = CostLedger::shared(tmp.path());
+        assert!(Arc::ptr_eq(&ledger, &CostLedger::shared(tmp.path())));
+
+        // Odd writers stand in for other processes with their own handle
+        let writers: Vec<_> = (0..8)
+            .map(|i| {
+                let path = tmp.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let ledger = if i % 2 == 0 {
+                        CostLedger::shared(&path)
+                    } else {
+                        Arc::new(CostLedger::open(&path))
+                    };
+                    for _ in 0..10 {
+                        ledger.record(usage(1, 1), 1.0);
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let total = CostLedger::open(tmp.path()).today();
+        assert_eq!(total.requests, 80);
+        assert!((total.cost_cents - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prunes_old_days() {
+        let ledger = CostLedger::in_memory();
+        let today = Utc::now().date_naive();
+        ledger.record_on(today - Duration::days(RETENTION_DAYS + 5), usage(1, 1), 1.0);
+        ledger.record_on(today - Duration::days(1), usage(1, 1), 1.0);
+        ledger.record_on(today, usage(1, 1), 1.0);
+
+        let state = ledger.lock();
+        assert_eq!(state.file.days.len(), 2);
+        assert!(state.file.days.contains_key(&(today - Duration::days(1))));
+    }
+
+    #[test]
+    fn corrupt_file_is_ignored() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("costs")).unwrap();
+        std::fs::write(tmp.path().join("costs/ledger.json"), "not json").unwrap();
+
+        let ledger = CostLedger::open(tmp.path());
+        assert_eq!(ledger.today(), DailySpend::default());
+        let total = ledger.record(usage(1, 1), 2.0);
+        assert!((total.cost_cents - 2.0).abs() < 1e-9);
+    }
+}
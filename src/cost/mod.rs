@@ -0,0 +1,123 @@
+//! Token usage accounting: a per-model price table and a daily spend ledger
+//! that `SecurityPolicy` checks against `max_cost_per_day_cents`.
+
+pub mod ledger;
+pub mod pricing;
+
+pub use ledger::{CostLedger, DailySpend};
+pub use pricing::cost_cents;
+
+use crate::providers::{ConversationMessage, Provider};
+use crate::security::SecurityPolicy;
+use anyhow::Result;
+
+/// Single tool-less LLM call for entry points outside the agent loop
+/// (channels, gateway webhooks). Refuses once the daily budget is spent and
+/// charges the call's token usage to it otherwise.
+pub async fn chat_within_budget(
+    provider: &dyn Provider,
+    security: &SecurityPolicy,
+    messages: &[ConversationMessage],
+    model: &str,
+    temperature: f64,
+) -> Result<String> {
+    security.check_budget().map_err(anyhow::Error::msg)?;
+    let response = provider
+        .chat_with_tools(messages, &[], model, temperature)
+        .await?;
+    if let Some(usage) = response.usage {
+        security.record_usage(&provider.resolved_model(model), usage);
+    }
+    Ok(response.text.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::{ChatResponse, TokenUsage};
+    use crate::providers::ChatMessage;
+    use crate::tools::ToolSpec;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct MeteredProvider {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MeteredProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            anyhow::bail!("metered calls go through chat_with_tools")
+        }
+
+        fn resolved_model(&self, model: &str) -> String {
+            if model == "hint:smart" {
+                "gpt-4o".into()
+            } else {
+                model.to_string()
+            }
+        }
+
+        async fn chat_with_tools(
+            &self,
+            _messages: &[ConversationMessage],
+            _tools: &[ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<ChatResponse> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(ChatResponse {
+                text: Some("hello".into()),
+                tool_calls: vec![],
+                // 2k in + 500 out on gpt-4o = 1 cent
+                usage: Some(TokenUsage {
+                    prompt_tokens: 2_000,
+                    completion_tokens: 500,
+                }),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn chat_within_budget_charges_and_then_refuses() {
+        let provider = MeteredProvider {
+            calls: AtomicUsize::new(0),
+        };
+        let security = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            ..SecurityPolicy::default()
+        };
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hi"))];
+
+        let reply = chat_within_budget(&provider, &security, &messages, "gpt-4o", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(reply, "hello");
+        assert_eq!(security.cost_ledger.today().requests, 1);
+
+        let err = chat_within_budget(&provider, &security, &messages, "gpt-4o", 0.0)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("budget exhausted"));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn route_hints_are_charged_at_the_resolved_model_price() {
+        let provider = MeteredProvider {
+            calls: AtomicUsize::new(0),
+        };
+        let security = SecurityPolicy::default();
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hi"))];
+
+        chat_within_budget(&provider, &security, &messages, "hint:smart", 0.0)
+            .await
+            .unwrap();
+        assert!((security.cost_ledger.today().cost_cents - 1.0).abs() < 1e-9);
+    }
+}
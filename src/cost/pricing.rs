@@ -0,0 +1,152 @@
+use crate::providers::traits::TokenUsage;
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+/// List price for a model in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl ModelPrice {
+    const fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    /// Cost of `usage` in US cents.
+    pub fn cost_cents(&self, usage: TokenUsage) -> f64 {
+        #[allow(clippy::cast_precision_loss)]
+        let dollars = (usage.prompt_tokens as f64 * self.input_per_million
+            + usage.completion_tokens as f64 * self.output_per_million)
+            / 1_000_000.0;
+        dollars * 100.0
+    }
+}
+
+/// Built-in price table, matched by model-name prefix (longest wins) so dated
+/// snapshots like `claude-3-5-sonnet-20241022` resolve to their family.
+const PRICES: &[(&str, ModelPrice)] = &[
+    // Anthropic
+    ("claude-opus-4-5", ModelPrice::new(5.0, 25.0)),
+    ("claude-opus-4", ModelPrice::new(15.0, 75.0)),
+    ("claude-sonnet-4", ModelPrice::new(3.0, 15.0)),
+    ("claude-haiku-4", ModelPrice::new(1.0, 5.0)),
+    ("claude-3-opus", ModelPrice::new(15.0, 75.0)),
+    ("claude-3-7-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-sonnet", ModelPrice::new(3.0, 15.0)),
+    ("claude-3-5-haiku", ModelPrice::new(0.8, 4.0)),
+    ("claude-3-haiku", ModelPrice::new(0.25, 1.25)),
+    // OpenAI
+    ("gpt-5", ModelPrice::new(1.25, 10.0)),
+    ("gpt-5-mini", ModelPrice::new(0.25, 2.0)),
+    ("gpt-5-nano", ModelPrice::new(0.05, 0.4)),
+    ("gpt-4.1", ModelPrice::new(2.0, 8.0)),
+    ("gpt-4.1-mini", ModelPrice::new(0.4, 1.6)),
+    ("gpt-4.1-nano", ModelPrice::new(0.1, 0.4)),
+    ("gpt-4o", ModelPrice::new(2.5, 10.0)),
+    ("gpt-4o-mini", ModelPrice::new(0.15, 0.6)),
+    ("gpt-4-turbo", ModelPrice::new(10.0, 30.0)),
+    ("o1", ModelPrice::new(15.0, 60.0)),
+    ("o1-mini", ModelPrice::new(1.1, 4.4)),
+    ("o3", ModelPrice::new(2.0, 8.0)),
+    ("o3-mini", ModelPrice::new(1.1, 4.4)),
+    ("o4-mini", ModelPrice::new(1.1, 4.4)),
+    // Google
+    ("gemini-2.5-pro", ModelPrice::new(1.25, 10.0)),
+    ("gemini-2.5-flash", ModelPrice::new(0.3, 2.5)),
+    ("gemini-2.5-flash-lite", ModelPrice::new(0.1, 0.4)),
+    ("gemini-2.0-flash", ModelPrice::new(0.1, 0.4)),
+    ("gemini-1.5-pro", ModelPrice::new(1.25, 5.0)),
+    ("gemini-1.5-flash", ModelPrice::new(0.075, 0.3)),
+    // DeepSeek
+    ("deepseek-chat", ModelPrice::new(0.27, 1.1)),
+    ("deepseek-reasoner", ModelPrice::new(0.55, 2.19)),
+];
+
+/// Look up the price for a model. Gateway routing prefixes, as in
+/// `anthropic/claude-sonnet-4`, are stripped before matching.
+pub fn price_for(model: &str) -> Option<ModelPrice> {
+    let name = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    PRICES
+        .iter()
+        .filter(|(prefix, _)| name.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, price)| *price)
+}
+
+/// Models already warned about in `cost_cents`.
+static UNPRICED_WARNED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Cost of `usage` on `model` in US cents. Models missing from the table
+/// (local models, unknown gateways) cost nothing; a warning is logged once
+/// per model so the gap in accounting is visible.
+pub fn cost_cents(model: &str, usage: TokenUsage) -> f64 {
+    if let Some(price) = price_for(model) {
+        return price.cost_cents(usage);
+    }
+
+    let mut warned = UNPRICED_WARNED
+        .get_or_init(|| Mutex::new(HashSet::new()))
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if warned.insert(model.to_string()) {
+        tracing::warn!(
+            "No price known for model {model}; its usage is not counted toward the daily budget"
+        );
+    }
+    0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        assert_eq!(
+            price_for("gpt-4o-mini-2024-07-18"),
+            price_for("gpt-4o-mini")
+        );
+        assert_ne!(price_for("gpt-4o-mini"), price_for("gpt-4o"));
+        let opus_4_5 = price_for("claude-opus-4-5-20251101").unwrap();
+        let opus_4_1 = price_for("claude-opus-4-1-20250805").unwrap();
+        assert!((opus_4_5.input_per_million - 5.0).abs() < f64::EPSILON);
+        assert!((opus_4_1.input_per_million - 15.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn strips_routing_prefix_and_ignores_case() {
+        assert_eq!(
+            price_for("anthropic/claude-sonnet-4"),
+            price_for("claude-sonnet-4-20250514")
+        );
+        assert!(price_for("openai/GPT-4o").is_some());
+    }
+
+    #[test]
+    fn unknown_models_are_free() {
+        assert!(price_for("llama3.2").is_none());
+        assert!(cost_cents("llama3.2", usage(1_000_000, 1_000_000)).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn cost_is_in_cents() {
+        // 1M input at $3 + 1M output at $15 = $18
+        let cents = cost_cents("claude-sonnet-4", usage(1_000_000, 1_000_000));
+        assert!((cents - 1800.0).abs() < 1e-9);
+
+        let cents = cost_cents("gpt-4o", usage(2_000, 500));
+        assert!((cents - 1.0).abs() < 1e-9);
+    }
+}
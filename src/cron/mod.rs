@@ -1,83 +1,417 @@
 use crate::config::Config;
+use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use cron::Schedule;
+use chrono::{DateTime, Duration, Utc};
 use rusqlite::{params, Connection};
-use std::str::FromStr;
+use schedule::JobSchedule;
 use uuid::Uuid;
 
+pub mod schedule;
 pub mod scheduler;
 
+/// What a job runs when it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobKind {
+    /// `command` is a shell command.
+    Shell,
+    /// `command` is a prompt answered by the agent loop, with tools and memory.
+    Agent,
+}
+
+impl JobKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Shell => "shell",
+            Self::Agent => "agent",
+        }
+    }
+
+    fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "shell" => Ok(Self::Shell),
+            "agent" => Ok(Self::Agent),
+            other => anyhow::bail!("Unknown cron job kind in cron DB: {other}"),
+        }
+    }
+}
+
+/// Where a job's result is sent: a configured channel and a recipient on it
+/// (chat id, Slack channel, email address, ...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delivery {
+    pub channel: String,
+    pub recipient: String,
+}
+
+/// What the scheduler does with a run that was due while the daemon was down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MissedRuns {
+    /// Run once as soon as the daemon is back.
+    #[default]
+    CatchUp,
+    /// Drop the missed run and wait for the next occurrence.
+    Skip,
+}
+
+impl MissedRuns {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::CatchUp => "catch-up",
+            Self::Skip => "skip",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "catch-up" => Ok(Self::CatchUp),
+            "skip" => Ok(Self::Skip),
+            other => {
+                anyhow::bail!("Unknown missed-run policy: {other} (expected catch-up or skip)")
+            }
+        }
+    }
+}
+
+/// Optional settings for a new job.
+#[derive(Debug, Clone, Default)]
+pub struct ScheduleOptions {
+    /// IANA timezone the expression is evaluated in; UTC when unset.
+    pub timezone: Option<String>,
+    /// Each run of a recurring job is delayed by up to this many seconds.
+    pub jitter_secs: u64,
+    pub missed: MissedRuns,
+    /// Conversation that created the job through the agent's `schedule` tool.
+    pub owner: Option<Delivery>,
+}
+
+/// Changes to an existing job; `None` fields are left as they are.
+#[derive(Debug, Clone, Default)]
+pub struct JobUpdate {
+    pub expression: Option<String>,
+    pub command: Option<String>,
+    pub timezone: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct CronJob {
     pub id: String,
     pub expression: String,
+    pub kind: JobKind,
+    /// Shell command, or the prompt for agent jobs.
     pub command: String,
+    pub delivery: Option<Delivery>,
     pub next_run: DateTime<Utc>,
     pub last_run: Option<DateTime<Utc>>,
     pub last_status: Option<String>,
+    /// Paused jobs keep their schedule but are skipped until resumed.
+    pub paused: bool,
+    pub timezone: Option<String>,
+    pub jitter_secs: u64,
+    pub missed: MissedRuns,
+    /// Conversation that created the job through the agent's `schedule`
+    /// tool; `None` for jobs added from the CLI.
+    pub owner: Option<Delivery>,
+}
+
+/// Result of executing a job once, including any retries.
+#[derive(Debug, Clone)]
+pub struct JobOutcome {
+    pub success: bool,
+    pub output: String,
+    pub attempts: u32,
+    /// Exit code of the last attempt, for shell jobs.
+    pub exit_code: Option<i32>,
 }
 
+/// One recorded execution from the `cron_runs` table.
+#[derive(Debug, Clone)]
+pub struct CronRun {
+    pub id: i64,
+    pub job_id: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub attempts: u32,
+    pub status: String,
+    pub exit_code: Option<i32>,
+    pub output: String,
+}
+
+/// Output kept per run; longer output is truncated.
+const MAX_RUN_OUTPUT_CHARS: usize = 16_000;
+/// Runs kept per job; older runs are pruned as new ones are recorded.
+const MAX_RUNS_PER_JOB: i64 = 100;
+
 #[allow(clippy::needless_pass_by_value)]
-pub fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
+pub async fn handle_command(command: crate::CronCommands, config: &Config) -> Result<()> {
     match command {
-        crate::CronCommands::List => {
-            let jobs = list_jobs(config)?;
-            if jobs.is_empty() {
-                println!("No scheduled tasks yet.");
-                println!("\nUsage:");
-                println!("  viziclaw cron add '0 9 * * *' 'agent -m \"Good morning!\"'");
-                return Ok(());
-            }
-
-            println!("🕒 Scheduled jobs ({}):", jobs.len());
-            for job in jobs {
-                let last_run = job
-                    .last_run
-                    .map_or_else(|| "never".into(), |d| d.to_rfc3339());
-                let last_status = job.last_status.unwrap_or_else(|| "n/a".into());
-                println!(
-                    "- {} | {} | next={} | last={} ({})\n    cmd: {}",
-                    job.id,
-                    job.expression,
-                    job.next_run.to_rfc3339(),
-                    last_run,
-                    last_status,
-                    job.command
-                );
-            }
-            Ok(())
-        }
+        crate::CronCommands::List => print_jobs(config),
         crate::CronCommands::Add {
             expression,
             command,
+            at,
+            in_,
+            tz,
+            jitter,
+            missed,
+            agent,
+            channel,
+            to,
         } => {
-            let job = add_job(config, &expression, &command)?;
-            println!("✅ Added cron job {}", job.id);
-            println!("  Expr: {}", job.expression);
-            println!("  Next: {}", job.next_run.to_rfc3339());
-            println!("  Cmd : {}", job.command);
+            let timezone = schedule::parse_timezone(tz.as_deref())?;
+            let (expression, command) = resolve_schedule(expression, command, at, in_, timezone)?;
+            let options = ScheduleOptions {
+                timezone: tz,
+                jitter_secs: jitter,
+                missed: MissedRuns::parse(&missed)?,
+                owner: None,
+            };
+            let job = if agent {
+                let delivery = match (channel, to) {
+                    (Some(channel), Some(recipient)) => {
+                        if crate::channels::configured_channel(config, &channel).is_none() {
+                            anyhow::bail!("Channel '{channel}' is not configured");
+                        }
+                        Some(Delivery { channel, recipient })
+                    }
+                    _ => None,
+                };
+                create_job(
+                    config,
+                    &expression,
+                    JobKind::Agent,
+                    &command,
+                    delivery,
+                    &options,
+                )?
+            } else {
+                create_job(
+                    config,
+                    &expression,
+                    JobKind::Shell,
+                    &command,
+                    None,
+                    &options,
+                )?
+            };
+            print_added(&job);
             Ok(())
         }
         crate::CronCommands::Remove { id } => remove_job(config, &id),
+        crate::CronCommands::Runs { id, limit } => print_runs(config, &id, limit),
+        crate::CronCommands::Output { run_id } => print_run(config, run_id),
+        crate::CronCommands::RunNow { id } => {
+            let job = get_job(config, &id)?;
+            println!("▶️  Running cron job {id}...");
+            let outcome = scheduler::run_now(config, &job).await?;
+            let status = if outcome.success { "✅" } else { "❌" };
+            println!("{status} Finished after {} attempt(s)", outcome.attempts);
+            println!();
+            println!("{}", outcome.output);
+            Ok(())
+        }
+        crate::CronCommands::Pause { id } => {
+            set_paused(config, &id, true)?;
+            println!("⏸️  Paused cron job {id}");
+            Ok(())
+        }
+        crate::CronCommands::Resume { id } => {
+            set_paused(config, &id, false)?;
+            println!("▶️  Resumed cron job {id}");
+            Ok(())
+        }
+    }
+}
+
+fn print_jobs(config: &Config) -> Result<()> {
+    let jobs = list_jobs(config)?;
+    if jobs.is_empty() {
+        println!("No scheduled tasks yet.");
+        println!("\nUsage:");
+        println!("  viziclaw cron add '*/30 * * * *' 'git -C ~/notes pull'");
+        println!("  viziclaw cron add --tz Europe/Berlin 'every 15m' 'git -C ~/notes pull'");
+        println!("  viziclaw cron add --in 2h 'echo reminder'");
+        println!(
+            "  viziclaw cron add --agent --channel telegram --to <chat-id> '0 9 * * *' 'Give me a morning briefing'"
+        );
+        return Ok(());
     }
+
+    println!("🕒 Scheduled jobs ({}):", jobs.len());
+    for job in jobs {
+        let last_run = job
+            .last_run
+            .map_or_else(|| "never".into(), |d| d.to_rfc3339());
+        let last_status = job.last_status.unwrap_or_else(|| "n/a".into());
+        let label = match job.kind {
+            JobKind::Shell => "cmd",
+            JobKind::Agent => "prompt",
+        };
+        let paused = if job.paused { " [paused]" } else { "" };
+        let timezone = job
+            .timezone
+            .map_or_else(String::new, |tz| format!(" ({tz})"));
+        println!(
+            "- {}{paused} | {}{timezone} | next={} | last={} ({})\n    {label}: {}",
+            job.id,
+            job.expression,
+            job.next_run.to_rfc3339(),
+            last_run,
+            last_status,
+            job.command
+        );
+        if let Some(delivery) = &job.delivery {
+            println!("    to: {} {}", delivery.channel, delivery.recipient);
+        }
+    }
+    Ok(())
+}
+
+/// The stored expression and command for `cron add`: `--at`/`--in` turn the
+/// single positional argument into the command of a one-shot job.
+fn resolve_schedule(
+    expression: Option<String>,
+    command: Option<String>,
+    at: Option<String>,
+    delay: Option<String>,
+    timezone: chrono_tz::Tz,
+) -> Result<(String, String)> {
+    let one_shot = match (at, delay) {
+        (Some(at), _) => Some(schedule::one_shot_at(&at, timezone)?),
+        (None, Some(delay)) => Some(schedule::one_shot_in(&delay, Utc::now())?),
+        (None, None) => None,
+    };
+    match (one_shot, expression, command) {
+        (Some(at), Some(command), None) => Ok((at, command)),
+        (None, Some(expression), Some(command)) => Ok((expression, command)),
+        (Some(_), _, _) => anyhow::bail!("With --at or --in, give only the command to run"),
+        (None, _, _) => anyhow::bail!("Give a schedule and a command to run"),
+    }
+}
+
+fn print_added(job: &CronJob) {
+    println!("✅ Added cron job {}", job.id);
+    println!("  Expr: {}", job.expression);
+    if let Some(timezone) = &job.timezone {
+        println!("  TZ  : {timezone}");
+    }
+    println!("  Next: {}", job.next_run.to_rfc3339());
+    match job.kind {
+        JobKind::Shell => println!("  Cmd : {}", job.command),
+        JobKind::Agent => println!("  Ask : {}", job.command),
+    }
+    if let Some(delivery) = &job.delivery {
+        println!("  To  : {} {}", delivery.channel, delivery.recipient);
+    }
+}
+
+fn print_runs(config: &Config, id: &str, limit: usize) -> Result<()> {
+    let job = get_job(config, id)?;
+    let runs = list_runs(config, &job.id, limit)?;
+    if runs.is_empty() {
+        println!("Job {id} has not run yet.");
+        return Ok(());
+    }
+    println!("🕒 Recent runs of {id} ({}):", runs.len());
+    for run in runs {
+        let exit = run
+            .exit_code
+            .map_or_else(String::new, |code| format!(" exit={code}"));
+        println!(
+            "- #{} | {} | {} | {}ms | attempts={}{exit}",
+            run.id,
+            run.started_at.to_rfc3339(),
+            run.status,
+            run.duration_ms,
+            run.attempts
+        );
+    }
+    Ok(())
+}
+
+fn print_run(config: &Config, run_id: i64) -> Result<()> {
+    let run = get_run(config, run_id)?;
+    println!("Run #{} of job {}", run.id, run.job_id);
+    println!("  Started : {}", run.started_at.to_rfc3339());
+    println!("  Finished: {}", run.finished_at.to_rfc3339());
+    println!("  Status  : {} ({} attempts)", run.status, run.attempts);
+    if let Some(code) = run.exit_code {
+        println!("  Exit    : {code}");
+    }
+    println!();
+    println!("{}", run.output);
+    Ok(())
 }
 
 pub fn add_job(config: &Config, expression: &str, command: &str) -> Result<CronJob> {
+    create_job(
+        config,
+        expression,
+        JobKind::Shell,
+        command,
+        None,
+        &ScheduleOptions::default(),
+    )
+}
+
+/// Schedule `prompt` to run through the agent, optionally sending the final
+/// answer to `delivery`.
+pub fn add_agent_job(
+    config: &Config,
+    expression: &str,
+    prompt: &str,
+    delivery: Option<Delivery>,
+) -> Result<CronJob> {
+    create_job(
+        config,
+        expression,
+        JobKind::Agent,
+        prompt,
+        delivery,
+        &ScheduleOptions::default(),
+    )
+}
+
+/// Schedule a job from a cron expression, `every <interval>` or
+/// `at <RFC 3339 time>`.
+pub fn create_job(
+    config: &Config,
+    expression: &str,
+    kind: JobKind,
+    command: &str,
+    delivery: Option<Delivery>,
+    options: &ScheduleOptions,
+) -> Result<CronJob> {
     let now = Utc::now();
-    let next_run = next_run_for(expression, now)?;
+    let next_run = next_run_after(
+        expression,
+        options.timezone.as_deref(),
+        options.jitter_secs,
+        now,
+    )?
+    .ok_or_else(|| anyhow::anyhow!("Scheduled time is in the past: {expression}"))?;
     let id = Uuid::new_v4().to_string();
 
     with_connection(config, |conn| {
         conn.execute(
-            "INSERT INTO cron_jobs (id, expression, command, created_at, next_run)
-             VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO cron_jobs
+                (id, expression, kind, command, channel, recipient, created_at, next_run,
+                 timezone, jitter_secs, missed, owner_channel, owner_recipient)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
             params![
                 id,
                 expression,
+                kind.as_str(),
                 command,
+                delivery.as_ref().map(|d| d.channel.as_str()),
+                delivery.as_ref().map(|d| d.recipient.as_str()),
                 now.to_rfc3339(),
-                next_run.to_rfc3339()
+                next_run.to_rfc3339(),
+                options.timezone,
+                i64::try_from(options.jitter_secs).unwrap_or(i64::MAX),
+                options.missed.as_str(),
+                options.owner.as_ref().map(|o| o.channel.as_str()),
+                options.owner.as_ref().map(|o| o.recipient.as_str())
             ],
         )
         .context("Failed to insert cron job")?;
@@ -87,57 +421,68 @@ pub fn add_job(config: &Config, expression: &str, command: &str) -> Result<CronJ
     Ok(CronJob {
         id,
         expression: expression.to_string(),
+        kind,
         command: command.to_string(),
+        delivery,
         next_run,
         last_run: None,
         last_status: None,
+        paused: false,
+        timezone: options.timezone.clone(),
+        jitter_secs: options.jitter_secs,
+        missed: options.missed,
+        owner: options.owner.clone(),
     })
 }
 
-pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
-    with_connection(config, |conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, expression, command, next_run, last_run, last_status
-             FROM cron_jobs ORDER BY next_run ASC",
-        )?;
+/// Change a job's schedule, timezone or command. A new schedule or timezone
+/// moves the job to its next occurrence from now.
+pub fn update_job(config: &Config, id: &str, update: &JobUpdate) -> Result<CronJob> {
+    let mut job = get_job(config, id)?;
+    if let Some(command) = &update.command {
+        job.command.clone_from(command);
+    }
+    if update.expression.is_some() || update.timezone.is_some() {
+        if let Some(expression) = &update.expression {
+            job.expression.clone_from(expression);
+        }
+        if let Some(timezone) = &update.timezone {
+            job.timezone = Some(timezone.clone());
+        }
+        job.next_run = next_occurrence(&job, Utc::now())?
+            .ok_or_else(|| anyhow::anyhow!("Scheduled time is in the past: {}", job.expression))?;
+    }
 
-        let rows = stmt.query_map([], |row| {
-            let next_run_raw: String = row.get(3)?;
-            let last_run_raw: Option<String> = row.get(4)?;
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                next_run_raw,
-                last_run_raw,
-                row.get::<_, Option<String>>(5)?,
-            ))
-        })?;
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET expression = ?1, command = ?2, timezone = ?3, next_run = ?4
+             WHERE id = ?5",
+            params![
+                job.expression,
+                job.command,
+                job.timezone,
+                job.next_run.to_rfc3339(),
+                job.id
+            ],
+        )
+        .context("Failed to update cron job")?;
+        Ok(())
+    })?;
+    Ok(job)
+}
 
-        let mut jobs = Vec::new();
-        for row in rows {
-            let (id, expression, command, next_run_raw, last_run_raw, last_status) = row?;
-            jobs.push(CronJob {
-                id,
-                expression,
-                command,
-                next_run: parse_rfc3339(&next_run_raw)?,
-                last_run: match last_run_raw {
-                    Some(raw) => Some(parse_rfc3339(&raw)?),
-                    None => None,
-                },
-                last_status,
-            });
-        }
-        Ok(jobs)
+pub fn list_jobs(config: &Config) -> Result<Vec<CronJob>> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM cron_jobs ORDER BY next_run ASC"
+        ))?;
+        let rows = stmt.query_map([], job_row)?;
+        collect_jobs(rows)
     })
 }
 
 pub fn remove_job(config: &Config, id: &str) -> Result<()> {
-    let changed = with_connection(config, |conn| {
-        conn.execute("DELETE FROM cron_jobs WHERE id = ?1", params![id])
-            .context("Failed to delete cron job")
-    })?;
+    let changed = with_connection(config, |conn| delete_job(conn, id))?;
 
     if changed == 0 {
         anyhow::bail!("Cron job '{id}' not found");
@@ -147,45 +492,135 @@ pub fn remove_job(config: &Config, id: &str) -> Result<()> {
     Ok(())
 }
 
+/// Delete a job and its run history; returns the number of jobs deleted.
+fn delete_job(conn: &Connection, id: &str) -> Result<usize> {
+    conn.execute("DELETE FROM cron_runs WHERE job_id = ?1", params![id])
+        .context("Failed to delete cron job runs")?;
+    conn.execute("DELETE FROM cron_jobs WHERE id = ?1", params![id])
+        .context("Failed to delete cron job")
+}
+
+pub fn get_job(config: &Config, id: &str) -> Result<CronJob> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM cron_jobs WHERE id = ?1"
+        ))?;
+        let rows = stmt.query_map(params![id], job_row)?;
+        collect_jobs(rows)
+    })?
+    .pop()
+    .ok_or_else(|| anyhow::anyhow!("Cron job '{id}' not found"))
+}
+
+/// Pause or resume a job without losing its schedule or history.
+pub fn set_paused(config: &Config, id: &str, paused: bool) -> Result<()> {
+    let job = get_job(config, id)?;
+    // A resumed job waits for its next occurrence rather than firing for
+    // the time it spent paused; a one-shot job whose time has passed runs on
+    // the next poll.
+    let next_run = if paused {
+        job.next_run
+    } else {
+        next_occurrence(&job, Utc::now())?.unwrap_or(job.next_run)
+    };
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs SET paused = ?1, next_run = ?2 WHERE id = ?3",
+            params![paused, next_run.to_rfc3339(), id],
+        )
+        .context("Failed to update cron job")?;
+        Ok(())
+    })
+}
+
 pub fn due_jobs(config: &Config, now: DateTime<Utc>) -> Result<Vec<CronJob>> {
     with_connection(config, |conn| {
-        let mut stmt = conn.prepare(
-            "SELECT id, expression, command, next_run, last_run, last_status
-             FROM cron_jobs WHERE next_run <= ?1 ORDER BY next_run ASC",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {JOB_COLUMNS} FROM cron_jobs
+             WHERE next_run <= ?1 AND paused = 0 ORDER BY next_run ASC"
+        ))?;
+        let rows = stmt.query_map(params![now.to_rfc3339()], job_row)?;
+        collect_jobs(rows)
+    })
+}
 
-        let rows = stmt.query_map(params![now.to_rfc3339()], |row| {
-            let next_run_raw: String = row.get(3)?;
-            let last_run_raw: Option<String> = row.get(4)?;
-            Ok((
-                row.get::<_, String>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                next_run_raw,
-                last_run_raw,
-                row.get::<_, Option<String>>(5)?,
-            ))
-        })?;
+const JOB_COLUMNS: &str = "id, expression, kind, command, channel, recipient, next_run, last_run,
+     last_status, paused, timezone, jitter_secs, missed, owner_channel, owner_recipient";
 
-        let mut jobs = Vec::new();
-        for row in rows {
-            let (id, expression, command, next_run_raw, last_run_raw, last_status) = row?;
-            jobs.push(CronJob {
-                id,
-                expression,
-                command,
-                next_run: parse_rfc3339(&next_run_raw)?,
-                last_run: match last_run_raw {
-                    Some(raw) => Some(parse_rfc3339(&raw)?),
-                    None => None,
-                },
-                last_status,
-            });
-        }
-        Ok(jobs)
+/// `JOB_COLUMNS` as stored; timestamps and kind are parsed by `collect_jobs`.
+struct JobRow {
+    id: String,
+    expression: String,
+    kind: String,
+    command: String,
+    channel: Option<String>,
+    recipient: Option<String>,
+    next_run: String,
+    last_run: Option<String>,
+    last_status: Option<String>,
+    paused: bool,
+    timezone: Option<String>,
+    jitter_secs: i64,
+    missed: String,
+    owner_channel: Option<String>,
+    owner_recipient: Option<String>,
+}
+
+fn job_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<JobRow> {
+    Ok(JobRow {
+        id: row.get(0)?,
+        expression: row.get(1)?,
+        kind: row.get(2)?,
+        command: row.get(3)?,
+        channel: row.get(4)?,
+        recipient: row.get(5)?,
+        next_run: row.get(6)?,
+        last_run: row.get(7)?,
+        last_status: row.get(8)?,
+        paused: row.get(9)?,
+        timezone: row.get(10)?,
+        jitter_secs: row.get(11)?,
+        missed: row.get(12)?,
+        owner_channel: row.get(13)?,
+        owner_recipient: row.get(14)?,
     })
 }
 
+fn collect_jobs(rows: impl Iterator<Item = rusqlite::Result<JobRow>>) -> Result<Vec<CronJob>> {
+    let mut jobs = Vec::new();
+    for row in rows {
+        let row = row?;
+        jobs.push(CronJob {
+            id: row.id,
+            expression: row.expression,
+            kind: JobKind::parse(&row.kind)?,
+            command: row.command,
+            delivery: match (row.channel, row.recipient) {
+                (Some(channel), Some(recipient)) => Some(Delivery { channel, recipient }),
+                _ => None,
+            },
+            next_run: parse_rfc3339(&row.next_run)?,
+            last_run: match row.last_run {
+                Some(raw) => Some(parse_rfc3339(&raw)?),
+                None => None,
+            },
+            last_status: row.last_status,
+            paused: row.paused,
+            timezone: row.timezone,
+            jitter_secs: u64::try_from(row.jitter_secs).unwrap_or(0),
+            missed: MissedRuns::parse(&row.missed)?,
+            owner: match (row.owner_channel, row.owner_recipient) {
+                (Some(channel), Some(recipient)) => Some(Delivery { channel, recipient }),
+                _ => None,
+            },
+        });
+    }
+    Ok(jobs)
+}
+
+/// Move `job` past a run. A one-shot job deletes itself once it succeeds;
+/// if it fails it is paused instead, so it can be inspected and re-run.
 pub fn reschedule_after_run(
     config: &Config,
     job: &CronJob,
@@ -193,19 +628,29 @@ pub fn reschedule_after_run(
     output: &str,
 ) -> Result<()> {
     let now = Utc::now();
-    let next_run = next_run_for(&job.expression, now)?;
+    // A one-shot job is done once it has run, even if run early with run-now
+    let next_run = if JobSchedule::parse(&job.expression)?.is_one_shot() {
+        None
+    } else {
+        next_occurrence(job, now)?
+    };
+    if next_run.is_none() && success {
+        return with_connection(config, |conn| delete_job(conn, &job.id).map(|_| ()));
+    }
     let status = if success { "ok" } else { "error" };
 
     with_connection(config, |conn| {
         conn.execute(
             "UPDATE cron_jobs
-             SET next_run = ?1, last_run = ?2, last_status = ?3, last_output = ?4
-             WHERE id = ?5",
+             SET next_run = ?1, last_run = ?2, last_status = ?3, last_output = ?4,
+                 paused = paused OR ?5
+             WHERE id = ?6",
             params![
-                next_run.to_rfc3339(),
+                next_run.unwrap_or(job.next_run).to_rfc3339(),
                 now.to_rfc3339(),
                 status,
                 output,
+                next_run.is_none(),
                 job.id
             ],
         )
@@ -214,29 +659,175 @@ pub fn reschedule_after_run(
     })
 }
 
-fn next_run_for(expression: &str, from: DateTime<Utc>) -> Result<DateTime<Utc>> {
-    let normalized = normalize_expression(expression)?;
-    let schedule = Schedule::from_str(&normalized)
-        .with_context(|| format!("Invalid cron expression: {expression}"))?;
-    schedule
-        .after(&from)
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("No future occurrence for expression: {expression}"))
+/// Move `job` past a run it missed while the daemon was down, without
+/// running it. A missed one-shot job is paused.
+pub fn skip_missed_run(config: &Config, job: &CronJob) -> Result<()> {
+    let next_run = next_occurrence(job, Utc::now())?;
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "UPDATE cron_jobs
+             SET next_run = ?1, last_status = 'skipped', paused = paused OR ?2
+             WHERE id = ?3",
+            params![
+                next_run.unwrap_or(job.next_run).to_rfc3339(),
+                next_run.is_none(),
+                job.id
+            ],
+        )
+        .context("Failed to update cron job run state")?;
+        Ok(())
+    })
+}
+
+/// Record one execution of `job_id` and prune its oldest runs beyond the
+/// retention limit. Returns the new run id.
+pub fn record_run(
+    config: &Config,
+    job_id: &str,
+    started_at: DateTime<Utc>,
+    finished_at: DateTime<Utc>,
+    outcome: &JobOutcome,
+) -> Result<i64> {
+    let status = if outcome.success { "ok" } else { "error" };
+    let output = truncate_with_ellipsis(&outcome.output, MAX_RUN_OUTPUT_CHARS);
+    let duration_ms = (finished_at - started_at).num_milliseconds();
+
+    with_connection(config, |conn| {
+        conn.execute(
+            "INSERT INTO cron_runs
+                (job_id, started_at, finished_at, duration_ms, attempts, status, exit_code, output)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                job_id,
+                started_at.to_rfc3339(),
+                finished_at.to_rfc3339(),
+                duration_ms,
+                outcome.attempts,
+                status,
+                outcome.exit_code,
+                output
+            ],
+        )
+        .context("Failed to record cron run")?;
+        let run_id = conn.last_insert_rowid();
+
+        conn.execute(
+            "DELETE FROM cron_runs WHERE job_id = ?1 AND id NOT IN (
+                SELECT id FROM cron_runs WHERE job_id = ?1 ORDER BY id DESC LIMIT ?2
+            )",
+            params![job_id, MAX_RUNS_PER_JOB],
+        )
+        .context("Failed to prune cron runs")?;
+        Ok(run_id)
+    })
+}
+
+/// The most recent `limit` runs of `job_id`, newest first.
+pub fn list_runs(config: &Config, job_id: &str, limit: usize) -> Result<Vec<CronRun>> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {RUN_COLUMNS} FROM cron_runs WHERE job_id = ?1 ORDER BY id DESC LIMIT ?2"
+        ))?;
+        let rows = stmt.query_map(
+            params![job_id, i64::try_from(limit).unwrap_or(i64::MAX)],
+            run_row,
+        )?;
+        collect_runs(rows)
+    })
+}
+
+pub fn get_run(config: &Config, run_id: i64) -> Result<CronRun> {
+    with_connection(config, |conn| {
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {RUN_COLUMNS} FROM cron_runs WHERE id = ?1"
+        ))?;
+        let rows = stmt.query_map(params![run_id], run_row)?;
+        collect_runs(rows)
+    })?
+    .pop()
+    .ok_or_else(|| anyhow::anyhow!("Cron run #{run_id} not found"))
+}
+
+const RUN_COLUMNS: &str =
+    "id, job_id, started_at, finished_at, duration_ms, attempts, status, exit_code, output";
+
+/// `RUN_COLUMNS` as stored; timestamps are parsed by `collect_runs`.
+struct RunRow {
+    id: i64,
+    job_id: String,
+    started_at: String,
+    finished_at: String,
+    duration_ms: i64,
+    attempts: u32,
+    status: String,
+    exit_code: Option<i32>,
+    output: String,
+}
+
+fn run_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<RunRow> {
+    Ok(RunRow {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        started_at: row.get(2)?,
+        finished_at: row.get(3)?,
+        duration_ms: row.get(4)?,
+        attempts: row.get(5)?,
+        status: row.get(6)?,
+        exit_code: row.get(7)?,
+        output: row.get(8)?,
+    })
+}
+
+fn collect_runs(rows: impl Iterator<Item = rusqlite::Result<RunRow>>) -> Result<Vec<CronRun>> {
+    let mut runs = Vec::new();
+    for row in rows {
+        let row = row?;
+        runs.push(CronRun {
+            id: row.id,
+            job_id: row.job_id,
+            started_at: parse_rfc3339(&row.started_at)?,
+            finished_at: parse_rfc3339(&row.finished_at)?,
+            duration_ms: row.duration_ms,
+            attempts: row.attempts,
+            status: row.status,
+            exit_code: row.exit_code,
+            output: row.output,
+        });
+    }
+    Ok(runs)
 }
 
-fn normalize_expression(expression: &str) -> Result<String> {
-    let expression = expression.trim();
-    let field_count = expression.split_whitespace().count();
+/// When `job` next runs after `from`, or `None` once a one-shot job is done.
+fn next_occurrence(job: &CronJob, from: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    next_run_after(
+        &job.expression,
+        job.timezone.as_deref(),
+        job.jitter_secs,
+        from,
+    )
+}
 
-    match field_count {
-        // standard crontab syntax: minute hour day month weekday
-        5 => Ok(format!("0 {expression}")),
-        // crate-native syntax includes seconds (+ optional year)
-        6 | 7 => Ok(expression.to_string()),
-        _ => anyhow::bail!(
-            "Invalid cron expression: {expression} (expected 5, 6, or 7 fields, got {field_count})"
-        ),
+fn next_run_after(
+    expression: &str,
+    timezone: Option<&str>,
+    jitter_secs: u64,
+    from: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>> {
+    let schedule = JobSchedule::parse(expression)?;
+    let Some(next) = schedule.next_after(from, schedule::parse_timezone(timezone)?) else {
+        if schedule.is_one_shot() {
+            return Ok(None);
+        }
+        anyhow::bail!("No future occurrence for expression: {expression}");
+    };
+    if schedule.is_one_shot() || jitter_secs == 0 {
+        return Ok(Some(next));
     }
+    let jitter = u64::from(Utc::now().timestamp_subsec_nanos()) % (jitter_secs + 1);
+    Ok(Some(
+        next + Duration::seconds(i64::try_from(jitter).unwrap_or(0)),
+    ))
 }
 
 fn parse_rfc3339(raw: &str) -> Result<DateTime<Utc>> {
@@ -259,20 +850,70 @@ fn with_connection<T>(config: &Config, f: impl FnOnce(&Connection) -> Result<T>)
         "CREATE TABLE IF NOT EXISTS cron_jobs (
             id          TEXT PRIMARY KEY,
             expression  TEXT NOT NULL,
+            kind        TEXT NOT NULL DEFAULT 'shell',
             command     TEXT NOT NULL,
+            channel     TEXT,
+            recipient   TEXT,
             created_at  TEXT NOT NULL,
             next_run    TEXT NOT NULL,
             last_run    TEXT,
             last_status TEXT,
-            last_output TEXT
+            last_output TEXT,
+            paused      INTEGER NOT NULL DEFAULT 0,
+            timezone    TEXT,
+            jitter_secs INTEGER NOT NULL DEFAULT 0,
+            missed      TEXT NOT NULL DEFAULT 'catch-up',
+            owner_channel   TEXT,
+            owner_recipient TEXT
         );
-        CREATE INDEX IF NOT EXISTS idx_cron_jobs_next_run ON cron_jobs(next_run);",
+        CREATE INDEX IF NOT EXISTS idx_cron_jobs_next_run ON cron_jobs(next_run);
+        CREATE TABLE IF NOT EXISTS cron_runs (
+            id          INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id      TEXT NOT NULL,
+            started_at  TEXT NOT NULL,
+            finished_at TEXT NOT NULL,
+            duration_ms INTEGER NOT NULL,
+            attempts    INTEGER NOT NULL,
+            status      TEXT NOT NULL,
+            exit_code   INTEGER,
+            output      TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_cron_runs_job ON cron_runs(job_id, id);",
     )
     .context("Failed to initialize cron schema")?;
+    migrate_schema(&conn)?;
 
     f(&conn)
 }
 
+/// Add columns introduced after the table was first created.
+fn migrate_schema(conn: &Connection) -> Result<()> {
+    let existing: Vec<String> = conn
+        .prepare("PRAGMA table_info(cron_jobs)")?
+        .query_map([], |row| row.get(1))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    for (column, definition) in [
+        ("kind", "TEXT NOT NULL DEFAULT 'shell'"),
+        ("channel", "TEXT"),
+        ("recipient", "TEXT"),
+        ("paused", "INTEGER NOT NULL DEFAULT 0"),
+        ("timezone", "TEXT"),
+        ("jitter_secs", "INTEGER NOT NULL DEFAULT 0"),
+        ("missed", "TEXT NOT NULL DEFAULT 'catch-up'"),
+        ("owner_channel", "TEXT"),
+        ("owner_recipient", "TEXT"),
+    ] {
+        if !existing.iter().any(|c| c == column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE cron_jobs ADD COLUMN {column} {definition};"
+            ))
+            .with_context(|| format!("Failed to add cron_jobs.{column}"))?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -352,4 +993,250 @@ mod tests {
         assert_eq!(stored.last_status.as_deref(), Some("error"));
         assert!(stored.last_run.is_some());
     }
+
+    #[test]
+    fn agent_jobs_keep_kind_and_delivery() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let delivery = Delivery {
+            channel: "telegram".into(),
+            recipient: "123456".into(),
+        };
+        let job = add_agent_job(
+            &config,
+            "0 9 * * *",
+            "Morning briefing",
+            Some(delivery.clone()),
+        )
+        .unwrap();
+        add_job(&config, "0 10 * * *", "echo shell").unwrap();
+
+        let listed = list_jobs(&config).unwrap();
+        let stored = listed.iter().find(|j| j.id == job.id).unwrap();
+        assert_eq!(stored.kind, JobKind::Agent);
+        assert_eq!(stored.command, "Morning briefing");
+        assert_eq!(stored.delivery.as_ref(), Some(&delivery));
+
+        let shell = listed.iter().find(|j| j.id != job.id).unwrap();
+        assert_eq!(shell.kind, JobKind::Shell);
+        assert!(shell.delivery.is_none());
+    }
+
+    #[test]
+    fn jobs_from_older_databases_are_shell_jobs() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let db_dir = config.workspace_dir.join("cron");
+        std::fs::create_dir_all(&db_dir).unwrap();
+        let conn = Connection::open(db_dir.join("jobs.db")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE cron_jobs (
+                id TEXT PRIMARY KEY, expression TEXT NOT NULL, command TEXT NOT NULL,
+                created_at TEXT NOT NULL, next_run TEXT NOT NULL,
+                last_run TEXT, last_status TEXT, last_output TEXT
+            );
+            INSERT INTO cron_jobs (id, expression, command, created_at, next_run)
+            VALUES ('old', '* * * * *', 'echo old', '2025-01-01T00:00:00Z', '2025-01-01T00:01:00Z');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let listed = list_jobs(&config).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].kind, JobKind::Shell);
+        assert!(listed[0].delivery.is_none());
+    }
+
+    fn outcome(success: bool, output: &str) -> JobOutcome {
+        JobOutcome {
+            success,
+            output: output.into(),
+            attempts: 1,
+            exit_code: Some(i32::from(!success)),
+        }
+    }
+
+    #[test]
+    fn runs_are_listed_newest_first_and_pruned() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo history").unwrap();
+
+        let start = Utc::now();
+        for i in 0..MAX_RUNS_PER_JOB + 5 {
+            let ok = i % 2 == 0;
+            record_run(
+                &config,
+                &job.id,
+                start,
+                start,
+                &outcome(ok, &format!("run {i}")),
+            )
+            .unwrap();
+        }
+
+        let runs = list_runs(&config, &job.id, 1000).unwrap();
+        assert_eq!(runs.len(), usize::try_from(MAX_RUNS_PER_JOB).unwrap());
+        assert_eq!(runs[0].output, format!("run {}", MAX_RUNS_PER_JOB + 4));
+        assert_eq!(list_runs(&config, &job.id, 3).unwrap().len(), 3);
+
+        let run = get_run(&config, runs[1].id).unwrap();
+        assert_eq!(run.job_id, job.id);
+        assert_eq!(run.status, "error");
+        assert_eq!(run.exit_code, Some(1));
+        assert!(get_run(&config, -1).is_err());
+    }
+
+    #[test]
+    fn long_run_output_is_truncated() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "yes").unwrap();
+
+        let now = Utc::now();
+        let long = "y\n".repeat(MAX_RUN_OUTPUT_CHARS);
+        let id = record_run(&config, &job.id, now, now, &outcome(true, &long)).unwrap();
+
+        let run = get_run(&config, id).unwrap();
+        assert!(run.output.len() < long.len());
+        assert!(run.output.ends_with("..."));
+    }
+
+    #[test]
+    fn paused_jobs_are_not_due_until_resumed() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "* * * * *", "echo paused").unwrap();
+        let far_future = Utc::now() + ChronoDuration::days(365);
+
+        set_paused(&config, &job.id, true).unwrap();
+        assert!(get_job(&config, &job.id).unwrap().paused);
+        assert!(due_jobs(&config, far_future).unwrap().is_empty());
+
+        set_paused(&config, &job.id, false).unwrap();
+        assert!(!get_job(&config, &job.id).unwrap().paused);
+        assert_eq!(due_jobs(&config, far_future).unwrap().len(), 1);
+
+        assert!(set_paused(&config, "missing", true).is_err());
+    }
+
+    #[test]
+    fn removing_a_job_drops_its_runs() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "*/5 * * * *", "echo gone").unwrap();
+        let now = Utc::now();
+        record_run(&config, &job.id, now, now, &outcome(true, "done")).unwrap();
+
+        remove_job(&config, &job.id).unwrap();
+        assert!(list_runs(&config, &job.id, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn one_shot_job_deletes_itself_after_success() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let at = schedule::one_shot_in("2h", Utc::now()).unwrap();
+        let options = ScheduleOptions::default();
+
+        let job = create_job(&config, &at, JobKind::Shell, "echo once", None, &options).unwrap();
+        reschedule_after_run(&config, &job, true, "done").unwrap();
+        assert!(get_job(&config, &job.id).is_err());
+
+        let job = create_job(&config, &at, JobKind::Shell, "echo once", None, &options).unwrap();
+        reschedule_after_run(&config, &job, false, "failed").unwrap();
+        let stored = get_job(&config, &job.id).unwrap();
+        assert!(stored.paused);
+        assert_eq!(stored.last_status.as_deref(), Some("error"));
+        assert_eq!(stored.next_run, job.next_run);
+    }
+
+    #[test]
+    fn one_shot_in_the_past_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let err = add_job(&config, "at 2020-01-01T09:00:00Z", "echo late").unwrap_err();
+        assert!(err.to_string().contains("in the past"));
+    }
+
+    #[test]
+    fn schedule_options_are_stored() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let options = ScheduleOptions {
+            timezone: Some("America/New_York".into()),
+            jitter_secs: 30,
+            missed: MissedRuns::Skip,
+            owner: None,
+        };
+
+        let before = Utc::now();
+        let job = create_job(
+            &config,
+            "every 15m",
+            JobKind::Shell,
+            "echo tick",
+            None,
+            &options,
+        )
+        .unwrap();
+        assert!(job.next_run >= before + Duration::minutes(15));
+        assert!(job.next_run <= Utc::now() + Duration::minutes(15) + Duration::seconds(30));
+
+        let stored = get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.timezone.as_deref(), Some("America/New_York"));
+        assert_eq!(stored.jitter_secs, 30);
+        assert_eq!(stored.missed, MissedRuns::Skip);
+
+        let bad_tz = ScheduleOptions {
+            timezone: Some("Nowhere/Special".into()),
+            ..ScheduleOptions::default()
+        };
+        let err =
+            create_job(&config, "0 9 * * *", JobKind::Shell, "echo", None, &bad_tz).unwrap_err();
+        assert!(err.to_string().contains("Unknown timezone"));
+    }
+
+    #[test]
+    fn skipping_a_missed_run_moves_to_the_next_occurrence() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = add_job(&config, "0 9 * * *", "echo daily").unwrap();
+        let missed = CronJob {
+            next_run: Utc::now() - Duration::days(2),
+            ..job.clone()
+        };
+
+        skip_missed_run(&config, &missed).unwrap();
+        let stored = get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.last_status.as_deref(), Some("skipped"));
+        assert!(stored.last_run.is_none());
+        assert!(stored.next_run > Utc::now());
+        assert!(!stored.paused);
+    }
+
+    #[test]
+    fn resolve_schedule_uses_the_positional_as_one_shot_command() {
+        let (expression, command) = resolve_schedule(
+            Some("echo hi".into()),
+            None,
+            Some("2026-11-01T09:00".into()),
+            None,
+            chrono_tz::Tz::UTC,
+        )
+        .unwrap();
+        assert_eq!(expression, "at 2026-11-01T09:00:00+00:00");
+        assert_eq!(command, "echo hi");
+
+        assert!(resolve_schedule(
+            Some("0 9 * * *".into()),
+            Some("echo hi".into()),
+            None,
+            Some("2h".into()),
+            chrono_tz::Tz::UTC,
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,211 @@
+use crate::util::parse_interval;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use cron::Schedule;
+use std::str::FromStr;
+
+/// When a job fires, parsed from its stored expression:
+/// - a cron expression (5, 6 or 7 fields), evaluated in the job's timezone
+/// - `every 15m` (`m`, `h` or `d`), measured from the previous run
+/// - `at <RFC 3339 time>`, a one-shot job
+#[derive(Debug, Clone)]
+pub enum JobSchedule {
+    Cron(Box<Schedule>),
+    Every(Duration),
+    At(DateTime<Utc>),
+}
+
+impl JobSchedule {
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        if let Some(interval) = expression.strip_prefix("every ") {
+            let interval = parse_interval(interval.trim()).with_context(|| {
+                format!("Invalid interval in '{expression}' (expected e.g. every 15m, 2h or 1d)")
+            })?;
+            return Ok(Self::Every(Duration::from_std(interval)?));
+        }
+        if let Some(at) = expression.strip_prefix("at ") {
+            let at = DateTime::parse_from_rfc3339(at.trim())
+                .with_context(|| format!("Invalid one-shot time in '{expression}'"))?;
+            return Ok(Self::At(at.with_timezone(&Utc)));
+        }
+
+        let normalized = normalize_expression(expression)?;
+        let schedule = Schedule::from_str(&normalized)
+            .with_context(|| format!("Invalid cron expression: {expression}"))?;
+        Ok(Self::Cron(Box::new(schedule)))
+    }
+
+    pub fn is_one_shot(&self) -> bool {
+        matches!(self, Self::At(_))
+    }
+
+    /// First occurrence strictly after `from`, or `None` once a one-shot job
+    /// has passed. Cron fields are matched against wall-clock time in `tz`:
+    /// a time skipped by a DST change runs just after the change, and a time
+    /// repeated by one runs only the first time.
+    pub fn next_after(&self, from: DateTime<Utc>, tz: Tz) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Cron(schedule) => {
+                // Walk the wall-clock times as if they were UTC, then place each in `tz`
+                let local_from = Utc.from_utc_datetime(&from.with_timezone(&tz).naive_local());
+                schedule
+                    .after(&local_from)
+                    .filter_map(|local| local_to_utc(tz, local.naive_utc()))
+                    .find(|at| *at > from)
+            }
+            Self::Every(interval) => Some(from + *interval),
+            Self::At(at) => (*at > from).then_some(*at),
+        }
+    }
+}
+
+/// Stored expression for a one-shot job at `value`: RFC 3339, or
+/// `YYYY-MM-DDTHH:MM[:SS]` wall-clock time in `tz`.
+pub fn one_shot_at(value: &str, tz: Tz) -> Result<String> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Ok(one_shot_expression(at.with_timezone(&Utc)));
+    }
+    let at = NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .and_then(|naive| local_to_utc(tz, naive))
+        .with_context(|| format!("Invalid time '{value}' (expected e.g. 2026-11-01T09:00)"))?;
+    Ok(one_shot_expression(at))
+}
+
+/// Stored expression for a one-shot job `value` (e.g. `2h`) after `now`.
+pub fn one_shot_in(value: &str, now: DateTime<Utc>) -> Result<String> {
+    let delay = parse_interval(value.trim())
+        .with_context(|| format!("Invalid delay '{value}' (expected e.g. 30m, 2h or 1d)"))?;
+    Ok(one_shot_expression(now + Duration::from_std(delay)?))
+}
+
+fn one_shot_expression(at: DateTime<Utc>) -> String {
+    format!("at {}", at.to_rfc3339_opts(SecondsFormat::Secs, false))
+}
+
+/// IANA timezone such as `Europe/Berlin`; `None` means UTC.
+pub fn parse_timezone(name: Option<&str>) -> Result<Tz> {
+    match name {
+        Some(name) => Tz::from_str(name.trim()).map_err(|_| {
+            anyhow::anyhow!("Unknown timezone '{name}' (expected e.g. America/New_York)")
+        }),
+        None => Ok(Tz::UTC),
+    }
+}
+
+/// The instant of wall-clock time `local` in `tz`. Ambiguous times resolve to
+/// the first occurrence; times in a DST gap move forward past the gap.
+fn local_to_utc(tz: Tz, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(at) | LocalResult::Ambiguous(at, _) => Some(at.with_timezone(&Utc)),
+        LocalResult::None => (1..=24).find_map(|step| {
+            tz.from_local_datetime(&(local + Duration::minutes(15 * step)))
+                .earliest()
+                .map(|at| at.with_timezone(&Utc))
+        }),
+    }
+}
+
+fn normalize_expression(expression: &str) -> Result<String> {
+    let expression = expression.trim();
+    let field_count = expression.split_whitespace().count();
+
+    match field_count {
+        // standard crontab syntax: minute hour day month weekday
+        5 => Ok(format!("0 {expression}")),
+        // crate-native syntax includes seconds (+ optional year)
+        6 | 7 => Ok(expression.to_string()),
+        _ => anyhow::bail!(
+            "Invalid cron expression: {expression} (expected 5, 6, or 7 fields, got {field_count})"
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(raw: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(raw)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn cron_follows_the_job_timezone() {
+        let schedule = JobSchedule::parse("0 9 * * *").unwrap();
+        let tz = parse_timezone(Some("America/New_York")).unwrap();
+        // 09:00 in New York is 13:00 UTC in summer and 14:00 UTC in winter
+        assert_eq!(
+            schedule.next_after(utc("2026-07-01T00:00:00Z"), tz),
+            Some(utc("2026-07-01T13:00:00Z"))
+        );
+        assert_eq!(
+            schedule.next_after(utc("2026-12-01T00:00:00Z"), tz),
+            Some(utc("2026-12-01T14:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn cron_handles_dst_transitions() {
+        let tz = parse_timezone(Some("Europe/Berlin")).unwrap();
+
+        // 2026-03-29: clocks jump from 02:00 to 03:00; the 02:30 run moves past the gap
+        let skipped = JobSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(
+            skipped.next_after(utc("2026-03-28T12:00:00Z"), tz),
+            Some(utc("2026-03-29T01:00:00Z"))
+        );
+
+        // 2026-10-25: 02:30 happens twice; the job runs only the first time
+        let repeated = JobSchedule::parse("30 2 * * *").unwrap();
+        let first = repeated
+            .next_after(utc("2026-10-24T12:00:00Z"), tz)
+            .unwrap();
+        assert_eq!(first, utc("2026-10-25T00:30:00Z"));
+        assert_eq!(
+            repeated.next_after(first, tz),
+            Some(utc("2026-10-26T01:30:00Z"))
+        );
+    }
+
+    #[test]
+    fn interval_and_one_shot_schedules() {
+        let now = utc("2026-05-01T10:00:00Z");
+        let every = JobSchedule::parse("every 15m").unwrap();
+        assert!(!every.is_one_shot());
+        assert_eq!(
+            every.next_after(now, Tz::UTC),
+            Some(utc("2026-05-01T10:15:00Z"))
+        );
+        assert!(JobSchedule::parse("every 0m").is_err());
+
+        let expression = one_shot_in("2h", now).unwrap();
+        let once = JobSchedule::parse(&expression).unwrap();
+        assert!(once.is_one_shot());
+        assert_eq!(
+            once.next_after(now, Tz::UTC),
+            Some(utc("2026-05-01T12:00:00Z"))
+        );
+        assert_eq!(once.next_after(utc("2026-05-01T12:00:00Z"), Tz::UTC), None);
+    }
+
+    #[test]
+    fn one_shot_times_use_the_timezone_unless_offset_given() {
+        let tokyo = parse_timezone(Some("Asia/Tokyo")).unwrap();
+        assert_eq!(
+            one_shot_at("2026-11-01T09:00", tokyo).unwrap(),
+            "at 2026-11-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            one_shot_at("2026-11-01T09:00:00+02:00", tokyo).unwrap(),
+            "at 2026-11-01T07:00:00+00:00"
+        );
+        assert!(one_shot_at("tomorrow", tokyo).is_err());
+        assert!(parse_timezone(Some("Mars/Olympus")).is_err());
+    }
+}
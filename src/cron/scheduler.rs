@@ -1,24 +1,61 @@
+use crate::agent::TaskAgent;
+use crate::channels::Channel;
 use crate::config::Config;
-use crate::cron::{due_jobs, reschedule_after_run, CronJob};
+use crate::cron::{
+    due_jobs, record_run, reschedule_after_run, skip_missed_run, CronJob, JobKind, JobOutcome,
+    MissedRuns,
+};
+use crate::observability::{self, Observer};
+use crate::runtime::{self, RuntimeAdapter};
+use crate::security::approval::ApprovalRoute;
 use crate::security::SecurityPolicy;
 use anyhow::Result;
-use chrono::Utc;
-use tokio::process::Command;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinSet;
 use tokio::time::{self, Duration};
 
 const MIN_POLL_SECONDS: u64 = 5;
+/// Longest an agent job may run, tool calls and approvals included.
+const AGENT_JOB_TIMEOUT_SECS: u64 = 600;
 
 pub async fn run(config: Config) -> Result<()> {
+    let config = Arc::new(config);
     let poll_secs = config.reliability.scheduler_poll_secs.max(MIN_POLL_SECONDS);
     let mut interval = time::interval(Duration::from_secs(poll_secs));
-    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let security = Arc::new(SecurityPolicy::from_config(
+        &config.autonomy,
+        &config.workspace_dir,
+    ));
+    let runtime: Arc<dyn RuntimeAdapter> = Arc::from(runtime::create_runtime(&config.runtime)?);
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    // Built on the first agent job so shell-only schedules never touch the provider
+    let mut agent: Option<Arc<TaskAgent>> = None;
+    // Agent jobs run in the background so a slow one cannot hold up the
+    // others; a job is not started again while its last run is in flight.
+    let mut agent_jobs = JoinSet::new();
+    let mut running: HashMap<tokio::task::Id, String> = HashMap::new();
 
     crate::health::mark_component_ok("scheduler");
 
     loop {
         interval.tick().await;
 
-        let jobs = match due_jobs(&config, Utc::now()) {
+        while let Some(finished) = agent_jobs.try_join_next_with_id() {
+            let id = match finished {
+                Ok((id, ())) => id,
+                Err(e) => {
+                    tracing::error!("Cron agent job task failed: {e}");
+                    e.id()
+                }
+            };
+            running.remove(&id);
+        }
+
+        let now = Utc::now();
+        let jobs = match due_jobs(&config, now) {
             Ok(jobs) => jobs,
             Err(e) => {
                 crate::health::mark_component_error("scheduler", e.to_string());
@@ -27,52 +64,253 @@ pub async fn run(config: Config) -> Result<()> {
             }
         };
 
+        let jobs: Vec<CronJob> = jobs
+            .into_iter()
+            .filter(|job| !running.values().any(|id| *id == job.id))
+            .collect();
         for job in jobs {
             crate::health::mark_component_ok("scheduler");
-            let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-
-            if !success {
-                crate::health::mark_component_error("scheduler", format!("job {} failed", job.id));
+            if job.missed == MissedRuns::Skip && was_missed(&job, now, poll_secs) {
+                tracing::info!("Skipping missed run of cron job {}", job.id);
+                if let Err(e) = skip_missed_run(&config, &job) {
+                    crate::health::mark_component_error("scheduler", e.to_string());
+                    tracing::warn!("Failed to skip missed cron run: {e}");
+                }
+                continue;
             }
-
-            if let Err(e) = reschedule_after_run(&config, &job, success, &output) {
-                crate::health::mark_component_error("scheduler", e.to_string());
-                tracing::warn!("Failed to persist scheduler run result: {e}");
+            if job.kind == JobKind::Agent {
+                // Build the agent once here so every run shares it; if that
+                // fails, the job retries and records the error itself
+                let _ = ensure_agent(&config, &observer, &mut agent);
+                let (config, security, runtime, observer) = (
+                    Arc::clone(&config),
+                    Arc::clone(&security),
+                    Arc::clone(&runtime),
+                    Arc::clone(&observer),
+                );
+                let mut agent = agent.clone();
+                let job_id = job.id.clone();
+                let handle = agent_jobs.spawn(async move {
+                    run_and_report(
+                        &config,
+                        &security,
+                        runtime.as_ref(),
+                        &observer,
+                        &mut agent,
+                        &job,
+                    )
+                    .await;
+                });
+                running.insert(handle.id(), job_id);
+                continue;
             }
+            run_and_report(
+                &config,
+                &security,
+                runtime.as_ref(),
+                &observer,
+                &mut agent,
+                &job,
+            )
+            .await;
         }
     }
 }
 
+/// Run `job` and mark the scheduler unhealthy if it fails.
+async fn run_and_report(
+    config: &Config,
+    security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
+    observer: &Arc<dyn Observer>,
+    agent: &mut Option<Arc<TaskAgent>>,
+    job: &CronJob,
+) {
+    let outcome = run_and_record(config, security, runtime, observer, agent, job).await;
+    if !outcome.success {
+        crate::health::mark_component_error("scheduler", format!("job {} failed", job.id));
+    }
+}
+
+/// Whether `job` came due long enough ago that it must have been missed
+/// (the daemon was down) rather than just picked up by this poll.
+fn was_missed(job: &CronJob, now: DateTime<Utc>, poll_secs: u64) -> bool {
+    let grace =
+        chrono::Duration::seconds(i64::try_from(poll_secs.saturating_mul(2)).unwrap_or(i64::MAX))
+            .max(chrono::Duration::seconds(60));
+    now - job.next_run > grace
+}
+
+/// Run `job` immediately, outside its schedule, recording the run like a
+/// scheduled one.
+pub async fn run_now(config: &Config, job: &CronJob) -> Result<JobOutcome> {
+    let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+    let runtime = runtime::create_runtime(&config.runtime)?;
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    Ok(run_and_record(
+        config,
+        &security,
+        runtime.as_ref(),
+        &observer,
+        &mut None,
+        job,
+    )
+    .await)
+}
+
+/// Execute `job`, add it to the run history and move it to its next
+/// occurrence.
+async fn run_and_record(
+    config: &Config,
+    security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
+    observer: &Arc<dyn Observer>,
+    agent: &mut Option<Arc<TaskAgent>>,
+    job: &CronJob,
+) -> JobOutcome {
+    let started_at = Utc::now();
+    let outcome = match job.kind {
+        JobKind::Shell => execute_job_with_retry(config, security, runtime, job).await,
+        JobKind::Agent => match ensure_agent(config, observer, agent) {
+            Ok(agent) => execute_agent_job(config, agent, job).await,
+            Err(e) => failure(format!("agent unavailable: {e}")),
+        },
+    };
+    let finished_at = Utc::now();
+
+    let persisted = record_run(config, &job.id, started_at, finished_at, &outcome)
+        .and_then(|_| reschedule_after_run(config, job, outcome.success, &outcome.output));
+    if let Err(e) = persisted {
+        crate::health::mark_component_error("scheduler", e.to_string());
+        tracing::warn!("Failed to persist scheduler run result: {e}");
+    }
+    outcome
+}
+
+/// A failed single attempt that never produced an exit code.
+fn failure(output: String) -> JobOutcome {
+    JobOutcome {
+        success: false,
+        output,
+        attempts: 1,
+        exit_code: None,
+    }
+}
+
 async fn execute_job_with_retry(
     config: &Config,
     security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
     job: &CronJob,
-) -> (bool, String) {
-    let mut last_output = String::new();
+) -> JobOutcome {
     let retries = config.reliability.scheduler_retries;
     let mut backoff_ms = config.reliability.provider_backoff_ms.max(200);
+    let mut attempt = 0;
 
-    for attempt in 0..=retries {
-        let (success, output) = run_job_command(config, security, job).await;
-        last_output = output;
+    loop {
+        let mut outcome = run_job_command(config, security, runtime, job).await;
+        attempt += 1;
+        outcome.attempts = attempt;
 
-        if success {
-            return (true, last_output);
+        if outcome.success {
+            return outcome;
         }
 
-        if last_output.starts_with("blocked by security policy:") {
+        if outcome.output.starts_with("blocked by security policy:") {
             // Deterministic policy violations are not retryable.
-            return (false, last_output);
+            return outcome;
         }
 
-        if attempt < retries {
-            let jitter_ms = u64::from(Utc::now().timestamp_subsec_millis() % 250);
-            time::sleep(Duration::from_millis(backoff_ms + jitter_ms)).await;
-            backoff_ms = (backoff_ms.saturating_mul(2)).min(30_000);
+        if attempt > retries {
+            return outcome;
         }
+
+        let jitter_ms = u64::from(Utc::now().timestamp_subsec_millis() % 250);
+        time::sleep(Duration::from_millis(backoff_ms + jitter_ms)).await;
+        backoff_ms = (backoff_ms.saturating_mul(2)).min(30_000);
     }
+}
+
+fn ensure_agent<'a>(
+    config: &Config,
+    observer: &Arc<dyn Observer>,
+    slot: &'a mut Option<Arc<TaskAgent>>,
+) -> Result<&'a TaskAgent> {
+    let agent = match slot.take() {
+        Some(agent) => agent,
+        None => Arc::new(TaskAgent::from_config(config, Arc::clone(observer))?),
+    };
+    Ok(slot.insert(agent))
+}
+
+/// Run an agent job once (its tool calls may not be safe to repeat) and
+/// deliver the answer, or the failure, to the job's channel.
+async fn execute_agent_job(config: &Config, agent: &TaskAgent, job: &CronJob) -> JobOutcome {
+    let channel = match &job.delivery {
+        Some(delivery) => match crate::channels::configured_channel(config, &delivery.channel) {
+            Some(channel) => Some(channel),
+            None => {
+                return failure(format!(
+                    "delivery channel '{}' is not configured",
+                    delivery.channel
+                ))
+            }
+        },
+        None => None,
+    };
+    let timeout = Duration::from_secs(AGENT_JOB_TIMEOUT_SECS);
+    run_agent_job(agent, job, channel.as_ref(), timeout).await
+}
 
-    (false, last_output)
+async fn run_agent_job(
+    agent: &TaskAgent,
+    job: &CronJob,
+    channel: Option<&Arc<dyn Channel>>,
+    timeout: Duration,
+) -> JobOutcome {
+    let route = match (&job.delivery, channel) {
+        (Some(delivery), Some(channel)) => ApprovalRoute::new(channel.name(), &delivery.recipient),
+        _ => ApprovalRoute::new("cron", &job.id),
+    };
+
+    let (mut outcome, message) = match time::timeout(timeout, agent.run(&job.command, route)).await
+    {
+        Ok(Ok(reply)) => (
+            JobOutcome {
+                success: true,
+                output: reply.clone(),
+                attempts: 1,
+                exit_code: None,
+            },
+            reply,
+        ),
+        Ok(Err(e)) => (
+            failure(format!("agent error: {e}")),
+            format!("⚠️ Scheduled job failed: {e}\n\nPrompt: {}", job.command),
+        ),
+        Err(_) => (
+            failure(format!("agent timed out after {}s", timeout.as_secs())),
+            format!(
+                "⚠️ Scheduled job timed out after {}s\n\nPrompt: {}",
+                timeout.as_secs(),
+                job.command
+            ),
+        ),
+    };
+
+    if let (Some(delivery), Some(channel)) = (&job.delivery, channel) {
+        if let Err(e) = channel.send(&message, &delivery.recipient).await {
+            tracing::warn!(
+                "Failed to deliver cron job {} on {}: {e}",
+                job.id,
+                channel.name()
+            );
+            outcome.success = false;
+            outcome.output = format!("{}\n\ndelivery error: {e}", outcome.output);
+        }
+    }
+    outcome
 }
 
 fn is_env_assignment(word: &str) -> bool {
@@ -133,48 +371,40 @@ fn forbidden_path_argument(security: &SecurityPolicy, command: &str) -> Option<S
     None
 }
 
+/// Run a shell job through the configured runtime (so `runtime.kind =
+/// "docker"` sandboxes it), with the shell tool's timeout and output caps.
 async fn run_job_command(
     config: &Config,
     security: &SecurityPolicy,
+    runtime: &dyn RuntimeAdapter,
     job: &CronJob,
-) -> (bool, String) {
+) -> JobOutcome {
     if !security.is_command_allowed(&job.command) {
-        return (
-            false,
-            format!(
-                "blocked by security policy: command not allowed: {}",
-                job.command
-            ),
-        );
+        return failure(format!(
+            "blocked by security policy: command not allowed: {}",
+            job.command
+        ));
     }
 
     if let Some(path) = forbidden_path_argument(security, &job.command) {
-        return (
-            false,
-            format!("blocked by security policy: forbidden path argument: {path}"),
-        );
+        return failure(format!(
+            "blocked by security policy: forbidden path argument: {path}"
+        ));
     }
 
-    let output = Command::new("sh")
-        .arg("-lc")
-        .arg(&job.command)
-        .current_dir(&config.workspace_dir)
-        .output()
-        .await;
-
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let combined = format!(
+    match crate::tools::shell::run_command(runtime, &job.command, &config.workspace_dir).await {
+        Ok(output) => JobOutcome {
+            success: output.status.success(),
+            output: format!(
                 "status={}\nstdout:\n{}\nstderr:\n{}",
                 output.status,
-                stdout.trim(),
-                stderr.trim()
-            );
-            (output.status.success(), combined)
-        }
-        Err(e) => (false, format!("spawn error: {e}")),
+                output.stdout.trim(),
+                output.stderr.trim()
+            ),
+            attempts: 1,
+            exit_code: output.status.code(),
+        },
+        Err(e) => failure(e.to_string()),
     }
 }
 
@@ -182,6 +412,7 @@ async fn run_job_command(
 mod tests {
     use super::*;
     use crate::config::Config;
+    use crate::runtime::NativeRuntime;
     use crate::security::SecurityPolicy;
     use tempfile::TempDir;
 
@@ -199,10 +430,17 @@ mod tests {
         CronJob {
             id: "test-job".into(),
             expression: "* * * * *".into(),
+            kind: JobKind::Shell,
             command: command.into(),
+            delivery: None,
             next_run: Utc::now(),
             last_run: None,
             last_status: None,
+            paused: false,
+            timezone: None,
+            jitter_secs: 0,
+            missed: MissedRuns::CatchUp,
+            owner: None,
         }
     }
 
@@ -213,10 +451,11 @@ mod tests {
         let job = test_job("echo scheduler-ok");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
-        assert!(success);
-        assert!(output.contains("scheduler-ok"));
-        assert!(output.contains("status=exit status: 0"));
+        let outcome = run_job_command(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(outcome.success);
+        assert!(outcome.output.contains("scheduler-ok"));
+        assert!(outcome.output.contains("status=exit status: 0"));
+        assert_eq!(outcome.exit_code, Some(0));
     }
 
     #[tokio::test]
@@ -226,10 +465,13 @@ mod tests {
         let job = test_job("ls definitely_missing_file_for_scheduler_test");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
-        assert!(!success);
-        assert!(output.contains("definitely_missing_file_for_scheduler_test"));
-        assert!(output.contains("status=exit status:"));
+        let outcome = run_job_command(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(!outcome.success);
+        assert!(outcome
+            .output
+            .contains("definitely_missing_file_for_scheduler_test"));
+        assert!(outcome.output.contains("status=exit status:"));
+        assert!(outcome.exit_code.is_some_and(|code| code != 0));
     }
 
     #[tokio::test]
@@ -240,10 +482,10 @@ mod tests {
         let job = test_job("curl https://evil.example");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
-        assert!(!success);
-        assert!(output.contains("blocked by security policy"));
-        assert!(output.contains("command not allowed"));
+        let outcome = run_job_command(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("blocked by security policy"));
+        assert!(outcome.output.contains("command not allowed"));
     }
 
     #[tokio::test]
@@ -254,11 +496,62 @@ mod tests {
         let job = test_job("cat /etc/passwd");
         let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
 
-        let (success, output) = run_job_command(&config, &security, &job).await;
-        assert!(!success);
-        assert!(output.contains("blocked by security policy"));
-        assert!(output.contains("forbidden path argument"));
-        assert!(output.contains("/etc/passwd"));
+        let outcome = run_job_command(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("blocked by security policy"));
+        assert!(outcome.output.contains("forbidden path argument"));
+        assert!(outcome.output.contains("/etc/passwd"));
+    }
+
+    /// Runtime that tags every command so tests can see it was used.
+    struct TaggingRuntime;
+
+    impl RuntimeAdapter for TaggingRuntime {
+        fn name(&self) -> &str {
+            "tagging"
+        }
+
+        fn has_shell_access(&self) -> bool {
+            true
+        }
+
+        fn has_filesystem_access(&self) -> bool {
+            true
+        }
+
+        fn storage_path(&self) -> std::path::PathBuf {
+            std::env::temp_dir()
+        }
+
+        fn supports_long_running(&self) -> bool {
+            true
+        }
+
+        fn build_shell_command(
+            &self,
+            command: &str,
+            workspace_dir: &std::path::Path,
+        ) -> anyhow::Result<tokio::process::Command> {
+            let mut process = tokio::process::Command::new("sh");
+            process
+                .arg("-c")
+                .arg(format!("echo via-runtime; {command}"))
+                .current_dir(workspace_dir);
+            Ok(process)
+        }
+    }
+
+    #[tokio::test]
+    async fn run_job_command_goes_through_the_runtime() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = test_job("echo job-ran");
+        let security = SecurityPolicy::from_config(&config.autonomy, &config.workspace_dir);
+
+        let outcome = run_job_command(&config, &security, &TaggingRuntime, &job).await;
+        assert!(outcome.success);
+        assert!(outcome.output.contains("via-runtime"));
+        assert!(outcome.output.contains("job-ran"));
     }
 
     #[tokio::test]
@@ -277,9 +570,10 @@ mod tests {
         .unwrap();
         let job = test_job("sh ./retry-once.sh");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-        assert!(success);
-        assert!(output.contains("recovered"));
+        let outcome = execute_job_with_retry(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(outcome.success);
+        assert!(outcome.output.contains("recovered"));
+        assert_eq!(outcome.attempts, 2);
     }
 
     #[tokio::test]
@@ -292,8 +586,219 @@ mod tests {
 
         let job = test_job("ls always_missing_for_retry_test");
 
-        let (success, output) = execute_job_with_retry(&config, &security, &job).await;
-        assert!(!success);
-        assert!(output.contains("always_missing_for_retry_test"));
+        let outcome = execute_job_with_retry(&config, &security, &NativeRuntime::new(), &job).await;
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("always_missing_for_retry_test"));
+        assert_eq!(outcome.attempts, 2);
+    }
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("agent jobs use the tool loop")
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        async fn chat_with_tools(
+            &self,
+            messages: &[crate::providers::ConversationMessage],
+            _tools: &[crate::tools::ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<crate::providers::traits::ChatResponse> {
+            let prompt = match messages.last() {
+                Some(crate::providers::ConversationMessage::Chat(m)) => m.content.clone(),
+                _ => String::new(),
+            };
+            if prompt.contains("unreachable") {
+                anyhow::bail!("provider unreachable");
+            }
+            if prompt.contains("hangs") {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Ok(crate::providers::traits::ChatResponse {
+                text: Some(format!("briefing: {prompt}")),
+                tool_calls: vec![],
+                usage: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((message.to_string(), recipient.to_string()));
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<crate::channels::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn test_agent(config: &Config) -> TaskAgent {
+        TaskAgent {
+            provider: Box::new(EchoProvider),
+            tools: vec![],
+            security: Arc::new(SecurityPolicy::from_config(
+                &config.autonomy,
+                &config.workspace_dir,
+            )),
+            mem: Arc::new(crate::memory::MarkdownMemory::new(&config.workspace_dir)),
+            observer: Arc::new(crate::observability::NoopObserver),
+            system_prompt: "You are a test agent.".into(),
+            model: "test-model".into(),
+            temperature: 0.0,
+        }
+    }
+
+    fn agent_job(prompt: &str) -> CronJob {
+        CronJob {
+            kind: JobKind::Agent,
+            delivery: Some(crate::cron::Delivery {
+                channel: "recording".into(),
+                recipient: "42".into(),
+            }),
+            ..test_job(prompt)
+        }
+    }
+
+    #[tokio::test]
+    async fn agent_job_delivers_the_answer() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let channel = Arc::new(RecordingChannel::default());
+        let dyn_channel: Arc<dyn Channel> = channel.clone();
+
+        let job = agent_job("Morning briefing");
+        let outcome = run_agent_job(
+            &test_agent(&config),
+            &job,
+            Some(&dyn_channel),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(outcome.success);
+        assert!(outcome.output.contains("briefing: Morning briefing"));
+        let sent = channel.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, outcome.output);
+        assert_eq!(sent[0].1, "42");
+    }
+
+    #[tokio::test]
+    async fn agent_job_failure_is_reported_to_the_channel() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let channel = Arc::new(RecordingChannel::default());
+        let dyn_channel: Arc<dyn Channel> = channel.clone();
+
+        let job = agent_job("Check the unreachable service");
+        let outcome = run_agent_job(
+            &test_agent(&config),
+            &job,
+            Some(&dyn_channel),
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("provider unreachable"));
+        let sent = channel.sent.lock().unwrap();
+        assert!(sent[0].0.contains("Scheduled job failed"));
+    }
+
+    #[tokio::test]
+    async fn stuck_agent_job_times_out_and_reports_it() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let channel = Arc::new(RecordingChannel::default());
+        let dyn_channel: Arc<dyn Channel> = channel.clone();
+
+        let job = agent_job("Poll the service that hangs");
+        let outcome = run_agent_job(
+            &test_agent(&config),
+            &job,
+            Some(&dyn_channel),
+            Duration::from_millis(50),
+        )
+        .await;
+
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("timed out"));
+        assert!(channel.sent.lock().unwrap()[0].0.contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn agent_job_with_unconfigured_channel_fails_without_running() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+
+        let job = agent_job("Morning briefing");
+        let outcome = execute_agent_job(&config, &test_agent(&config), &job).await;
+
+        assert!(!outcome.success);
+        assert!(outcome.output.contains("'recording' is not configured"));
+    }
+
+    #[tokio::test]
+    async fn run_now_records_the_run() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config(&tmp);
+        let job = crate::cron::add_job(&config, "0 0 1 1 *", "echo run-now-ok").unwrap();
+
+        let outcome = run_now(&config, &job).await.unwrap();
+        assert!(outcome.success);
+
+        let runs = crate::cron::list_runs(&config, &job.id, 10).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].status, "ok");
+        assert_eq!(runs[0].attempts, 1);
+        assert_eq!(runs[0].exit_code, Some(0));
+        assert!(runs[0].output.contains("run-now-ok"));
+
+        let stored = crate::cron::get_job(&config, &job.id).unwrap();
+        assert_eq!(stored.last_status.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn only_long_overdue_runs_count_as_missed() {
+        let now = Utc::now();
+        let job = |overdue_secs| CronJob {
+            next_run: now - chrono::Duration::seconds(overdue_secs),
+            ..test_job("echo missed")
+        };
+        assert!(!was_missed(&job(20), now, 15));
+        assert!(!was_missed(&job(59), now, 15));
+        assert!(was_missed(&job(61), now, 15));
+        assert!(!was_missed(&job(200), now, 120));
+        assert!(was_missed(&job(3600), now, 120));
     }
 }
@@ -1,5 +1,5 @@
 use crate::config::Config;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use chrono::Utc;
 use std::future::Future;
 use std::path::PathBuf;
@@ -173,35 +173,26 @@ where
 async fn run_heartbeat_worker(config: Config) -> Result<()> {
     let observer: std::sync::Arc<dyn crate::observability::Observer> =
         std::sync::Arc::from(crate::observability::create_observer(&config.observability));
-    let engine = crate::heartbeat::engine::HeartbeatEngine::new(
+    let agent = crate::agent::TaskAgent::from_config(&config, observer.clone())?;
+    let mut engine = crate::heartbeat::engine::HeartbeatEngine::new(
         config.heartbeat.clone(),
         config.workspace_dir.clone(),
         observer,
-    );
-
-    let interval_mins = config.heartbeat.interval_minutes.max(5);
-    let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_mins) * 60));
-
-    loop {
-        interval.tick().await;
-
-        let tasks = engine.collect_tasks().await?;
-        if tasks.is_empty() {
-            continue;
-        }
-
-        for task in tasks {
-            let prompt = format!("[Heartbeat Task] {task}");
-            let temp = config.default_temperature;
-            if let Err(e) = crate::agent::run(config.clone(), Some(prompt), None, None, temp).await
-            {
-                crate::health::mark_component_error("heartbeat", e.to_string());
-                tracing::warn!("Heartbeat task failed: {e}");
-            } else {
-                crate::health::mark_component_ok("heartbeat");
-            }
-        }
+    )
+    .with_agent(agent);
+
+    if let Some(name) = &config.heartbeat.channel {
+        let channel = crate::channels::configured_channel(&config, name)
+            .with_context(|| format!("Heartbeat channel '{name}' is not configured"))?;
+        let recipient = config
+            .heartbeat
+            .recipient
+            .clone()
+            .context("heartbeat.recipient is required when heartbeat.channel is set")?;
+        engine = engine.with_delivery(channel, recipient);
     }
+
+    engine.run().await
 }
 
 fn has_supervised_channels(config: &Config) -> bool {
@@ -7,16 +7,19 @@
 //! - Request timeouts (30s) to prevent slow-loris attacks
 //! - Header sanitization (handled by axum/hyper)
 
+use crate::channels::inbox::Inbox;
 use crate::channels::{Channel, WhatsAppChannel};
 use crate::config::Config;
-use crate::memory::{self, Memory, MemoryCategory};
-use crate::providers::{self, Provider};
+use crate::memory::{self, Memory, MemoryCategory, MemoryMetadata};
+use crate::observability::{self, Observer, ObserverEvent, ObserverMetric};
+use crate::providers::{self, ChatMessage, ConversationMessage, Provider};
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
+use crate::security::{approval, SecurityPolicy};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use axum::{
     body::Bytes,
-    extract::{Query, State},
+    extract::{Path, Query, State},
     http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json},
     routing::{get, post},
@@ -154,6 +157,8 @@ pub struct AppState {
     pub temperature: f64,
     pub mem: Arc<dyn Memory>,
     pub auto_save: bool,
+    /// Enforces the daily cost budget on LLM calls.
+    pub security: Arc<SecurityPolicy>,
     pub webhook_secret: Option<Arc<str>>,
     pub pairing: Arc<PairingGuard>,
     pub rate_limiter: Arc<GatewayRateLimiter>,
@@ -161,6 +166,39 @@ pub struct AppState {
     pub whatsapp: Option<Arc<WhatsAppChannel>>,
     /// `WhatsApp` app secret for webhook signature verification (`X-Hub-Signature-256`)
     pub whatsapp_app_secret: Option<Arc<str>>,
+    /// Records channel traffic and LLM latency for the configured backend.
+    pub observer: Arc<dyn Observer>,
+}
+
+impl AppState {
+    /// One budgeted LLM call, recording its latency and any failure.
+    async fn chat(&self, message: &str) -> Result<String> {
+        let started = Instant::now();
+        let result = crate::cost::chat_within_budget(
+            self.provider.as_ref(),
+            &self.security,
+            &[ConversationMessage::Chat(ChatMessage::user(message))],
+            &self.model,
+            self.temperature,
+        )
+        .await;
+        self.observer
+            .record_metric(&ObserverMetric::RequestLatency(started.elapsed()));
+        if let Err(e) = &result {
+            self.observer.record_event(&ObserverEvent::Error {
+                component: "gateway".into(),
+                message: providers::sanitize_api_error(&e.to_string()),
+            });
+        }
+        result
+    }
+
+    fn record_message(&self, channel: &str, direction: &str) {
+        self.observer.record_event(&ObserverEvent::ChannelMessage {
+            channel: channel.into(),
+            direction: direction.into(),
+        });
+    }
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
@@ -213,7 +251,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
                 wa.phone_number_id.clone(),
                 wa.verify_token.clone(),
                 wa.allowed_numbers.clone(),
-            ))
+            )
+            .with_inbox(Inbox::from_config(&config)))
         });
 
     // WhatsApp app secret for webhook signature verification
@@ -276,7 +315,14 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         println!("  GET  /whatsapp  — Meta webhook verification");
         println!("  POST /whatsapp  — WhatsApp message webhook");
     }
+    if pairing.require_pairing() {
+        println!("  GET  /approvals — shell commands awaiting approval");
+        println!("  POST /approvals/:id — {{\"approve\": true|false}}");
+    }
     println!("  GET  /health    — health check");
+    if config.observability.backend == "prometheus" {
+        println!("  GET  /metrics   — Prometheus metrics");
+    }
     if let Some(code) = pairing.pairing_code() {
         println!();
         println!("  🔐 PAIRING REQUIRED — use this one-time code:");
@@ -303,21 +349,35 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         temperature,
         mem,
         auto_save: config.memory.auto_save,
+        security: Arc::new(SecurityPolicy::from_config(
+            &config.autonomy,
+            &config.workspace_dir,
+        )),
         webhook_secret,
         pairing,
         rate_limiter,
         idempotency_store,
         whatsapp: whatsapp_channel,
         whatsapp_app_secret,
+        observer: Arc::from(observability::create_observer(&config.observability)),
     };
 
+    // Held shell commands without a reachable chat channel wait for a
+    // decision here; only paired clients may give one.
+    if state.pairing.require_pairing() {
+        approval::broker().enable_remote_approvals();
+    }
+
     // Build router with middleware
     let app = Router::new()
         .route("/health", get(handle_health))
+        .route("/metrics", get(handle_metrics))
         .route("/pair", post(handle_pair))
         .route("/webhook", post(handle_webhook))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
+        .route("/approvals", get(handle_approvals_list))
+        .route("/approvals/:id", post(handle_approval_decision))
         .with_state(state)
         .layer(RequestBodyLimitLayer::new(MAX_BODY_SIZE))
         .layer(TimeoutLayer::with_status_code(
@@ -345,6 +405,26 @@ async fn handle_health(State(state): State<AppState>) -> impl IntoResponse {
     Json(body)
 }
 
+/// GET /metrics — Prometheus scrape endpoint (observability backend "prometheus")
+async fn handle_metrics() -> impl IntoResponse {
+    match observability::prometheus::encode_metrics() {
+        Some(body) => (
+            StatusCode::OK,
+            [(
+                header::CONTENT_TYPE,
+                observability::prometheus::CONTENT_TYPE,
+            )],
+            body,
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            "Prometheus metrics are disabled; set [observability] backend = \"prometheus\"\n"
+                .to_string(),
+        ),
+    }
+}
+
 /// POST /pair — exchange one-time code for bearer token
 async fn handle_pair(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
     let client_key = client_key_from_headers(&headers);
@@ -473,20 +553,28 @@ async fn handle_webhook(
     }
 
     let message = &webhook_body.message;
+    state.record_message("webhook", "inbound");
 
     if state.auto_save {
         let _ = state
             .mem
-            .store("webhook_msg", message, MemoryCategory::Conversation)
+            .store_with_metadata(
+                "webhook_msg",
+                message,
+                MemoryCategory::Conversation,
+                &MemoryMetadata::with_source("webhook"),
+            )
             .await;
     }
 
-    match state
-        .provider
-        .chat(message, &state.model, state.temperature)
-        .await
-    {
+    if let Err(reason) = state.security.check_budget() {
+        let err = serde_json::json!({"error": reason});
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err));
+    }
+
+    match state.chat(message).await {
         Ok(response) => {
+            state.record_message("webhook", "outbound");
             let body = serde_json::json!({"response": response, "model": state.model});
             (StatusCode::OK, Json(body))
         }
@@ -512,6 +600,91 @@ pub struct WhatsAppVerifyQuery {
     pub challenge: Option<String>,
 }
 
+/// Paired-client check for the approval endpoints. Unlike `/webhook` these
+/// are refused outright when pairing is disabled: an unauthenticated caller
+/// must never be able to release a held command.
+fn approval_auth_error(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Option<(StatusCode, Json<serde_json::Value>)> {
+    if !state.pairing.require_pairing() {
+        let err = serde_json::json!({
+            "error": "Approvals over the gateway require pairing to be enabled"
+        });
+        return Some((StatusCode::FORBIDDEN, Json(err)));
+    }
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .unwrap_or("");
+    if !state.pairing.is_authenticated(token) {
+        tracing::warn!("Approvals: rejected — not paired / invalid bearer token");
+        let err = serde_json::json!({
+            "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token>"
+        });
+        return Some((StatusCode::UNAUTHORIZED, Json(err)));
+    }
+    None
+}
+
+/// GET /approvals — shell commands waiting for an operator decision
+async fn handle_approvals_list(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Some(err) = approval_auth_error(&state, &headers) {
+        return err;
+    }
+    let pending = approval::broker().pending();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({ "pending": pending })),
+    )
+}
+
+/// `POST /approvals/:id` request body
+#[derive(serde::Deserialize)]
+pub struct ApprovalDecisionBody {
+    pub approve: bool,
+}
+
+/// POST /approvals/:id — approve or deny a held shell command
+async fn handle_approval_decision(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    body: Result<Json<ApprovalDecisionBody>, axum::extract::rejection::JsonRejection>,
+) -> impl IntoResponse {
+    if let Some(err) = approval_auth_error(&state, &headers) {
+        return err;
+    }
+    let Json(decision) = match body {
+        Ok(b) => b,
+        Err(e) => {
+            let err = serde_json::json!({
+                "error": format!("Invalid JSON: {e}. Expected: {{\"approve\": true|false}}")
+            });
+            return (StatusCode::BAD_REQUEST, Json(err));
+        }
+    };
+
+    if approval::broker().resolve(&id, decision.approve, "gateway") {
+        let status = if decision.approve {
+            "approved"
+        } else {
+            "denied"
+        };
+        (
+            StatusCode::OK,
+            Json(serde_json::json!({ "id": id, "status": status })),
+        )
+    } else {
+        let err = serde_json::json!({ "error": format!("No pending approval with id {id}") });
+        (StatusCode::NOT_FOUND, Json(err))
+    }
+}
+
 /// GET /whatsapp — Meta webhook verification
 async fn handle_whatsapp_verify(
     State(state): State<AppState>,
@@ -610,7 +783,7 @@ async fn handle_whatsapp_message(
     };
 
     // Parse messages from the webhook payload
-    let messages = wa.parse_webhook_payload(&payload);
+    let messages = wa.receive_webhook(&payload).await;
 
     if messages.is_empty() {
         // Acknowledge the webhook even if no messages (could be status updates)
@@ -624,29 +797,28 @@ async fn handle_whatsapp_message(
             msg.sender,
             truncate_with_ellipsis(&msg.content, 50)
         );
+        state.record_message("whatsapp", "inbound");
 
         // Auto-save to memory
         if state.auto_save {
             let _ = state
                 .mem
-                .store(
+                .store_with_metadata(
                     &format!("whatsapp_{}", msg.sender),
                     &msg.content,
                     MemoryCategory::Conversation,
+                    &MemoryMetadata::with_source(format!("whatsapp:{}", msg.sender)),
                 )
                 .await;
         }
 
         // Call the LLM
-        match state
-            .provider
-            .chat(&msg.content, &state.model, state.temperature)
-            .await
-        {
+        match state.chat(&msg.prompt_text()).await {
             Ok(response) => {
                 // Send reply via WhatsApp
-                if let Err(e) = wa.send(&response, &msg.sender).await {
-                    tracing::error!("Failed to send WhatsApp reply: {e}");
+                match wa.send(&response, &msg.sender).await {
+                    Ok(()) => state.record_message("whatsapp", "outbound"),
+                    Err(e) => tracing::error!("Failed to send WhatsApp reply: {e}"),
                 }
             }
             Err(e) => {
@@ -669,6 +841,7 @@ async fn handle_whatsapp_message(
 mod tests {
     use super::*;
     use crate::memory::{Memory, MemoryCategory, MemoryEntry};
+    use crate::observability::{NoopObserver, PrometheusObserver};
     use crate::providers::Provider;
     use async_trait::async_trait;
     use axum::http::HeaderValue;
@@ -807,12 +980,14 @@ mod tests {
             temperature: 0.0,
             mem: memory,
             auto_save: false,
+            security: Arc::new(SecurityPolicy::default()),
             webhook_secret: None,
             pairing: Arc::new(PairingGuard::new(false, &[])),
             rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
             idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
             whatsapp: None,
             whatsapp_app_secret: None,
+            observer: Arc::new(NoopObserver),
         };
 
         let mut headers = HeaderMap::new();
@@ -841,6 +1016,132 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
     }
 
+    #[tokio::test]
+    async fn webhook_refuses_when_daily_budget_spent() {
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let security = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            ..SecurityPolicy::default()
+        };
+        security
+            .cost_ledger
+            .record(crate::providers::traits::TokenUsage::default(), 1.0);
+
+        let state = AppState {
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            security: Arc::new(security),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            observer: Arc::new(NoopObserver),
+        };
+
+        let body = Ok(Json(WebhookBody {
+            message: "hello".into(),
+        }));
+        let response = handle_webhook(State(state), HeaderMap::new(), body)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn metrics_endpoint_exports_webhook_traffic() {
+        let state = AppState {
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            security: Arc::new(SecurityPolicy::default()),
+            webhook_secret: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            observer: Arc::new(PrometheusObserver::new().unwrap()),
+        };
+
+        let body = Ok(Json(WebhookBody {
+            message: "hello".into(),
+        }));
+        let response = handle_webhook(State(state), HeaderMap::new(), body)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = handle_metrics().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let text = String::from_utf8(payload.to_vec()).unwrap();
+        assert!(text.contains(r#"channel="webhook",direction="outbound""#));
+        assert!(text.contains("viziclaw_request_latency_seconds_count"));
+    }
+
+    #[tokio::test]
+    async fn approval_endpoints_require_a_paired_client() {
+        let state_with = |pairing: PairingGuard| AppState {
+            provider: Arc::new(MockProvider::default()),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(MockMemory),
+            auto_save: false,
+            security: Arc::new(SecurityPolicy::default()),
+            webhook_secret: None,
+            pairing: Arc::new(pairing),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            observer: Arc::new(NoopObserver),
+        };
+
+        let open = state_with(PairingGuard::new(false, &[]));
+        let response = handle_approvals_list(State(open), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let paired = state_with(PairingGuard::new(true, &["zc_test_token".into()]));
+        let response = handle_approvals_list(State(paired.clone()), HeaderMap::new())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer zc_test_token"),
+        );
+        let response = handle_approvals_list(State(paired.clone()), headers.clone())
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let payload = response.into_body().collect().await.unwrap().to_bytes();
+        let parsed: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert!(parsed["pending"].is_array());
+
+        let response = handle_approval_decision(
+            State(paired),
+            Path("no-such-id".into()),
+            headers,
+            Ok(Json(ApprovalDecisionBody { approve: true })),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     // ══════════════════════════════════════════════════════════
     // WhatsApp Signature Verification Tests (CWE-345 Prevention)
     // ══════════════════════════════════════════════════════════
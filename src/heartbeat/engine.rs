@@ -1,31 +1,171 @@
+use crate::agent::TaskAgent;
+use crate::channels::Channel;
 use crate::config::HeartbeatConfig;
+use crate::memory::{MemoryCategory, MemoryMetadata};
 use crate::observability::{Observer, ObserverEvent};
-use anyhow::Result;
-use std::path::Path;
-use std::sync::Arc;
+use crate::security::approval::ApprovalRoute;
+use crate::util::parse_interval;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use tokio::time::{self, Duration};
 use tracing::{info, warn};
 
+/// When a heartbeat task runs, set with a leading tag on its bullet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskSchedule {
+    /// Every heartbeat tick (no tag)
+    EveryTick,
+    /// At most once per interval: `[every 30m]`, `[every 2h]`, `[every 1d]`
+    Every(Duration),
+    /// Once a day, on the first tick at or after a local time: `[daily 09:00]`
+    Daily(NaiveTime),
+}
+
+/// A task from HEARTBEAT.md. Tags in square brackets before the text set its
+/// schedule and an optional condition, e.g.
+/// `- [every 2h] [if inbox.md] Triage the notes in inbox.md`.
+/// Brackets that are not a known tag are left in the prompt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartbeatTask {
+    /// What the agent is asked to do
+    pub prompt: String,
+    pub schedule: TaskSchedule,
+    /// Workspace-relative file that must exist and be non-empty: `[if path]`
+    pub condition: Option<PathBuf>,
+}
+
+enum TaskTag {
+    Schedule(TaskSchedule),
+    Condition(PathBuf),
+}
+
+impl HeartbeatTask {
+    /// Split the leading tags off a task bullet's text.
+    pub fn parse(text: &str) -> Self {
+        let mut task = Self {
+            prompt: String::new(),
+            schedule: TaskSchedule::EveryTick,
+            condition: None,
+        };
+        let mut rest = text.trim();
+        while let Some((tag, after)) = rest
+            .strip_prefix('[')
+            .and_then(|inner| inner.split_once(']'))
+        {
+            match parse_tag(tag) {
+                Some(TaskTag::Schedule(schedule)) => task.schedule = schedule,
+                Some(TaskTag::Condition(path)) => task.condition = Some(path),
+                None => break,
+            }
+            rest = after.trim_start();
+        }
+        task.prompt = rest.to_string();
+        task
+    }
+
+    /// Stable identifier for the task's run state and memory entry.
+    fn id(&self) -> String {
+        let digest = Sha256::digest(self.prompt.as_bytes());
+        hex::encode(&digest[..8])
+    }
+
+    /// Whether the schedule allows a run at `now`, given the previous run.
+    fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Local>) -> bool {
+        match self.schedule {
+            TaskSchedule::EveryTick => true,
+            TaskSchedule::Every(interval) => last_run.is_none_or(|last| {
+                (now.with_timezone(&Utc) - last)
+                    .to_std()
+                    .is_ok_and(|elapsed| elapsed >= interval)
+            }),
+            TaskSchedule::Daily(at) => {
+                let Some(slot) = Local
+                    .from_local_datetime(&now.date_naive().and_time(at))
+                    .earliest()
+                else {
+                    return false;
+                };
+                now >= slot && last_run.is_none_or(|last| last < slot)
+            }
+        }
+    }
+}
+
+fn parse_tag(tag: &str) -> Option<TaskTag> {
+    let (kind, value) = tag.trim().split_once(char::is_whitespace)?;
+    let value = value.trim();
+    match kind.to_ascii_lowercase().as_str() {
+        "every" => parse_interval(value).map(|d| TaskTag::Schedule(TaskSchedule::Every(d))),
+        "daily" => NaiveTime::parse_from_str(value, "%H:%M")
+            .ok()
+            .map(|t| TaskTag::Schedule(TaskSchedule::Daily(t))),
+        "if" => {
+            let path = PathBuf::from(value);
+            path.components()
+                .all(|c| matches!(c, Component::Normal(_)))
+                .then_some(TaskTag::Condition(path))
+        }
+        _ => None,
+    }
+}
+
 /// Heartbeat engine — reads HEARTBEAT.md and executes tasks periodically
 pub struct HeartbeatEngine {
     config: HeartbeatConfig,
     workspace_dir: std::path::PathBuf,
     observer: Arc<dyn Observer>,
+    agent: Option<TaskAgent>,
+    delivery: Option<(Arc<dyn Channel>, String)>,
+    /// When each task (by id) last ran, persisted across restarts.
+    last_runs: Mutex<HashMap<String, DateTime<Utc>>>,
 }
 
 impl HeartbeatEngine {
+    /// Engine for the HEARTBEAT.md in `workspace_dir`. Until an agent is
+    /// attached with [`Self::with_agent`], ticks only count the due tasks.
     pub fn new(
         config: HeartbeatConfig,
         workspace_dir: std::path::PathBuf,
         observer: Arc<dyn Observer>,
     ) -> Self {
+        let last_runs = std::fs::read_to_string(state_path(&workspace_dir))
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
         Self {
             config,
             workspace_dir,
             observer,
+            agent: None,
+            delivery: None,
+            last_runs: Mutex::new(last_runs),
         }
     }
 
+    /// Execute due tasks with `agent`.
+    #[must_use]
+    pub fn with_agent(mut self, agent: TaskAgent) -> Self {
+        self.agent = Some(agent);
+        self
+    }
+
+    /// Send each task's result to `recipient` on `channel`. Approval
+    /// requests for commands the tasks run are sent there too.
+    #[must_use]
+    pub fn with_delivery(
+        mut self,
+        channel: Arc<dyn Channel>,
+        recipient: impl Into<String>,
+    ) -> Self {
+        self.delivery = Some((channel, recipient.into()));
+        self
+    }
+
     /// Start the heartbeat loop (runs until cancelled)
     pub async fn run(&self) -> Result<()> {
         if !self.config.enabled {
@@ -59,19 +199,119 @@ impl HeartbeatEngine {
         }
     }
 
-    /// Single heartbeat tick — read HEARTBEAT.md and return task count
+    /// Single heartbeat tick — run every task that is due and return how
+    /// many were due. A failing task is reported and does not stop the rest.
     async fn tick(&self) -> Result<usize> {
-        Ok(self.collect_tasks().await?.len())
+        let mut due = Vec::new();
+        for task in self.collect_tasks().await? {
+            if self.is_due(&task).await {
+                due.push(task);
+            }
+        }
+
+        if let Some(agent) = &self.agent {
+            for task in &due {
+                self.execute(agent, task).await;
+            }
+        }
+        Ok(due.len())
+    }
+
+    async fn is_due(&self, task: &HeartbeatTask) -> bool {
+        let last_run = self.lock_last_runs().get(&task.id()).copied();
+        if !task.is_due(last_run, Local::now()) {
+            return false;
+        }
+        match &task.condition {
+            Some(path) => tokio::fs::metadata(self.workspace_dir.join(path))
+                .await
+                .is_ok_and(|m| m.is_file() && m.len() > 0),
+            None => true,
+        }
+    }
+
+    /// Run one task through the agent, then record and deliver the result.
+    async fn execute(&self, agent: &TaskAgent, task: &HeartbeatTask) {
+        let route = match &self.delivery {
+            Some((channel, recipient)) => ApprovalRoute::new(channel.name(), recipient),
+            None => ApprovalRoute::new("heartbeat", "daemon"),
+        };
+        let started = Instant::now();
+        let result = agent
+            .run(&format!("[Heartbeat Task] {}", task.prompt), route)
+            .await;
+
+        self.observer.record_event(&ObserverEvent::HeartbeatTask {
+            task: task.prompt.clone(),
+            duration: started.elapsed(),
+            success: result.is_ok(),
+        });
+        self.mark_run(task);
+
+        let report = match result {
+            Ok(reply) => {
+                crate::health::mark_component_ok("heartbeat");
+                let _ = agent
+                    .memory()
+                    .store_with_metadata(
+                        &format!("heartbeat_{}", task.id()),
+                        &format!("Heartbeat task \"{}\": {reply}", task.prompt),
+                        MemoryCategory::Daily,
+                        &MemoryMetadata::with_source("heartbeat"),
+                    )
+                    .await;
+                format!("💓 {}\n\n{reply}", task.prompt)
+            }
+            Err(e) => {
+                warn!("💓 Heartbeat task failed: {e}");
+                crate::health::mark_component_error("heartbeat", e.to_string());
+                self.observer.record_event(&ObserverEvent::Error {
+                    component: "heartbeat".into(),
+                    message: e.to_string(),
+                });
+                format!("💓 {}\n\n⚠️ Task failed: {e}", task.prompt)
+            }
+        };
+
+        if let Some((channel, recipient)) = &self.delivery {
+            if let Err(e) = channel.send(&report, recipient).await {
+                warn!(
+                    "💓 Failed to deliver heartbeat result on {}: {e}",
+                    channel.name()
+                );
+            }
+        }
+    }
+
+    fn mark_run(&self, task: &HeartbeatTask) {
+        let snapshot = {
+            let mut last_runs = self.lock_last_runs();
+            last_runs.insert(task.id(), Utc::now());
+            last_runs.clone()
+        };
+        if let Err(e) = save_state(&self.workspace_dir, &snapshot) {
+            warn!("💓 Failed to save heartbeat state: {e}");
+        }
+    }
+
+    fn lock_last_runs(&self) -> std::sync::MutexGuard<'_, HashMap<String, DateTime<Utc>>> {
+        self.last_runs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 
     /// Read HEARTBEAT.md and return all parsed tasks.
-    pub async fn collect_tasks(&self) -> Result<Vec<String>> {
+    pub async fn collect_tasks(&self) -> Result<Vec<HeartbeatTask>> {
         let heartbeat_path = self.workspace_dir.join("HEARTBEAT.md");
         if !heartbeat_path.exists() {
             return Ok(Vec::new());
         }
         let content = tokio::fs::read_to_string(&heartbeat_path).await?;
-        Ok(Self::parse_tasks(&content))
+        Ok(Self::parse_tasks(&content)
+            .iter()
+            .map(|text| HeartbeatTask::parse(text))
+            .filter(|task| !task.prompt.is_empty())
+            .collect())
     }
 
     /// Parse tasks from HEARTBEAT.md (lines starting with `- `)
@@ -92,17 +332,33 @@ impl HeartbeatEngine {
             let default = "# Periodic Tasks\n\n\
                            # Add tasks below (one per line, starting with `- `)\n\
                            # The agent will check this file on each heartbeat tick.\n\
+                           # Optional tags before a task: [every 2h], [daily 09:00],\n\
+                           # [if notes/inbox.md] (only when that file is non-empty).\n\
                            #\n\
                            # Examples:\n\
                            # - Check my email for important messages\n\
-                           # - Review my calendar for upcoming events\n\
-                           # - Check the weather forecast\n";
+                           # - [daily 08:00] Review my calendar for upcoming events\n\
+                           # - [every 3h] Check the weather forecast\n";
             tokio::fs::write(&path, default).await?;
         }
         Ok(())
     }
 }
 
+fn state_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("heartbeat").join("state.json")
+}
+
+fn save_state(workspace_dir: &Path, last_runs: &HashMap<String, DateTime<Utc>>) -> Result<()> {
+    let path = state_path(workspace_dir);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    std::fs::write(&path, serde_json::to_vec_pretty(last_runs)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +504,8 @@ mod tests {
             HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 30,
+                channel: None,
+                recipient: None,
             },
             dir.clone(),
             observer,
@@ -273,6 +531,8 @@ mod tests {
             HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 30,
+                channel: None,
+                recipient: None,
             },
             dir.clone(),
             observer,
@@ -290,6 +550,8 @@ mod tests {
             HeartbeatConfig {
                 enabled: false,
                 interval_minutes: 30,
+                channel: None,
+                recipient: None,
             },
             std::env::temp_dir(),
             observer,
@@ -298,4 +560,208 @@ mod tests {
         let result = engine.run().await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn task_tags_set_schedule_and_condition() {
+        let task = HeartbeatTask::parse("[every 2h] [if notes/inbox.md] Triage the inbox");
+        assert_eq!(task.prompt, "Triage the inbox");
+        assert_eq!(
+            task.schedule,
+            TaskSchedule::Every(Duration::from_secs(2 * 60 * 60))
+        );
+        assert_eq!(task.condition, Some(PathBuf::from("notes/inbox.md")));
+
+        let task = HeartbeatTask::parse("[daily 09:30] Review calendar");
+        assert_eq!(
+            task.schedule,
+            TaskSchedule::Daily(NaiveTime::from_hms_opt(9, 30, 0).unwrap())
+        );
+        assert_eq!(task.condition, None);
+    }
+
+    #[test]
+    fn unknown_or_unsafe_tags_stay_in_prompt() {
+        let task = HeartbeatTask::parse("[URGENT] Check email");
+        assert_eq!(task.prompt, "[URGENT] Check email");
+        assert_eq!(task.schedule, TaskSchedule::EveryTick);
+
+        let task = HeartbeatTask::parse("[if ../secrets.txt] Leak it");
+        assert_eq!(task.prompt, "[if ../secrets.txt] Leak it");
+        assert_eq!(task.condition, None);
+
+        assert_eq!(HeartbeatTask::parse("[every 0m] x").prompt, "[every 0m] x");
+        assert_eq!(
+            HeartbeatTask::parse("[daily 25:00] x").prompt,
+            "[daily 25:00] x"
+        );
+    }
+
+    #[test]
+    fn interval_tasks_wait_for_their_interval() {
+        let task = HeartbeatTask::parse("[every 1h] Check");
+        let now = Local::now();
+        let utc = now.with_timezone(&Utc);
+        assert!(task.is_due(None, now));
+        assert!(!task.is_due(Some(utc - chrono::Duration::minutes(30)), now));
+        assert!(task.is_due(Some(utc - chrono::Duration::minutes(61)), now));
+    }
+
+    #[test]
+    fn daily_tasks_run_once_after_their_time() {
+        let task = HeartbeatTask::parse("[daily 09:00] Review");
+        let at = |h, m| {
+            Local
+                .with_ymd_and_hms(2026, 3, 10, h, m, 0)
+                .earliest()
+                .unwrap()
+        };
+        assert!(!task.is_due(None, at(8, 59)));
+        assert!(task.is_due(None, at(9, 5)));
+        let yesterday = (at(9, 5) - chrono::Duration::days(1)).with_timezone(&Utc);
+        assert!(task.is_due(Some(yesterday), at(9, 5)));
+        assert!(!task.is_due(Some(at(9, 5).with_timezone(&Utc)), at(17, 0)));
+    }
+
+    struct EchoProvider;
+
+    #[async_trait::async_trait]
+    impl crate::providers::Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("heartbeat tasks use the tool loop")
+        }
+
+        fn supports_native_tools(&self) -> bool {
+            true
+        }
+
+        async fn chat_with_tools(
+            &self,
+            messages: &[crate::providers::ConversationMessage],
+            _tools: &[crate::tools::ToolSpec],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<crate::providers::traits::ChatResponse> {
+            let prompt = match messages.last() {
+                Some(crate::providers::ConversationMessage::Chat(m)) => m.content.clone(),
+                _ => String::new(),
+            };
+            Ok(crate::providers::traits::ChatResponse {
+                text: Some(format!("done: {prompt}")),
+                tool_calls: vec![],
+                usage: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingChannel {
+        sent: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for RecordingChannel {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn send(&self, message: &str, recipient: &str) -> anyhow::Result<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((message.to_string(), recipient.to_string()));
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<crate::channels::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn test_agent(workspace: &Path) -> TaskAgent {
+        let mem: Arc<dyn crate::memory::Memory> =
+            Arc::new(crate::memory::MarkdownMemory::new(workspace));
+        TaskAgent {
+            provider: Box::new(EchoProvider),
+            tools: vec![],
+            security: Arc::new(crate::security::SecurityPolicy::from_config(
+                &crate::config::AutonomyConfig::default(),
+                workspace,
+            )),
+            mem,
+            observer: Arc::new(crate::observability::NoopObserver),
+            system_prompt: "You are a test agent.".into(),
+            model: "test-model".into(),
+            temperature: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn tick_executes_due_tasks_and_delivers_results() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        tokio::fs::write(
+            tmp.path().join("HEARTBEAT.md"),
+            "- Check email\n- [if inbox.md] Triage inbox\n- [every 1d] Water plants",
+        )
+        .await
+        .unwrap();
+
+        let channel = Arc::new(RecordingChannel::default());
+        let engine = HeartbeatEngine::new(
+            HeartbeatConfig::default(),
+            tmp.path().to_path_buf(),
+            Arc::new(crate::observability::NoopObserver),
+        )
+        .with_agent(test_agent(tmp.path()))
+        .with_delivery(channel.clone(), "42");
+
+        // inbox.md does not exist, so only two tasks run
+        assert_eq!(engine.tick().await.unwrap(), 2);
+        {
+            let sent = channel.sent.lock().unwrap();
+            assert_eq!(sent.len(), 2);
+            assert!(sent[0].0.contains("done: [Heartbeat Task] Check email"));
+            assert_eq!(sent[0].1, "42");
+        }
+
+        let memories = engine
+            .agent
+            .as_ref()
+            .unwrap()
+            .memory()
+            .list(None)
+            .await
+            .unwrap();
+        assert!(memories
+            .iter()
+            .any(|m| m.content.contains("Heartbeat task \"Water plants\"")));
+
+        // The daily task is not due again, even after a restart
+        tokio::fs::write(tmp.path().join("inbox.md"), "buy milk")
+            .await
+            .unwrap();
+        let restarted = HeartbeatEngine::new(
+            HeartbeatConfig::default(),
+            tmp.path().to_path_buf(),
+            Arc::new(crate::observability::NoopObserver),
+        );
+        let due: Vec<String> = {
+            let mut due = Vec::new();
+            for task in restarted.collect_tasks().await.unwrap() {
+                if restarted.is_due(&task).await {
+                    due.push(task.prompt);
+                }
+            }
+            due
+        };
+        assert_eq!(due, vec!["Check email", "Triage inbox"]);
+    }
 }
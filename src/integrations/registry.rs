@@ -789,6 +789,10 @@ mod tests {
             access_token: "tok".into(),
             room_id: "!r:m".into(),
             allowed_users: vec![],
+            room_allowed_users: std::collections::HashMap::new(),
+            auto_join: true,
+            e2ee: true,
+            verified_devices: vec![],
         });
         let entries = all_integrations();
         let mx = entries.iter().find(|e| e.name == "Matrix").unwrap();
@@ -17,6 +17,7 @@ use serde::{Deserialize, Serialize};
 pub mod agent;
 pub mod channels;
 pub mod config;
+pub mod cost;
 pub mod cron;
 pub mod daemon;
 pub mod doctor;
@@ -110,6 +111,77 @@ pub enum MigrateCommands {
     },
 }
 
+/// Memory subcommands
+#[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MemoryCommands {
+    /// Chunk documents (Markdown, text, source files) into memory; re-ingesting replaces edited files
+    Ingest {
+        /// Files, directories or quoted glob patterns (e.g. 'docs/**/*.md')
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// List stored memories, newest first
+    List {
+        /// Only show this category (core, daily, conversation or a custom name)
+        #[arg(long)]
+        category: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Search memories and show how each result was scored
+    Search {
+        /// Search query
+        query: String,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Only memories carrying this tag (repeatable; all must match)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Only memories from this source, or source prefix (e.g. telegram)
+        #[arg(long)]
+        source: Option<String>,
+        /// Only memories from this session
+        #[arg(long)]
+        session: Option<String>,
+        /// Only memories created at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only memories created at or before this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Show a memory in full
+    Get {
+        /// Memory key
+        key: String,
+    },
+    /// Delete a memory
+    Forget {
+        /// Memory key
+        key: String,
+    },
+    /// Show entry counts per category and storage details
+    Stats,
+    /// Export memories as JSONL (one JSON object per line)
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+    },
+    /// Import memories from a JSONL export; existing keys are overwritten
+    Import {
+        /// File written by `viziclaw memory export`
+        file: std::path::PathBuf,
+    },
+    /// Rebuild the keyword index, embed entries missing vectors and rebuild the vector index (sqlite only)
+    Reindex,
+}
+
 /// Cron subcommands
 #[derive(Subcommand, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum CronCommands {
@@ -117,16 +189,69 @@ pub enum CronCommands {
     List,
     /// Add a new scheduled task
     Add {
-        /// Cron expression
-        expression: String,
-        /// Command to run
-        command: String,
+        /// Cron expression or `every 15m` (omit with --at/--in)
+        #[arg(required_unless_present_any = ["at", "in_"])]
+        expression: Option<String>,
+        /// Command to run (with --agent, the prompt to ask)
+        command: Option<String>,
+        /// Run once at this time (e.g. `2026-11-01T09:00`), then delete the job
+        #[arg(long, conflicts_with = "in_")]
+        at: Option<String>,
+        /// Run once after this delay (e.g. 30m, 2h, 1d), then delete the job
+        #[arg(long = "in")]
+        in_: Option<String>,
+        /// IANA timezone for the schedule (e.g. `America/New_York`); defaults to UTC
+        #[arg(long)]
+        tz: Option<String>,
+        /// Delay each run by a random 0..=N seconds
+        #[arg(long, default_value_t = 0)]
+        jitter: u64,
+        /// What to do with runs missed while the daemon was down
+        #[arg(long, default_value = "catch-up", value_parser = ["catch-up", "skip"])]
+        missed: String,
+        /// Answer the prompt with the agent (tools + memory) instead of running a shell command
+        #[arg(long)]
+        agent: bool,
+        /// Channel to send the agent's answer to (e.g. telegram, slack, email)
+        #[arg(long, requires = "agent", requires = "to")]
+        channel: Option<String>,
+        /// Recipient on that channel (chat id, Slack channel, email address)
+        #[arg(long, requires = "channel")]
+        to: Option<String>,
     },
     /// Remove a scheduled task
     Remove {
         /// Task ID
         id: String,
     },
+    /// List recent runs of a scheduled task
+    Runs {
+        /// Task ID
+        id: String,
+        /// Number of runs to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show the output of one run
+    Output {
+        /// Run ID (from `cron runs`)
+        run_id: i64,
+    },
+    /// Run a scheduled task immediately
+    RunNow {
+        /// Task ID
+        id: String,
+    },
+    /// Pause a scheduled task without deleting it
+    Pause {
+        /// Task ID
+        id: String,
+    },
+    /// Resume a paused task
+    Resume {
+        /// Task ID
+        id: String,
+    },
 }
 
 /// Integration subcommands
@@ -16,6 +16,7 @@ use tracing_subscriber::FmtSubscriber;
 mod agent;
 mod channels;
 mod config;
+mod cost;
 mod cron;
 mod daemon;
 mod doctor;
@@ -167,6 +168,12 @@ enum Commands {
         skill_command: SkillCommands,
     },
 
+    /// Manage long-term memory
+    Memory {
+        #[command(subcommand)]
+        memory_command: MemoryCommands,
+    },
+
     /// Migrate data from other agent runtimes
     Migrate {
         #[command(subcommand)]
@@ -174,6 +181,76 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum MemoryCommands {
+    /// Chunk documents (Markdown, text, source files) into memory; re-ingesting replaces edited files
+    Ingest {
+        /// Files, directories or quoted glob patterns (e.g. 'docs/**/*.md')
+        #[arg(required = true)]
+        targets: Vec<String>,
+    },
+    /// List stored memories, newest first
+    List {
+        /// Only show this category (core, daily, conversation or a custom name)
+        #[arg(long)]
+        category: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+    },
+    /// Search memories and show how each result was scored
+    Search {
+        /// Search query
+        query: String,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Only memories carrying this tag (repeatable; all must match)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Only memories from this source, or source prefix (e.g. telegram)
+        #[arg(long)]
+        source: Option<String>,
+        /// Only memories from this session
+        #[arg(long)]
+        session: Option<String>,
+        /// Only memories created at or after this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only memories created at or before this time (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+    },
+    /// Show a memory in full
+    Get {
+        /// Memory key
+        key: String,
+    },
+    /// Delete a memory
+    Forget {
+        /// Memory key
+        key: String,
+    },
+    /// Show entry counts per category and storage details
+    Stats,
+    /// Export memories as JSONL (one JSON object per line)
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(long, short)]
+        output: Option<std::path::PathBuf>,
+        /// Only export this category
+        #[arg(long)]
+        category: Option<String>,
+    },
+    /// Import memories from a JSONL export; existing keys are overwritten
+    Import {
+        /// File written by `viziclaw memory export`
+        file: std::path::PathBuf,
+    },
+    /// Rebuild the keyword index, embed entries missing vectors and rebuild the vector index (sqlite only)
+    Reindex,
+}
+
 #[derive(Subcommand, Debug)]
 enum MigrateCommands {
     /// Import memory from an `OpenClaw` workspace into this `ViziClaw` workspace
@@ -194,16 +271,69 @@ enum CronCommands {
     List,
     /// Add a new scheduled task
     Add {
-        /// Cron expression
-        expression: String,
-        /// Command to run
-        command: String,
+        /// Cron expression or `every 15m` (omit with --at/--in)
+        #[arg(required_unless_present_any = ["at", "in_"])]
+        expression: Option<String>,
+        /// Command to run (with --agent, the prompt to ask)
+        command: Option<String>,
+        /// Run once at this time (e.g. `2026-11-01T09:00`), then delete the job
+        #[arg(long, conflicts_with = "in_")]
+        at: Option<String>,
+        /// Run once after this delay (e.g. 30m, 2h, 1d), then delete the job
+        #[arg(long = "in")]
+        in_: Option<String>,
+        /// IANA timezone for the schedule (e.g. `America/New_York`); defaults to UTC
+        #[arg(long)]
+        tz: Option<String>,
+        /// Delay each run by a random 0..=N seconds
+        #[arg(long, default_value_t = 0)]
+        jitter: u64,
+        /// What to do with runs missed while the daemon was down
+        #[arg(long, default_value = "catch-up", value_parser = ["catch-up", "skip"])]
+        missed: String,
+        /// Answer the prompt with the agent (tools + memory) instead of running a shell command
+        #[arg(long)]
+        agent: bool,
+        /// Channel to send the agent's answer to (e.g. telegram, slack, email)
+        #[arg(long, requires = "agent", requires = "to")]
+        channel: Option<String>,
+        /// Recipient on that channel (chat id, Slack channel, email address)
+        #[arg(long, requires = "channel")]
+        to: Option<String>,
     },
     /// Remove a scheduled task
     Remove {
         /// Task ID
         id: String,
     },
+    /// List recent runs of a scheduled task
+    Runs {
+        /// Task ID
+        id: String,
+        /// Number of runs to show
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Show the output of one run
+    Output {
+        /// Run ID (from `cron runs`)
+        run_id: i64,
+    },
+    /// Run a scheduled task immediately
+    RunNow {
+        /// Task ID
+        id: String,
+    },
+    /// Pause a scheduled task without deleting it
+    Pause {
+        /// Task ID
+        id: String,
+    },
+    /// Resume a paused task
+    Resume {
+        /// Task ID
+        id: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -380,6 +510,13 @@ async fn main() -> Result<()> {
                 "  Max cost/day:      ${:.2}",
                 f64::from(config.autonomy.max_cost_per_day_cents) / 100.0
             );
+            let spent = cost::CostLedger::shared(&config.workspace_dir).today();
+            println!(
+                "  Spent today:       ${:.2} ({} requests, {} tokens)",
+                spent.cost_cents / 100.0,
+                spent.requests,
+                spent.prompt_tokens + spent.completion_tokens
+            );
             println!();
             println!("Channels:");
             println!("  CLI:      ✅ always");
@@ -402,7 +539,7 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
-        Commands::Cron { cron_command } => cron::handle_command(cron_command, &config),
+        Commands::Cron { cron_command } => cron::handle_command(cron_command, &config).await,
 
         Commands::Service { service_command } => service::handle_command(&service_command, &config),
 
@@ -425,6 +562,10 @@ async fn main() -> Result<()> {
         Commands::Migrate { migrate_command } => {
             migration::handle_command(migrate_command, &config).await
         }
+
+        Commands::Memory { memory_command } => {
+            memory::handle_command(memory_command, &config).await
+        }
     }
 }
 
@@ -23,9 +23,21 @@ pub fn chunk_markdown(text: &str, max_tokens: usize) -> Vec<Chunk> {
     if text.trim().is_empty() {
         return Vec::new();
     }
+    chunk_sections(split_on_headings(text), max_tokens)
+}
+
+/// Split plain text or source code into chunks on paragraph and line
+/// boundaries only — `# ` lines are comments in many languages, not headings.
+pub fn chunk_text(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+    chunk_sections(vec![(None, text.to_string())], max_tokens)
+}
 
+/// Chunk `(heading, body)` sections, splitting any that exceed `max_tokens`.
+fn chunk_sections(sections: Vec<(Option<String>, String)>, max_tokens: usize) -> Vec<Chunk> {
     let max_chars = max_tokens * 4;
-    let sections = split_on_headings(text);
     let mut chunks = Vec::new();
 
     for (heading, body) in sections {
@@ -374,4 +386,29 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn chunk_text_treats_hash_lines_as_content() {
+        let text = "# a shell comment
+echo hi
+
+# another
+echo bye";
+        let chunks = chunk_text(text, 512);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].heading.is_none());
+        assert!(chunks[0].content.starts_with("# a shell comment"));
+    }
+
+    #[test]
+    fn chunk_text_splits_long_input_on_paragraphs() {
+        let text = (0..20)
+            .map(|i| format!("paragraph {i} {}", "word ".repeat(20)))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let chunks = chunk_text(&text, 50);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.heading.is_none()));
+        assert!(chunks.iter().enumerate().all(|(i, c)| c.index == i));
+    }
 }
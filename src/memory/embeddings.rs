@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -105,17 +106,89 @@ impl EmbeddingProvider for OpenAiEmbedding {
 
         let mut embeddings = Vec::with_capacity(data.len());
         for item in data {
-            let embedding = item
-                .get("embedding")
-                .and_then(|e| e.as_array())
-                .ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+            let vec =
+                parse_embedding(item).ok_or_else(|| anyhow::anyhow!("Invalid embedding item"))?;
+            embeddings.push(vec);
+        }
+
+        Ok(embeddings)
+    }
+}
+
+/// Read the `"embedding"` number array out of a response object
+fn parse_embedding(item: &serde_json::Value) -> Option<Vec<f32>> {
+    let embedding = item.get("embedding")?.as_array()?;
+    #[allow(clippy::cast_possible_truncation)]
+    let vec = embedding
+        .iter()
+        .filter_map(|v| v.as_f64().map(|f| f as f32))
+        .collect();
+    Some(vec)
+}
+
+// ── Ollama embedding provider ────────────────────────────────
+
+pub struct OllamaEmbedding {
+    client: reqwest::Client,
+    base_url: String,
+    model: String,
+    dims: usize,
+}
 
-            #[allow(clippy::cast_possible_truncation)]
-            let vec: Vec<f32> = embedding
-                .iter()
-                .filter_map(|v| v.as_f64().map(|f| f as f32))
-                .collect();
+impl OllamaEmbedding {
+    pub fn new(base_url: &str, model: &str, dims: usize) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            dims,
+        }
+    }
+}
 
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbedding {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        // /api/embeddings takes a single prompt per request
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for text in texts {
+            let body = serde_json::json!({
+                "model": self.model,
+                "prompt": text,
+            });
+
+            let resp = self
+                .client
+                .post(format!("{}/api/embeddings", self.base_url))
+                .json(&body)
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let text = resp.text().await.unwrap_or_default();
+                anyhow::bail!("Ollama embedding error {status}: {text}");
+            }
+
+            let json: serde_json::Value = resp.json().await?;
+            let vec = parse_embedding(&json)
+                .ok_or_else(|| anyhow::anyhow!("Invalid Ollama response: missing 'embedding'"))?;
+            if vec.len() != self.dims {
+                anyhow::bail!(
+                    "Ollama model '{}' returned {}-dimensional embeddings, but embedding_dimensions is {}",
+                    self.model,
+                    vec.len(),
+                    self.dims
+                );
+            }
             embeddings.push(vec);
         }
 
@@ -123,6 +196,171 @@ impl EmbeddingProvider for OpenAiEmbedding {
     }
 }
 
+// ── Local (in-process) embedding provider ────────────────────
+
+/// Sentence embeddings from a BERT-style model directory on disk
+/// (`config.json`, `tokenizer.json`, `model.safetensors`, e.g. a
+/// sentence-transformers export such as all-MiniLM-L6-v2), run on the CPU.
+///
+/// The model is loaded on first use. Inference needs the
+/// `local-embeddings` cargo feature; without it `embed` reports how to
+/// enable it instead of silently degrading to keyword search.
+pub struct LocalEmbedding {
+    model_dir: PathBuf,
+    dims: usize,
+    #[cfg(feature = "local-embeddings")]
+    model: tokio::sync::OnceCell<std::sync::Arc<local::SentenceModel>>,
+}
+
+impl LocalEmbedding {
+    /// `fallback_dims` is used only if the model's `config.json` does not
+    /// declare `hidden_size`.
+    pub fn new(model_dir: &Path, fallback_dims: usize) -> Self {
+        let model_dir = PathBuf::from(shellexpand::tilde(&model_dir.to_string_lossy()).as_ref());
+        let dims = std::fs::read_to_string(model_dir.join("config.json"))
+            .ok()
+            .and_then(|raw| serde_json::from_str::<serde_json::Value>(&raw).ok())
+            .and_then(|config| config.get("hidden_size")?.as_u64())
+            .and_then(|size| usize::try_from(size).ok())
+            .unwrap_or(fallback_dims);
+        Self {
+            model_dir,
+            dims,
+            #[cfg(feature = "local-embeddings")]
+            model: tokio::sync::OnceCell::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for LocalEmbedding {
+    fn name(&self) -> &str {
+        "local"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    #[cfg(feature = "local-embeddings")]
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let model = self
+            .model
+            .get_or_try_init(|| async {
+                let dir = self.model_dir.clone();
+                tokio::task::spawn_blocking(move || local::SentenceModel::load(&dir))
+                    .await?
+                    .map(std::sync::Arc::new)
+            })
+            .await?
+            .clone();
+
+        let texts: Vec<String> = texts.iter().map(|t| (*t).to_string()).collect();
+        tokio::task::spawn_blocking(move || model.embed(&texts)).await?
+    }
+
+    #[cfg(not(feature = "local-embeddings"))]
+    async fn embed(&self, _texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        anyhow::bail!(
+            "Local embedding model {} needs a build with `--features local-embeddings`",
+            self.model_dir.display()
+        )
+    }
+}
+
+#[cfg(feature = "local-embeddings")]
+mod local {
+    use candle_core::{Device, Tensor};
+    use candle_nn::VarBuilder;
+    use candle_transformers::models::bert::{BertModel, Config, DTYPE};
+    use std::path::Path;
+    use tokenizers::{PaddingParams, Tokenizer, TruncationParams};
+
+    /// Texts per forward pass, bounding peak memory on large batches
+    const BATCH_SIZE: usize = 32;
+
+    pub struct SentenceModel {
+        model: BertModel,
+        tokenizer: Tokenizer,
+    }
+
+    impl SentenceModel {
+        pub fn load(dir: &Path) -> anyhow::Result<Self> {
+            let read = |name: &str| {
+                let path = dir.join(name);
+                std::fs::read(&path).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to read embedding model file {}: {e}",
+                        path.display()
+                    )
+                })
+            };
+
+            let config: Config = serde_json::from_slice(&read("config.json")?)?;
+            let mut tokenizer = Tokenizer::from_bytes(read("tokenizer.json")?)
+                .map_err(|e| anyhow::anyhow!("Invalid tokenizer.json: {e}"))?;
+            tokenizer.with_padding(Some(PaddingParams::default()));
+            tokenizer
+                .with_truncation(Some(TruncationParams {
+                    max_length: config.max_position_embeddings,
+                    ..TruncationParams::default()
+                }))
+                .map_err(|e| anyhow::anyhow!("Invalid tokenizer.json: {e}"))?;
+
+            let weights = VarBuilder::from_buffered_safetensors(
+                read("model.safetensors")?,
+                DTYPE,
+                &Device::Cpu,
+            )?;
+            let model = BertModel::load(weights, &config)?;
+            Ok(Self { model, tokenizer })
+        }
+
+        /// Mean-pooled, L2-normalized embeddings
+        pub fn embed(&self, texts: &[String]) -> anyhow::Result<Vec<Vec<f32>>> {
+            let mut embeddings = Vec::with_capacity(texts.len());
+            for batch in texts.chunks(BATCH_SIZE) {
+                let encodings = self
+                    .tokenizer
+                    .encode_batch(batch.to_vec(), true)
+                    .map_err(|e| anyhow::anyhow!("Tokenization failed: {e}"))?;
+
+                let device = &self.model.device;
+                let ids = encodings
+                    .iter()
+                    .map(|e| Tensor::new(e.get_ids(), device))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let masks = encodings
+                    .iter()
+                    .map(|e| Tensor::new(e.get_attention_mask(), device))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let input_ids = Tensor::stack(&ids, 0)?;
+                let attention_mask = Tensor::stack(&masks, 0)?;
+                let token_type_ids = input_ids.zeros_like()?;
+
+                // (batch, tokens, hidden)
+                let hidden =
+                    self.model
+                        .forward(&input_ids, &token_type_ids, Some(&attention_mask))?;
+
+                // Average over real tokens only, ignoring padding
+                let mask = attention_mask.to_dtype(DTYPE)?.unsqueeze(2)?;
+                let pooled = hidden
+                    .broadcast_mul(&mask)?
+                    .sum(1)?
+                    .broadcast_div(&mask.sum(1)?)?;
+                let norm = pooled.sqr()?.sum_keepdim(1)?.sqrt()?;
+                embeddings.extend(pooled.broadcast_div(&norm)?.to_vec2::<f32>()?);
+            }
+            Ok(embeddings)
+        }
+    }
+}
+
 // ── Factory ──────────────────────────────────────────────────
 
 pub fn create_embedding_provider(
@@ -146,6 +384,15 @@ pub fn create_embedding_provider(
             let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
         }
+        "ollama" => Box::new(OllamaEmbedding::new("http://localhost:11434", model, dims)),
+        name if name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:").unwrap_or("");
+            Box::new(OllamaEmbedding::new(base_url, model, dims))
+        }
+        name if name.starts_with("local:") => {
+            let model_dir = name.strip_prefix("local:").unwrap_or("");
+            Box::new(LocalEmbedding::new(Path::new(model_dir), dims))
+        }
         _ => Box::new(NoopEmbedding),
     }
 }
@@ -249,4 +496,98 @@ mod tests {
         let p = OpenAiEmbedding::new("http://localhost", "k", "m", 384);
         assert_eq!(p.dimensions(), 384);
     }
+
+    // ── Ollama / local ───────────────────────────────────────────
+
+    #[test]
+    fn factory_ollama_default_url() {
+        let p = create_embedding_provider("ollama", None, "nomic-embed-text", 768);
+        assert_eq!(p.name(), "ollama");
+        assert_eq!(p.dimensions(), 768);
+    }
+
+    #[test]
+    fn ollama_custom_url_trailing_slash_stripped() {
+        let p = OllamaEmbedding::new("http://gpu-box:11434/", "nomic-embed-text", 768);
+        assert_eq!(p.base_url, "http://gpu-box:11434");
+    }
+
+    #[test]
+    fn parse_embedding_reads_number_array() {
+        let json = serde_json::json!({"embedding": [0.5, -1.0, 2]});
+        assert_eq!(parse_embedding(&json), Some(vec![0.5, -1.0, 2.0]));
+        assert_eq!(parse_embedding(&serde_json::json!({"error": "x"})), None);
+    }
+
+    #[test]
+    fn local_dimensions_come_from_model_config() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("config.json"), r#"{"hidden_size": 384}"#).unwrap();
+        let provider = format!("local:{}", tmp.path().display());
+        let p = create_embedding_provider(&provider, None, "model", 1536);
+        assert_eq!(p.name(), "local");
+        assert_eq!(p.dimensions(), 384);
+    }
+
+    #[test]
+    fn local_dimensions_fall_back_to_config_value() {
+        let p = LocalEmbedding::new(Path::new("/nonexistent/model"), 768);
+        assert_eq!(p.dimensions(), 768);
+    }
+
+    #[tokio::test]
+    async fn local_embed_reports_missing_model() {
+        let p = LocalEmbedding::new(Path::new("/nonexistent/model"), 384);
+        let err = p.embed(&["hello"]).await.unwrap_err().to_string();
+        assert!(err.contains("/nonexistent/model"), "{err}");
+    }
+
+    #[cfg(feature = "local-embeddings")]
+    #[tokio::test]
+    async fn local_model_embeds_with_a_tiny_bert() {
+        use candle_core::{DType, Device};
+        use candle_nn::{VarBuilder, VarMap};
+        use candle_transformers::models::bert::{BertModel, Config};
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let config = serde_json::json!({
+            "vocab_size": 8, "hidden_size": 16, "num_hidden_layers": 1,
+            "num_attention_heads": 2, "intermediate_size": 32, "hidden_act": "gelu",
+            "hidden_dropout_prob": 0.0, "max_position_embeddings": 32, "type_vocab_size": 2,
+            "initializer_range": 0.02, "layer_norm_eps": 1e-12, "pad_token_id": 0,
+            "classifier_dropout": null, "model_type": "bert"
+        });
+        std::fs::write(tmp.path().join("config.json"), config.to_string()).unwrap();
+        let tokenizer = serde_json::json!({
+            "version": "1.0", "truncation": null, "padding": null, "added_tokens": [],
+            "normalizer": null, "pre_tokenizer": {"type": "Whitespace"},
+            "post_processor": null, "decoder": null,
+            "model": {
+                "type": "WordLevel", "unk_token": "[UNK]",
+                "vocab": {"[PAD]": 0, "[UNK]": 1, "deploy": 2, "the": 3, "server": 4}
+            }
+        });
+        std::fs::write(tmp.path().join("tokenizer.json"), tokenizer.to_string()).unwrap();
+
+        // Randomly initialised weights are enough to exercise the pipeline
+        let varmap = VarMap::new();
+        let vb = VarBuilder::from_varmap(&varmap, DType::F32, &Device::Cpu);
+        BertModel::load(vb, &serde_json::from_value::<Config>(config).unwrap()).unwrap();
+        varmap.save(tmp.path().join("model.safetensors")).unwrap();
+
+        let p = LocalEmbedding::new(tmp.path(), 0);
+        assert_eq!(p.dimensions(), 16);
+        let vectors = p.embed(&["deploy the server", "server"]).await.unwrap();
+        assert_eq!(vectors.len(), 2);
+        for v in &vectors {
+            assert_eq!(v.len(), 16);
+            let norm: f32 = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!((norm - 1.0).abs() < 1e-4);
+        }
+        // Padding must not change a text's embedding
+        let alone = p.embed_one("server").await.unwrap();
+        for (a, b) in alone.iter().zip(&vectors[1]) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
 }
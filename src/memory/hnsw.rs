@@ -0,0 +1,718 @@
+// HNSW approximate nearest-neighbour index over memory embeddings.
+//
+// Vectors are L2-normalized on insert, so similarity is a dot product
+// (cosine). Removing an entry leaves a tombstone: the node keeps routing
+// searches but is never returned; rebuilding the index drops them.
+// Quantized indexes keep each vector as int8 plus a scale (4x smaller);
+// queries stay f32, so only the stored side loses precision.
+
+use anyhow::{Context, Result};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const MAGIC: &[u8; 8] = b"VZHNSW01";
+/// Links per node on the upper layers.
+const M: usize = 16;
+/// Links per node on the bottom layer.
+const M0: usize = 2 * M;
+/// Candidate list size while inserting.
+const EF_CONSTRUCTION: usize = 100;
+/// Minimum candidate list size while searching.
+const EF_SEARCH: usize = 64;
+const MAX_LEVEL: usize = 16;
+/// Sanity bound on dimensions read from disk.
+const MAX_DIMS: usize = 65_536;
+
+/// A stored, normalized vector.
+enum Stored {
+    F32(Vec<f32>),
+    I8 { values: Vec<i8>, scale: f32 },
+}
+
+impl Stored {
+    fn new(normalized: Vec<f32>, quantized: bool) -> Self {
+        if !quantized {
+            return Self::F32(normalized);
+        }
+        let max = normalized.iter().fold(0.0_f32, |m, x| m.max(x.abs()));
+        let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+        #[allow(clippy::cast_possible_truncation)]
+        let values = normalized
+            .iter()
+            .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+            .collect();
+        Self::I8 { values, scale }
+    }
+
+    /// Similarity to a normalized f32 query.
+    fn dot_query(&self, query: &[f32]) -> f32 {
+        match self {
+            Self::F32(v) => v.iter().zip(query).map(|(a, b)| a * b).sum(),
+            Self::I8 { values, scale } => {
+                values
+                    .iter()
+                    .zip(query)
+                    .map(|(a, b)| f32::from(*a) * b)
+                    .sum::<f32>()
+                    * scale
+            }
+        }
+    }
+
+    /// Similarity between two stored vectors.
+    fn dot(&self, other: &Self) -> f32 {
+        match (self, other) {
+            (Self::F32(v), other) | (other, Self::F32(v)) => other.dot_query(v),
+            (
+                Self::I8 {
+                    values: a,
+                    scale: sa,
+                },
+                Self::I8 {
+                    values: b,
+                    scale: sb,
+                },
+            ) => {
+                let dot: i32 = a
+                    .iter()
+                    .zip(b)
+                    .map(|(x, y)| i32::from(*x) * i32::from(*y))
+                    .sum();
+                #[allow(clippy::cast_precision_loss)]
+                let dot = dot as f32;
+                dot * sa * sb
+            }
+        }
+    }
+}
+
+struct Node {
+    id: String,
+    /// Caller's version marker (e.g. `updated_at`) to detect stale vectors
+    stamp: String,
+    vector: Stored,
+    /// Neighbour lists, one per layer `0..=level`
+    links: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+/// Candidate ordered by similarity.
+#[derive(Clone, Copy, PartialEq)]
+struct Scored(f32, u32);
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+/// Hierarchical navigable small world graph keyed by memory id.
+pub struct HnswIndex {
+    /// Vector length, fixed by the first insert
+    dims: usize,
+    quantized: bool,
+    nodes: Vec<Node>,
+    /// Live (non-tombstoned) node per id
+    live: HashMap<String, u32>,
+    entry: Option<u32>,
+    rng: u64,
+}
+
+impl HnswIndex {
+    pub fn new(quantized: bool) -> Self {
+        Self {
+            dims: 0,
+            quantized,
+            nodes: Vec::new(),
+            live: HashMap::new(),
+            entry: None,
+            rng: 0x5EED_1DEA_F00D_CAFE,
+        }
+    }
+
+    pub fn quantized(&self) -> bool {
+        self.quantized
+    }
+
+    pub fn dims(&self) -> usize {
+        self.dims
+    }
+
+    /// Number of searchable entries.
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// Removed entries still held in the graph.
+    pub fn tombstones(&self) -> usize {
+        self.nodes.len() - self.live.len()
+    }
+
+    /// Whether tombstones outnumber live entries, so a rebuild would pay off.
+    pub fn needs_compaction(&self) -> bool {
+        self.tombstones() > self.live.len().max(64)
+    }
+
+    /// The stamp an id was inserted with, if it is in the index.
+    pub fn stamp(&self, id: &str) -> Option<&str> {
+        self.live
+            .get(id)
+            .map(|&n| self.nodes[n as usize].stamp.as_str())
+    }
+
+    /// Live `(id, stamp)` pairs.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.live.values().map(|&n| {
+            let node = &self.nodes[n as usize];
+            (node.id.as_str(), node.stamp.as_str())
+        })
+    }
+
+    /// Insert or replace the vector for `id`. Returns `false` (leaving the
+    /// index unchanged) for zero vectors or a dimension mismatch.
+    pub fn insert(&mut self, id: &str, stamp: &str, vector: &[f32]) -> bool {
+        if self.dims != 0 && vector.len() != self.dims {
+            return false;
+        }
+        let Some(normalized) = normalize(vector) else {
+            return false;
+        };
+        self.dims = vector.len();
+        self.remove(id);
+
+        let Ok(new) = u32::try_from(self.nodes.len()) else {
+            return false;
+        };
+        let level = self.random_level();
+        self.nodes.push(Node {
+            id: id.to_string(),
+            stamp: stamp.to_string(),
+            vector: Stored::new(normalized, self.quantized),
+            links: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.live.insert(id.to_string(), new);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(new);
+            return true;
+        };
+        let top = self.nodes[entry as usize].links.len() - 1;
+
+        let mut entry_points = vec![entry];
+        for layer in (level + 1..=top).rev() {
+            let sim = |n: u32| self.similarity(n, new);
+            entry_points = vec![self.search_layer(&sim, &entry_points, 1, layer)[0].1];
+        }
+        for layer in (0..=level.min(top)).rev() {
+            let sim = |n: u32| self.similarity(n, new);
+            let candidates = self.search_layer(&sim, &entry_points, EF_CONSTRUCTION, layer);
+            let neighbours = self.select_neighbours(&candidates, M);
+            let max_links = if layer == 0 { M0 } else { M };
+            for &n in &neighbours {
+                let links = &mut self.nodes[n as usize].links[layer];
+                links.push(new);
+                if links.len() > max_links {
+                    self.shrink(n, layer, max_links);
+                }
+            }
+            self.nodes[new as usize].links[layer] = neighbours;
+            entry_points = candidates.iter().map(|c| c.1).collect();
+        }
+
+        if level > top {
+            self.entry = Some(new);
+        }
+        true
+    }
+
+    /// Tombstone the entry for `id`. Returns whether it was present.
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.live.remove(id) {
+            Some(n) => {
+                self.nodes[n as usize].deleted = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The `limit` most similar live entries as `(id, cosine similarity)`,
+    /// best first. Like the exact scan, only positive similarities count.
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<(String, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if query.len() != self.dims || limit == 0 {
+            return Vec::new();
+        }
+        let Some(query) = normalize(query) else {
+            return Vec::new();
+        };
+        let sim = |n: u32| self.nodes[n as usize].vector.dot_query(&query);
+
+        let top = self.nodes[entry as usize].links.len() - 1;
+        let mut entry_points = vec![entry];
+        for layer in (1..=top).rev() {
+            entry_points = vec![self.search_layer(&sim, &entry_points, 1, layer)[0].1];
+        }
+        let ef = EF_SEARCH.max(limit) + self.tombstones().min(EF_SEARCH);
+        self.search_layer(&sim, &entry_points, ef, 0)
+            .into_iter()
+            .filter(|&(score, n)| score > 0.0 && !self.nodes[n as usize].deleted)
+            .take(limit)
+            .map(|(score, n)| (self.nodes[n as usize].id.clone(), score.min(1.0)))
+            .collect()
+    }
+
+    fn similarity(&self, a: u32, b: u32) -> f32 {
+        self.nodes[a as usize]
+            .vector
+            .dot(&self.nodes[b as usize].vector)
+    }
+
+    /// Best-first search of one layer, returning up to `ef` candidates as
+    /// `(similarity, node)`, best first.
+    fn search_layer(
+        &self,
+        sim: &impl Fn(u32) -> f32,
+        entry_points: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<(f32, u32)> {
+        let mut visited: HashSet<u32> = entry_points.iter().copied().collect();
+        let mut candidates: BinaryHeap<Scored> = BinaryHeap::new();
+        let mut best: BinaryHeap<Reverse<Scored>> = BinaryHeap::new();
+        for &n in entry_points {
+            let scored = Scored(sim(n), n);
+            candidates.push(scored);
+            best.push(Reverse(scored));
+            if best.len() > ef {
+                best.pop();
+            }
+        }
+
+        while let Some(Scored(score, n)) = candidates.pop() {
+            let worst = best.peek().map_or(f32::MIN, |r| r.0 .0);
+            if score < worst && best.len() >= ef {
+                break;
+            }
+            let Some(links) = self.nodes[n as usize].links.get(layer) else {
+                continue;
+            };
+            for &next in links {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let scored = Scored(sim(next), next);
+                let worst = best.peek().map_or(f32::MIN, |r| r.0 .0);
+                if best.len() < ef || scored.0 > worst {
+                    candidates.push(scored);
+                    best.push(Reverse(scored));
+                    if best.len() > ef {
+                        best.pop();
+                    }
+                }
+            }
+        }
+
+        let mut found: Vec<(f32, u32)> = best.into_iter().map(|Reverse(s)| (s.0, s.1)).collect();
+        found.sort_by(|a, b| b.0.total_cmp(&a.0));
+        found
+    }
+
+    /// Pick up to `m` neighbours from candidates (best first), preferring
+    /// ones closer to the new node than to any neighbour already picked so
+    /// links spread across clusters; pruned candidates fill any gap.
+    fn select_neighbours(&self, candidates: &[(f32, u32)], m: usize) -> Vec<u32> {
+        let mut selected: Vec<u32> = Vec::with_capacity(m);
+        let mut pruned = Vec::new();
+        for &(score, c) in candidates {
+            if selected.len() >= m {
+                break;
+            }
+            if selected.iter().all(|&s| self.similarity(c, s) < score) {
+                selected.push(c);
+            } else {
+                pruned.push(c);
+            }
+        }
+        for c in pruned {
+            if selected.len() >= m {
+                break;
+            }
+            selected.push(c);
+        }
+        selected
+    }
+
+    fn shrink(&mut self, node: u32, layer: usize, max_links: usize) {
+        let mut candidates: Vec<(f32, u32)> = self.nodes[node as usize].links[layer]
+            .iter()
+            .map(|&n| (self.similarity(node, n), n))
+            .collect();
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+        self.nodes[node as usize].links[layer] = self.select_neighbours(&candidates, max_links);
+    }
+
+    /// Level for a new node: geometric with ratio `1/M`.
+    fn random_level(&mut self) -> usize {
+        // SplitMix64
+        self.rng = self.rng.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        #[allow(clippy::cast_precision_loss)]
+        let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let level = (-uniform.ln() / (M as f64).ln()) as usize;
+        level.min(MAX_LEVEL)
+    }
+
+    // ── Persistence ──────────────────────────────────────────────
+
+    /// Write the index atomically (temp file + rename).
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        {
+            let mut out = BufWriter::new(std::fs::File::create(&tmp)?);
+            out.write_all(MAGIC)?;
+            write_u32(&mut out, self.dims)?;
+            out.write_all(&[u8::from(self.quantized)])?;
+            out.write_all(&self.rng.to_le_bytes())?;
+            write_u32(
+                &mut out,
+                self.entry.map_or(u32::MAX as usize, |e| e as usize),
+            )?;
+            write_u32(&mut out, self.nodes.len())?;
+            for node in &self.nodes {
+                write_str(&mut out, &node.id)?;
+                write_str(&mut out, &node.stamp)?;
+                out.write_all(&[u8::from(node.deleted)])?;
+                match &node.vector {
+                    Stored::F32(values) => {
+                        for v in values {
+                            out.write_all(&v.to_le_bytes())?;
+                        }
+                    }
+                    Stored::I8 { values, scale } => {
+                        out.write_all(&scale.to_le_bytes())?;
+                        #[allow(clippy::cast_sign_loss)]
+                        let bytes: Vec<u8> = values.iter().map(|v| *v as u8).collect();
+                        out.write_all(&bytes)?;
+                    }
+                }
+                write_u32(&mut out, node.links.len())?;
+                for links in &node.links {
+                    write_u32(&mut out, links.len())?;
+                    for link in links {
+                        out.write_all(&link.to_le_bytes())?;
+                    }
+                }
+            }
+            out.flush()?;
+        }
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut input = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        input.read_exact(&mut magic)?;
+        anyhow::ensure!(&magic == MAGIC, "not a vector index file");
+        let dims = read_u32(&mut input)?;
+        anyhow::ensure!(dims <= MAX_DIMS, "implausible dimensions {dims}");
+        let quantized = read_u8(&mut input)? != 0;
+        let mut rng = [0u8; 8];
+        input.read_exact(&mut rng)?;
+        let entry = read_u32(&mut input)?;
+        let count = read_u32(&mut input)?;
+
+        let mut nodes = Vec::with_capacity(count.min(1 << 20));
+        let mut live = HashMap::new();
+        for n in 0..count {
+            let id = read_str(&mut input)?;
+            let stamp = read_str(&mut input)?;
+            let deleted = read_u8(&mut input)? != 0;
+            let vector = if quantized {
+                let mut scale = [0u8; 4];
+                input.read_exact(&mut scale)?;
+                let mut bytes = vec![0u8; dims];
+                input.read_exact(&mut bytes)?;
+                #[allow(clippy::cast_possible_wrap)]
+                let values = bytes.into_iter().map(|b| b as i8).collect();
+                Stored::I8 {
+                    values,
+                    scale: f32::from_le_bytes(scale),
+                }
+            } else {
+                let mut bytes = vec![0u8; dims * 4];
+                input.read_exact(&mut bytes)?;
+                Stored::F32(super::vector::bytes_to_vec(&bytes))
+            };
+            let levels = read_u32(&mut input)?;
+            anyhow::ensure!((1..=MAX_LEVEL + 1).contains(&levels), "corrupt node levels");
+            let mut links = Vec::with_capacity(levels);
+            for _ in 0..levels {
+                let len = read_u32(&mut input)?;
+                anyhow::ensure!(len <= M0, "corrupt link list");
+                let mut layer = Vec::with_capacity(len);
+                for _ in 0..len {
+                    let link = read_u32(&mut input)?;
+                    anyhow::ensure!(link < count, "link out of range");
+                    #[allow(clippy::cast_possible_truncation)]
+                    layer.push(link as u32);
+                }
+                links.push(layer);
+            }
+            if !deleted {
+                #[allow(clippy::cast_possible_truncation)]
+                live.insert(id.clone(), n as u32);
+            }
+            nodes.push(Node {
+                id,
+                stamp,
+                vector,
+                links,
+                deleted,
+            });
+        }
+
+        let entry = if entry == u32::MAX as usize {
+            None
+        } else {
+            anyhow::ensure!(entry < count, "entry point out of range");
+            #[allow(clippy::cast_possible_truncation)]
+            Some(entry as u32)
+        };
+        Ok(Self {
+            dims,
+            quantized,
+            nodes,
+            live,
+            entry,
+            rng: u64::from_le_bytes(rng),
+        })
+    }
+}
+
+/// Unit-length copy of `v`, or `None` for zero / non-finite vectors.
+fn normalize(v: &[f32]) -> Option<Vec<f32>> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if !norm.is_finite() || norm < f32::EPSILON {
+        return None;
+    }
+    Some(v.iter().map(|x| x / norm).collect())
+}
+
+fn write_u32(out: &mut impl Write, value: usize) -> Result<()> {
+    let value = u32::try_from(value).context("value too large for index file")?;
+    out.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
+
+fn write_str(out: &mut impl Write, value: &str) -> Result<()> {
+    write_u32(out, value.len())?;
+    out.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+fn read_u8(input: &mut impl Read) -> Result<u8> {
+    let mut buf = [0u8; 1];
+    input.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_u32(input: &mut impl Read) -> Result<usize> {
+    let mut buf = [0u8; 4];
+    input.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf) as usize)
+}
+
+fn read_str(input: &mut impl Read) -> Result<String> {
+    let len = read_u32(input)?;
+    anyhow::ensure!(len <= 1 << 16, "corrupt string length");
+    let mut buf = vec![0u8; len];
+    input.read_exact(&mut buf)?;
+    Ok(String::from_utf8(buf)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Deterministic pseudo-random vectors in [-1, 1).
+    fn random_vectors(count: usize, dims: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..count)
+            .map(|_| {
+                (0..dims)
+                    .map(|_| {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1_442_695_040_888_963_407);
+                        #[allow(clippy::cast_precision_loss)]
+                        let unit = (state >> 40) as f32 / (1u64 << 24) as f32;
+                        unit * 2.0 - 1.0
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn exact_top(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<String> {
+        let mut scored: Vec<(usize, f32)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, super::super::vector::cosine_similarity(query, v)))
+            .filter(|(_, s)| *s > 0.0)
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored
+            .into_iter()
+            .take(k)
+            .map(|(i, _)| i.to_string())
+            .collect()
+    }
+
+    fn build(vectors: &[Vec<f32>], quantized: bool) -> HnswIndex {
+        let mut index = HnswIndex::new(quantized);
+        for (i, v) in vectors.iter().enumerate() {
+            assert!(index.insert(&i.to_string(), "t", v));
+        }
+        index
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn recall_at_10(index: &HnswIndex, vectors: &[Vec<f32>], queries: &[Vec<f32>]) -> f32 {
+        let mut hits = 0;
+        let mut total = 0;
+        for query in queries {
+            let expected = exact_top(vectors, query, 10);
+            let found: HashSet<String> = index
+                .search(query, 10)
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect();
+            hits += expected.iter().filter(|id| found.contains(*id)).count();
+            total += expected.len();
+        }
+        hits as f32 / total as f32
+    }
+
+    #[test]
+    fn finds_the_exact_vector_first() {
+        let vectors = random_vectors(200, 16, 1);
+        let index = build(&vectors, false);
+        let results = index.search(&vectors[42], 3);
+        assert_eq!(results[0].0, "42");
+        assert!((results[0].1 - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn recall_matches_brute_force() {
+        let vectors = random_vectors(1500, 24, 7);
+        let queries = random_vectors(30, 24, 99);
+        let index = build(&vectors, false);
+        let recall = recall_at_10(&index, &vectors, &queries);
+        assert!(recall >= 0.95, "recall@10 was {recall}");
+    }
+
+    #[test]
+    fn quantized_recall_stays_high() {
+        let vectors = random_vectors(1500, 24, 7);
+        let queries = random_vectors(30, 24, 99);
+        let index = build(&vectors, true);
+        let recall = recall_at_10(&index, &vectors, &queries);
+        assert!(recall >= 0.85, "quantized recall@10 was {recall}");
+    }
+
+    #[test]
+    fn removed_and_replaced_entries() {
+        let vectors = random_vectors(100, 8, 3);
+        let mut index = build(&vectors, false);
+
+        assert!(index.remove("5"));
+        assert!(!index.remove("5"));
+        assert!(index.search(&vectors[5], 5).iter().all(|(id, _)| id != "5"));
+        assert_eq!(index.len(), 99);
+        assert_eq!(index.tombstones(), 1);
+
+        // Re-inserting an id replaces its vector
+        assert!(index.insert("6", "t2", &vectors[7]));
+        assert_eq!(index.stamp("6"), Some("t2"));
+        let top: Vec<String> = index
+            .search(&vectors[7], 2)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert!(top.contains(&"6".to_string()) && top.contains(&"7".to_string()));
+    }
+
+    #[test]
+    fn rejects_mismatched_and_zero_vectors() {
+        let mut index = HnswIndex::new(false);
+        assert!(!index.insert("zero", "t", &[0.0, 0.0]));
+        assert!(index.insert("a", "t", &[1.0, 0.0]));
+        assert!(!index.insert("b", "t", &[1.0, 0.0, 0.0]));
+        assert!(index.search(&[1.0, 0.0, 0.0], 5).is_empty());
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let vectors = random_vectors(300, 12, 5);
+        for quantized in [false, true] {
+            let path = tmp.path().join(format!("index-{quantized}.hnsw"));
+            let mut index = build(&vectors, quantized);
+            index.remove("3");
+            index.save(&path).unwrap();
+
+            let loaded = HnswIndex::load(&path).unwrap();
+            assert_eq!(loaded.quantized(), quantized);
+            assert_eq!(loaded.len(), 299);
+            assert_eq!(loaded.stamp("10"), Some("t"));
+            assert_eq!(loaded.stamp("3"), None);
+            assert_eq!(
+                loaded.search(&vectors[10], 5),
+                index.search(&vectors[10], 5)
+            );
+        }
+    }
+
+    #[test]
+    fn load_rejects_garbage() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("bad.hnsw");
+        std::fs::write(&path, b"not an index").unwrap();
+        assert!(HnswIndex::load(&path).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use super::sqlite::expiry_string;
 use crate::config::MemoryConfig;
 use anyhow::Result;
 use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
@@ -17,6 +18,8 @@ struct HygieneReport {
     purged_memory_archives: u64,
     purged_session_archives: u64,
     pruned_conversation_rows: u64,
+    #[serde(default)]
+    pruned_expired_rows: u64,
 }
 
 impl HygieneReport {
@@ -26,6 +29,7 @@ impl HygieneReport {
             + self.purged_memory_archives
             + self.purged_session_archives
             + self.pruned_conversation_rows
+            + self.pruned_expired_rows
     }
 }
 
@@ -59,18 +63,20 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
             workspace_dir,
             config.conversation_retention_days,
         )?,
+        pruned_expired_rows: prune_expired_rows(workspace_dir)?,
     };
 
     write_state(workspace_dir, &report)?;
 
     if report.total_actions() > 0 {
         tracing::info!(
-            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={}",
+            "memory hygiene complete: archived_memory={} archived_sessions={} purged_memory={} purged_sessions={} pruned_conversation_rows={} pruned_expired_rows={}",
             report.archived_memory_files,
             report.archived_session_files,
             report.purged_memory_archives,
             report.purged_session_archives,
             report.pruned_conversation_rows,
+            report.pruned_expired_rows,
         );
     }
 
@@ -316,6 +322,32 @@ fn prune_conversation_rows(workspace_dir: &Path, retention_days: u32) -> Result<
     Ok(u64::try_from(affected).unwrap_or(0))
 }
 
+/// Delete memories whose `expires_at` has passed (always on: an expired
+/// entry is already hidden from recall)
+fn prune_expired_rows(workspace_dir: &Path) -> Result<u64> {
+    let db_path = workspace_dir.join("memory").join("brain.db");
+    if !db_path.exists() {
+        return Ok(0);
+    }
+
+    let conn = Connection::open(db_path)?;
+    let has_expiry: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info('memories') WHERE name = 'expires_at'",
+        [],
+        |row| row.get(0),
+    )?;
+    if has_expiry == 0 {
+        return Ok(0);
+    }
+
+    let affected = conn.execute(
+        "DELETE FROM memories WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+        params![expiry_string(Utc::now())],
+    )?;
+
+    Ok(u64::try_from(affected).unwrap_or(0))
+}
+
 fn memory_date_from_filename(filename: &str) -> Option<NaiveDate> {
     let stem = filename.strip_suffix(".md")?;
     let date_part = stem.split('_').next().unwrap_or(stem);
@@ -377,7 +409,7 @@ fn split_name(filename: &str) -> (&str, &str) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use crate::memory::{Memory, MemoryCategory, MemoryMetadata, SqliteMemory};
     use tempfile::TempDir;
 
     fn default_cfg() -> MemoryConfig {
@@ -535,4 +567,48 @@ mod tests {
             "core memory should remain"
         );
     }
+
+    #[tokio::test]
+    async fn prunes_expired_rows_in_sqlite_backend() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+
+        let mem = SqliteMemory::new(workspace).unwrap();
+        let expiring = |at: DateTime<Utc>| MemoryMetadata {
+            expires_at: Some(at.to_rfc3339()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "reminder_past",
+            "standup moved",
+            MemoryCategory::Core,
+            &expiring(Utc::now() - Duration::hours(1)),
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "reminder_future",
+            "release freeze",
+            MemoryCategory::Core,
+            &expiring(Utc::now() + Duration::days(1)),
+        )
+        .await
+        .unwrap();
+        mem.store("core_keep", "durable", MemoryCategory::Core)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let mut cfg = default_cfg();
+        cfg.archive_after_days = 0;
+        cfg.purge_after_days = 0;
+        cfg.conversation_retention_days = 0;
+
+        run_if_due(&cfg, workspace).unwrap();
+
+        let mem2 = SqliteMemory::new(workspace).unwrap();
+        assert!(mem2.get("reminder_past").await.unwrap().is_none());
+        assert!(mem2.get("reminder_future").await.unwrap().is_some());
+        assert!(mem2.get("core_keep").await.unwrap().is_some());
+    }
 }
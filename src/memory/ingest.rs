@@ -0,0 +1,560 @@
+// Document ingestion — chunk files into memory entries.
+//
+// Markdown is split on headings, plain text and source files on paragraph
+// boundaries. Each chunk is stored as `doc:<source>#<index>`, and a manifest
+// of content hashes lets re-ingest skip unchanged files and replace the
+// chunks of edited or deleted ones.
+
+use super::chunker::{chunk_markdown, chunk_text, Chunk};
+use super::traits::{Memory, MemoryCategory, MemoryMetadata};
+use anyhow::{Context, Result};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Memory category for ingested document chunks.
+pub const DOCUMENT_CATEGORY: &str = "document";
+
+/// Files larger than this are skipped (2 MB).
+const MAX_DOCUMENT_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Directories never descended into when ingesting a directory.
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "__pycache__"];
+
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx"];
+
+const TEXT_EXTENSIONS: &[&str] = &["txt", "text", "rst", "adoc", "org", "csv", "log"];
+
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "swift", "c", "h", "cc", "cpp",
+    "hpp", "cs", "rb", "php", "scala", "lua", "sh", "bash", "zsh", "sql", "proto", "tf", "toml",
+    "yaml", "yml", "json", "html", "css", "scss",
+];
+
+/// How a file is split into chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// Split on headings, then paragraphs
+    Markdown,
+    /// Plain text and source code, split on paragraphs
+    Text,
+}
+
+impl DocumentKind {
+    /// Kind of a file by extension; `None` for files not ingested from
+    /// directories and globs.
+    pub fn of(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        if MARKDOWN_EXTENSIONS.contains(&ext.as_str()) {
+            Some(Self::Markdown)
+        } else if TEXT_EXTENSIONS.contains(&ext.as_str())
+            || SOURCE_EXTENSIONS.contains(&ext.as_str())
+        {
+            Some(Self::Text)
+        } else {
+            None
+        }
+    }
+
+    fn chunk(self, text: &str, max_tokens: usize) -> Vec<Chunk> {
+        match self {
+            Self::Markdown => chunk_markdown(text, max_tokens),
+            Self::Text => chunk_text(text, max_tokens),
+        }
+    }
+}
+
+/// Files resolved from an ingest target.
+#[derive(Debug, Default)]
+pub struct DocumentSet {
+    /// Canonical paths of the files to ingest
+    pub files: Vec<PathBuf>,
+    /// The directory walked, if the target was one; documents ingested from
+    /// it earlier that no longer exist are dropped from memory.
+    pub root: Option<PathBuf>,
+}
+
+/// Resolve a file, directory or glob pattern to the documents it names.
+///
+/// An explicit file is always taken; directories (walked recursively,
+/// skipping hidden and build directories) and globs keep only known
+/// Markdown, text and source extensions.
+pub fn collect_documents(target: &str) -> Result<DocumentSet> {
+    let path = Path::new(target);
+    if path.is_file() {
+        return Ok(DocumentSet {
+            files: vec![path.canonicalize()?],
+            root: None,
+        });
+    }
+
+    let (pattern, root) = if path.is_dir() {
+        let root = path.canonicalize()?;
+        let pattern = format!("{}/**/*", glob::Pattern::escape(&root.to_string_lossy()));
+        (pattern, Some(root))
+    } else if target.contains(['*', '?', '[']) {
+        (target.to_string(), None)
+    } else {
+        anyhow::bail!("No such file or directory: {target}");
+    };
+
+    let mut files = Vec::new();
+    for entry in glob::glob(&pattern).with_context(|| format!("Invalid pattern: {target}"))? {
+        let Ok(file) = entry else { continue };
+        if !file.is_file() || DocumentKind::of(&file).is_none() {
+            continue;
+        }
+        if let Some(root) = &root {
+            if is_skipped(file.strip_prefix(root).unwrap_or(&file)) {
+                continue;
+            }
+        }
+        if let Ok(file) = file.canonicalize() {
+            files.push(file);
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(DocumentSet { files, root })
+}
+
+/// Hidden files and anything under a build/dependency directory.
+fn is_skipped(relative: &Path) -> bool {
+    relative.components().any(|c| {
+        let name = c.as_os_str().to_string_lossy();
+        name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref())
+    })
+}
+
+// ── Manifest ─────────────────────────────────────────────────────
+
+/// What was stored for each document, keyed by source name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    documents: BTreeMap<String, IngestedDocument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IngestedDocument {
+    path: PathBuf,
+    /// SHA-256 of the file; empty when the last ingest failed part-way
+    hash: String,
+    /// Chunk keys `#0..chunks` may exist in memory
+    chunks: usize,
+    chunk_max_tokens: usize,
+    ingested_at: String,
+}
+
+fn manifest_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("memory").join("documents.json")
+}
+
+fn load_manifest(workspace_dir: &Path) -> Manifest {
+    let path = manifest_path(workspace_dir);
+    let Ok(raw) = std::fs::read_to_string(&path) else {
+        return Manifest::default();
+    };
+    serde_json::from_str(&raw).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring unreadable {}: {e}", path.display());
+        Manifest::default()
+    })
+}
+
+fn save_manifest(workspace_dir: &Path, manifest: &Manifest) -> Result<()> {
+    let path = manifest_path(workspace_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+// ── Ingestion ────────────────────────────────────────────────────
+
+/// Outcome of one ingest run, by source name.
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub unchanged: Vec<String>,
+    pub removed: Vec<String>,
+    pub failed: Vec<(String, String)>,
+    pub chunks_stored: usize,
+}
+
+impl IngestReport {
+    pub fn summary(&self) -> String {
+        let mut out = format!(
+            "{} new, {} updated, {} unchanged, {} removed — {} chunks stored",
+            self.added.len(),
+            self.updated.len(),
+            self.unchanged.len(),
+            self.removed.len(),
+            self.chunks_stored,
+        );
+        for (source, reason) in &self.failed {
+            let _ = write!(out, "\nFailed {source}: {reason}");
+        }
+        out
+    }
+}
+
+/// Memory key of one document chunk.
+pub fn chunk_key(source: &str, index: usize) -> String {
+    format!("doc:{source}#{index}")
+}
+
+/// Store every changed document in `set` as chunk entries, replacing the
+/// chunks of earlier versions and dropping documents deleted from a walked
+/// directory.
+pub async fn ingest_documents(
+    memory: &dyn Memory,
+    workspace_dir: &Path,
+    set: &DocumentSet,
+    max_tokens: usize,
+) -> Result<IngestReport> {
+    if memory.name() == "markdown" {
+        anyhow::bail!(
+            "Document ingestion needs the sqlite memory backend: markdown memory is append-only, \
+             so edited documents could not replace their old chunks"
+        );
+    }
+
+    let workspace_root = workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    let mut manifest = load_manifest(workspace_dir);
+    let mut report = IngestReport::default();
+
+    for path in &set.files {
+        let source = source_name(path, &workspace_root);
+        let previous = manifest.documents.get(&source).cloned();
+        match ingest_file(memory, path, &source, previous.as_ref(), max_tokens).await {
+            Ok(FileOutcome::Unchanged) => report.unchanged.push(source),
+            Ok(FileOutcome::Stored(doc)) => {
+                report.chunks_stored += doc.chunks;
+                manifest.documents.insert(source.clone(), doc);
+                if previous.is_some() {
+                    report.updated.push(source);
+                } else {
+                    report.added.push(source);
+                }
+            }
+            Err(failure) => {
+                if let Some(doc) = failure.partial {
+                    manifest.documents.insert(source.clone(), doc);
+                }
+                report.failed.push((source, failure.reason));
+            }
+        }
+    }
+
+    if let Some(root) = &set.root {
+        let deleted: Vec<(String, IngestedDocument)> = manifest
+            .documents
+            .iter()
+            .filter(|(_, doc)| doc.path.starts_with(root) && !doc.path.exists())
+            .map(|(source, doc)| (source.clone(), doc.clone()))
+            .collect();
+        for (source, doc) in deleted {
+            forget_chunks(memory, &source, 0..doc.chunks).await?;
+            manifest.documents.remove(&source);
+            report.removed.push(source);
+        }
+    }
+
+    save_manifest(workspace_dir, &manifest)?;
+    Ok(report)
+}
+
+enum FileOutcome {
+    Unchanged,
+    Stored(IngestedDocument),
+}
+
+struct FileFailure {
+    reason: String,
+    /// Manifest entry covering chunks stored before the failure
+    partial: Option<IngestedDocument>,
+}
+
+impl From<String> for FileFailure {
+    fn from(reason: String) -> Self {
+        Self {
+            reason,
+            partial: None,
+        }
+    }
+}
+
+async fn ingest_file(
+    memory: &dyn Memory,
+    path: &Path,
+    source: &str,
+    previous: Option<&IngestedDocument>,
+    max_tokens: usize,
+) -> Result<FileOutcome, FileFailure> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("cannot read file: {e}"))?
+        .len();
+    if size > MAX_DOCUMENT_BYTES {
+        return Err(format!("{size} bytes is over the {MAX_DOCUMENT_BYTES} byte limit").into());
+    }
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("cannot read file: {e}"))?;
+    let hash = hex::encode(Sha256::digest(&bytes));
+    if previous.is_some_and(|p| p.hash == hash && p.chunk_max_tokens == max_tokens) {
+        return Ok(FileOutcome::Unchanged);
+    }
+    let text = String::from_utf8(bytes).map_err(|_| "not UTF-8 text".to_string())?;
+
+    let kind = DocumentKind::of(path).unwrap_or(DocumentKind::Text);
+    let chunks = kind.chunk(&text, max_tokens);
+    let previous_chunks = previous.map_or(0, |p| p.chunks);
+    let mut doc = IngestedDocument {
+        path: path.to_path_buf(),
+        hash: String::new(),
+        chunks: previous_chunks,
+        chunk_max_tokens: max_tokens,
+        ingested_at: Local::now().to_rfc3339(),
+    };
+
+    let category = MemoryCategory::Custom(DOCUMENT_CATEGORY.into());
+    let metadata = MemoryMetadata::with_source(format!("file:{source}"));
+    for chunk in &chunks {
+        let key = chunk_key(source, chunk.index);
+        if let Err(e) = memory
+            .store_with_metadata(
+                &key,
+                &chunk_entry(source, chunk),
+                category.clone(),
+                &metadata,
+            )
+            .await
+        {
+            doc.chunks = doc.chunks.max(chunk.index);
+            return Err(FileFailure {
+                reason: format!("storing {key}: {e}"),
+                partial: Some(doc),
+            });
+        }
+    }
+
+    if let Err(e) = forget_chunks(memory, source, chunks.len()..previous_chunks).await {
+        return Err(FileFailure {
+            reason: format!("removing old chunks: {e}"),
+            partial: Some(IngestedDocument {
+                chunks: previous_chunks.max(chunks.len()),
+                ..doc
+            }),
+        });
+    }
+
+    doc.hash = hash;
+    doc.chunks = chunks.len();
+    Ok(FileOutcome::Stored(doc))
+}
+
+async fn forget_chunks(
+    memory: &dyn Memory,
+    source: &str,
+    indexes: std::ops::Range<usize>,
+) -> Result<()> {
+    for index in indexes {
+        memory.forget(&chunk_key(source, index)).await?;
+    }
+    Ok(())
+}
+
+/// A chunk as stored: where it came from, then its text.
+fn chunk_entry(source: &str, chunk: &Chunk) -> String {
+    let mut entry = format!("Source: {source} (chunk {})", chunk.index);
+    if let Some(heading) = &chunk.heading {
+        let _ = write!(
+            entry,
+            "\nSection: {}",
+            heading.trim_start_matches('#').trim()
+        );
+    }
+    entry.push_str("\n\n");
+    entry.push_str(&chunk.content);
+    entry
+}
+
+/// Workspace-relative path for documents inside the workspace, absolute
+/// otherwise.
+fn source_name(path: &Path, workspace_root: &Path) -> String {
+    path.strip_prefix(workspace_root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MarkdownMemory, SqliteMemory};
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, SqliteMemory) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        (tmp, mem)
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    async fn ingest(mem: &SqliteMemory, workspace: &Path, target: &Path) -> IngestReport {
+        let set = collect_documents(&target.to_string_lossy()).unwrap();
+        ingest_documents(mem, workspace, &set, 512).await.unwrap()
+    }
+
+    #[test]
+    fn document_kind_by_extension() {
+        assert_eq!(
+            DocumentKind::of(Path::new("a.md")),
+            Some(DocumentKind::Markdown)
+        );
+        assert_eq!(
+            DocumentKind::of(Path::new("a.RS")),
+            Some(DocumentKind::Text)
+        );
+        assert_eq!(
+            DocumentKind::of(Path::new("a.txt")),
+            Some(DocumentKind::Text)
+        );
+        assert_eq!(DocumentKind::of(Path::new("a.png")), None);
+        assert_eq!(DocumentKind::of(Path::new("Makefile")), None);
+    }
+
+    #[test]
+    fn collect_walks_directories_skipping_hidden_and_build_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let docs = tmp.path().join("docs");
+        write(&docs, "a.md", "a");
+        write(&docs, "adr/b.md", "b");
+        write(&docs, "logo.png", "png");
+        write(&docs, ".git/c.md", "c");
+        write(&docs, "node_modules/d.md", "d");
+
+        let set = collect_documents(&docs.to_string_lossy()).unwrap();
+        let names: Vec<_> = set
+            .files
+            .iter()
+            .map(|f| f.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.md", "b.md"]);
+        assert_eq!(set.root, Some(docs.canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn collect_expands_globs_and_rejects_missing_paths() {
+        let tmp = TempDir::new().unwrap();
+        write(tmp.path(), "one.md", "1");
+        write(tmp.path(), "two.txt", "2");
+
+        let pattern = format!("{}/*.md", tmp.path().display());
+        let set = collect_documents(&pattern).unwrap();
+        assert_eq!(set.files.len(), 1);
+        assert!(set.root.is_none());
+
+        let missing = tmp.path().join("nope.md");
+        assert!(collect_documents(&missing.to_string_lossy()).is_err());
+    }
+
+    #[tokio::test]
+    async fn ingest_stores_chunks_with_source_and_heading() {
+        let (tmp, mem) = setup();
+        let doc = write(
+            tmp.path(),
+            "runbooks/deploy.md",
+            "# Deploy\nRun the pipeline.\n\n## Rollback\nRevert the release tag.",
+        );
+
+        let report = ingest(&mem, tmp.path(), &doc).await;
+        assert_eq!(report.added, vec!["runbooks/deploy.md"]);
+        assert_eq!(report.chunks_stored, 2);
+
+        let entry = mem.get("doc:runbooks/deploy.md#1").await.unwrap().unwrap();
+        assert!(entry
+            .content
+            .starts_with("Source: runbooks/deploy.md (chunk 1)"));
+        assert!(entry.content.contains("Section: Rollback"));
+        assert!(entry.content.contains("Revert the release tag."));
+        assert_eq!(
+            entry.category,
+            MemoryCategory::Custom(DOCUMENT_CATEGORY.into())
+        );
+    }
+
+    #[tokio::test]
+    async fn reingest_skips_unchanged_and_replaces_edited_chunks() {
+        let (tmp, mem) = setup();
+        let doc = write(
+            tmp.path(),
+            "adr.md",
+            "# One\nFirst.\n\n# Two\nSecond.\n\n# Three\nThird.",
+        );
+        ingest(&mem, tmp.path(), &doc).await;
+
+        let report = ingest(&mem, tmp.path(), &doc).await;
+        assert_eq!(report.unchanged, vec!["adr.md"]);
+        assert_eq!(report.chunks_stored, 0);
+
+        std::fs::write(&doc, "# One\nRewritten.").unwrap();
+        let report = ingest(&mem, tmp.path(), &doc).await;
+        assert_eq!(report.updated, vec!["adr.md"]);
+        let first = mem.get("doc:adr.md#0").await.unwrap().unwrap();
+        assert!(first.content.contains("Rewritten."));
+        assert!(mem.get("doc:adr.md#1").await.unwrap().is_none());
+        assert!(mem.get("doc:adr.md#2").await.unwrap().is_none());
+        assert_eq!(mem.count().await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn files_deleted_from_an_ingested_directory_are_forgotten() {
+        let (tmp, mem) = setup();
+        let docs = tmp.path().join("docs");
+        write(&docs, "keep.md", "Keep me.");
+        let gone = write(&docs, "gone.md", "Delete me.");
+        ingest(&mem, tmp.path(), &docs).await;
+        assert_eq!(mem.count().await.unwrap(), 2);
+
+        std::fs::remove_file(gone).unwrap();
+        let report = ingest(&mem, tmp.path(), &docs).await;
+        assert_eq!(report.removed, vec!["docs/gone.md"]);
+        assert_eq!(report.unchanged, vec!["docs/keep.md"]);
+        assert!(mem.get("doc:docs/gone.md#0").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn non_utf8_files_are_reported_as_failed() {
+        let (tmp, mem) = setup();
+        let path = tmp.path().join("blob.txt");
+        std::fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+
+        let report = ingest(&mem, tmp.path(), &path).await;
+        assert_eq!(report.failed.len(), 1);
+        assert!(report.summary().contains("not UTF-8"));
+    }
+
+    #[tokio::test]
+    async fn markdown_backend_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let mem = MarkdownMemory::new(tmp.path());
+        let doc = write(tmp.path(), "a.md", "text");
+        let set = collect_documents(&doc.to_string_lossy()).unwrap();
+        assert!(ingest_documents(&mem, tmp.path(), &set, 512).await.is_err());
+    }
+}
@@ -91,6 +91,12 @@ impl MarkdownMemory {
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    tags: Vec::new(),
+                    source: None,
+                    importance: None,
+                    expires_at: None,
+                    access_count: 0,
+                    last_accessed: None,
                 }
             })
             .collect()
@@ -1,18 +1,24 @@
 pub mod chunker;
 pub mod embeddings;
+pub mod hnsw;
 pub mod hygiene;
+pub mod ingest;
 pub mod markdown;
 pub mod sqlite;
 pub mod traits;
+pub mod transfer;
 pub mod vector;
 
 pub use markdown::MarkdownMemory;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 
-use crate::config::MemoryConfig;
+use crate::config::{Config, MemoryConfig};
+use crate::util::truncate_with_ellipsis;
+use anyhow::Result;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -28,25 +34,11 @@ pub fn create_memory(
     }
 
     match config.backend.as_str() {
-        "sqlite" => {
-            let embedder: Arc<dyn embeddings::EmbeddingProvider> =
-                Arc::from(embeddings::create_embedding_provider(
-                    &config.embedding_provider,
-                    api_key,
-                    &config.embedding_model,
-                    config.embedding_dimensions,
-                ));
-
-            #[allow(clippy::cast_possible_truncation)]
-            let mem = SqliteMemory::with_embedder(
-                workspace_dir,
-                embedder,
-                config.vector_weight as f32,
-                config.keyword_weight as f32,
-                config.embedding_cache_size,
-            )?;
-            Ok(Box::new(mem))
-        }
+        "sqlite" => Ok(Box::new(create_sqlite_memory(
+            config,
+            workspace_dir,
+            api_key,
+        )?)),
         "markdown" | "none" => Ok(Box::new(MarkdownMemory::new(workspace_dir))),
         other => {
             tracing::warn!("Unknown memory backend '{other}', falling back to markdown");
@@ -55,6 +47,322 @@ pub fn create_memory(
     }
 }
 
+/// The sqlite backend with its configured embedder and vector index
+pub fn create_sqlite_memory(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    api_key: Option<&str>,
+) -> anyhow::Result<SqliteMemory> {
+    let embedder: Arc<dyn embeddings::EmbeddingProvider> =
+        Arc::from(embeddings::create_embedding_provider(
+            &config.embedding_provider,
+            api_key,
+            &config.embedding_model,
+            config.embedding_dimensions,
+        ));
+
+    #[allow(clippy::cast_possible_truncation)]
+    let mem = SqliteMemory::with_embedder(
+        workspace_dir,
+        embedder,
+        config.vector_weight as f32,
+        config.keyword_weight as f32,
+        config.embedding_cache_size,
+    )?;
+    let mode =
+        sqlite::VectorIndexMode::from_config(&config.vector_index, config.vector_index_quantized)
+            .unwrap_or_else(|| {
+                tracing::warn!(
+                    "Unknown vector index '{}', falling back to hnsw",
+                    config.vector_index
+                );
+                sqlite::VectorIndexMode::Hnsw
+            });
+    mem.with_vector_index(mode)
+}
+
+// ── CLI ──────────────────────────────────────────────────────
+
+/// The configured backend, kept concrete where commands need sqlite extras
+enum Backend {
+    Sqlite(Box<SqliteMemory>),
+    Other(Box<dyn Memory>),
+}
+
+impl Backend {
+    fn open(config: &Config) -> Result<Self> {
+        let api_key = config.api_key.as_deref();
+        if config.memory.backend == "sqlite" {
+            let mem = create_sqlite_memory(&config.memory, &config.workspace_dir, api_key)?;
+            Ok(Self::Sqlite(Box::new(mem)))
+        } else {
+            let mem = create_memory(&config.memory, &config.workspace_dir, api_key)?;
+            Ok(Self::Other(mem))
+        }
+    }
+
+    fn memory(&self) -> &dyn Memory {
+        match self {
+            Self::Sqlite(mem) => mem.as_ref(),
+            Self::Other(mem) => mem.as_ref(),
+        }
+    }
+}
+
+/// Single-line preview of memory content
+fn preview(content: &str) -> String {
+    let flat = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_with_ellipsis(&flat, 100)
+}
+
+fn format_score(score: Option<f32>) -> String {
+    score.map_or_else(|| "-".into(), |s| format!("{s:.3}"))
+}
+
+/// Entry counts keyed by category name
+fn category_counts(entries: &[MemoryEntry]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for entry in entries {
+        *counts.entry(entry.category.to_string()).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 20 => format!("{:.1} MB", b as f64 / f64::from(1 << 20)),
+        b if b >= 1 << 10 => format!("{:.1} KB", b as f64 / f64::from(1 << 10)),
+        b => format!("{b} B"),
+    }
+}
+
+#[allow(clippy::too_many_lines)]
+pub async fn handle_command(command: crate::MemoryCommands, config: &Config) -> Result<()> {
+    match command {
+        crate::MemoryCommands::Ingest { targets } => {
+            let mem = create_memory(
+                &config.memory,
+                &config.workspace_dir,
+                config.api_key.as_deref(),
+            )?;
+            for target in targets {
+                let set = ingest::collect_documents(&target)?;
+                println!("📚 Ingesting {target} ({} files)...", set.files.len());
+                let report = ingest::ingest_documents(
+                    mem.as_ref(),
+                    &config.workspace_dir,
+                    &set,
+                    config.memory.chunk_max_tokens,
+                )
+                .await?;
+                for source in &report.added {
+                    println!("  + {source}");
+                }
+                for source in &report.updated {
+                    println!("  ~ {source}");
+                }
+                for source in &report.removed {
+                    println!("  - {source}");
+                }
+                println!("  {}", report.summary().replace('\n', "\n  "));
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::List { category, limit } => {
+            let backend = Backend::open(config)?;
+            let category = category.as_deref().map(MemoryCategory::from);
+            let entries = backend.memory().list(category.as_ref()).await?;
+            if entries.is_empty() {
+                println!("No memories stored yet.");
+                return Ok(());
+            }
+
+            println!(
+                "🧠 Memories ({} of {}):",
+                entries.len().min(limit),
+                entries.len()
+            );
+            for entry in entries.iter().take(limit) {
+                println!(
+                    "- {} [{}] {}\n    {}",
+                    entry.key,
+                    entry.category,
+                    entry.timestamp,
+                    preview(&entry.content)
+                );
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Search {
+            query,
+            limit,
+            tags,
+            source,
+            session,
+            since,
+            until,
+        } => {
+            let filter = RecallFilter {
+                tags,
+                source,
+                session_id: session,
+                since: since.as_deref().map(RecallFilter::parse_time).transpose()?,
+                until: until.as_deref().map(RecallFilter::parse_time).transpose()?,
+            };
+            let backend = Backend::open(config)?;
+            // (entry, final, vector, keyword)
+            let results: Vec<(MemoryEntry, f32, Option<f32>, Option<f32>)> = match &backend {
+                Backend::Sqlite(mem) => mem
+                    .recall_scored(&query, limit, &filter)
+                    .await?
+                    .into_iter()
+                    .map(|(entry, s)| (entry, s.final_score, s.vector_score, s.keyword_score))
+                    .collect(),
+                Backend::Other(mem) => mem
+                    .recall_filtered(&query, limit, &filter)
+                    .await?
+                    .into_iter()
+                    .map(|entry| {
+                        #[allow(clippy::cast_possible_truncation)]
+                        let score = entry.score.unwrap_or(0.0) as f32;
+                        (entry, score, None, None)
+                    })
+                    .collect(),
+            };
+            if results.is_empty() {
+                println!("No memories match \"{query}\".");
+                return Ok(());
+            }
+
+            println!("🔎 {} result(s) for \"{query}\":", results.len());
+            for (entry, score, vector_score, keyword_score) in &results {
+                println!(
+                    "- {} [{}] score {score:.3} (vector {}, keyword {})\n    {}",
+                    entry.key,
+                    entry.category,
+                    format_score(*vector_score),
+                    format_score(*keyword_score),
+                    preview(&entry.content)
+                );
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Get { key } => {
+            let backend = Backend::open(config)?;
+            let Some(entry) = backend.memory().get(&key).await? else {
+                anyhow::bail!("No memory with key '{key}'");
+            };
+            println!("🧠 {}", entry.key);
+            println!("   Category: {}", entry.category);
+            println!("   Created:  {}", entry.timestamp);
+            if let Some(session) = &entry.session_id {
+                println!("   Session:  {session}");
+            }
+            if let Some(source) = &entry.source {
+                println!("   Source:   {source}");
+            }
+            if !entry.tags.is_empty() {
+                println!("   Tags:     {}", entry.tags.join(", "));
+            }
+            if let Some(importance) = entry.importance {
+                println!("   Importance: {importance:.2}");
+            }
+            if let Some(expires_at) = &entry.expires_at {
+                println!("   Expires:  {expires_at}");
+            }
+            if entry.access_count > 0 {
+                println!(
+                    "   Recalled: {} time(s), last {}",
+                    entry.access_count,
+                    entry.last_accessed.as_deref().unwrap_or("unknown")
+                );
+            }
+            println!("\n{}", entry.content);
+            Ok(())
+        }
+        crate::MemoryCommands::Forget { key } => {
+            let backend = Backend::open(config)?;
+            let mem = backend.memory();
+            if mem.forget(&key).await? {
+                println!("✅ Forgot '{key}'");
+                Ok(())
+            } else if mem.name() == "markdown" {
+                anyhow::bail!("The markdown memory backend is append-only; nothing was removed")
+            } else {
+                anyhow::bail!("No memory with key '{key}'")
+            }
+        }
+        crate::MemoryCommands::Stats => {
+            let backend = Backend::open(config)?;
+            let entries = backend.memory().list(None).await?;
+            println!("🧠 Memory ({})", backend.memory().name());
+            println!("   Entries: {}", entries.len());
+            for (category, count) in category_counts(&entries) {
+                println!("     {category}: {count}");
+            }
+            if let Backend::Sqlite(mem) = &backend {
+                let stats = mem.stats()?;
+                println!(
+                    "   Embeddings: {}/{} ({})",
+                    stats.embedded,
+                    entries.len(),
+                    stats.embedder
+                );
+                match stats.indexed {
+                    Some(indexed) => println!("   Vector index: hnsw ({indexed} entries)"),
+                    None => println!("   Vector index: exact search"),
+                }
+                println!("   Database: {}", format_bytes(stats.db_bytes));
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Export { output, category } => {
+            let backend = Backend::open(config)?;
+            let category = category.as_deref().map(MemoryCategory::from);
+            if let Some(path) = output {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                let count =
+                    transfer::export_jsonl(backend.memory(), category.as_ref(), &mut file).await?;
+                println!("✅ Exported {count} memories to {}", path.display());
+            } else {
+                let mut stdout = std::io::stdout();
+                transfer::export_jsonl(backend.memory(), category.as_ref(), &mut stdout).await?;
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Import { file } => {
+            let jsonl = std::fs::read_to_string(&file)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {e}", file.display()))?;
+            let backend = Backend::open(config)?;
+            let report = transfer::import_jsonl(backend.memory(), &jsonl).await?;
+            println!(
+                "✅ Imported {} memories ({} replaced existing keys)",
+                report.imported, report.replaced
+            );
+            for (line, error) in &report.failed {
+                println!("  ⚠️ line {line}: {error}");
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Reindex => {
+            let Backend::Sqlite(mem) = Backend::open(config)? else {
+                anyhow::bail!(
+                    "Reindex needs the sqlite memory backend (current: {})",
+                    config.memory.backend
+                );
+            };
+            println!("🔄 Reindexing memory...");
+            let embedded = mem.reindex().await?;
+            println!("✅ Rebuilt keyword index, embedded {embedded} entries");
+            if let Some(indexed) = mem.stats()?.indexed {
+                println!("   Vector index: {indexed} entries");
+            }
+            Ok(())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
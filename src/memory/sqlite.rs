@@ -1,13 +1,80 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::hnsw::HnswIndex;
+use super::traits::{
+    Memory, MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter, FILTER_OVERFETCH,
+};
 use super::vector;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
 use rusqlite::{params, Connection};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 use uuid::Uuid;
 
+/// Index writes buffered in memory before the ANN index is flushed to disk
+const INDEX_SAVE_INTERVAL: usize = 256;
+
+/// Columns added to `memories` after its first release, with their types;
+/// older databases gain them on open
+const METADATA_COLUMNS: &[(&str, &str)] = &[
+    ("session_id", "TEXT"),
+    ("tags", "TEXT"),
+    ("source", "TEXT"),
+    ("importance", "REAL"),
+    ("expires_at", "TEXT"),
+    ("access_count", "INTEGER NOT NULL DEFAULT 0"),
+    ("last_accessed", "TEXT"),
+];
+
+/// Columns read by `SqliteMemory::row_to_entry`, in order
+const ENTRY_COLUMNS: &str = "id, key, content, category, created_at, session_id, tags, source, \
+     importance, expires_at, access_count, last_accessed";
+
+/// Canonical `expires_at` form (UTC, whole seconds, `Z`), so stored expiry
+/// times compare correctly as strings in SQL
+pub fn expiry_string(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Storage figures beyond what the `Memory` trait exposes
+#[derive(Debug, Clone)]
+pub struct SqliteStats {
+    /// Embedding provider name ("none" when keyword-only)
+    pub embedder: String,
+    /// Entries that have an embedding
+    pub embedded: usize,
+    /// Live entries in the ANN index, if one is loaded
+    pub indexed: Option<usize>,
+    /// Size of `brain.db` on disk
+    pub db_bytes: u64,
+}
+
+/// How vector similarity queries are answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorIndexMode {
+    /// Linear cosine scan over every stored embedding — slow but exact,
+    /// kept as the reference to verify the index against
+    Exact,
+    /// HNSW graph over f32 vectors
+    Hnsw,
+    /// HNSW graph over int8-quantized vectors
+    HnswQuantized,
+}
+
+impl VectorIndexMode {
+    /// Parse the `memory.vector_index` config value
+    pub fn from_config(name: &str, quantized: bool) -> Option<Self> {
+        match name {
+            "exact" => Some(Self::Exact),
+            "hnsw" if quantized => Some(Self::HnswQuantized),
+            "hnsw" => Some(Self::Hnsw),
+            _ => None,
+        }
+    }
+}
+
 /// SQLite-backed persistent memory — the brain
 ///
 /// Full-stack search engine:
@@ -15,6 +82,7 @@ use uuid::Uuid;
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
+/// - **ANN Index**: optional HNSW graph (`brain.hnsw`) kept in sync on store/forget
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
 pub struct SqliteMemory {
     conn: Mutex<Connection>,
@@ -23,6 +91,10 @@ pub struct SqliteMemory {
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    /// ANN index; `None` means exact linear search
+    index: Option<Mutex<HnswIndex>>,
+    index_path: PathBuf,
+    unsaved_index_changes: AtomicUsize,
 }
 
 impl SqliteMemory {
@@ -44,6 +116,7 @@ impl SqliteMemory {
         cache_max: usize,
     ) -> anyhow::Result<Self> {
         let db_path = workspace_dir.join("memory").join("brain.db");
+        let index_path = db_path.with_extension("hnsw");
 
         if let Some(parent) = db_path.parent() {
             std::fs::create_dir_all(parent)?;
@@ -59,9 +132,147 @@ impl SqliteMemory {
             vector_weight,
             keyword_weight,
             cache_max,
+            index: None,
+            index_path,
+            unsaved_index_changes: AtomicUsize::new(0),
         })
     }
 
+    /// Answer vector queries from an ANN index instead of a linear scan.
+    ///
+    /// The index is loaded from disk and brought up to date with the
+    /// database; a missing, corrupt or differently-quantized file is rebuilt.
+    pub fn with_vector_index(mut self, mode: VectorIndexMode) -> anyhow::Result<Self> {
+        let quantized = match mode {
+            VectorIndexMode::Exact => {
+                self.index = None;
+                return Ok(self);
+            }
+            VectorIndexMode::Hnsw => false,
+            VectorIndexMode::HnswQuantized => true,
+        };
+
+        let index = match HnswIndex::load(&self.index_path) {
+            Ok(index) if index.quantized() == quantized => index,
+            Ok(_) => HnswIndex::new(quantized),
+            Err(e) => {
+                if self.index_path.exists() {
+                    tracing::warn!("Rebuilding vector index: {e}");
+                }
+                HnswIndex::new(quantized)
+            }
+        };
+        self.index = Some(Mutex::new(index));
+        self.sync_index()?;
+        Ok(self)
+    }
+
+    /// Reconcile the ANN index with the embeddings in the database, which
+    /// may have changed while the index was not loaded (other processes,
+    /// hygiene, an older binary).
+    fn sync_index(&self) -> anyhow::Result<()> {
+        let Some(index) = &self.index else {
+            return Ok(());
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let mut index = index
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+
+        let stamps: HashMap<String, String> = {
+            let mut stmt =
+                conn.prepare("SELECT id, updated_at FROM memories WHERE embedding IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let stale: Vec<String> = index
+            .entries()
+            .filter(|(id, stamp)| stamps.get(*id).map(String::as_str) != Some(*stamp))
+            .map(|(id, _)| id.to_string())
+            .collect();
+        let missing: HashSet<&str> = stamps
+            .iter()
+            .filter(|(id, stamp)| index.stamp(id) != Some(stamp.as_str()))
+            .map(|(id, _)| id.as_str())
+            .collect();
+        if stale.is_empty() && missing.is_empty() {
+            return Ok(());
+        }
+
+        for id in &stale {
+            index.remove(id);
+        }
+        // A switched embedding model makes every old vector unusable
+        let dims = self.embedder.dimensions();
+        let rebuild = index.needs_compaction() || (dims != 0 && index.dims() != dims);
+        if rebuild {
+            *index = HnswIndex::new(index.quantized());
+        }
+        Self::fill_index(&conn, &mut index, |id| rebuild || missing.contains(id))?;
+        drop(conn);
+        index.save(&self.index_path)?;
+        self.unsaved_index_changes.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Insert stored embeddings accepted by `wanted` into the index
+    fn fill_index(
+        conn: &Connection,
+        index: &mut HnswIndex,
+        wanted: impl Fn(&str) -> bool,
+    ) -> anyhow::Result<()> {
+        let mut stmt = conn.prepare(
+            "SELECT id, updated_at, embedding FROM memories WHERE embedding IS NOT NULL",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let id: String = row.get(0)?;
+            if wanted(&id) {
+                let stamp: String = row.get(1)?;
+                let blob: Vec<u8> = row.get(2)?;
+                index.insert(&id, &stamp, &vector::bytes_to_vec(&blob));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count an index write, flushing to disk every `INDEX_SAVE_INTERVAL`
+    fn note_index_change(&self, index: &HnswIndex) {
+        let pending = self.unsaved_index_changes.fetch_add(1, Ordering::Relaxed) + 1;
+        if pending >= INDEX_SAVE_INTERVAL {
+            match index.save(&self.index_path) {
+                Ok(()) => self.unsaved_index_changes.store(0, Ordering::Relaxed),
+                Err(e) => tracing::warn!("Failed to save vector index: {e}"),
+            }
+        }
+    }
+
+    /// The `limit` stored embeddings most similar to `embedding`, as
+    /// `(id, cosine similarity)`. Uses the ANN index when one is loaded,
+    /// unless `exact` asks for the linear scan.
+    pub fn nearest(
+        &self,
+        embedding: &[f32],
+        limit: usize,
+        exact: bool,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        if let (Some(index), false) = (&self.index, exact) {
+            let index = index
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+            return Ok(index.search(embedding, limit));
+        }
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        Self::vector_search(&conn, embedding, limit)
+    }
+
     /// Initialize all tables: memories, FTS5, `embedding_cache`
     fn init_schema(conn: &Connection) -> anyhow::Result<()> {
         conn.execute_batch(
@@ -108,9 +319,52 @@ impl SqliteMemory {
             );
             CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
         )?;
+        Self::migrate_schema(conn)
+    }
+
+    /// Add metadata columns missing from databases created by older versions
+    fn migrate_schema(conn: &Connection) -> anyhow::Result<()> {
+        let existing: HashSet<String> = {
+            let mut stmt = conn.prepare("SELECT name FROM pragma_table_info('memories')")?;
+            let rows = stmt.query_map([], |row| row.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        for (column, definition) in METADATA_COLUMNS {
+            if !existing.contains(*column) {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE memories ADD COLUMN {column} {definition};"
+                ))?;
+            }
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_memories_expires ON memories(expires_at);",
+        )?;
         Ok(())
     }
 
+    /// Map a row selected with `ENTRY_COLUMNS` (unscored)
+    fn row_to_entry(row: &rusqlite::Row) -> rusqlite::Result<MemoryEntry> {
+        let tags: Option<String> = row.get(6)?;
+        let access_count: i64 = row.get(10)?;
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: row.get(2)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: row.get(5)?,
+            score: None,
+            tags: tags
+                .and_then(|t| serde_json::from_str(&t).ok())
+                .unwrap_or_default(),
+            source: row.get(7)?,
+            importance: row.get(8)?,
+            expires_at: row.get(9)?,
+            access_count: u64::try_from(access_count).unwrap_or(0),
+            last_accessed: row.get(11)?,
+        })
+    }
+
     fn category_to_str(cat: &MemoryCategory) -> String {
         match cat {
             MemoryCategory::Core => "core".into(),
@@ -282,8 +536,212 @@ impl SqliteMemory {
         Ok(scored)
     }
 
+    /// Hybrid recall that keeps the per-source scores behind each result
+    /// (the keyword-less LIKE fallback reports neither).
+    ///
+    /// Expired entries and those rejected by `filter` are skipped, the rest
+    /// re-ranked by importance and recency (`vector::boost_score`), and the
+    /// access counters of the returned entries bumped.
+    #[allow(clippy::too_many_lines)]
+    pub async fn recall_scored(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<(MemoryEntry, vector::ScoredResult)>> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Fetch extra candidates so filtering and re-ranking have room
+        let pool = if filter.is_empty() {
+            limit * 2
+        } else {
+            limit * 2 * FILTER_OVERFETCH
+        };
+
+        // Compute query embedding (async, before lock)
+        let query_embedding = self.get_or_compute_embedding(query).await?;
+
+        // Vector similarity search (if embeddings available)
+        let vector_results = if let Some(ref qe) = query_embedding {
+            self.nearest(qe, pool, false).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+
+        // FTS5 BM25 keyword search
+        let keyword_results = Self::fts5_search(&conn, query, pool).unwrap_or_default();
+
+        // Hybrid merge
+        let merged = if vector_results.is_empty() {
+            // No embeddings — use keyword results only
+            keyword_results
+                .iter()
+                .map(|(id, score)| vector::ScoredResult {
+                    id: id.clone(),
+                    vector_score: None,
+                    keyword_score: Some(*score),
+                    final_score: *score,
+                })
+                .collect::<Vec<_>>()
+        } else {
+            vector::hybrid_merge(
+                &vector_results,
+                &keyword_results,
+                self.vector_weight,
+                self.keyword_weight,
+                pool,
+            )
+        };
+
+        // Fetch full entries for merged results, then re-rank
+        let now = Utc::now();
+        let mut results = Vec::new();
+        {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS}, updated_at FROM memories WHERE id = ?1"
+            ))?;
+            for scored in merged {
+                let Ok((entry, updated_at)) = stmt.query_row(params![scored.id], |row| {
+                    Ok((Self::row_to_entry(row)?, row.get::<_, String>(12)?))
+                }) else {
+                    continue;
+                };
+                if entry.is_expired(now) || !filter.matches(&entry) {
+                    continue;
+                }
+
+                #[allow(clippy::cast_precision_loss)]
+                let age_days = DateTime::parse_from_rfc3339(&updated_at).map_or(0.0, |t| {
+                    now.signed_duration_since(t).num_seconds() as f64 / 86_400.0
+                });
+                let decays = matches!(
+                    entry.category,
+                    MemoryCategory::Conversation | MemoryCategory::Daily
+                );
+                let final_score =
+                    vector::boost_score(scored.final_score, entry.importance, age_days, decays);
+                results.push((
+                    MemoryEntry {
+                        score: Some(f64::from(final_score)),
+                        ..entry
+                    },
+                    vector::ScoredResult {
+                        final_score,
+                        ..scored
+                    },
+                ));
+            }
+        }
+        results.sort_by(|a, b| b.1.final_score.total_cmp(&a.1.final_score));
+
+        // If hybrid returned nothing, fall back to LIKE search
+        if results.is_empty() {
+            let keywords: Vec<String> =
+                query.split_whitespace().map(|w| format!("%{w}%")).collect();
+            if !keywords.is_empty() {
+                let conditions: Vec<String> = keywords
+                    .iter()
+                    .enumerate()
+                    .map(|(i, _)| {
+                        format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
+                    })
+                    .collect();
+                let where_clause = conditions.join(" OR ");
+                let sql = format!(
+                    "SELECT {ENTRY_COLUMNS} FROM memories
+                     WHERE {where_clause}
+                     ORDER BY updated_at DESC
+                     LIMIT ?{}",
+                    keywords.len() * 2 + 1
+                );
+                let mut stmt = conn.prepare(&sql)?;
+                let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+                for kw in &keywords {
+                    param_values.push(Box::new(kw.clone()));
+                    param_values.push(Box::new(kw.clone()));
+                }
+                #[allow(clippy::cast_possible_wrap)]
+                param_values.push(Box::new(pool as i64));
+                let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+                    param_values.iter().map(AsRef::as_ref).collect();
+                let rows = stmt.query_map(params_ref.as_slice(), Self::row_to_entry)?;
+                for row in rows {
+                    let entry = row?;
+                    if entry.is_expired(now) || !filter.matches(&entry) {
+                        continue;
+                    }
+                    let scored = vector::ScoredResult {
+                        id: entry.id.clone(),
+                        vector_score: None,
+                        keyword_score: None,
+                        final_score: 1.0,
+                    };
+                    results.push((
+                        MemoryEntry {
+                            score: Some(1.0),
+                            ..entry
+                        },
+                        scored,
+                    ));
+                }
+            }
+        }
+
+        results.truncate(limit);
+
+        // Access tracking (leaves updated_at alone, so the ANN index stays valid)
+        if !results.is_empty() {
+            let accessed_at = Local::now().to_rfc3339();
+            let mut stmt = conn.prepare(
+                "UPDATE memories SET access_count = access_count + 1, last_accessed = ?1
+                 WHERE id = ?2",
+            )?;
+            for (entry, _) in &results {
+                stmt.execute(params![accessed_at, entry.id])?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    pub fn stats(&self) -> anyhow::Result<SqliteStats> {
+        let embedded: i64 = {
+            let conn = self
+                .conn
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+            conn.query_row(
+                "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )?
+        };
+        let indexed = match &self.index {
+            Some(index) => Some(
+                index
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?
+                    .len(),
+            ),
+            None => None,
+        };
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(SqliteStats {
+            embedder: self.embedder.name().to_string(),
+            embedded: embedded as usize,
+            indexed,
+            db_bytes: std::fs::metadata(&self.db_path).map_or(0, |m| m.len()),
+        })
+    }
+
     /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
-    #[allow(dead_code)]
     pub async fn reindex(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
@@ -297,6 +755,7 @@ impl SqliteMemory {
 
         // Step 2: Re-embed all memories that lack embeddings
         if self.embedder.dimensions() == 0 {
+            self.rebuild_index()?;
             return Ok(0);
         }
 
@@ -330,8 +789,43 @@ impl SqliteMemory {
             }
         }
 
+        // Step 3: Rebuild the ANN index from scratch
+        self.rebuild_index()?;
+
         Ok(count)
     }
+
+    fn rebuild_index(&self) -> anyhow::Result<()> {
+        let Some(index) = &self.index else {
+            return Ok(());
+        };
+        let conn = self
+            .conn
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let mut index = index
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        *index = HnswIndex::new(index.quantized());
+        Self::fill_index(&conn, &mut index, |_| true)?;
+        drop(conn);
+        index.save(&self.index_path)?;
+        self.unsaved_index_changes.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+impl Drop for SqliteMemory {
+    fn drop(&mut self) {
+        if self.unsaved_index_changes.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        if let Some(Ok(index)) = self.index.as_ref().map(Mutex::lock) {
+            if let Err(e) = index.save(&self.index_path) {
+                tracing::warn!("Failed to save vector index: {e}");
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -346,11 +840,40 @@ impl Memory for SqliteMemory {
         content: &str,
         category: MemoryCategory,
     ) -> anyhow::Result<()> {
+        self.store_with_metadata(key, content, category, &MemoryMetadata::default())
+            .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        if let Some(importance) = metadata.importance {
+            anyhow::ensure!(
+                (0.0..=1.0).contains(&importance),
+                "importance must be between 0.0 and 1.0, got {importance}"
+            );
+        }
+        let expires_at = match metadata.expires_at.as_deref() {
+            Some(raw) => Some(expiry_string(
+                DateTime::parse_from_rfc3339(raw)
+                    .map_err(|e| anyhow::anyhow!("Invalid expiry time '{raw}': {e}"))?
+                    .with_timezone(&Utc),
+            )),
+            None => None,
+        };
+        let tags = if metadata.tags.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&metadata.tags)?)
+        };
+
         // Compute embedding (async, before lock)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding.as_deref().map(vector::vec_to_bytes);
 
         let conn = self
             .conn
@@ -361,134 +884,69 @@ impl Memory for SqliteMemory {
         let id = Uuid::new_v4().to_string();
 
         conn.execute(
-            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at,
+                                   session_id, tags, source, importance, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
              ON CONFLICT(key) DO UPDATE SET
                 content = excluded.content,
                 category = excluded.category,
                 embedding = excluded.embedding,
-                updated_at = excluded.updated_at",
-            params![id, key, content, cat, embedding_bytes, now, now],
+                updated_at = excluded.updated_at,
+                session_id = excluded.session_id,
+                tags = excluded.tags,
+                source = excluded.source,
+                importance = excluded.importance,
+                expires_at = excluded.expires_at",
+            params![
+                id,
+                key,
+                content,
+                cat,
+                embedding_bytes,
+                now,
+                now,
+                metadata.session_id,
+                tags,
+                metadata.source,
+                metadata.importance,
+                expires_at
+            ],
         )?;
 
-        Ok(())
-    }
-
-    async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
-        if query.trim().is_empty() {
-            return Ok(Vec::new());
-        }
-
-        // Compute query embedding (async, before lock)
-        let query_embedding = self.get_or_compute_embedding(query).await?;
-
-        let conn = self
-            .conn
-            .lock()
-            .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
-
-        // FTS5 BM25 keyword search
-        let keyword_results = Self::fts5_search(&conn, query, limit * 2).unwrap_or_default();
-
-        // Vector similarity search (if embeddings available)
-        let vector_results = if let Some(ref qe) = query_embedding {
-            Self::vector_search(&conn, qe, limit * 2).unwrap_or_default()
-        } else {
-            Vec::new()
-        };
-
-        // Hybrid merge
-        let merged = if vector_results.is_empty() {
-            // No embeddings — use keyword results only
-            keyword_results
-                .iter()
-                .map(|(id, score)| vector::ScoredResult {
-                    id: id.clone(),
-                    vector_score: None,
-                    keyword_score: Some(*score),
-                    final_score: *score,
-                })
-                .collect::<Vec<_>>()
-        } else {
-            vector::hybrid_merge(
-                &vector_results,
-                &keyword_results,
-                self.vector_weight,
-                self.keyword_weight,
-                limit,
-            )
-        };
-
-        // Fetch full entries for merged results
-        let mut results = Vec::new();
-        for scored in &merged {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories WHERE id = ?1",
+        if let Some(index) = &self.index {
+            // An upsert keeps the existing row's id
+            let id: String = conn.query_row(
+                "SELECT id FROM memories WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
             )?;
-            if let Ok(entry) = stmt.query_row(params![scored.id], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: None,
-                    score: Some(f64::from(scored.final_score)),
-                })
-            }) {
-                results.push(entry);
+            let mut index = index
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+            let indexed = embedding
+                .as_deref()
+                .is_some_and(|emb| index.insert(&id, &now, emb));
+            if indexed || index.remove(&id) {
+                self.note_index_change(&index);
             }
         }
 
-        // If hybrid returned nothing, fall back to LIKE search
-        if results.is_empty() {
-            let keywords: Vec<String> =
-                query.split_whitespace().map(|w| format!("%{w}%")).collect();
-            if !keywords.is_empty() {
-                let conditions: Vec<String> = keywords
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| {
-                        format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
-                    })
-                    .collect();
-                let where_clause = conditions.join(" OR ");
-                let sql = format!(
-                    "SELECT id, key, content, category, created_at FROM memories
-                     WHERE {where_clause}
-                     ORDER BY updated_at DESC
-                     LIMIT ?{}",
-                    keywords.len() * 2 + 1
-                );
-                let mut stmt = conn.prepare(&sql)?;
-                let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-                for kw in &keywords {
-                    param_values.push(Box::new(kw.clone()));
-                    param_values.push(Box::new(kw.clone()));
-                }
-                #[allow(clippy::cast_possible_wrap)]
-                param_values.push(Box::new(limit as i64));
-                let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                    param_values.iter().map(AsRef::as_ref).collect();
-                let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok(MemoryEntry {
-                        id: row.get(0)?,
-                        key: row.get(1)?,
-                        content: row.get(2)?,
-                        category: Self::str_to_category(&row.get::<_, String>(3)?),
-                        timestamp: row.get(4)?,
-                        session_id: None,
-                        score: Some(1.0),
-                    })
-                })?;
-                for row in rows {
-                    results.push(row?);
-                }
-            }
-        }
+        Ok(())
+    }
 
-        results.truncate(limit);
-        Ok(results)
+    async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, &RecallFilter::default())
+            .await
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let scored = self.recall_scored(query, limit, filter).await?;
+        Ok(scored.into_iter().map(|(entry, _)| entry).collect())
     }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
@@ -497,21 +955,11 @@ impl Memory for SqliteMemory {
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, key, content, category, created_at FROM memories WHERE key = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories WHERE key = ?1"
+        ))?;
 
-        let mut rows = stmt.query_map(params![key], |row| {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: None,
-                score: None,
-            })
-        })?;
+        let mut rows = stmt.query_map(params![key], Self::row_to_entry)?;
 
         match rows.next() {
             Some(Ok(entry)) => Ok(Some(entry)),
@@ -527,34 +975,21 @@ impl Memory for SqliteMemory {
 
         let mut results = Vec::new();
 
-        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: None,
-                score: None,
-            })
-        };
-
         if let Some(cat) = category {
             let cat_str = Self::category_to_str(cat);
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories
-                 WHERE category = ?1 ORDER BY updated_at DESC",
-            )?;
-            let rows = stmt.query_map(params![cat_str], row_mapper)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE category = ?1 ORDER BY updated_at DESC"
+            ))?;
+            let rows = stmt.query_map(params![cat_str], Self::row_to_entry)?;
             for row in rows {
                 results.push(row?);
             }
         } else {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at FROM memories
-                 ORDER BY updated_at DESC",
-            )?;
-            let rows = stmt.query_map([], row_mapper)?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories ORDER BY updated_at DESC"
+            ))?;
+            let rows = stmt.query_map([], Self::row_to_entry)?;
             for row in rows {
                 results.push(row?);
             }
@@ -568,7 +1003,23 @@ impl Memory for SqliteMemory {
             .conn
             .lock()
             .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+        let id: Option<String> = conn
+            .query_row(
+                "SELECT id FROM memories WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .ok();
         let affected = conn.execute("DELETE FROM memories WHERE key = ?1", params![key])?;
+
+        if let (Some(index), Some(id)) = (&self.index, id) {
+            let mut index = index
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Lock error: {e}"))?;
+            if index.remove(&id) {
+                self.note_index_change(&index);
+            }
+        }
         Ok(affected > 0)
     }
 
@@ -1370,4 +1821,381 @@ mod tests {
         let all = mem.list(None).await.unwrap();
         assert!(all.is_empty());
     }
+
+    // ── Vector index ─────────────────────────────────────────────
+
+    /// Letter-frequency embeddings: texts sharing letters are similar
+    struct LetterEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for LetterEmbedding {
+        fn name(&self) -> &str {
+            "letters"
+        }
+
+        fn dimensions(&self) -> usize {
+            26
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0; 26];
+                    for b in text.bytes().filter(u8::is_ascii_lowercase) {
+                        v[usize::from(b - b'a')] += 1.0;
+                    }
+                    v
+                })
+                .collect())
+        }
+    }
+
+    fn indexed_sqlite(dir: &Path, mode: VectorIndexMode) -> SqliteMemory {
+        SqliteMemory::with_embedder(dir, Arc::new(LetterEmbedding), 0.7, 0.3, 100)
+            .unwrap()
+            .with_vector_index(mode)
+            .unwrap()
+    }
+
+    fn ids(results: &[(String, f32)]) -> Vec<&str> {
+        results.iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn index_tracks_store_and_forget() {
+        let tmp = TempDir::new().unwrap();
+        let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+        mem.store("a", "aaaa", MemoryCategory::Core).await.unwrap();
+        mem.store("z", "zzzz", MemoryCategory::Core).await.unwrap();
+        let z_id = mem.get("z").await.unwrap().unwrap().id;
+
+        let query = LetterEmbedding.embed_one("zz").await.unwrap();
+        assert_eq!(
+            ids(&mem.nearest(&query, 1, false).unwrap()),
+            [z_id.as_str()]
+        );
+
+        // Updating keeps the id but replaces the vector
+        mem.store("z", "aaab", MemoryCategory::Core).await.unwrap();
+        assert!(mem.nearest(&query, 5, false).unwrap().is_empty());
+
+        mem.forget("a").await.unwrap();
+        let query = LetterEmbedding.embed_one("ab").await.unwrap();
+        assert_eq!(
+            ids(&mem.nearest(&query, 5, false).unwrap()),
+            [z_id.as_str()]
+        );
+    }
+
+    #[tokio::test]
+    async fn index_matches_exact_search() {
+        let tmp = TempDir::new().unwrap();
+        let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+        for word in ["apple", "banana", "cherry", "grape", "lemon", "mango"] {
+            mem.store(word, word, MemoryCategory::Core).await.unwrap();
+        }
+        let query = LetterEmbedding.embed_one("melon").await.unwrap();
+        let approx = mem.nearest(&query, 3, false).unwrap();
+        let exact = mem.nearest(&query, 3, true).unwrap();
+        assert_eq!(ids(&approx), ids(&exact));
+    }
+
+    #[tokio::test]
+    async fn index_persists_and_catches_up_on_reopen() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+            mem.store("a", "aaaa", MemoryCategory::Core).await.unwrap();
+        }
+        assert!(tmp.path().join("memory/brain.hnsw").exists());
+
+        // Changes made without the index are picked up when it is reloaded
+        {
+            let mem =
+                SqliteMemory::with_embedder(tmp.path(), Arc::new(LetterEmbedding), 0.7, 0.3, 100)
+                    .unwrap();
+            mem.forget("a").await.unwrap();
+            mem.store("b", "bbbb", MemoryCategory::Core).await.unwrap();
+        }
+        let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+        let b_id = mem.get("b").await.unwrap().unwrap().id;
+        let query = LetterEmbedding.embed_one("ab").await.unwrap();
+        assert_eq!(
+            ids(&mem.nearest(&query, 5, false).unwrap()),
+            [b_id.as_str()]
+        );
+    }
+
+    #[tokio::test]
+    async fn quantized_index_and_reindex() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+            mem.store("a", "aaaa", MemoryCategory::Core).await.unwrap();
+        }
+        // Switching to quantized vectors rebuilds the index file
+        let mem = indexed_sqlite(tmp.path(), VectorIndexMode::HnswQuantized);
+        assert!(HnswIndex::load(&tmp.path().join("memory/brain.hnsw"))
+            .unwrap()
+            .quantized());
+        mem.reindex().await.unwrap();
+
+        let results = mem.recall("aa", 5).await.unwrap();
+        assert_eq!(results[0].key, "a");
+    }
+
+    #[tokio::test]
+    async fn recall_scored_reports_sources() {
+        let tmp = TempDir::new().unwrap();
+        let mem = indexed_sqlite(tmp.path(), VectorIndexMode::Hnsw);
+        mem.store("a", "deploy the server", MemoryCategory::Core)
+            .await
+            .unwrap();
+        mem.store("b", "zzz", MemoryCategory::Core).await.unwrap();
+
+        let results = mem
+            .recall_scored("deploy", 5, &RecallFilter::default())
+            .await
+            .unwrap();
+        let (entry, scored) = &results[0];
+        assert_eq!(entry.key, "a");
+        assert!(scored.vector_score.is_some());
+        assert!(scored.keyword_score.is_some());
+        assert_eq!(entry.score, Some(f64::from(scored.final_score)));
+    }
+
+    // ── Metadata ─────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn metadata_round_trips_and_survives_overwrite_access_counts() {
+        let (_tmp, mem) = temp_sqlite();
+        let metadata = MemoryMetadata {
+            tags: vec!["ops".into(), "prod".into()],
+            importance: Some(0.8),
+            expires_at: Some("2999-01-01T00:00:00+02:00".into()),
+            ..MemoryMetadata::with_source("telegram:alice").with_session("telegram:alice")
+        };
+        mem.store_with_metadata(
+            "runbook",
+            "restart the worker",
+            MemoryCategory::Core,
+            &metadata,
+        )
+        .await
+        .unwrap();
+
+        let entry = mem.get("runbook").await.unwrap().unwrap();
+        assert_eq!(entry.tags, ["ops", "prod"]);
+        assert_eq!(entry.source.as_deref(), Some("telegram:alice"));
+        assert_eq!(entry.session_id.as_deref(), Some("telegram:alice"));
+        assert_eq!(entry.importance, Some(0.8));
+        // Normalized to UTC so SQL string comparison works
+        assert_eq!(entry.expires_at.as_deref(), Some("2998-12-31T22:00:00Z"));
+        assert_eq!(entry.access_count, 0);
+
+        mem.recall("worker", 5).await.unwrap();
+        mem.recall("worker", 5).await.unwrap();
+        let entry = mem.get("runbook").await.unwrap().unwrap();
+        assert_eq!(entry.access_count, 2);
+        assert!(entry.last_accessed.is_some());
+
+        // Overwriting replaces the metadata but keeps the access history
+        mem.store("runbook", "restart the worker twice", MemoryCategory::Core)
+            .await
+            .unwrap();
+        let entry = mem.get("runbook").await.unwrap().unwrap();
+        assert!(entry.tags.is_empty());
+        assert_eq!(entry.source, None);
+        assert_eq!(entry.access_count, 2);
+    }
+
+    #[tokio::test]
+    async fn store_rejects_invalid_metadata() {
+        let (_tmp, mem) = temp_sqlite();
+        let importance = MemoryMetadata {
+            importance: Some(1.5),
+            ..MemoryMetadata::default()
+        };
+        assert!(mem
+            .store_with_metadata("a", "x", MemoryCategory::Core, &importance)
+            .await
+            .is_err());
+        let expiry = MemoryMetadata {
+            expires_at: Some("tomorrow".into()),
+            ..MemoryMetadata::default()
+        };
+        assert!(mem
+            .store_with_metadata("a", "x", MemoryCategory::Core, &expiry)
+            .await
+            .is_err());
+        assert_eq!(mem.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn recall_filtered_by_tag_source_and_time() {
+        let (_tmp, mem) = temp_sqlite();
+        let tagged = |tag: &str, source: &str| MemoryMetadata {
+            tags: vec![tag.into()],
+            ..MemoryMetadata::with_source(source)
+        };
+        mem.store_with_metadata(
+            "a",
+            "deploy on friday",
+            MemoryCategory::Core,
+            &tagged("release", "telegram:alice"),
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "b",
+            "deploy with care",
+            MemoryCategory::Core,
+            &tagged("ops", "discord:bob"),
+        )
+        .await
+        .unwrap();
+
+        let keys = |entries: Vec<MemoryEntry>| {
+            let mut keys: Vec<String> = entries.into_iter().map(|e| e.key).collect();
+            keys.sort();
+            keys
+        };
+        let filter = RecallFilter {
+            tags: vec!["ops".into()],
+            ..RecallFilter::default()
+        };
+        assert_eq!(
+            keys(mem.recall_filtered("deploy", 5, &filter).await.unwrap()),
+            ["b"]
+        );
+        let filter = RecallFilter {
+            source: Some("telegram".into()),
+            ..RecallFilter::default()
+        };
+        assert_eq!(
+            keys(mem.recall_filtered("deploy", 5, &filter).await.unwrap()),
+            ["a"]
+        );
+        let filter = RecallFilter {
+            since: Some(Utc::now() + chrono::Duration::days(1)),
+            ..RecallFilter::default()
+        };
+        assert!(mem
+            .recall_filtered("deploy", 5, &filter)
+            .await
+            .unwrap()
+            .is_empty());
+        let filter = RecallFilter {
+            since: Some(Utc::now() - chrono::Duration::days(1)),
+            ..RecallFilter::default()
+        };
+        assert_eq!(
+            keys(mem.recall_filtered("deploy", 5, &filter).await.unwrap()),
+            ["a", "b"]
+        );
+    }
+
+    #[tokio::test]
+    async fn recall_skips_expired_entries() {
+        let (_tmp, mem) = temp_sqlite();
+        let expired = MemoryMetadata {
+            expires_at: Some(expiry_string(Utc::now() - chrono::Duration::minutes(1))),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("old", "standup at 9", MemoryCategory::Core, &expired)
+            .await
+            .unwrap();
+        mem.store("new", "standup at 10", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let results = mem.recall("standup", 5).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].key, "new");
+        // Still readable by key until hygiene prunes it
+        assert!(mem.get("old").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn recall_ranks_important_entries_first() {
+        let (_tmp, mem) = temp_sqlite();
+        let importance = |value: f64| MemoryMetadata {
+            importance: Some(value),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "high",
+            "backup policy alpha",
+            MemoryCategory::Core,
+            &importance(1.0),
+        )
+        .await
+        .unwrap();
+        mem.store_with_metadata(
+            "low",
+            "backup policy beta",
+            MemoryCategory::Core,
+            &importance(0.0),
+        )
+        .await
+        .unwrap();
+
+        let results = mem.recall("backup policy", 5).await.unwrap();
+        assert_eq!(results[0].key, "high");
+        assert_eq!(results[1].key, "low");
+        assert!(results[0].score.unwrap() > results[1].score.unwrap());
+    }
+
+    #[tokio::test]
+    async fn opens_databases_without_metadata_columns() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("lang", "Prefers Rust", MemoryCategory::Core)
+            .await
+            .unwrap();
+        drop(mem);
+
+        // Roll the table back to its original shape
+        let conn = Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+        conn.execute_batch("DROP INDEX idx_memories_expires;")
+            .unwrap();
+        for (column, _) in METADATA_COLUMNS {
+            conn.execute_batch(&format!("ALTER TABLE memories DROP COLUMN {column};"))
+                .unwrap();
+        }
+        drop(conn);
+
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let entry = mem.get("lang").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Prefers Rust");
+        assert!(entry.tags.is_empty());
+        assert_eq!(entry.access_count, 0);
+
+        let tagged = MemoryMetadata {
+            tags: vec!["stack".into()],
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("lang", "Prefers Rust", MemoryCategory::Core, &tagged)
+            .await
+            .unwrap();
+        assert_eq!(mem.get("lang").await.unwrap().unwrap().tags, ["stack"]);
+    }
+
+    #[test]
+    fn vector_index_mode_from_config() {
+        assert_eq!(
+            VectorIndexMode::from_config("hnsw", false),
+            Some(VectorIndexMode::Hnsw)
+        );
+        assert_eq!(
+            VectorIndexMode::from_config("hnsw", true),
+            Some(VectorIndexMode::HnswQuantized)
+        );
+        assert_eq!(
+            VectorIndexMode::from_config("exact", true),
+            Some(VectorIndexMode::Exact)
+        );
+        assert_eq!(VectorIndexMode::from_config("faiss", false), None);
+    }
 }
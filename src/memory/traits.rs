@@ -1,6 +1,10 @@
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Candidates fetched per requested result when a recall filter is applied
+pub const FILTER_OVERFETCH: usize = 5;
+
 /// A single memory entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -11,6 +15,132 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Where the memory came from, e.g. "telegram:alice", "agent", "cli"
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Recall weight from 0.0 to 1.0 (unset counts as 0.5)
+    #[serde(default)]
+    pub importance: Option<f64>,
+    /// RFC 3339 time after which the entry is no longer recalled and is pruned
+    #[serde(default)]
+    pub expires_at: Option<String>,
+    /// Times the entry has been returned by recall
+    #[serde(default)]
+    pub access_count: u64,
+    #[serde(default)]
+    pub last_accessed: Option<String>,
+}
+
+impl MemoryEntry {
+    /// The writable metadata of this entry, e.g. to store it elsewhere
+    pub fn metadata(&self) -> MemoryMetadata {
+        MemoryMetadata {
+            session_id: self.session_id.clone(),
+            tags: self.tags.clone(),
+            source: self.source.clone(),
+            importance: self.importance,
+            expires_at: self.expires_at.clone(),
+        }
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .is_some_and(|t| t <= now)
+    }
+}
+
+/// Optional attributes recorded when storing a memory
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryMetadata {
+    pub session_id: Option<String>,
+    pub tags: Vec<String>,
+    pub source: Option<String>,
+    /// 0.0–1.0
+    pub importance: Option<f64>,
+    /// RFC 3339 expiry time
+    pub expires_at: Option<String>,
+}
+
+impl MemoryMetadata {
+    pub fn with_source(source: impl Into<String>) -> Self {
+        Self {
+            source: Some(source.into()),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_session(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// Restricts which entries a recall may return
+#[derive(Debug, Clone, Default)]
+pub struct RecallFilter {
+    /// Entries must carry every one of these tags
+    pub tags: Vec<String>,
+    /// Exact source, or its prefix before a ':' ("telegram" matches "telegram:alice")
+    pub source: Option<String>,
+    pub session_id: Option<String>,
+    /// Only entries created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries created at or before this time
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl RecallFilter {
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+            && self.source.is_none()
+            && self.session_id.is_none()
+            && self.since.is_none()
+            && self.until.is_none()
+    }
+
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        if !self.tags.iter().all(|tag| entry.tags.contains(tag)) {
+            return false;
+        }
+        if let Some(source) = &self.source {
+            let matched = entry.source.as_deref().is_some_and(|s| {
+                s == source
+                    || s.strip_prefix(source.as_str())
+                        .is_some_and(|rest| rest.starts_with(':'))
+            });
+            if !matched {
+                return false;
+            }
+        }
+        if self.session_id.is_some() && entry.session_id != self.session_id {
+            return false;
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let Ok(created) = DateTime::parse_from_rfc3339(&entry.timestamp) else {
+                return false;
+            };
+            if self.since.is_some_and(|since| created < since)
+                || self.until.is_some_and(|until| created > until)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Parse a time bound given as RFC 3339 or a `YYYY-MM-DD` date (UTC midnight)
+    pub fn parse_time(value: &str) -> anyhow::Result<DateTime<Utc>> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+            return Ok(time.with_timezone(&Utc));
+        }
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| anyhow::anyhow!("Invalid time '{value}': use RFC 3339 or YYYY-MM-DD"))?;
+        Ok(date.and_time(chrono::NaiveTime::MIN).and_utc())
+    }
 }
 
 /// Memory categories for organization
@@ -38,6 +168,18 @@ impl std::fmt::Display for MemoryCategory {
     }
 }
 
+impl From<&str> for MemoryCategory {
+    /// Parse a category name as produced by `Display`
+    fn from(name: &str) -> Self {
+        match name {
+            "core" => Self::Core,
+            "daily" => Self::Daily,
+            "conversation" => Self::Conversation,
+            other => Self::Custom(other.to_string()),
+        }
+    }
+}
+
 /// Core memory trait — implement for any persistence backend
 #[async_trait]
 pub trait Memory: Send + Sync {
@@ -48,9 +190,41 @@ pub trait Memory: Send + Sync {
     async fn store(&self, key: &str, content: &str, category: MemoryCategory)
         -> anyhow::Result<()>;
 
+    /// Store a memory entry with metadata. Backends that cannot persist
+    /// metadata store the plain entry.
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        _metadata: &MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.store(key, content, category).await
+    }
+
     /// Recall memories matching a query (keyword search)
     async fn recall(&self, query: &str, limit: usize) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall memories matching a query, restricted by `filter`
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        if filter.is_empty() {
+            return self.recall(query, limit).await;
+        }
+        let entries = self
+            .recall(query, limit.saturating_mul(FILTER_OVERFETCH))
+            .await?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| filter.matches(entry))
+            .take(limit)
+            .collect())
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
@@ -66,3 +240,79 @@ pub trait Memory: Send + Sync {
     /// Health check
     async fn health_check(&self) -> bool;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str) -> MemoryEntry {
+        MemoryEntry {
+            id: "1".into(),
+            key: "k".into(),
+            content: "c".into(),
+            category: MemoryCategory::Core,
+            timestamp: timestamp.into(),
+            session_id: Some("telegram:alice".into()),
+            score: None,
+            tags: vec!["ops".into(), "infra".into()],
+            source: Some("telegram:alice".into()),
+            importance: None,
+            expires_at: None,
+            access_count: 0,
+            last_accessed: None,
+        }
+    }
+
+    #[test]
+    fn filter_matches_tags_source_and_session() {
+        let e = entry("2026-01-10T12:00:00+00:00");
+        assert!(RecallFilter::default().matches(&e));
+
+        let tags = |tags: &[&str]| RecallFilter {
+            tags: tags.iter().map(ToString::to_string).collect(),
+            ..RecallFilter::default()
+        };
+        assert!(tags(&["ops"]).matches(&e));
+        assert!(!tags(&["ops", "billing"]).matches(&e));
+
+        let source = |s: &str| RecallFilter {
+            source: Some(s.into()),
+            ..RecallFilter::default()
+        };
+        assert!(source("telegram").matches(&e));
+        assert!(source("telegram:alice").matches(&e));
+        assert!(!source("tele").matches(&e));
+        assert!(!source("discord").matches(&e));
+
+        let session = RecallFilter {
+            session_id: Some("telegram:bob".into()),
+            ..RecallFilter::default()
+        };
+        assert!(!session.matches(&e));
+    }
+
+    #[test]
+    fn filter_matches_time_range() {
+        let e = entry("2026-01-10T12:00:00+00:00");
+        let range = |since: &str, until: &str| RecallFilter {
+            since: Some(RecallFilter::parse_time(since).unwrap()),
+            until: Some(RecallFilter::parse_time(until).unwrap()),
+            ..RecallFilter::default()
+        };
+        assert!(range("2026-01-10", "2026-01-11").matches(&e));
+        assert!(!range("2026-01-11", "2026-01-12").matches(&e));
+        assert!(!range("2026-01-01", "2026-01-10T11:59:59Z").matches(&e));
+        assert!(RecallFilter::parse_time("last week").is_err());
+    }
+
+    #[test]
+    fn expiry() {
+        let mut e = entry("2026-01-10T12:00:00+00:00");
+        let now = RecallFilter::parse_time("2026-02-01").unwrap();
+        assert!(!e.is_expired(now));
+        e.expires_at = Some("2026-01-31T00:00:00Z".into());
+        assert!(e.is_expired(now));
+        e.expires_at = Some("2026-03-01T00:00:00Z".into());
+        assert!(!e.is_expired(now));
+    }
+}
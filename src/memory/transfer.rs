@@ -0,0 +1,228 @@
+// JSONL export/import — move memories between machines and backends.
+//
+// One JSON object per line with the entry's key, content, category name,
+// timestamp, session id and metadata (tags, source, importance, expiry).
+// Import stores every record through the `Memory` trait, so the target
+// backend assigns its own ids and timestamps and an existing key is
+// overwritten.
+
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// One exported memory (a single JSONL line).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportRecord {
+    pub key: String,
+    pub content: String,
+    /// "core", "daily", "conversation" or a custom category name
+    #[serde(default = "default_category")]
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+fn default_category() -> String {
+    "core".into()
+}
+
+impl From<&MemoryEntry> for ExportRecord {
+    fn from(entry: &MemoryEntry) -> Self {
+        Self {
+            key: entry.key.clone(),
+            content: entry.content.clone(),
+            category: entry.category.to_string(),
+            timestamp: Some(entry.timestamp.clone()).filter(|t| !t.is_empty()),
+            session_id: entry.session_id.clone(),
+            tags: entry.tags.clone(),
+            source: entry.source.clone(),
+            importance: entry.importance,
+            expires_at: entry.expires_at.clone(),
+        }
+    }
+}
+
+impl ExportRecord {
+    fn metadata(&self) -> MemoryMetadata {
+        MemoryMetadata {
+            session_id: self.session_id.clone(),
+            tags: self.tags.clone(),
+            source: self.source.clone(),
+            importance: self.importance,
+            expires_at: self.expires_at.clone(),
+        }
+    }
+}
+
+/// Outcome of an import.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Imported records whose key already existed
+    pub replaced: usize,
+    /// `(line number, error)` for lines that could not be imported
+    pub failed: Vec<(usize, String)>,
+}
+
+/// Write memories (optionally one category) as JSONL, oldest first so an
+/// import replays them in their original order. Returns the number written.
+pub async fn export_jsonl(
+    memory: &dyn Memory,
+    category: Option<&MemoryCategory>,
+    out: &mut (impl Write + Send),
+) -> Result<usize> {
+    let entries = memory.list(category).await?;
+    for entry in entries.iter().rev() {
+        serde_json::to_writer(&mut *out, &ExportRecord::from(entry))?;
+        out.write_all(b"\n")?;
+    }
+    out.flush()?;
+    Ok(entries.len())
+}
+
+/// Store every record of a JSONL export. Malformed lines are reported and
+/// skipped; storage errors abort the import.
+pub async fn import_jsonl(memory: &dyn Memory, jsonl: &str) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+    for (i, line) in jsonl.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record: ExportRecord = match serde_json::from_str(line) {
+            Ok(record) => record,
+            Err(e) => {
+                report.failed.push((i + 1, e.to_string()));
+                continue;
+            }
+        };
+        if record.key.trim().is_empty() {
+            report.failed.push((i + 1, "empty key".into()));
+            continue;
+        }
+
+        if memory.get(&record.key).await?.is_some() {
+            report.replaced += 1;
+        }
+        memory
+            .store_with_metadata(
+                &record.key,
+                &record.content,
+                MemoryCategory::from(record.category.as_str()),
+                &record.metadata(),
+            )
+            .await?;
+        report.imported += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn export_then_import_round_trips() {
+        let source_dir = TempDir::new().unwrap();
+        let source = SqliteMemory::new(source_dir.path()).unwrap();
+        source
+            .store("lang", "Prefers Rust", MemoryCategory::Core)
+            .await
+            .unwrap();
+        source
+            .store("standup", "Moved to 10am", MemoryCategory::Daily)
+            .await
+            .unwrap();
+        source
+            .store_with_metadata(
+                "adr-7",
+                "Use SQLite",
+                MemoryCategory::Custom("decisions".into()),
+                &MemoryMetadata {
+                    tags: vec!["storage".into()],
+                    importance: Some(0.9),
+                    ..MemoryMetadata::with_source("cli")
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut jsonl = Vec::new();
+        assert_eq!(export_jsonl(&source, None, &mut jsonl).await.unwrap(), 3);
+        let jsonl = String::from_utf8(jsonl).unwrap();
+        assert_eq!(jsonl.lines().count(), 3);
+        assert!(jsonl.contains(r#""category":"decisions""#));
+
+        let target_dir = TempDir::new().unwrap();
+        let target = SqliteMemory::new(target_dir.path()).unwrap();
+        let report = import_jsonl(&target, &jsonl).await.unwrap();
+        assert_eq!(report.imported, 3);
+        assert_eq!(report.replaced, 0);
+        assert!(report.failed.is_empty());
+
+        let adr = target.get("adr-7").await.unwrap().unwrap();
+        assert_eq!(adr.content, "Use SQLite");
+        assert_eq!(adr.category, MemoryCategory::Custom("decisions".into()));
+        assert_eq!(adr.tags, ["storage"]);
+        assert_eq!(adr.source.as_deref(), Some("cli"));
+        assert_eq!(adr.importance, Some(0.9));
+    }
+
+    #[tokio::test]
+    async fn export_filters_by_category() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("a", "core fact", MemoryCategory::Core)
+            .await
+            .unwrap();
+        mem.store("b", "daily note", MemoryCategory::Daily)
+            .await
+            .unwrap();
+
+        let mut jsonl = Vec::new();
+        let written = export_jsonl(&mem, Some(&MemoryCategory::Daily), &mut jsonl)
+            .await
+            .unwrap();
+        assert_eq!(written, 1);
+        assert!(String::from_utf8(jsonl).unwrap().contains("daily note"));
+    }
+
+    #[tokio::test]
+    async fn import_reports_bad_lines_and_replacements() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("lang", "Prefers Go", MemoryCategory::Core)
+            .await
+            .unwrap();
+
+        let jsonl = "{\"key\":\"lang\",\"content\":\"Prefers Rust\"}\n\nnot json\n{\"key\":\" \",\"content\":\"x\"}\n";
+        let report = import_jsonl(&mem, jsonl).await.unwrap();
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.replaced, 1);
+        assert_eq!(
+            report
+                .failed
+                .iter()
+                .map(|(line, _)| *line)
+                .collect::<Vec<_>>(),
+            [3, 4]
+        );
+
+        let lang = mem.get("lang").await.unwrap().unwrap();
+        assert_eq!(lang.content, "Prefers Rust");
+        assert_eq!(lang.category, MemoryCategory::Core);
+    }
+}
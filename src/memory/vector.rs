@@ -131,6 +131,30 @@ pub fn hybrid_merge(
     results
 }
 
+/// Importance assumed for entries that don't set one
+pub const DEFAULT_IMPORTANCE: f64 = 0.5;
+
+/// Age at which the recency factor of short-lived memories is halfway to its floor
+pub const RECENCY_HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Re-rank a relevance score by importance and recency.
+///
+/// Importance scales the score from 0.5× (0.0) to 1.5× (1.0), neutral at the
+/// default 0.5. When `decays` (conversation/daily notes), the score also
+/// fades from 1.0× toward 0.5× with age, so stale chatter sinks below
+/// durable facts without vanishing.
+pub fn boost_score(score: f32, importance: Option<f64>, age_days: f64, decays: bool) -> f32 {
+    let importance = importance.unwrap_or(DEFAULT_IMPORTANCE).clamp(0.0, 1.0);
+    let recency = if decays {
+        0.5 + 0.5 * 0.5_f64.powf(age_days.max(0.0) / RECENCY_HALF_LIFE_DAYS)
+    } else {
+        1.0
+    };
+    #[allow(clippy::cast_possible_truncation)]
+    let factor = ((0.5 + importance) * recency) as f32;
+    score * factor
+}
+
 #[cfg(test)]
 #[allow(
     clippy::float_cmp,
@@ -243,6 +267,22 @@ mod tests {
         assert!(merged.is_empty());
     }
 
+    #[test]
+    fn boost_is_neutral_for_fresh_default_entries() {
+        assert!((boost_score(0.8, None, 0.0, true) - 0.8).abs() < 1e-6);
+        assert!((boost_score(0.8, None, 365.0, false) - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn boost_scales_by_importance_and_age() {
+        assert!((boost_score(1.0, Some(1.0), 0.0, false) - 1.5).abs() < 1e-6);
+        assert!((boost_score(1.0, Some(0.0), 0.0, false) - 0.5).abs() < 1e-6);
+        // One half-life: 0.5 + 0.5 * 0.5
+        let aged = boost_score(1.0, None, RECENCY_HALF_LIFE_DAYS, true);
+        assert!((aged - 0.75).abs() < 1e-6);
+        assert!(boost_score(1.0, None, 10_000.0, true) >= 0.5);
+    }
+
     // ── Edge cases: cosine similarity ────────────────────────────
 
     #[test]
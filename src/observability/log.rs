@@ -37,6 +37,14 @@ impl Observer for LogObserver {
             ObserverEvent::HeartbeatTick => {
                 info!("heartbeat.tick");
             }
+            ObserverEvent::HeartbeatTask {
+                task,
+                duration,
+                success,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(task = %task, duration_ms = ms, success = success, "heartbeat.task");
+            }
             ObserverEvent::Error { component, message } => {
                 info!(component = %component, error = %message, "error");
             }
@@ -101,6 +109,11 @@ mod tests {
             direction: "outbound".into(),
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
+        obs.record_event(&ObserverEvent::HeartbeatTask {
+            task: "Check email".into(),
+            duration: Duration::from_secs(3),
+            success: false,
+        });
         obs.record_event(&ObserverEvent::Error {
             component: "provider".into(),
             message: "timeout".into(),
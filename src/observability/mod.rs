@@ -2,12 +2,14 @@ pub mod log;
 pub mod multi;
 pub mod noop;
 pub mod otel;
+pub mod prometheus;
 pub mod traits;
 
 pub use self::log::LogObserver;
+pub use self::prometheus::PrometheusObserver;
 pub use noop::NoopObserver;
 pub use otel::OtelObserver;
-pub use traits::{Observer, ObserverEvent};
+pub use traits::{Observer, ObserverEvent, ObserverMetric};
 
 use crate::config::ObservabilityConfig;
 
@@ -33,6 +35,13 @@ pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
                 }
             }
         }
+        "prometheus" => match PrometheusObserver::new() {
+            Ok(obs) => Box::new(obs),
+            Err(e) => {
+                tracing::error!("{e}. Falling back to noop.");
+                Box::new(NoopObserver)
+            }
+        },
         "none" | "noop" => Box::new(NoopObserver),
         _ => {
             tracing::warn!(
@@ -75,6 +84,15 @@ mod tests {
         assert_eq!(create_observer(&cfg).name(), "log");
     }
 
+    #[test]
+    fn factory_prometheus_returns_prometheus() {
+        let cfg = ObservabilityConfig {
+            backend: "prometheus".into(),
+            ..ObservabilityConfig::default()
+        };
+        assert_eq!(create_observer(&cfg).name(), "prometheus");
+    }
+
     #[test]
     fn factory_otel_returns_otel() {
         let cfg = ObservabilityConfig {
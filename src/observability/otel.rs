@@ -19,6 +19,7 @@ pub struct OtelObserver {
     tool_duration: Histogram<f64>,
     channel_messages: Counter<u64>,
     heartbeat_ticks: Counter<u64>,
+    heartbeat_tasks: Counter<u64>,
     errors: Counter<u64>,
     request_latency: Histogram<f64>,
     tokens_used: Counter<u64>,
@@ -106,6 +107,11 @@ impl OtelObserver {
             .with_description("Total heartbeat ticks")
             .build();
 
+        let heartbeat_tasks = meter
+            .u64_counter("viziclaw.heartbeat.tasks")
+            .with_description("Total heartbeat tasks executed")
+            .build();
+
         let errors = meter
             .u64_counter("viziclaw.errors")
             .with_description("Total errors by component")
@@ -141,6 +147,7 @@ impl OtelObserver {
             tool_duration,
             channel_messages,
             heartbeat_ticks,
+            heartbeat_tasks,
             errors,
             request_latency,
             tokens_used,
@@ -239,6 +246,10 @@ impl Observer for OtelObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.add(1, &[]);
             }
+            ObserverEvent::HeartbeatTask { success, .. } => {
+                self.heartbeat_tasks
+                    .add(1, &[KeyValue::new("success", success.to_string())]);
+            }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
                 let mut span = tracer.build(
@@ -345,6 +356,11 @@ mod tests {
             direction: "inbound".into(),
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
+        obs.record_event(&ObserverEvent::HeartbeatTask {
+            task: "Check email".into(),
+            duration: Duration::from_secs(3),
+            success: true,
+        });
         obs.record_event(&ObserverEvent::Error {
             component: "provider".into(),
             message: "timeout".into(),
@@ -0,0 +1,251 @@
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge, Opts,
+    Registry, TextEncoder,
+};
+use std::sync::OnceLock;
+
+/// Metrics shared by every `PrometheusObserver` in the process, so the
+/// gateway's `/metrics` route sees what channels, heartbeat and the agent
+/// record through their own observers.
+struct Metrics {
+    registry: Registry,
+    agent_starts: IntCounterVec,
+    agent_duration: Histogram,
+    tool_calls: IntCounterVec,
+    tool_duration: HistogramVec,
+    channel_messages: IntCounterVec,
+    heartbeat_ticks: IntCounter,
+    heartbeat_tasks: IntCounterVec,
+    errors: IntCounterVec,
+    request_latency: Histogram,
+    tokens_used: IntCounter,
+    active_sessions: IntGauge,
+    queue_depth: IntGauge,
+}
+
+static METRICS: OnceLock<Metrics> = OnceLock::new();
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("viziclaw".into()), None)?;
+
+        let agent_starts = IntCounterVec::new(
+            Opts::new("agent_starts_total", "Total agent invocations"),
+            &["provider", "model"],
+        )?;
+        let agent_duration = Histogram::with_opts(HistogramOpts::new(
+            "agent_duration_seconds",
+            "Agent invocation duration in seconds",
+        ))?;
+        let tool_calls = IntCounterVec::new(
+            Opts::new("tool_calls_total", "Total tool calls"),
+            &["tool", "success"],
+        )?;
+        let tool_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "tool_duration_seconds",
+                "Tool execution duration in seconds",
+            ),
+            &["tool"],
+        )?;
+        let channel_messages = IntCounterVec::new(
+            Opts::new("channel_messages_total", "Total channel messages"),
+            &["channel", "direction"],
+        )?;
+        let heartbeat_ticks = IntCounter::new("heartbeat_ticks_total", "Total heartbeat ticks")?;
+        let heartbeat_tasks = IntCounterVec::new(
+            Opts::new("heartbeat_tasks_total", "Total heartbeat tasks executed"),
+            &["success"],
+        )?;
+        let errors = IntCounterVec::new(
+            Opts::new("errors_total", "Total errors by component"),
+            &["component"],
+        )?;
+        let request_latency = Histogram::with_opts(HistogramOpts::new(
+            "request_latency_seconds",
+            "LLM request latency in seconds",
+        ))?;
+        let tokens_used = IntCounter::new("tokens_used_total", "Total tokens consumed")?;
+        let active_sessions =
+            IntGauge::new("sessions_active", "Current number of active sessions")?;
+        let queue_depth = IntGauge::new("queue_depth", "Current message queue depth")?;
+
+        registry.register(Box::new(agent_starts.clone()))?;
+        registry.register(Box::new(agent_duration.clone()))?;
+        registry.register(Box::new(tool_calls.clone()))?;
+        registry.register(Box::new(tool_duration.clone()))?;
+        registry.register(Box::new(channel_messages.clone()))?;
+        registry.register(Box::new(heartbeat_ticks.clone()))?;
+        registry.register(Box::new(heartbeat_tasks.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+        registry.register(Box::new(request_latency.clone()))?;
+        registry.register(Box::new(tokens_used.clone()))?;
+        registry.register(Box::new(active_sessions.clone()))?;
+        registry.register(Box::new(queue_depth.clone()))?;
+
+        Ok(Self {
+            registry,
+            agent_starts,
+            agent_duration,
+            tool_calls,
+            tool_duration,
+            channel_messages,
+            heartbeat_ticks,
+            heartbeat_tasks,
+            errors,
+            request_latency,
+            tokens_used,
+            active_sessions,
+            queue_depth,
+        })
+    }
+}
+
+/// Prometheus observer — records into the process-wide registry that the
+/// gateway serves on `GET /metrics`.
+pub struct PrometheusObserver {
+    metrics: &'static Metrics,
+}
+
+impl PrometheusObserver {
+    pub fn new() -> Result<Self, String> {
+        if let Some(metrics) = METRICS.get() {
+            return Ok(Self { metrics });
+        }
+        let metrics =
+            Metrics::new().map_err(|e| format!("Failed to register Prometheus metrics: {e}"))?;
+        Ok(Self {
+            metrics: METRICS.get_or_init(|| metrics),
+        })
+    }
+}
+
+/// Everything recorded so far in the Prometheus text exposition format, or
+/// `None` when no Prometheus observer has been created in this process.
+pub fn encode_metrics() -> Option<String> {
+    let metrics = METRICS.get()?;
+    let mut buf = Vec::new();
+    TextEncoder::new()
+        .encode(&metrics.registry.gather(), &mut buf)
+        .ok()?;
+    String::from_utf8(buf).ok()
+}
+
+/// Content type of [`encode_metrics`] output.
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+impl Observer for PrometheusObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let m = self.metrics;
+        match event {
+            ObserverEvent::AgentStart { provider, model } => {
+                m.agent_starts
+                    .with_label_values(&[provider.as_str(), model.as_str()])
+                    .inc();
+            }
+            ObserverEvent::AgentEnd { duration, .. } => {
+                // Tokens arrive separately as ObserverMetric::TokensUsed
+                m.agent_duration.observe(duration.as_secs_f64());
+            }
+            ObserverEvent::ToolCall {
+                tool,
+                duration,
+                success,
+            } => {
+                let success = if *success { "true" } else { "false" };
+                m.tool_calls
+                    .with_label_values(&[tool.as_str(), success])
+                    .inc();
+                m.tool_duration
+                    .with_label_values(&[tool.as_str()])
+                    .observe(duration.as_secs_f64());
+            }
+            ObserverEvent::ChannelMessage { channel, direction } => {
+                m.channel_messages
+                    .with_label_values(&[channel.as_str(), direction.as_str()])
+                    .inc();
+            }
+            ObserverEvent::HeartbeatTick => m.heartbeat_ticks.inc(),
+            ObserverEvent::HeartbeatTask { success, .. } => {
+                let success = if *success { "true" } else { "false" };
+                m.heartbeat_tasks.with_label_values(&[success]).inc();
+            }
+            ObserverEvent::Error { component, .. } => {
+                m.errors.with_label_values(&[component.as_str()]).inc();
+            }
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        let m = self.metrics;
+        match metric {
+            ObserverMetric::RequestLatency(d) => m.request_latency.observe(d.as_secs_f64()),
+            ObserverMetric::TokensUsed(t) => m.tokens_used.inc_by(*t),
+            ObserverMetric::ActiveSessions(s) => {
+                m.active_sessions.set(i64::try_from(*s).unwrap_or(i64::MAX));
+            }
+            ObserverMetric::QueueDepth(d) => {
+                m.queue_depth.set(i64::try_from(*d).unwrap_or(i64::MAX));
+            }
+        }
+    }
+
+    fn name(&self) -> &str {
+        "prometheus"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn prometheus_observer_name() {
+        assert_eq!(PrometheusObserver::new().unwrap().name(), "prometheus");
+    }
+
+    #[test]
+    fn events_and_metrics_are_exported() {
+        let obs = PrometheusObserver::new().unwrap();
+        obs.record_event(&ObserverEvent::AgentStart {
+            provider: "openrouter".into(),
+            model: "claude-sonnet".into(),
+        });
+        obs.record_event(&ObserverEvent::ToolCall {
+            tool: "prom_test_tool".into(),
+            duration: Duration::from_millis(10),
+            success: false,
+        });
+        obs.record_event(&ObserverEvent::ChannelMessage {
+            channel: "prom_test_channel".into(),
+            direction: "inbound".into(),
+        });
+        obs.record_event(&ObserverEvent::Error {
+            component: "prom_test_component".into(),
+            message: "timeout".into(),
+        });
+        obs.record_metric(&ObserverMetric::RequestLatency(Duration::from_millis(250)));
+        obs.record_metric(&ObserverMetric::TokensUsed(42));
+        obs.record_metric(&ObserverMetric::QueueDepth(3));
+
+        // A second observer shares the same registry
+        PrometheusObserver::new()
+            .unwrap()
+            .record_event(&ObserverEvent::HeartbeatTick);
+
+        let text = encode_metrics().unwrap();
+        assert!(
+            text.contains(r#"viziclaw_tool_calls_total{success="false",tool="prom_test_tool"} 1"#)
+        );
+        assert!(text.contains(
+            r#"viziclaw_channel_messages_total{channel="prom_test_channel",direction="inbound"} 1"#
+        ));
+        assert!(text.contains(r#"viziclaw_errors_total{component="prom_test_component"} 1"#));
+        assert!(text.contains("viziclaw_request_latency_seconds_bucket"));
+        assert!(text.contains("viziclaw_tokens_used_total"));
+        assert!(text.contains("viziclaw_heartbeat_ticks_total"));
+        assert!(text.contains("viziclaw_queue_depth"));
+    }
+}
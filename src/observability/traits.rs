@@ -21,6 +21,11 @@ pub enum ObserverEvent {
         direction: String,
     },
     HeartbeatTick,
+    HeartbeatTask {
+        task: String,
+        duration: Duration,
+        success: bool,
+    },
     Error {
         component: String,
         message: String,
@@ -274,6 +274,8 @@ pub fn run_quick_setup(
             0
         },
         chunk_max_tokens: 512,
+        vector_index: "hnsw".into(),
+        vector_index_quantized: false,
     };
 
     let config = Config {
@@ -1102,6 +1104,8 @@ fn setup_memory() -> Result<MemoryConfig> {
         keyword_weight: 0.3,
         embedding_cache_size: if backend == "sqlite" { 10000 } else { 0 },
         chunk_max_tokens: 512,
+        vector_index: "hnsw".into(),
+        vector_index_quantized: false,
     })
 }
 
@@ -1113,18 +1117,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
     print_bullet("CLI is always available. Connect more channels now.");
     println!();
 
-    let mut config = ChannelsConfig {
-        cli: true,
-        telegram: None,
-        discord: None,
-        slack: None,
-        webhook: None,
-        imessage: None,
-        matrix: None,
-        whatsapp: None,
-        email: None,
-        irc: None,
-    };
+    let mut config = ChannelsConfig::default();
 
     loop {
         let options = vec![
@@ -1441,12 +1434,12 @@ fn setup_channels() -> Result<ChannelsConfig> {
                 }
 
                 let app_token: String = Input::new()
-                    .with_prompt("  App token (xapp-..., optional, Enter to skip)")
+                    .with_prompt("  App token (xapp-..., enables Socket Mode, Enter to skip)")
                     .allow_empty(true)
                     .interact_text()?;
 
                 let channel: String = Input::new()
-                    .with_prompt("  Default channel ID (optional, Enter to skip)")
+                    .with_prompt("  Only listen in channel ID (optional, Enter for all channels)")
                     .allow_empty(true)
                     .interact_text()?;
 
@@ -1549,6 +1542,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                 );
                 print_bullet("You need a Matrix account and an access token.");
                 print_bullet("Get a token via Element → Settings → Help & About → Access Token.");
+                print_bullet("The bot answers in every room it joins; invite it from an allowed account.");
                 println!();
 
                 let homeserver: String = Input::new()
@@ -1598,7 +1592,7 @@ fn setup_channels() -> Result<ChannelsConfig> {
                 }
 
                 let room_id: String = Input::new()
-                    .with_prompt("  Room ID (e.g. !abc123:matrix.org)")
+                    .with_prompt("  Default room ID (e.g. !abc123:matrix.org)")
                     .interact_text()?;
 
                 let users_str: String = Input::new()
@@ -1612,11 +1606,20 @@ fn setup_channels() -> Result<ChannelsConfig> {
                     users_str.split(',').map(|s| s.trim().to_string()).collect()
                 };
 
+                let e2ee = Confirm::new()
+                    .with_prompt("  Enable end-to-end encryption (encrypted rooms)?")
+                    .default(true)
+                    .interact()?;
+
                 config.matrix = Some(MatrixConfig {
                     homeserver: homeserver.trim_end_matches('/').to_string(),
                     access_token,
                     room_id,
                     allowed_users,
+                    room_allowed_users: std::collections::HashMap::new(),
+                    auto_join: true,
+                    e2ee,
+                    verified_devices: vec![],
                 });
             }
             5 => {
@@ -2158,11 +2161,13 @@ fn scaffold_workspace(workspace_dir: &Path, ctx: &ProjectContext) -> Result<()>
         "# HEARTBEAT.md\n\n\
          # Keep this file empty (or with only comments) to skip heartbeat work.\n\
          # Add tasks below when you want {agent} to check something periodically.\n\
+         # Optional tags before a task: [every 2h], [daily 09:00],\n\
+         # [if notes/inbox.md] (only when that file is non-empty).\n\
          #\n\
          # Examples:\n\
          # - Check my email for important messages\n\
-         # - Review my calendar for upcoming events\n\
-         # - Run `git status` on my active projects\n"
+         # - [daily 08:00] Review my calendar for upcoming events\n\
+         # - [every 4h] Run `git status` on my active projects\n"
     );
 
     let soul = format!(
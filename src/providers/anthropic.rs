@@ -1,4 +1,9 @@
-use crate::providers::traits::Provider;
+use crate::providers::streaming::{response_lines, sse_data};
+use crate::providers::traits::{
+    resolve_images, ChatResponse as ProviderChatResponse, ChatStream, ConversationMessage,
+    ImageContent, Provider, StreamEvent, TokenUsage, ToolCall,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -35,6 +40,329 @@ struct ContentBlock {
     text: String,
 }
 
+// ── Native tool use (`tools` / `tool_use` / `tool_result`) ──────
+
+#[derive(Debug, Serialize)]
+struct NativeChatRequest {
+    model: String,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<String>,
+    messages: Vec<NativeMessage>,
+    temperature: f64,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<NativeToolSpec>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeMessage {
+    role: String,
+    content: Vec<NativeContentOut>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeContentOut {
+    Text {
+        text: String,
+    },
+    Image {
+        source: NativeImageSource,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: Vec<NativeContentOut>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct NativeImageSource {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    media_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeToolSpec {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeChatResponse {
+    content: Vec<NativeContentIn>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+}
+
+impl From<NativeUsage> for TokenUsage {
+    fn from(usage: NativeUsage) -> Self {
+        TokenUsage {
+            prompt_tokens: usage.input_tokens,
+            completion_tokens: usage.output_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeContentIn {
+    Text {
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: serde_json::Value,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeStreamEvent {
+    MessageStart {
+        message: NativeStreamMessage,
+    },
+    MessageDelta {
+        usage: NativeUsage,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: NativeContentIn,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: NativeStreamDelta,
+    },
+    Error {
+        error: NativeStreamError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeStreamDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamMessage {
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamError {
+    message: String,
+}
+
+/// Parse one line of a Messages API SSE stream. Tool input arrives as
+/// `input_json_delta` fragments after the `tool_use` block starts. Input
+/// token usage comes with `message_start`, the final output count with
+/// `message_delta`.
+fn parse_stream_line(line: &str) -> anyhow::Result<Vec<StreamEvent>> {
+    let Some(data) = sse_data(line) else {
+        return Ok(Vec::new());
+    };
+
+    let event = match serde_json::from_str::<NativeStreamEvent>(data)? {
+        NativeStreamEvent::MessageStart {
+            message: NativeStreamMessage { usage: Some(usage) },
+        }
+        | NativeStreamEvent::MessageDelta { usage } => StreamEvent::Usage(usage.into()),
+        NativeStreamEvent::ContentBlockStart {
+            index,
+            content_block: NativeContentIn::ToolUse { id, name, .. },
+        } => StreamEvent::ToolCallDelta {
+            index,
+            id: Some(id),
+            name: Some(name),
+            arguments: String::new(),
+        },
+        NativeStreamEvent::ContentBlockStart {
+            content_block: NativeContentIn::Text { text },
+            ..
+        }
+        | NativeStreamEvent::ContentBlockDelta {
+            delta: NativeStreamDelta::TextDelta { text },
+            ..
+        } if !text.is_empty() => StreamEvent::TextDelta(text),
+        NativeStreamEvent::ContentBlockDelta {
+            index,
+            delta: NativeStreamDelta::InputJsonDelta { partial_json },
+        } => StreamEvent::ToolCallDelta {
+            index,
+            id: None,
+            name: None,
+            arguments: partial_json,
+        },
+        NativeStreamEvent::Error { error } => {
+            anyhow::bail!(
+                "Anthropic stream error: {}",
+                super::sanitize_api_error(&error.message)
+            )
+        }
+        _ => return Ok(Vec::new()),
+    };
+    Ok(vec![event])
+}
+
+/// A text block (when there is text) followed by base64 image blocks.
+fn content_blocks(text: &str, images: &[ImageContent]) -> Vec<NativeContentOut> {
+    let (text, images) = resolve_images(text, images);
+    let mut blocks = Vec::with_capacity(images.len() + 1);
+    if !text.is_empty() {
+        blocks.push(NativeContentOut::Text { text });
+    }
+    blocks.extend(
+        images
+            .into_iter()
+            .map(|(media_type, data)| NativeContentOut::Image {
+                source: NativeImageSource {
+                    kind: "base64",
+                    media_type,
+                    data,
+                },
+            }),
+    );
+    blocks
+}
+
+/// Split a structured conversation into Anthropic's top-level `system` field
+/// and alternating user/assistant messages. Consecutive turns with the same
+/// role are merged, since the Messages API rejects them (this is also how
+/// several `tool_result` blocks end up in one user turn).
+fn build_native_messages(messages: &[ConversationMessage]) -> (Option<String>, Vec<NativeMessage>) {
+    let mut system_parts: Vec<&str> = Vec::new();
+    let mut out: Vec<NativeMessage> = Vec::new();
+
+    for message in messages {
+        let (role, blocks) = match message {
+            ConversationMessage::Chat(chat) if chat.role == "system" => {
+                system_parts.push(&chat.content);
+                continue;
+            }
+            ConversationMessage::Chat(chat) => {
+                let role = if chat.role == "assistant" {
+                    "assistant"
+                } else {
+                    "user"
+                };
+                (role, content_blocks(&chat.content, &chat.images))
+            }
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+                let mut blocks = Vec::with_capacity(tool_calls.len() + 1);
+                if let Some(text) = text.as_ref().filter(|t| !t.is_empty()) {
+                    blocks.push(NativeContentOut::Text { text: text.clone() });
+                }
+                for call in tool_calls {
+                    blocks.push(NativeContentOut::ToolUse {
+                        id: call.id.clone(),
+                        name: call.name.clone(),
+                        input: serde_json::from_str(&call.arguments)
+                            .unwrap_or_else(|_| serde_json::json!({})),
+                    });
+                }
+                ("assistant", blocks)
+            }
+            ConversationMessage::ToolResult(result) => (
+                "user",
+                vec![NativeContentOut::ToolResult {
+                    tool_use_id: result.tool_call_id.clone(),
+                    content: content_blocks(&result.content, &result.images),
+                }],
+            ),
+        };
+
+        if blocks.is_empty() {
+            continue;
+        }
+
+        match out.last_mut() {
+            Some(last) if last.role == role => last.content.extend(blocks),
+            _ => out.push(NativeMessage {
+                role: role.to_string(),
+                content: blocks,
+            }),
+        }
+    }
+
+    let system = if system_parts.is_empty() {
+        None
+    } else {
+        Some(system_parts.join("\n\n"))
+    };
+    (system, out)
+}
+
+fn build_native_tools(tools: &[ToolSpec]) -> Vec<NativeToolSpec> {
+    tools
+        .iter()
+        .map(|tool| NativeToolSpec {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            input_schema: tool.parameters.clone(),
+        })
+        .collect()
+}
+
+fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
+    let usage = response.usage.map(TokenUsage::from);
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    for block in response.content {
+        match block {
+            NativeContentIn::Text { text: t } => text.push_str(&t),
+            NativeContentIn::ToolUse { id, name, input } => tool_calls.push(ToolCall {
+                id,
+                name,
+                arguments: if input.is_null() {
+                    "{}".to_string()
+                } else {
+                    input.to_string()
+                },
+            }),
+            NativeContentIn::Other => {}
+        }
+    }
+
+    ProviderChatResponse {
+        text: if text.is_empty() { None } else { Some(text) },
+        tool_calls,
+        usage,
+    }
+}
+
 impl AnthropicProvider {
     pub fn new(api_key: Option<&str>) -> Self {
         Self::with_base_url(api_key, None)
@@ -62,6 +390,65 @@ impl AnthropicProvider {
     fn is_setup_token(token: &str) -> bool {
         token.starts_with("sk-ant-oat01-")
     }
+
+    fn credential(&self) -> anyhow::Result<&str> {
+        self.credential.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
+            )
+        })
+    }
+
+    /// Build an authenticated `POST /v1/messages` request.
+    /// Setup tokens use Bearer auth + the OAuth beta header; API keys use `x-api-key`.
+    fn messages_request(&self, credential: &str) -> reqwest::RequestBuilder {
+        let request = self
+            .client
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json");
+
+        if Self::is_setup_token(credential) {
+            request
+                .header("Authorization", format!("Bearer {credential}"))
+                .header("anthropic-beta", "oauth-2025-04-20")
+        } else {
+            request.header("x-api-key", credential)
+        }
+    }
+
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let credential = self.credential()?;
+        let (system, messages) = build_native_messages(messages);
+
+        let request = NativeChatRequest {
+            model: model.to_string(),
+            max_tokens: 4096,
+            system,
+            messages,
+            temperature,
+            tools: build_native_tools(tools),
+            stream,
+        };
+
+        let response = self
+            .messages_request(credential)
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("Anthropic", response).await);
+        }
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -73,11 +460,7 @@ impl Provider for AnthropicProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let credential = self.credential.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token)."
-            )
-        })?;
+        let credential = self.credential()?;
 
         let request = ChatRequest {
             model: model.to_string(),
@@ -90,21 +473,11 @@ impl Provider for AnthropicProvider {
             temperature,
         };
 
-        let mut request = self
-            .client
-            .post(format!("{}/v1/messages", self.base_url))
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request);
-
-        if Self::is_setup_token(credential) {
-            request = request.header("Authorization", format!("Bearer {credential}"));
-            request = request.header("anthropic-beta", "oauth-2025-04-20");
-        } else {
-            request = request.header("x-api-key", credential);
-        }
-
-        let response = request.send().await?;
+        let response = self
+            .messages_request(credential)
+            .json(&request)
+            .send()
+            .await?;
 
         if !response.status().is_success() {
             return Err(super::api_error("Anthropic", response).await);
@@ -119,6 +492,41 @@ impl Provider for AnthropicProvider {
             .map(|c| c.text)
             .ok_or_else(|| anyhow::anyhow!("No response from Anthropic"))
     }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let native: NativeChatResponse = response.json().await?;
+        Ok(parse_native_response(native))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, parse_stream_line))
+    }
 }
 
 #[cfg(test)]
@@ -264,6 +672,128 @@ mod tests {
         assert_eq!(resp.content[1].text, "Second");
     }
 
+    #[test]
+    fn native_messages_hoist_system_and_merge_tool_results() {
+        use crate::providers::traits::{ChatMessage, ToolResultMessage};
+
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::system("Be brief")),
+            ConversationMessage::Chat(ChatMessage::user("read both files")),
+            ConversationMessage::AssistantToolCalls {
+                text: Some("Reading.".into()),
+                tool_calls: vec![
+                    ToolCall {
+                        id: "toolu_1".into(),
+                        name: "file_read".into(),
+                        arguments: r#"{"path":"a"}"#.into(),
+                    },
+                    ToolCall {
+                        id: "toolu_2".into(),
+                        name: "file_read".into(),
+                        arguments: r#"{"path":"b"}"#.into(),
+                    },
+                ],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "toolu_1".into(),
+                content: "A".into(),
+                images: Vec::new(),
+            }),
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "toolu_2".into(),
+                content: "B".into(),
+                images: Vec::new(),
+            }),
+        ];
+
+        let (system, native) = build_native_messages(&messages);
+        assert_eq!(system.as_deref(), Some("Be brief"));
+        assert_eq!(native.len(), 3);
+
+        let json = serde_json::to_value(&native).unwrap();
+        assert_eq!(json[1]["role"], "assistant");
+        assert_eq!(json[1]["content"][0]["type"], "text");
+        assert_eq!(json[1]["content"][1]["type"], "tool_use");
+        assert_eq!(json[1]["content"][1]["input"]["path"], "a");
+        assert_eq!(json[2]["role"], "user");
+        assert_eq!(json[2]["content"].as_array().unwrap().len(), 2);
+        assert_eq!(json[2]["content"][1]["type"], "tool_result");
+        assert_eq!(json[2]["content"][1]["tool_use_id"], "toolu_2");
+    }
+
+    #[test]
+    fn native_messages_send_images_as_base64_blocks() {
+        use crate::providers::traits::{ChatMessage, ImageContent, ToolResultMessage};
+
+        let png = ImageContent::base64("image/png", "aGk=");
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::user("look").with_images([png.clone()])),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "toolu_1".into(),
+                    name: "screenshot".into(),
+                    arguments: "{}".into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "toolu_1".into(),
+                content: "saved".into(),
+                images: vec![png],
+            }),
+        ];
+
+        let (_, native) = build_native_messages(&messages);
+        let json = serde_json::to_value(&native).unwrap();
+        assert_eq!(json[0]["content"][1]["type"], "image");
+        assert_eq!(json[0]["content"][1]["source"]["type"], "base64");
+        assert_eq!(json[0]["content"][1]["source"]["media_type"], "image/png");
+        assert_eq!(json[0]["content"][1]["source"]["data"], "aGk=");
+        let result = &json[2]["content"][0];
+        assert_eq!(result["type"], "tool_result");
+        assert_eq!(result["content"][0]["text"], "saved");
+        assert_eq!(result["content"][1]["type"], "image");
+    }
+
+    #[test]
+    fn native_tools_use_input_schema() {
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let json = serde_json::to_value(build_native_tools(&tools)).unwrap();
+        assert_eq!(json[0]["name"], "shell");
+        assert_eq!(json[0]["input_schema"]["type"], "object");
+    }
+
+    #[test]
+    fn native_response_parses_tool_use_blocks() {
+        let json = r#"{"content":[
+            {"type":"text","text":"Let me look."},
+            {"type":"tool_use","id":"toolu_9","name":"shell","input":{"command":"ls"}},
+            {"type":"thinking","thinking":"..."}
+        ],"usage":{"input_tokens":310,"output_tokens":48}}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response);
+
+        assert_eq!(parsed.text.as_deref(), Some("Let me look."));
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].id, "toolu_9");
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(parsed.usage.unwrap().total(), 358);
+    }
+
+    #[tokio::test]
+    async fn chat_with_tools_fails_without_key() {
+        let p = AnthropicProvider::new(None);
+        let result = p.chat_with_tools(&[], &[], "claude-3-opus", 0.7).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("credentials not set"));
+    }
+
     #[test]
     fn temperature_range_serializes() {
         for temp in [0.0, 0.5, 1.0, 2.0] {
@@ -278,4 +808,87 @@ mod tests {
             assert!(json.contains(&format!("{temp}")));
         }
     }
+
+    #[test]
+    fn stream_line_parses_text_and_tool_use_deltas() {
+        let text = parse_stream_line(
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+        )
+        .unwrap();
+        assert_eq!(text, vec![StreamEvent::TextDelta("Hello".into())]);
+
+        let start = parse_stream_line(
+            r#"data: {"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"shell","input":{}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            start,
+            vec![StreamEvent::ToolCallDelta {
+                index: 1,
+                id: Some("toolu_1".into()),
+                name: Some("shell".into()),
+                arguments: String::new(),
+            }]
+        );
+
+        let args = parse_stream_line(
+            r#"data: {"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"command\""}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            vec![StreamEvent::ToolCallDelta {
+                index: 1,
+                id: None,
+                name: None,
+                arguments: r#"{"command""#.into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn stream_line_ignores_bookkeeping_and_surfaces_errors() {
+        assert!(parse_stream_line("event: message_start")
+            .unwrap()
+            .is_empty());
+        assert!(parse_stream_line(r#"data: {"type":"ping"}"#)
+            .unwrap()
+            .is_empty());
+        assert!(parse_stream_line(r#"data: {"type":"message_stop"}"#)
+            .unwrap()
+            .is_empty());
+
+        let err = parse_stream_line(
+            r#"data: {"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+
+    #[test]
+    fn stream_line_reports_usage_from_message_start_and_delta() {
+        let start = parse_stream_line(
+            r#"data: {"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":25,"output_tokens":1}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            start,
+            vec![StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 25,
+                completion_tokens: 1,
+            })]
+        );
+
+        let delta = parse_stream_line(
+            r#"data: {"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":15}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            delta,
+            vec![StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 15,
+            })]
+        );
+    }
 }
@@ -2,7 +2,12 @@
 //! Most LLM APIs follow the same `/v1/chat/completions` format.
 //! This module provides a single implementation that works for all of them.
 
-use crate::providers::traits::{ChatMessage, Provider};
+use crate::providers::streaming::{response_lines, sse_data};
+use crate::providers::traits::{
+    resolve_images, ChatMessage, ChatResponse, ChatStream, ConversationMessage, ImageContent,
+    Provider, StreamEvent, TokenUsage, ToolCall,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -44,6 +49,43 @@ impl OpenAiCompatibleProvider {
         }
     }
 
+    /// Send a native tool-calling request and return the successful response.
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "{} API key not set. Run `viziclaw onboard` or set the appropriate env var.",
+                self.name
+            )
+        })?;
+
+        let request = NativeChatRequest {
+            model: model.to_string(),
+            messages: build_native_messages(messages),
+            temperature,
+            tools: build_native_tools(tools),
+            stream: stream.then_some(true),
+            stream_options: None,
+        };
+
+        let url = self.chat_completions_url();
+        let response = self
+            .apply_auth_header(self.client.post(&url).json(&request), api_key)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error(&self.name, response).await);
+        }
+        Ok(response)
+    }
+
     /// Build the full URL for chat completions, detecting if base_url already includes the path.
     /// This allows custom providers with non-standard endpoints (e.g., VolcEngine ARK uses
     /// `/api/coding/v3/chat/completions` instead of `/v1/chat/completions`).
@@ -77,7 +119,7 @@ struct ChatRequest {
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,6 +137,348 @@ struct ResponseMessage {
     content: String,
 }
 
+// ── Native function calling (`tools` / `tool_calls`) ───────────
+//
+// Shared with the OpenAI and OpenRouter providers, which speak the same
+// chat completions format.
+
+#[derive(Debug, Serialize)]
+pub(crate) struct NativeChatRequest {
+    pub(crate) model: String,
+    pub(crate) messages: Vec<NativeMessage>,
+    pub(crate) temperature: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) tools: Option<Vec<NativeToolSpec>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) stream_options: Option<NativeStreamOptions>,
+}
+
+/// Asks for a final usage chunk on streamed responses. Only sent to
+/// endpoints known to accept it; some compatible servers reject unknown
+/// request fields.
+#[derive(Debug, Serialize)]
+pub(crate) struct NativeStreamOptions {
+    pub(crate) include_usage: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct NativeMessage {
+    role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_call_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_calls: Option<Vec<NativeToolCall>>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct NativeToolSpec {
+    #[serde(rename = "type")]
+    kind: String,
+    function: NativeFunctionSpec,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeFunctionSpec {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct NativeToolCall {
+    #[serde(default)]
+    id: String,
+    #[serde(rename = "type", default = "default_tool_call_type")]
+    kind: String,
+    function: NativeFunctionCall,
+}
+
+/// Some servers return `arguments` as an object instead of a JSON string,
+/// so it is kept as a raw value and normalized in `into_tool_call`.
+#[derive(Debug, Serialize, Deserialize)]
+struct NativeFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+fn default_tool_call_type() -> String {
+    "function".into()
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NativeChatResponse {
+    choices: Vec<NativeChoice>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+impl From<NativeUsage> for TokenUsage {
+    fn from(usage: NativeUsage) -> Self {
+        TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeChoice {
+    message: NativeResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<NativeToolCall>>,
+}
+
+impl NativeToolCall {
+    fn into_tool_call(self) -> ToolCall {
+        let arguments = match self.function.arguments {
+            serde_json::Value::String(raw) => raw,
+            serde_json::Value::Null => "{}".to_string(),
+            other => other.to_string(),
+        };
+        ToolCall {
+            id: if self.id.is_empty() {
+                format!("call_{}", uuid::Uuid::new_v4().simple())
+            } else {
+                self.id
+            },
+            name: self.function.name,
+            arguments,
+        }
+    }
+}
+
+/// Convert tool specs into the chat completions `tools` array.
+/// Returns `None` for an empty registry so the field is omitted entirely.
+pub(crate) fn build_native_tools(tools: &[ToolSpec]) -> Option<Vec<NativeToolSpec>> {
+    if tools.is_empty() {
+        return None;
+    }
+    Some(
+        tools
+            .iter()
+            .map(|tool| NativeToolSpec {
+                kind: "function".into(),
+                function: NativeFunctionSpec {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    parameters: tool.parameters.clone(),
+                },
+            })
+            .collect(),
+    )
+}
+
+/// Message content in chat completions format: a plain string, or text and
+/// `image_url` parts when the message has images.
+pub(crate) fn message_content(text: &str, images: &[ImageContent]) -> serde_json::Value {
+    if images.is_empty() {
+        return text.into();
+    }
+    let (text, images) = resolve_images(text, images);
+    let mut parts = Vec::with_capacity(images.len() + 1);
+    if !text.is_empty() {
+        parts.push(serde_json::json!({ "type": "text", "text": text }));
+    }
+    for (media_type, data) in images {
+        parts.push(serde_json::json!({
+            "type": "image_url",
+            "image_url": { "url": format!("data:{media_type};base64,{data}") }
+        }));
+    }
+    serde_json::Value::Array(parts)
+}
+
+/// Convert a structured conversation into chat completions messages.
+///
+/// Tool messages can only hold text, so images returned by tools follow the
+/// run of tool results as one user message.
+pub(crate) fn build_native_messages(messages: &[ConversationMessage]) -> Vec<NativeMessage> {
+    let mut out = Vec::with_capacity(messages.len());
+    let mut tool_images: Vec<ImageContent> = Vec::new();
+
+    for message in messages {
+        if !matches!(message, ConversationMessage::ToolResult(_)) && !tool_images.is_empty() {
+            out.push(tool_images_message(&std::mem::take(&mut tool_images)));
+        }
+        out.push(match message {
+            ConversationMessage::Chat(chat) => NativeMessage {
+                role: chat.role.clone(),
+                content: Some(message_content(&chat.content, &chat.images)),
+                tool_call_id: None,
+                tool_calls: None,
+            },
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => NativeMessage {
+                role: "assistant".into(),
+                content: text.clone().filter(|t| !t.is_empty()).map(Into::into),
+                tool_call_id: None,
+                tool_calls: Some(
+                    tool_calls
+                        .iter()
+                        .map(|call| NativeToolCall {
+                            id: call.id.clone(),
+                            kind: default_tool_call_type(),
+                            function: NativeFunctionCall {
+                                name: call.name.clone(),
+                                arguments: serde_json::Value::String(call.arguments.clone()),
+                            },
+                        })
+                        .collect(),
+                ),
+            },
+            ConversationMessage::ToolResult(result) => {
+                tool_images.extend(result.images.iter().cloned());
+                NativeMessage {
+                    role: "tool".into(),
+                    content: Some(result.content.clone().into()),
+                    tool_call_id: Some(result.tool_call_id.clone()),
+                    tool_calls: None,
+                }
+            }
+        });
+    }
+    if !tool_images.is_empty() {
+        out.push(tool_images_message(&tool_images));
+    }
+    out
+}
+
+fn tool_images_message(images: &[ImageContent]) -> NativeMessage {
+    NativeMessage {
+        role: "user".into(),
+        content: Some(message_content(
+            "[Images returned by the tools above]",
+            images,
+        )),
+        tool_call_id: None,
+        tool_calls: None,
+    }
+}
+
+/// Extract text and tool calls from the first choice of a native response.
+pub(crate) fn parse_native_response(response: NativeChatResponse) -> Option<ChatResponse> {
+    let usage = response.usage.map(TokenUsage::from);
+    let message = response.choices.into_iter().next()?.message;
+    Some(ChatResponse {
+        text: message.content.filter(|t| !t.is_empty()),
+        tool_calls: message
+            .tool_calls
+            .unwrap_or_default()
+            .into_iter()
+            .map(NativeToolCall::into_tool_call)
+            .collect(),
+        usage,
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamChunk {
+    #[serde(default)]
+    choices: Vec<NativeStreamChoice>,
+    #[serde(default)]
+    error: Option<serde_json::Value>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamChoice {
+    #[serde(default)]
+    delta: NativeStreamDelta,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NativeStreamDelta {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<NativeStreamToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamToolCall {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<NativeStreamFunction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeStreamFunction {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<serde_json::Value>,
+}
+
+/// Parse one line of a chat completions SSE stream.
+pub(crate) fn parse_stream_line(line: &str) -> anyhow::Result<Vec<StreamEvent>> {
+    let Some(data) = sse_data(line) else {
+        return Ok(Vec::new());
+    };
+    if data == "[DONE]" {
+        return Ok(Vec::new());
+    }
+
+    let chunk: NativeStreamChunk = serde_json::from_str(data)?;
+    if let Some(error) = chunk.error {
+        anyhow::bail!(
+            "Stream error: {}",
+            super::sanitize_api_error(&error.to_string())
+        );
+    }
+
+    let mut events = Vec::new();
+    for choice in chunk.choices {
+        if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+            events.push(StreamEvent::TextDelta(content));
+        }
+        for call in choice.delta.tool_calls {
+            let (name, arguments) = match call.function {
+                Some(f) => (
+                    f.name,
+                    match f.arguments {
+                        Some(serde_json::Value::String(raw)) => raw,
+                        None | Some(serde_json::Value::Null) => String::new(),
+                        Some(other) => other.to_string(),
+                    },
+                ),
+                None => (None, String::new()),
+            };
+            events.push(StreamEvent::ToolCallDelta {
+                index: call.index,
+                id: call.id,
+                name,
+                arguments,
+            });
+        }
+    }
+    if let Some(usage) = chunk.usage {
+        events.push(StreamEvent::Usage(usage.into()));
+    }
+    Ok(events)
+}
+
 #[derive(Debug, Serialize)]
 struct ResponsesRequest {
     model: String,
@@ -239,13 +623,13 @@ impl Provider for OpenAiCompatibleProvider {
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.into(),
             });
         }
 
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.into(),
         });
 
         let request = ChatRequest {
@@ -308,7 +692,7 @@ impl Provider for OpenAiCompatibleProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: m.content.clone(),
+                content: message_content(&m.content, &m.images),
             })
             .collect();
 
@@ -362,6 +746,42 @@ impl Provider for OpenAiCompatibleProvider {
             .map(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))
     }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let native: NativeChatResponse = response.json().await?;
+        parse_native_response(native)
+            .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, parse_stream_line))
+    }
 }
 
 #[cfg(test)]
@@ -412,11 +832,11 @@ mod tests {
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: "You are ViziClaw".to_string(),
+                    content: "You are ViziClaw".into(),
                 },
                 Message {
                     role: "user".to_string(),
-                    content: "hello".to_string(),
+                    content: "hello".into(),
                 },
             ],
             temperature: 0.7,
@@ -441,6 +861,177 @@ mod tests {
         assert!(resp.choices.is_empty());
     }
 
+    #[test]
+    fn native_request_includes_tools_and_tool_messages() {
+        use crate::providers::traits::ToolResultMessage;
+
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::user("list files")),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "Cargo.toml".into(),
+                images: Vec::new(),
+            }),
+        ];
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+
+        let request = NativeChatRequest {
+            model: "gpt-4o".into(),
+            messages: build_native_messages(&messages),
+            temperature: 0.0,
+            tools: build_native_tools(&tools),
+            stream: None,
+            stream_options: None,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["tools"][0]["type"], "function");
+        assert_eq!(json["tools"][0]["function"]["name"], "shell");
+        assert!(json["messages"][1].get("content").is_none());
+        assert_eq!(json["messages"][1]["tool_calls"][0]["id"], "call_1");
+        assert_eq!(
+            json["messages"][1]["tool_calls"][0]["function"]["arguments"],
+            r#"{"command":"ls"}"#
+        );
+        assert_eq!(json["messages"][2]["role"], "tool");
+        assert_eq!(json["messages"][2]["tool_call_id"], "call_1");
+    }
+
+    #[test]
+    fn images_become_content_parts_and_follow_tool_results() {
+        use crate::providers::traits::{ImageContent, ToolResultMessage};
+
+        let png = ImageContent::base64("image/png", "aGk=");
+        let messages = vec![
+            ConversationMessage::Chat(
+                ChatMessage::user("what is this?").with_images([png.clone()]),
+            ),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "screenshot".into(),
+                    arguments: "{}".into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "Screenshot saved".into(),
+                images: vec![png],
+            }),
+        ];
+
+        let json = serde_json::to_value(build_native_messages(&messages)).unwrap();
+        assert_eq!(json[0]["content"][0]["type"], "text");
+        assert_eq!(json[0]["content"][1]["type"], "image_url");
+        assert_eq!(
+            json[0]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,aGk="
+        );
+        assert_eq!(json[2]["role"], "tool");
+        assert_eq!(json[2]["content"], "Screenshot saved");
+        assert_eq!(json[3]["role"], "user");
+        assert_eq!(json[3]["content"][1]["type"], "image_url");
+    }
+
+    #[test]
+    fn text_only_messages_keep_plain_string_content() {
+        assert_eq!(message_content("hi", &[]), serde_json::json!("hi"));
+    }
+
+    #[test]
+    fn native_request_omits_empty_tools() {
+        let request = NativeChatRequest {
+            model: "gpt-4o".into(),
+            messages: vec![],
+            temperature: 0.0,
+            tools: build_native_tools(&[]),
+            stream: None,
+            stream_options: None,
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(!json.contains("tools"));
+    }
+
+    #[test]
+    fn native_response_parses_tool_calls() {
+        let json = r#"{"choices":[{"message":{"content":null,"tool_calls":[
+            {"id":"call_9","type":"function","function":{"name":"shell","arguments":"{\"command\":\"pwd\"}"}}
+        ]}}]}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response).unwrap();
+
+        assert!(parsed.text.is_none());
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].id, "call_9");
+        assert_eq!(parsed.tool_calls[0].name, "shell");
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"command":"pwd"}"#);
+    }
+
+    #[test]
+    fn native_response_normalizes_object_arguments_and_missing_ids() {
+        let json = r#"{"choices":[{"message":{"content":"ok","tool_calls":[
+            {"function":{"name":"file_read","arguments":{"path":"a.txt"}}}
+        ]}}]}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response).unwrap();
+
+        assert_eq!(parsed.text.as_deref(), Some("ok"));
+        assert!(parsed.tool_calls[0].id.starts_with("call_"));
+        let args: serde_json::Value =
+            serde_json::from_str(&parsed.tool_calls[0].arguments).unwrap();
+        assert_eq!(args["path"], "a.txt");
+    }
+
+    #[test]
+    fn native_response_reports_usage() {
+        let json = r#"{"choices":[{"message":{"content":"Hi"}}],
+            "usage":{"prompt_tokens":12,"completion_tokens":3,"total_tokens":15}}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let usage = parse_native_response(response).unwrap().usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 12);
+        assert_eq!(usage.completion_tokens, 3);
+    }
+
+    #[test]
+    fn native_response_without_tool_calls_is_text_only() {
+        let json = r#"{"choices":[{"message":{"content":"Hello"}}]}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response).unwrap();
+        assert_eq!(parsed.text_or_empty(), "Hello");
+        assert!(!parsed.has_tool_calls());
+    }
+
+    #[tokio::test]
+    async fn chat_with_tools_fails_without_key() {
+        let p = make_provider("Venice", "https://api.venice.ai", None);
+        assert!(p.supports_native_tools());
+        let result = p
+            .chat_with_tools(
+                &[ConversationMessage::Chat(ChatMessage::user("hi"))],
+                &[],
+                "model",
+                0.7,
+            )
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Venice API key not set"));
+    }
+
     #[test]
     fn x_api_key_auth_style() {
         let p = OpenAiCompatibleProvider::new(
@@ -643,4 +1234,79 @@ mod tests {
             "https://opencode.ai/zen/v1/chat/completions"
         );
     }
+
+    #[test]
+    fn stream_line_parses_text_and_tool_call_deltas() {
+        let text = parse_stream_line(r#"data: {"choices":[{"delta":{"content":"Hi"}}]}"#).unwrap();
+        assert_eq!(text, vec![StreamEvent::TextDelta("Hi".into())]);
+
+        let start = parse_stream_line(
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"shell","arguments":""}}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            start,
+            vec![StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("call_1".into()),
+                name: Some("shell".into()),
+                arguments: String::new(),
+            }]
+        );
+
+        let more = parse_stream_line(
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"command\":"}}]}}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            more,
+            vec![StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments: r#"{"command":"#.into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn stream_line_skips_done_and_surfaces_errors() {
+        assert!(parse_stream_line("data: [DONE]").unwrap().is_empty());
+        assert!(parse_stream_line(": OPENROUTER PROCESSING")
+            .unwrap()
+            .is_empty());
+        let err = parse_stream_line(r#"data: {"error":{"message":"overloaded"}}"#).unwrap_err();
+        assert!(err.to_string().contains("overloaded"));
+    }
+
+    #[test]
+    fn stream_line_reports_final_usage_chunk() {
+        let events = parse_stream_line(
+            r#"data: {"choices":[],"usage":{"prompt_tokens":40,"completion_tokens":7}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            events,
+            vec![StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 40,
+                completion_tokens: 7,
+            })]
+        );
+    }
+
+    #[test]
+    fn stream_options_serialized_only_when_requested() {
+        let request = NativeChatRequest {
+            model: "gpt-4o".into(),
+            messages: vec![],
+            temperature: 0.0,
+            tools: None,
+            stream: Some(true),
+            stream_options: Some(NativeStreamOptions {
+                include_usage: true,
+            }),
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["stream_options"]["include_usage"], true);
+    }
 }
@@ -3,7 +3,12 @@
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
-use crate::providers::traits::Provider;
+use crate::providers::streaming::{response_lines, sse_data};
+use crate::providers::traits::{
+    resolve_images, ChatResponse, ChatStream, ConversationMessage, Provider, StreamEvent,
+    TokenUsage, ToolCall,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use directories::UserDirs;
 use reqwest::Client;
@@ -86,21 +91,44 @@ struct GenerationConfig {
 struct GenerateContentResponse {
     candidates: Option<Vec<Candidate>>,
     error: Option<ApiError>,
+    #[serde(rename = "usageMetadata", default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageMetadata {
+    #[serde(rename = "promptTokenCount", default)]
+    prompt_token_count: u64,
+    #[serde(rename = "candidatesTokenCount", default)]
+    candidates_token_count: u64,
+}
+
+impl From<UsageMetadata> for TokenUsage {
+    fn from(usage: UsageMetadata) -> Self {
+        TokenUsage {
+            prompt_tokens: usage.prompt_token_count,
+            completion_tokens: usage.candidates_token_count,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct Candidate {
+    #[serde(default)]
     content: CandidateContent,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct CandidateContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ResponsePart {
     text: Option<String>,
+    #[serde(rename = "functionCall", default)]
+    function_call: Option<FunctionCall>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -108,6 +136,283 @@ struct ApiError {
     message: String,
 }
 
+// ── Native function calling (`functionDeclarations`) ────────────
+
+#[derive(Debug, Serialize)]
+struct NativeGenerateContentRequest {
+    contents: Vec<NativeContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<NativeTool>,
+    #[serde(rename = "generationConfig")]
+    generation_config: GenerationConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeContent {
+    role: String,
+    parts: Vec<NativePart>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NativePart {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_call: Option<FunctionCall>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function_response: Option<FunctionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inline_data: Option<InlineData>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InlineData {
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeTool {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<FunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionDeclaration {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+/// JSON Schema keywords the Gemini API rejects in function declarations.
+const UNSUPPORTED_SCHEMA_KEYS: [&str; 4] =
+    ["$schema", "additionalProperties", "default", "examples"];
+
+/// Strip schema keywords Gemini does not accept, recursively.
+fn sanitize_schema(schema: &serde_json::Value) -> serde_json::Value {
+    match schema {
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .filter(|(key, _)| !UNSUPPORTED_SCHEMA_KEYS.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), sanitize_schema(value)))
+                .collect(),
+        ),
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.iter().map(sanitize_schema).collect())
+        }
+        other => other.clone(),
+    }
+}
+
+fn build_native_tools(tools: &[ToolSpec]) -> Vec<NativeTool> {
+    if tools.is_empty() {
+        return Vec::new();
+    }
+    vec![NativeTool {
+        function_declarations: tools
+            .iter()
+            .map(|tool| FunctionDeclaration {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: sanitize_schema(&tool.parameters),
+            })
+            .collect(),
+    }]
+}
+
+fn image_parts(images: Vec<(String, String)>) -> impl Iterator<Item = NativePart> {
+    images.into_iter().map(|(mime_type, data)| NativePart {
+        inline_data: Some(InlineData { mime_type, data }),
+        ..NativePart::default()
+    })
+}
+
+/// Convert a structured conversation into Gemini `contents` plus a system
+/// instruction. Gemini has no tool-call ids, so each `functionResponse` is
+/// named after the call it answers; consecutive same-role turns are merged.
+fn build_native_contents(
+    messages: &[ConversationMessage],
+) -> (Option<Content>, Vec<NativeContent>) {
+    let mut system_parts: Vec<&str> = Vec::new();
+    let mut call_names: Vec<(&str, &str)> = Vec::new();
+    let mut contents: Vec<NativeContent> = Vec::new();
+
+    for message in messages {
+        let (role, parts) = match message {
+            ConversationMessage::Chat(chat) if chat.role == "system" => {
+                system_parts.push(&chat.content);
+                continue;
+            }
+            ConversationMessage::Chat(chat) => {
+                let role = if chat.role == "assistant" {
+                    "model"
+                } else {
+                    "user"
+                };
+                let (text, images) = resolve_images(&chat.content, &chat.images);
+                let mut parts = vec![NativePart {
+                    text: Some(text),
+                    ..NativePart::default()
+                }];
+                parts.extend(image_parts(images));
+                (role, parts)
+            }
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+                let mut parts = Vec::with_capacity(tool_calls.len() + 1);
+                if let Some(text) = text.as_ref().filter(|t| !t.is_empty()) {
+                    parts.push(NativePart {
+                        text: Some(text.clone()),
+                        ..NativePart::default()
+                    });
+                }
+                for call in tool_calls {
+                    call_names.push((call.id.as_str(), call.name.as_str()));
+                    parts.push(NativePart {
+                        function_call: Some(FunctionCall {
+                            name: call.name.clone(),
+                            args: serde_json::from_str(&call.arguments)
+                                .unwrap_or_else(|_| serde_json::json!({})),
+                        }),
+                        ..NativePart::default()
+                    });
+                }
+                ("model", parts)
+            }
+            ConversationMessage::ToolResult(result) => {
+                let name = call_names
+                    .iter()
+                    .rfind(|(id, _)| *id == result.tool_call_id)
+                    .map_or("unknown", |(_, name)| *name);
+                // Images ride along as parts of the same user turn
+                let (text, images) = resolve_images(&result.content, &result.images);
+                let mut parts = vec![NativePart {
+                    function_response: Some(FunctionResponse {
+                        name: name.to_string(),
+                        response: serde_json::json!({ "content": text }),
+                    }),
+                    ..NativePart::default()
+                }];
+                parts.extend(image_parts(images));
+                ("user", parts)
+            }
+        };
+
+        match contents.last_mut() {
+            Some(last) if last.role == role => last.parts.extend(parts),
+            _ => contents.push(NativeContent {
+                role: role.to_string(),
+                parts,
+            }),
+        }
+    }
+
+    let system_instruction = if system_parts.is_empty() {
+        None
+    } else {
+        Some(Content {
+            role: None,
+            parts: vec![Part {
+                text: system_parts.join("\n\n"),
+            }],
+        })
+    };
+    (system_instruction, contents)
+}
+
+/// Build a parser for `streamGenerateContent?alt=sse` lines. Gemini sends
+/// each function call whole, so calls are numbered as they arrive. Every
+/// chunk repeats the running `usageMetadata` totals.
+fn stream_line_parser() -> impl FnMut(&str) -> anyhow::Result<Vec<StreamEvent>> + Send {
+    let mut next_index = 0;
+    move |line| {
+        let Some(data) = sse_data(line) else {
+            return Ok(Vec::new());
+        };
+        let chunk: GenerateContentResponse = serde_json::from_str(data)?;
+        if let Some(err) = chunk.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+
+        let mut events = Vec::new();
+        let usage = chunk.usage_metadata.map(TokenUsage::from);
+        let parts = chunk
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .map(|c| c.content.parts)
+            .unwrap_or_default();
+        for part in parts {
+            if let Some(text) = part.text.filter(|t| !t.is_empty()) {
+                events.push(StreamEvent::TextDelta(text));
+            }
+            if let Some(call) = part.function_call {
+                events.push(StreamEvent::ToolCallDelta {
+                    index: next_index,
+                    id: None,
+                    name: Some(call.name),
+                    arguments: if call.args.is_null() {
+                        String::new()
+                    } else {
+                        call.args.to_string()
+                    },
+                });
+                next_index += 1;
+            }
+        }
+        if let Some(usage) = usage {
+            events.push(StreamEvent::Usage(usage));
+        }
+        Ok(events)
+    }
+}
+
+/// Extract text and function calls from the first candidate.
+fn parse_native_response(response: GenerateContentResponse) -> Option<ChatResponse> {
+    let usage = response.usage_metadata.map(TokenUsage::from);
+    let candidate = response.candidates?.into_iter().next()?;
+    let mut text = String::new();
+    let mut tool_calls = Vec::new();
+
+    for part in candidate.content.parts {
+        if let Some(t) = part.text {
+            text.push_str(&t);
+        }
+        if let Some(call) = part.function_call {
+            tool_calls.push(ToolCall {
+                id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+                name: call.name,
+                arguments: if call.args.is_null() {
+                    "{}".to_string()
+                } else {
+                    call.args.to_string()
+                },
+            });
+        }
+    }
+
+    Some(ChatResponse {
+        text: if text.is_empty() { None } else { Some(text) },
+        tool_calls,
+        usage,
+    })
+}
+
 // ══════════════════════════════════════════════════════════════════════════════
 // GEMINI CLI TOKEN STRUCTURES
 // ══════════════════════════════════════════════════════════════════════════════
@@ -238,11 +543,65 @@ impl GeminiProvider {
         }
     }
 
-    fn build_generate_content_request(
+    fn build_stream_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+        let model_name = Self::format_model_name(model);
+        let base_url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/{model_name}:streamGenerateContent?alt=sse"
+        );
+
+        if auth.is_api_key() {
+            format!("{base_url}&key={}", auth.credential())
+        } else {
+            base_url
+        }
+    }
+
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let auth = self.auth.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Gemini API key not found. Set GEMINI_API_KEY or run `viziclaw onboard` to configure."
+            )
+        })?;
+
+        let (system_instruction, contents) = build_native_contents(messages);
+        let request = NativeGenerateContentRequest {
+            contents,
+            system_instruction,
+            tools: build_native_tools(tools),
+            generation_config: GenerationConfig {
+                temperature,
+                max_output_tokens: 8192,
+            },
+        };
+
+        let url = if stream {
+            Self::build_stream_generate_content_url(model, auth)
+        } else {
+            Self::build_generate_content_url(model, auth)
+        };
+        let response = self
+            .build_generate_content_request(auth, &url, &request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("Gemini", response).await);
+        }
+        Ok(response)
+    }
+
+    fn build_generate_content_request<T: Serialize + ?Sized>(
         &self,
         auth: &GeminiAuth,
         url: &str,
-        request: &GenerateContentRequest,
+        request: &T,
     ) -> reqwest::RequestBuilder {
         let req = self.client.post(url).json(request);
         match auth {
@@ -254,6 +613,45 @@ impl GeminiProvider {
 
 #[async_trait]
 impl Provider for GeminiProvider {
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let result: GenerateContentResponse = response.json().await?;
+        if let Some(err) = result.error {
+            anyhow::bail!("Gemini API error: {}", err.message);
+        }
+
+        parse_native_response(result).ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, stream_line_parser()))
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -424,6 +822,52 @@ mod tests {
         assert!(!url.contains("?key="));
     }
 
+    #[test]
+    fn stream_url_requests_sse_and_appends_key() {
+        let auth = GeminiAuth::ExplicitKey("api-key-123".into());
+        let url = GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &auth);
+        assert!(url.ends_with(":streamGenerateContent?alt=sse&key=api-key-123"));
+
+        let auth = GeminiAuth::OAuthToken("ya29.test-token".into());
+        let url = GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &auth);
+        assert!(url.ends_with(":streamGenerateContent?alt=sse"));
+    }
+
+    #[test]
+    fn stream_parser_numbers_function_calls() {
+        let mut parse = stream_line_parser();
+        let text =
+            parse(r#"data: {"candidates":[{"content":{"parts":[{"text":"Hi"}]}}]}"#).unwrap();
+        assert_eq!(text, vec![StreamEvent::TextDelta("Hi".into())]);
+
+        let calls = parse(
+            r#"data: {"candidates":[{"content":{"parts":[{"functionCall":{"name":"a","args":{}}},{"functionCall":{"name":"b","args":{"x":1}}}]}}]}"#,
+        )
+        .unwrap();
+        assert!(
+            matches!(&calls[0], StreamEvent::ToolCallDelta { index: 0, name: Some(n), .. } if n == "a")
+        );
+        assert!(
+            matches!(&calls[1], StreamEvent::ToolCallDelta { index: 1, arguments, .. } if arguments == r#"{"x":1}"#)
+        );
+
+        assert!(parse(r#"data: {"candidates":[{"finishReason":"STOP"}]}"#)
+            .unwrap()
+            .is_empty());
+
+        let usage = parse(
+            r#"data: {"candidates":[{"finishReason":"STOP"}],"usageMetadata":{"promptTokenCount":8,"candidatesTokenCount":4}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            usage,
+            vec![StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 8,
+                completion_tokens: 4,
+            })]
+        );
+    }
+
     #[test]
     fn oauth_request_uses_bearer_auth_header() {
         let provider = GeminiProvider {
@@ -545,6 +989,107 @@ mod tests {
         assert_eq!(text, Some("Hello there!".to_string()));
     }
 
+    #[test]
+    fn native_contents_name_function_responses_after_their_calls() {
+        use crate::providers::traits::{ChatMessage, ToolResultMessage};
+
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::system("Be brief")),
+            ConversationMessage::Chat(ChatMessage::user("what's here?")),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "Cargo.toml".into(),
+                images: Vec::new(),
+            }),
+        ];
+
+        let (system, contents) = build_native_contents(&messages);
+        assert_eq!(system.unwrap().parts[0].text, "Be brief");
+
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[1]["role"], "model");
+        assert_eq!(json[1]["parts"][0]["functionCall"]["name"], "shell");
+        assert_eq!(json[1]["parts"][0]["functionCall"]["args"]["command"], "ls");
+        assert_eq!(json[2]["role"], "user");
+        assert_eq!(json[2]["parts"][0]["functionResponse"]["name"], "shell");
+        assert_eq!(
+            json[2]["parts"][0]["functionResponse"]["response"]["content"],
+            "Cargo.toml"
+        );
+    }
+
+    #[test]
+    fn native_contents_send_images_as_inline_data() {
+        use crate::providers::traits::{ChatMessage, ImageContent};
+
+        let messages = vec![ConversationMessage::Chat(
+            ChatMessage::user("describe").with_images([ImageContent::base64("image/jpeg", "aGk=")]),
+        )];
+
+        let (_, contents) = build_native_contents(&messages);
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json[0]["parts"][0]["text"], "describe");
+        assert_eq!(json[0]["parts"][1]["inlineData"]["mimeType"], "image/jpeg");
+        assert_eq!(json[0]["parts"][1]["inlineData"]["data"], "aGk=");
+        assert!(json[0]["parts"][1].get("text").is_none());
+    }
+
+    #[test]
+    fn native_tools_strip_unsupported_schema_keys() {
+        let tools = vec![ToolSpec {
+            name: "shell".into(),
+            description: "Run commands".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "approved": {"type": "boolean", "default": false}
+                }
+            }),
+        }];
+
+        let json = serde_json::to_value(build_native_tools(&tools)).unwrap();
+        let params = &json[0]["functionDeclarations"][0]["parameters"];
+        assert!(params.get("additionalProperties").is_none());
+        assert!(params["properties"]["approved"].get("default").is_none());
+        assert_eq!(params["properties"]["approved"]["type"], "boolean");
+    }
+
+    #[test]
+    fn native_response_parses_function_calls() {
+        let json = r#"{
+            "candidates": [{
+                "content": {
+                    "parts": [
+                        {"text": "Checking."},
+                        {"functionCall": {"name": "file_read", "args": {"path": "a.txt"}}}
+                    ]
+                }
+            }],
+            "usageMetadata": {"promptTokenCount": 52, "candidatesTokenCount": 9, "totalTokenCount": 61}
+        }"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response).unwrap();
+
+        assert_eq!(parsed.text.as_deref(), Some("Checking."));
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].name, "file_read");
+        assert!(parsed.tool_calls[0].id.starts_with("call_"));
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"path":"a.txt"}"#);
+        let usage = parsed.usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 52);
+        assert_eq!(usage.completion_tokens, 9);
+    }
+
     #[test]
     fn error_response_deserialization() {
         let json = r#"{
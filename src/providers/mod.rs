@@ -6,9 +6,13 @@ pub mod openai;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+mod streaming;
 pub mod traits;
 
-pub use traits::{ChatMessage, Provider};
+pub use traits::{
+    collect_stream, ChatMessage, ConversationMessage, ImageContent, Provider, ToolCall,
+    ToolResultMessage,
+};
 
 use compatible::{AuthStyle, OpenAiCompatibleProvider};
 use reliable::ReliableProvider;
@@ -1,4 +1,10 @@
-use crate::providers::traits::Provider;
+use crate::providers::compatible::{build_native_tools, NativeToolSpec};
+use crate::providers::streaming::response_lines;
+use crate::providers::traits::{
+    resolve_images, ChatResponse as ProviderChatResponse, ChatStream, ConversationMessage,
+    ImageContent, Provider, StreamEvent, TokenUsage, ToolCall,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -37,6 +43,217 @@ struct ResponseMessage {
     content: String,
 }
 
+// ── Native tool calling ─────────────────────────────────────────
+//
+// Ollama accepts the chat completions `tools` array but returns (and
+// expects back) tool call arguments as JSON objects, without call ids.
+
+#[derive(Debug, Serialize)]
+struct NativeChatRequest {
+    model: String,
+    messages: Vec<NativeMessage>,
+    stream: bool,
+    options: Options,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<NativeToolSpec>>,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeMessage {
+    role: String,
+    content: String,
+    /// Base64-encoded images, for multimodal models
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tool_calls: Vec<NativeToolCall>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NativeToolCall {
+    function: NativeFunctionCall,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NativeFunctionCall {
+    name: String,
+    #[serde(default)]
+    arguments: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeChatResponse {
+    message: NativeResponseMessage,
+    #[serde(flatten)]
+    usage: NativeUsage,
+}
+
+/// Token counts Ollama reports on the final (`done: true`) response.
+#[derive(Debug, Default, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
+impl NativeUsage {
+    fn into_token_usage(self) -> Option<TokenUsage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        Some(TokenUsage {
+            prompt_tokens: self.prompt_eval_count.unwrap_or(0),
+            completion_tokens: self.eval_count.unwrap_or(0),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeResponseMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    tool_calls: Vec<NativeToolCall>,
+}
+
+/// One line of a streamed `/api/chat` response (newline-delimited JSON).
+#[derive(Debug, Deserialize)]
+struct NativeStreamChunk {
+    #[serde(default)]
+    message: Option<NativeResponseMessage>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(flatten)]
+    usage: NativeUsage,
+}
+
+/// Build a parser for streamed `/api/chat` lines. Ollama sends each tool
+/// call whole, so calls are numbered as they arrive. Token counts arrive on
+/// the final `done` line.
+fn stream_line_parser() -> impl FnMut(&str) -> anyhow::Result<Vec<StreamEvent>> + Send {
+    let mut next_index = 0;
+    move |line| {
+        let chunk: NativeStreamChunk = serde_json::from_str(line)?;
+        if let Some(error) = chunk.error {
+            anyhow::bail!("Ollama stream error: {error}");
+        }
+
+        let mut events = Vec::new();
+        if let Some(message) = chunk.message {
+            if !message.content.is_empty() {
+                events.push(StreamEvent::TextDelta(message.content));
+            }
+            for call in message.tool_calls {
+                events.push(StreamEvent::ToolCallDelta {
+                    index: next_index,
+                    id: None,
+                    name: Some(call.function.name),
+                    arguments: match call.function.arguments {
+                        serde_json::Value::String(raw) => raw,
+                        serde_json::Value::Null => String::new(),
+                        other => other.to_string(),
+                    },
+                });
+                next_index += 1;
+            }
+        }
+        if let Some(usage) = chunk.usage.into_token_usage() {
+            events.push(StreamEvent::Usage(usage));
+        }
+        Ok(events)
+    }
+}
+
+/// Convert a structured conversation into Ollama chat messages. Images
+/// returned by tools follow the run of tool results as one user message.
+fn build_native_messages(messages: &[ConversationMessage]) -> Vec<NativeMessage> {
+    let mut out = Vec::with_capacity(messages.len());
+    let mut tool_images: Vec<ImageContent> = Vec::new();
+
+    for message in messages {
+        if !matches!(message, ConversationMessage::ToolResult(_)) && !tool_images.is_empty() {
+            out.push(user_message(
+                "[Images returned by the tools above]",
+                &std::mem::take(&mut tool_images),
+            ));
+        }
+        out.push(match message {
+            ConversationMessage::Chat(chat) => NativeMessage {
+                role: chat.role.clone(),
+                ..user_message(&chat.content, &chat.images)
+            },
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => NativeMessage {
+                role: "assistant".into(),
+                content: text.clone().unwrap_or_default(),
+                images: Vec::new(),
+                tool_calls: tool_calls
+                    .iter()
+                    .map(|call| NativeToolCall {
+                        function: NativeFunctionCall {
+                            name: call.name.clone(),
+                            arguments: serde_json::from_str(&call.arguments)
+                                .unwrap_or_else(|_| serde_json::json!({})),
+                        },
+                    })
+                    .collect(),
+            },
+            ConversationMessage::ToolResult(result) => {
+                tool_images.extend(result.images.iter().cloned());
+                NativeMessage {
+                    role: "tool".into(),
+                    content: result.content.clone(),
+                    images: Vec::new(),
+                    tool_calls: Vec::new(),
+                }
+            }
+        });
+    }
+    if !tool_images.is_empty() {
+        out.push(user_message(
+            "[Images returned by the tools above]",
+            &tool_images,
+        ));
+    }
+    out
+}
+
+fn user_message(text: &str, images: &[ImageContent]) -> NativeMessage {
+    let (content, images) = resolve_images(text, images);
+    NativeMessage {
+        role: "user".into(),
+        content,
+        images: images.into_iter().map(|(_, data)| data).collect(),
+        tool_calls: Vec::new(),
+    }
+}
+
+fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
+    let usage = response.usage.into_token_usage();
+    let message = response.message;
+    ProviderChatResponse {
+        text: if message.content.is_empty() {
+            None
+        } else {
+            Some(message.content)
+        },
+        tool_calls: message
+            .tool_calls
+            .into_iter()
+            .map(|call| ToolCall {
+                id: format!("call_{}", uuid::Uuid::new_v4().simple()),
+                name: call.function.name,
+                arguments: match call.function.arguments {
+                    serde_json::Value::String(raw) => raw,
+                    serde_json::Value::Null => "{}".to_string(),
+                    other => other.to_string(),
+                },
+            })
+            .collect(),
+        usage,
+    }
+}
+
 impl OllamaProvider {
     pub fn new(base_url: Option<&str>) -> Self {
         Self {
@@ -51,10 +268,72 @@ impl OllamaProvider {
                 .unwrap_or_else(|_| Client::new()),
         }
     }
+
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let request = NativeChatRequest {
+            model: model.to_string(),
+            messages: build_native_messages(messages),
+            stream,
+            options: Options { temperature },
+            tools: build_native_tools(tools),
+        };
+
+        let url = format!("{}/api/chat", self.base_url);
+
+        let response = self.client.post(&url).json(&request).send().await?;
+
+        if !response.status().is_success() {
+            let err = super::api_error("Ollama", response).await;
+            anyhow::bail!("{err}. Is Ollama running? (brew install ollama && ollama serve)");
+        }
+        Ok(response)
+    }
 }
 
 #[async_trait]
 impl Provider for OllamaProvider {
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let chat_response: NativeChatResponse = response.json().await?;
+        Ok(parse_native_response(chat_response))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, stream_line_parser()))
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -185,4 +464,128 @@ mod tests {
         let resp: ChatResponse = serde_json::from_str(json).unwrap();
         assert!(resp.message.content.contains("line1"));
     }
+
+    #[test]
+    fn native_messages_send_object_arguments_and_tool_role() {
+        use crate::providers::traits::{ChatMessage, ToolResultMessage};
+
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::user("list files")),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "shell".into(),
+                    arguments: r#"{"command":"ls"}"#.into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "README.md".into(),
+                images: Vec::new(),
+            }),
+        ];
+
+        let json = serde_json::to_value(build_native_messages(&messages)).unwrap();
+        assert!(json[0].get("tool_calls").is_none());
+        assert_eq!(
+            json[1]["tool_calls"][0]["function"]["arguments"]["command"],
+            "ls"
+        );
+        assert_eq!(json[2]["role"], "tool");
+        assert_eq!(json[2]["content"], "README.md");
+    }
+
+    #[test]
+    fn native_messages_attach_images_as_base64_list() {
+        use crate::providers::traits::{ChatMessage, ImageContent, ToolResultMessage};
+
+        let png = ImageContent::base64("image/png", "aGk=");
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::user("look").with_images([png.clone()])),
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "call_1".into(),
+                    name: "screenshot".into(),
+                    arguments: "{}".into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "call_1".into(),
+                content: "saved".into(),
+                images: vec![png],
+            }),
+        ];
+
+        let json = serde_json::to_value(build_native_messages(&messages)).unwrap();
+        assert_eq!(json[0]["images"][0], "aGk=");
+        assert!(json[1].get("images").is_none());
+        assert_eq!(json[2]["role"], "tool");
+        assert_eq!(json[3]["role"], "user");
+        assert_eq!(json[3]["images"][0], "aGk=");
+    }
+
+    #[test]
+    fn native_response_parses_tool_calls() {
+        let json = r#"{"message":{"role":"assistant","content":"","tool_calls":[
+            {"function":{"name":"file_read","arguments":{"path":"a.txt"}}}
+        ]}}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = parse_native_response(response);
+
+        assert!(parsed.text.is_none());
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].name, "file_read");
+        assert_eq!(parsed.tool_calls[0].arguments, r#"{"path":"a.txt"}"#);
+        assert!(parsed.tool_calls[0].id.starts_with("call_"));
+        assert!(parsed.usage.is_none());
+    }
+
+    #[test]
+    fn native_response_reports_eval_counts() {
+        let json = r#"{"message":{"role":"assistant","content":"Hi"},"done":true,
+            "prompt_eval_count":26,"eval_count":5}"#;
+        let response: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let usage = parse_native_response(response).usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 26);
+        assert_eq!(usage.completion_tokens, 5);
+    }
+
+    #[test]
+    fn stream_parser_handles_ndjson_chunks() {
+        let mut parse = stream_line_parser();
+        let text =
+            parse(r#"{"message":{"role":"assistant","content":"Hel"},"done":false}"#).unwrap();
+        assert_eq!(text, vec![StreamEvent::TextDelta("Hel".into())]);
+
+        let call = parse(
+            r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"shell","arguments":{"command":"ls"}}}]},"done":false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            call,
+            vec![StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: Some("shell".into()),
+                arguments: r#"{"command":"ls"}"#.into(),
+            }]
+        );
+
+        assert!(
+            parse(r#"{"message":{"role":"assistant","content":""},"done":true}"#)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            parse(r#"{"message":{"role":"assistant","content":""},"done":true,"prompt_eval_count":10,"eval_count":3}"#)
+                .unwrap(),
+            vec![StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 10,
+                completion_tokens: 3,
+            })]
+        );
+        assert!(parse(r#"{"error":"model not found"}"#).is_err());
+    }
 }
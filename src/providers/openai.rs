@@ -1,4 +1,12 @@
-use crate::providers::traits::Provider;
+use crate::providers::compatible::{
+    build_native_messages, build_native_tools, parse_native_response, parse_stream_line,
+    NativeChatRequest, NativeChatResponse, NativeStreamOptions,
+};
+use crate::providers::streaming::response_lines;
+use crate::providers::traits::{
+    ChatResponse as ProviderChatResponse, ChatStream, ConversationMessage, Provider,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -47,6 +55,43 @@ impl OpenAiProvider {
                 .unwrap_or_else(|_| Client::new()),
         }
     }
+
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let api_key = self.api_key.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.")
+        })?;
+
+        let request = NativeChatRequest {
+            model: model.to_string(),
+            messages: build_native_messages(messages),
+            temperature,
+            tools: build_native_tools(tools),
+            stream: stream.then_some(true),
+            stream_options: stream.then_some(NativeStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        let response = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {api_key}"))
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenAI", response).await);
+        }
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -103,6 +148,41 @@ impl Provider for OpenAiProvider {
             .map(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
     }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let native: NativeChatResponse = response.json().await?;
+        parse_native_response(native).ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, parse_stream_line))
+    }
 }
 
 #[cfg(test)]
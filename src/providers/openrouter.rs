@@ -1,4 +1,12 @@
-use crate::providers::traits::{ChatMessage, Provider};
+use crate::providers::compatible::{
+    build_native_messages, build_native_tools, message_content, parse_native_response,
+    parse_stream_line, NativeChatRequest, NativeChatResponse, NativeStreamOptions,
+};
+use crate::providers::streaming::response_lines;
+use crate::providers::traits::{
+    ChatMessage, ChatResponse, ChatStream, ConversationMessage, Provider,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -18,7 +26,7 @@ struct ChatRequest {
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: serde_json::Value,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,6 +55,47 @@ impl OpenRouterProvider {
                 .unwrap_or_else(|_| Client::new()),
         }
     }
+
+    async fn send_native(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+        stream: bool,
+    ) -> anyhow::Result<reqwest::Response> {
+        let api_key = self.api_key.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("OpenRouter API key not set. Run `viziclaw onboard` or set OPENROUTER_API_KEY env var."))?;
+
+        let request = NativeChatRequest {
+            model: model.to_string(),
+            messages: build_native_messages(messages),
+            temperature,
+            tools: build_native_tools(tools),
+            stream: stream.then_some(true),
+            stream_options: stream.then_some(NativeStreamOptions {
+                include_usage: true,
+            }),
+        };
+
+        let response = self
+            .client
+            .post("https://openrouter.ai/api/v1/chat/completions")
+            .header("Authorization", format!("Bearer {api_key}"))
+            .header(
+                "HTTP-Referer",
+                "https://github.com/dew2105/ViziClaw",
+            )
+            .header("X-Title", "ViziClaw")
+            .json(&request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(super::api_error("OpenRouter", response).await);
+        }
+        Ok(response)
+    }
 }
 
 #[async_trait]
@@ -80,13 +129,13 @@ impl Provider for OpenRouterProvider {
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.into(),
             });
         }
 
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.into(),
         });
 
         let request = ChatRequest {
@@ -135,7 +184,7 @@ impl Provider for OpenRouterProvider {
             .iter()
             .map(|m| Message {
                 role: m.role.clone(),
-                content: m.content.clone(),
+                content: message_content(&m.content, &m.images),
             })
             .collect();
 
@@ -171,4 +220,39 @@ impl Provider for OpenRouterProvider {
             .map(|c| c.message.content)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))
     }
+
+    fn supports_native_tools(&self) -> bool {
+        true
+    }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let response = self
+            .send_native(messages, tools, model, temperature, false)
+            .await?;
+        let native: NativeChatResponse = response.json().await?;
+        parse_native_response(native).ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .send_native(messages, tools, model, temperature, true)
+            .await?;
+        Ok(response_lines(response, parse_stream_line))
+    }
 }
@@ -1,5 +1,6 @@
-use super::traits::ChatMessage;
+use super::traits::{ChatMessage, ChatResponse, ChatStream, ConversationMessage};
 use super::Provider;
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use std::time::Duration;
 
@@ -50,6 +51,20 @@ impl ReliableProvider {
 
 #[async_trait]
 impl Provider for ReliableProvider {
+    /// Native tools are only advertised when every provider in the chain
+    /// supports them, so a fallback never receives a tool-less prompt.
+    fn supports_native_tools(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_native_tools())
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_vision())
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up provider connection pool");
@@ -184,6 +199,134 @@ impl Provider for ReliableProvider {
 
         anyhow::bail!("All providers failed. Attempts:\n{}", failures.join("\n"))
     }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let mut failures = Vec::new();
+
+        for (provider_name, provider) in &self.providers {
+            let mut backoff_ms = self.base_backoff_ms;
+
+            for attempt in 0..=self.max_retries {
+                match provider
+                    .chat_with_tools(messages, tools, model, temperature)
+                    .await
+                {
+                    Ok(resp) => {
+                        if attempt > 0 {
+                            tracing::info!(
+                                provider = provider_name,
+                                attempt,
+                                "Provider recovered after retries"
+                            );
+                        }
+                        return Ok(resp);
+                    }
+                    Err(e) => {
+                        let non_retryable = is_non_retryable(&e);
+                        failures.push(format!(
+                            "{provider_name} attempt {}/{}: {e}",
+                            attempt + 1,
+                            self.max_retries + 1
+                        ));
+
+                        if non_retryable {
+                            tracing::warn!(
+                                provider = provider_name,
+                                "Non-retryable error, switching provider"
+                            );
+                            break;
+                        }
+
+                        if attempt < self.max_retries {
+                            tracing::warn!(
+                                provider = provider_name,
+                                attempt = attempt + 1,
+                                max_retries = self.max_retries,
+                                "Provider call failed, retrying"
+                            );
+                            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                            backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
+                        }
+                    }
+                }
+            }
+
+            tracing::warn!(provider = provider_name, "Switching to fallback provider");
+        }
+
+        anyhow::bail!("All providers failed. Attempts:\n{}", failures.join("\n"))
+    }
+
+    /// Retries cover opening the stream; once events start flowing, errors
+    /// are passed through to the caller.
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let mut failures = Vec::new();
+
+        for (provider_name, provider) in &self.providers {
+            let mut backoff_ms = self.base_backoff_ms;
+
+            for attempt in 0..=self.max_retries {
+                match provider
+                    .chat_stream(messages, tools, model, temperature)
+                    .await
+                {
+                    Ok(resp) => {
+                        if attempt > 0 {
+                            tracing::info!(
+                                provider = provider_name,
+                                attempt,
+                                "Provider recovered after retries"
+                            );
+                        }
+                        return Ok(resp);
+                    }
+                    Err(e) => {
+                        let non_retryable = is_non_retryable(&e);
+                        failures.push(format!(
+                            "{provider_name} attempt {}/{}: {e}",
+                            attempt + 1,
+                            self.max_retries + 1
+                        ));
+
+                        if non_retryable {
+                            tracing::warn!(
+                                provider = provider_name,
+                                "Non-retryable error, switching provider"
+                            );
+                            break;
+                        }
+
+                        if attempt < self.max_retries {
+                            tracing::warn!(
+                                provider = provider_name,
+                                attempt = attempt + 1,
+                                max_retries = self.max_retries,
+                                "Provider call failed, retrying"
+                            );
+                            tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+                            backoff_ms = (backoff_ms.saturating_mul(2)).min(10_000);
+                        }
+                    }
+                }
+            }
+
+            tracing::warn!(provider = provider_name, "Switching to fallback provider");
+        }
+
+        anyhow::bail!("All providers failed. Attempts:\n{}", failures.join("\n"))
+    }
 }
 
 #[cfg(test)]
@@ -475,4 +618,61 @@ mod tests {
         assert_eq!(primary_calls.load(Ordering::SeqCst), 2);
         assert_eq!(fallback_calls.load(Ordering::SeqCst), 1);
     }
+
+    #[tokio::test]
+    async fn chat_with_tools_retries_and_reports_native_support() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: 1,
+                    response: "tool-less answer",
+                    error: "temporary",
+                }),
+            )],
+            2,
+            1,
+        );
+
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hi"))];
+        let result = provider
+            .chat_with_tools(&messages, &[], "test", 0.0)
+            .await
+            .unwrap();
+        assert_eq!(result.text.as_deref(), Some("tool-less answer"));
+        assert!(result.tool_calls.is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(!provider.supports_native_tools());
+    }
+
+    #[tokio::test]
+    async fn chat_stream_retries_opening_the_stream() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let provider = ReliableProvider::new(
+            vec![(
+                "primary".into(),
+                Box::new(MockProvider {
+                    calls: Arc::clone(&calls),
+                    fail_until_attempt: 1,
+                    response: "streamed",
+                    error: "temporary",
+                }),
+            )],
+            2,
+            1,
+        );
+
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hi"))];
+        let stream = provider
+            .chat_stream(&messages, &[], "test", 0.0)
+            .await
+            .unwrap();
+        let result = crate::providers::collect_stream(stream, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(result.text.as_deref(), Some("streamed"));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
 }
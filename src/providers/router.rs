@@ -1,5 +1,6 @@
-use super::traits::ChatMessage;
+use super::traits::{ChatMessage, ChatResponse, ChatStream, ConversationMessage};
 use super::Provider;
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use std::collections::HashMap;
 
@@ -126,6 +127,52 @@ impl Provider for RouterProvider {
             .await
     }
 
+    /// Any route may be picked per request, so native tools are only
+    /// advertised when every routed provider supports them.
+    fn supports_native_tools(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_native_tools())
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.providers
+            .iter()
+            .all(|(_, provider)| provider.supports_vision())
+    }
+
+    fn resolved_model(&self, model: &str) -> String {
+        self.resolve(model).1
+    }
+
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        let (_, provider) = &self.providers[provider_idx];
+        provider
+            .chat_with_tools(messages, tools, &resolved_model, temperature)
+            .await
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let (provider_idx, resolved_model) = self.resolve(model);
+        let (_, provider) = &self.providers[provider_idx];
+        provider
+            .chat_stream(messages, tools, &resolved_model, temperature)
+            .await
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
@@ -234,6 +281,10 @@ mod tests {
                 .chat_with_system(system_prompt, message, model, temperature)
                 .await
         }
+
+        fn supports_native_tools(&self) -> bool {
+            self.as_ref().supports_native_tools()
+        }
     }
 
     #[tokio::test]
@@ -320,6 +371,17 @@ mod tests {
         assert_eq!(model, "claude-opus");
     }
 
+    #[test]
+    fn resolved_model_maps_hints_to_route_target() {
+        let (router, _) = make_router(
+            vec![("fast", "ok"), ("smart", "ok")],
+            vec![("reasoning", "smart", "claude-opus")],
+        );
+
+        assert_eq!(router.resolved_model("hint:reasoning"), "claude-opus");
+        assert_eq!(router.resolved_model("gpt-4o"), "gpt-4o");
+    }
+
     #[test]
     fn skips_routes_with_unknown_provider() {
         let (router, _) = make_router(
@@ -358,4 +420,21 @@ mod tests {
         assert_eq!(result, "response");
         assert_eq!(mock.call_count(), 1);
     }
+
+    #[tokio::test]
+    async fn chat_with_tools_resolves_hint() {
+        let (router, mocks) = make_router(
+            vec![("fast", "fast-response"), ("smart", "smart-response")],
+            vec![("reasoning", "smart", "claude-opus")],
+        );
+
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hello"))];
+        let result = router
+            .chat_with_tools(&messages, &[], "hint:reasoning", 0.5)
+            .await
+            .unwrap();
+        assert_eq!(result.text.as_deref(), Some("smart-response"));
+        assert_eq!(mocks[1].last_model(), "claude-opus");
+        assert!(!router.supports_native_tools());
+    }
 }
@@ -0,0 +1,169 @@
+//! Line-oriented decoding of streamed provider responses.
+//!
+//! Server-sent events (used by the hosted APIs) and newline-delimited JSON
+//! (used by Ollama) are both framed by newlines, so every provider hands raw
+//! lines to its own parser and gets a `ChatStream` back.
+
+use super::traits::{ChatStream, StreamEvent};
+use futures_util::{Stream, StreamExt};
+use std::collections::VecDeque;
+
+struct LineDecoder<S, F> {
+    bytes: S,
+    buffer: Vec<u8>,
+    pending: VecDeque<anyhow::Result<StreamEvent>>,
+    on_line: F,
+    finished: bool,
+}
+
+impl<S, F> LineDecoder<S, F>
+where
+    F: FnMut(&str) -> anyhow::Result<Vec<StreamEvent>>,
+{
+    fn feed_line(&mut self, raw: &[u8]) {
+        let line = String::from_utf8_lossy(raw);
+        let line = line.trim();
+        if line.is_empty() || self.finished {
+            return;
+        }
+        match (self.on_line)(line) {
+            Ok(events) => self.pending.extend(events.into_iter().map(Ok)),
+            Err(e) => {
+                self.pending.push_back(Err(e));
+                self.finished = true;
+            }
+        }
+    }
+
+    fn feed_complete_lines(&mut self) {
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            self.feed_line(&line);
+        }
+    }
+}
+
+/// Split a byte stream into lines and run `on_line` on each non-empty one.
+///
+/// Lines are split on raw bytes before UTF-8 decoding so multi-byte
+/// characters straddling chunk boundaries survive intact. A single
+/// `StreamEvent::Done` is appended when the body ends; parsers should not
+/// emit it themselves. An error from `on_line` ends the stream.
+pub(crate) fn decode_lines<S, B, E, F>(bytes: S, on_line: F) -> ChatStream
+where
+    S: Stream<Item = Result<B, E>> + Send + Unpin + 'static,
+    B: AsRef<[u8]>,
+    E: Into<anyhow::Error>,
+    F: FnMut(&str) -> anyhow::Result<Vec<StreamEvent>> + Send + 'static,
+{
+    let decoder = LineDecoder {
+        bytes,
+        buffer: Vec::new(),
+        pending: VecDeque::new(),
+        on_line,
+        finished: false,
+    };
+
+    Box::pin(futures_util::stream::unfold(
+        decoder,
+        |mut decoder| async move {
+            loop {
+                if let Some(event) = decoder.pending.pop_front() {
+                    return Some((event, decoder));
+                }
+                if decoder.finished {
+                    return None;
+                }
+                match decoder.bytes.next().await {
+                    Some(Ok(chunk)) => {
+                        decoder.buffer.extend_from_slice(chunk.as_ref());
+                        decoder.feed_complete_lines();
+                    }
+                    Some(Err(e)) => {
+                        decoder.pending.push_back(Err(e.into()));
+                        decoder.finished = true;
+                    }
+                    None => {
+                        let rest = std::mem::take(&mut decoder.buffer);
+                        decoder.feed_line(&rest);
+                        if !decoder.finished {
+                            decoder.pending.push_back(Ok(StreamEvent::Done));
+                            decoder.finished = true;
+                        }
+                    }
+                }
+            }
+        },
+    ))
+}
+
+/// Decode a streaming HTTP response body line by line.
+pub(crate) fn response_lines<F>(response: reqwest::Response, on_line: F) -> ChatStream
+where
+    F: FnMut(&str) -> anyhow::Result<Vec<StreamEvent>> + Send + 'static,
+{
+    decode_lines(Box::pin(response.bytes_stream()), on_line)
+}
+
+/// Extract the payload of an SSE `data:` line. Other fields (`event:`,
+/// `id:`, comments) return `None`.
+pub(crate) fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::traits::collect_stream;
+
+    fn chunks(parts: &[&[u8]]) -> impl Stream<Item = Result<Vec<u8>, std::io::Error>> + Unpin {
+        futures_util::stream::iter(parts.iter().map(|p| Ok(p.to_vec())).collect::<Vec<_>>())
+    }
+
+    fn text_lines(line: &str) -> Vec<StreamEvent> {
+        sse_data(line)
+            .map(|data| vec![StreamEvent::TextDelta(data.to_string())])
+            .unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn joins_lines_split_across_chunks() {
+        let stream = decode_lines(
+            chunks(&[
+                b"data: he",
+                b"llo\n\nevent: ping\nda",
+                b"ta: w\xc3",
+                b"\xb6rld",
+            ]),
+            |line| Ok(text_lines(line)),
+        );
+        let mut deltas = Vec::new();
+        let response = collect_stream(stream, |d| deltas.push(d.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(deltas, vec!["hello", "wörld"]);
+        assert_eq!(response.text.as_deref(), Some("hellowörld"));
+    }
+
+    #[tokio::test]
+    async fn parser_error_ends_stream() {
+        let stream = decode_lines(chunks(&[b"data: ok\nboom\ndata: never\n"]), |line| {
+            if line == "boom" {
+                anyhow::bail!("bad line");
+            }
+            Ok(text_lines(line))
+        });
+
+        let err = collect_stream(stream, |_| {}).await.unwrap_err();
+        assert!(err.to_string().contains("bad line"));
+    }
+
+    #[test]
+    fn sse_data_ignores_other_fields() {
+        assert_eq!(sse_data("data: {\"a\":1}"), Some("{\"a\":1}"));
+        assert_eq!(sse_data("data:[DONE]"), Some("[DONE]"));
+        assert_eq!(sse_data("event: message_start"), None);
+        assert_eq!(sse_data(": keep-alive"), None);
+    }
+}
@@ -1,11 +1,21 @@
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use base64::Engine;
+use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
 
-/// A single message in a conversation.
+/// A single message in a conversation: a text part followed by any image
+/// parts, for models that can see images.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageContent>,
 }
 
 impl ChatMessage {
@@ -13,6 +23,7 @@ impl ChatMessage {
         Self {
             role: "system".into(),
             content: content.into(),
+            images: Vec::new(),
         }
     }
 
@@ -20,6 +31,7 @@ impl ChatMessage {
         Self {
             role: "user".into(),
             content: content.into(),
+            images: Vec::new(),
         }
     }
 
@@ -27,8 +39,113 @@ impl ChatMessage {
         Self {
             role: "assistant".into(),
             content: content.into(),
+            images: Vec::new(),
         }
     }
+
+    #[must_use]
+    pub fn with_images(mut self, images: impl IntoIterator<Item = ImageContent>) -> Self {
+        self.images.extend(images);
+        self
+    }
+}
+
+// ── Images ──────────────────────────────────────────────────────
+
+/// Largest image file read into a request; provider limits are around 5–20 MB.
+pub const MAX_IMAGE_FILE_BYTES: u64 = 20 * 1024 * 1024;
+
+/// An image part of a message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageContent {
+    /// MIME type, e.g. `image/png`
+    pub media_type: String,
+    pub source: ImageSource,
+}
+
+/// Where an image's bytes come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSource {
+    /// Base64-encoded image data
+    Base64(String),
+    /// A file read when the request is built, which keeps saved
+    /// conversations small
+    File(PathBuf),
+}
+
+impl ImageContent {
+    pub fn base64(media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            media_type: media_type.into(),
+            source: ImageSource::Base64(data.into()),
+        }
+    }
+
+    pub fn from_bytes(media_type: impl Into<String>, bytes: &[u8]) -> Self {
+        Self::base64(
+            media_type,
+            base64::engine::general_purpose::STANDARD.encode(bytes),
+        )
+    }
+
+    /// Reference an image file, guessing its type from the extension.
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let media_type = mime_guess::from_path(&path)
+            .first()
+            .filter(|mime| mime.type_() == mime_guess::mime::IMAGE)
+            .map_or_else(|| "image/png".to_string(), |mime| mime.to_string());
+        Self {
+            media_type,
+            source: ImageSource::File(path),
+        }
+    }
+
+    /// The image as base64, reading it from disk for file references.
+    pub fn data(&self) -> anyhow::Result<Cow<'_, str>> {
+        match &self.source {
+            ImageSource::Base64(data) => Ok(Cow::Borrowed(data)),
+            ImageSource::File(path) => {
+                let size = std::fs::metadata(path)
+                    .map_err(|e| anyhow::anyhow!("cannot read image {}: {e}", path.display()))?
+                    .len();
+                if size > MAX_IMAGE_FILE_BYTES {
+                    anyhow::bail!(
+                        "image {} is {size} bytes, over the {MAX_IMAGE_FILE_BYTES} byte limit",
+                        path.display()
+                    );
+                }
+                let bytes = std::fs::read(path)
+                    .map_err(|e| anyhow::anyhow!("cannot read image {}: {e}", path.display()))?;
+                Ok(Cow::Owned(
+                    base64::engine::general_purpose::STANDARD.encode(bytes),
+                ))
+            }
+        }
+    }
+}
+
+/// Resolve a message's images for a request, as `(media_type, base64)`
+/// pairs, returning the text to send along with them. Images that can no
+/// longer be read (say, a screenshot deleted since) become a note in the text
+/// rather than failing the whole conversation.
+pub fn resolve_images(text: &str, images: &[ImageContent]) -> (String, Vec<(String, String)>) {
+    let mut text = text.to_string();
+    let mut resolved = Vec::with_capacity(images.len());
+    for image in images {
+        match image.data() {
+            Ok(data) => resolved.push((image.media_type.clone(), data.into_owned())),
+            Err(e) => {
+                tracing::warn!("Dropping image from request: {e}");
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                let _ = write!(text, "[Image unavailable: {e}]");
+            }
+        }
+    }
+    (text, resolved)
 }
 
 /// A tool call requested by the LLM.
@@ -46,6 +163,29 @@ pub struct ChatResponse {
     pub text: Option<String>,
     /// Tool calls requested by the LLM.
     pub tool_calls: Vec<ToolCall>,
+    /// Token usage reported by the provider, when available.
+    pub usage: Option<TokenUsage>,
+}
+
+/// Prompt and completion token counts for a single LLM call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.prompt_tokens + self.completion_tokens
+    }
+
+    /// Fold in a later usage report. Providers send cumulative counts (and
+    /// some split input and output across events), so keep the larger of
+    /// each field rather than summing.
+    pub fn merge(&mut self, other: TokenUsage) {
+        self.prompt_tokens = self.prompt_tokens.max(other.prompt_tokens);
+        self.completion_tokens = self.completion_tokens.max(other.completion_tokens);
+    }
 }
 
 impl ChatResponse {
@@ -60,11 +200,153 @@ impl ChatResponse {
     }
 }
 
+/// An incremental event from a streaming chat completion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// A chunk of assistant text.
+    TextDelta(String),
+    /// A fragment of a tool call. `index` identifies the call within the
+    /// response; `id` and `name` arrive on the first fragment and `arguments`
+    /// is a piece of the JSON argument string to append.
+    ToolCallDelta {
+        index: usize,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: String,
+    },
+    /// Token usage for the request so far.
+    Usage(TokenUsage),
+    /// The response is complete.
+    Done,
+}
+
+/// A stream of events from `Provider::chat_stream`.
+pub type ChatStream = Pin<Box<dyn Stream<Item = anyhow::Result<StreamEvent>> + Send>>;
+
+/// Reassembles streamed events into a complete `ChatResponse`.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    text: String,
+    tool_calls: Vec<(usize, ToolCall)>,
+    usage: Option<TokenUsage>,
+}
+
+impl StreamAccumulator {
+    pub fn push(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::TextDelta(delta) => self.text.push_str(delta),
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                let pos = self
+                    .tool_calls
+                    .iter()
+                    .position(|(i, _)| i == index)
+                    .unwrap_or_else(|| {
+                        self.tool_calls.push((
+                            *index,
+                            ToolCall {
+                                id: String::new(),
+                                name: String::new(),
+                                arguments: String::new(),
+                            },
+                        ));
+                        self.tool_calls.len() - 1
+                    });
+                let call = &mut self.tool_calls[pos].1;
+                if let Some(id) = id.as_ref().filter(|id| !id.is_empty()) {
+                    call.id.clone_from(id);
+                }
+                if let Some(name) = name.as_ref().filter(|name| !name.is_empty()) {
+                    call.name.clone_from(name);
+                }
+                call.arguments.push_str(arguments);
+            }
+            StreamEvent::Usage(usage) => self
+                .usage
+                .get_or_insert_with(TokenUsage::default)
+                .merge(*usage),
+            StreamEvent::Done => {}
+        }
+    }
+
+    pub fn finish(self) -> ChatResponse {
+        ChatResponse {
+            text: if self.text.is_empty() {
+                None
+            } else {
+                Some(self.text)
+            },
+            tool_calls: self
+                .tool_calls
+                .into_iter()
+                .map(|(_, mut call)| {
+                    if call.id.is_empty() {
+                        call.id = format!("call_{}", uuid::Uuid::new_v4().simple());
+                    }
+                    if call.arguments.trim().is_empty() {
+                        call.arguments = "{}".into();
+                    }
+                    call
+                })
+                .collect(),
+            usage: self.usage,
+        }
+    }
+}
+
+/// Drain a chat stream, calling `on_text` for every text delta, and return
+/// the assembled response.
+pub async fn collect_stream(
+    mut stream: ChatStream,
+    mut on_text: impl FnMut(&str) + Send,
+) -> anyhow::Result<ChatResponse> {
+    let mut accumulator = StreamAccumulator::default();
+    while let Some(event) = stream.next().await {
+        let event = event?;
+        if let StreamEvent::TextDelta(delta) = &event {
+            on_text(delta);
+        }
+        accumulator.push(&event);
+        if event == StreamEvent::Done {
+            break;
+        }
+    }
+    Ok(accumulator.finish())
+}
+
+/// Replay a complete response as a stream, for providers without streaming.
+pub fn response_stream(response: ChatResponse) -> ChatStream {
+    let mut events = Vec::with_capacity(response.tool_calls.len() + 3);
+    if let Some(text) = response.text.filter(|t| !t.is_empty()) {
+        events.push(StreamEvent::TextDelta(text));
+    }
+    for (index, call) in response.tool_calls.into_iter().enumerate() {
+        events.push(StreamEvent::ToolCallDelta {
+            index,
+            id: Some(call.id),
+            name: Some(call.name),
+            arguments: call.arguments,
+        });
+    }
+    if let Some(usage) = response.usage {
+        events.push(StreamEvent::Usage(usage));
+    }
+    events.push(StreamEvent::Done);
+    Box::pin(futures_util::stream::iter(events.into_iter().map(Ok)))
+}
+
 /// A tool result to feed back to the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolResultMessage {
     pub tool_call_id: String,
     pub content: String,
+    /// Images the tool returned, e.g. a screenshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageContent>,
 }
 
 /// A message in a multi-turn conversation, including tool interactions.
@@ -82,6 +364,73 @@ pub enum ConversationMessage {
     ToolResult(ToolResultMessage),
 }
 
+/// Flatten a structured conversation into plain chat messages for providers
+/// without native function calling.
+///
+/// Tool calls are rendered back into the `<tool_call>` XML protocol and
+/// consecutive tool results are merged into a single `[Tool results]` user
+/// message, matching what the text-mode parser in the agent loop expects.
+pub fn flatten_conversation(messages: &[ConversationMessage]) -> Vec<ChatMessage> {
+    let mut flat: Vec<ChatMessage> = Vec::with_capacity(messages.len());
+    let mut tool_names: Vec<(&str, &str)> = Vec::new();
+    let mut pending_results = String::new();
+    let mut pending_images: Vec<ImageContent> = Vec::new();
+
+    for message in messages {
+        if !matches!(message, ConversationMessage::ToolResult(_)) && !pending_results.is_empty() {
+            flat.push(
+                ChatMessage::user(format!(
+                    "[Tool results]\n{}",
+                    std::mem::take(&mut pending_results)
+                ))
+                .with_images(std::mem::take(&mut pending_images)),
+            );
+        }
+
+        match message {
+            ConversationMessage::Chat(chat) => flat.push(chat.clone()),
+            ConversationMessage::AssistantToolCalls { text, tool_calls } => {
+                let mut content = text.clone().unwrap_or_default();
+                for call in tool_calls {
+                    tool_names.push((call.id.as_str(), call.name.as_str()));
+                    let arguments = serde_json::from_str::<serde_json::Value>(&call.arguments)
+                        .unwrap_or_else(|_| serde_json::Value::String(call.arguments.clone()));
+                    let payload = serde_json::json!({
+                        "name": call.name,
+                        "arguments": arguments,
+                    });
+                    if !content.is_empty() {
+                        content.push('\n');
+                    }
+                    let _ = write!(content, "<tool_call>\n{payload}\n</tool_call>");
+                }
+                flat.push(ChatMessage::assistant(content));
+            }
+            ConversationMessage::ToolResult(result) => {
+                let name = tool_names
+                    .iter()
+                    .rfind(|(id, _)| *id == result.tool_call_id)
+                    .map_or("unknown", |(_, name)| *name);
+                let _ = writeln!(
+                    pending_results,
+                    "<tool_result name=\"{name}\">\n{}\n</tool_result>",
+                    result.content
+                );
+                pending_images.extend(result.images.iter().cloned());
+            }
+        }
+    }
+
+    if !pending_results.is_empty() {
+        flat.push(
+            ChatMessage::user(format!("[Tool results]\n{pending_results}"))
+                .with_images(pending_images),
+        );
+    }
+
+    flat
+}
+
 #[async_trait]
 pub trait Provider: Send + Sync {
     async fn chat(&self, message: &str, model: &str, temperature: f64) -> anyhow::Result<String> {
@@ -118,6 +467,61 @@ pub trait Provider: Send + Sync {
             .await
     }
 
+    /// Whether `chat_with_tools` uses the provider's native function-calling
+    /// API. When false, callers must describe tools in the system prompt and
+    /// parse `<tool_call>` blocks out of the text reply.
+    fn supports_native_tools(&self) -> bool {
+        false
+    }
+
+    /// Whether the provider sends message images to the model. When false,
+    /// callers should describe images in text instead.
+    fn supports_vision(&self) -> bool {
+        false
+    }
+
+    /// The model that actually serves a request for `model`, used to price
+    /// its token usage. Routing providers map route hints to their target
+    /// model; others serve `model` as-is.
+    fn resolved_model(&self, model: &str) -> String {
+        model.to_string()
+    }
+
+    /// Multi-turn conversation with tool definitions. Default implementation
+    /// flattens the conversation, delegates to `chat_with_history`, and
+    /// returns the raw text with no structured tool calls.
+    async fn chat_with_tools(
+        &self,
+        messages: &[ConversationMessage],
+        _tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let history = flatten_conversation(messages);
+        let text = self.chat_with_history(&history, model, temperature).await?;
+        Ok(ChatResponse {
+            text: Some(text),
+            tool_calls: Vec::new(),
+            usage: None,
+        })
+    }
+
+    /// Streaming variant of `chat_with_tools`. Default implementation makes a
+    /// single `chat_with_tools` call and replays the result as one text delta
+    /// followed by whole tool calls.
+    async fn chat_stream(
+        &self,
+        messages: &[ConversationMessage],
+        tools: &[ToolSpec],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatStream> {
+        let response = self
+            .chat_with_tools(messages, tools, model, temperature)
+            .await?;
+        Ok(response_stream(response))
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -147,6 +551,7 @@ mod tests {
         let empty = ChatResponse {
             text: None,
             tool_calls: vec![],
+            usage: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -158,6 +563,7 @@ mod tests {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            usage: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
@@ -184,8 +590,233 @@ mod tests {
         let tool_result = ConversationMessage::ToolResult(ToolResultMessage {
             tool_call_id: "1".into(),
             content: "done".into(),
+            images: Vec::new(),
         });
         let json = serde_json::to_string(&tool_result).unwrap();
         assert!(json.contains("\"type\":\"ToolResult\""));
     }
+
+    #[test]
+    fn flatten_conversation_renders_tool_protocol() {
+        let messages = vec![
+            ConversationMessage::Chat(ChatMessage::system("sys")),
+            ConversationMessage::Chat(ChatMessage::user("list files")),
+            ConversationMessage::AssistantToolCalls {
+                text: Some("Checking.".into()),
+                tool_calls: vec![
+                    ToolCall {
+                        id: "a".into(),
+                        name: "shell".into(),
+                        arguments: r#"{"command":"ls"}"#.into(),
+                    },
+                    ToolCall {
+                        id: "b".into(),
+                        name: "file_read".into(),
+                        arguments: r#"{"path":"x"}"#.into(),
+                    },
+                ],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "a".into(),
+                content: "Cargo.toml".into(),
+                images: Vec::new(),
+            }),
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "b".into(),
+                content: "hello".into(),
+                images: Vec::new(),
+            }),
+            ConversationMessage::Chat(ChatMessage::assistant("Done")),
+        ];
+
+        let flat = flatten_conversation(&messages);
+        assert_eq!(flat.len(), 5);
+        assert_eq!(flat[2].role, "assistant");
+        assert!(flat[2].content.starts_with("Checking."));
+        assert_eq!(flat[2].content.matches("<tool_call>").count(), 2);
+        assert!(flat[2].content.contains(r#""command":"ls""#));
+        assert_eq!(flat[3].role, "user");
+        assert!(flat[3].content.starts_with("[Tool results]"));
+        assert!(flat[3]
+            .content
+            .contains("<tool_result name=\"shell\">\nCargo.toml"));
+        assert!(flat[3]
+            .content
+            .contains("<tool_result name=\"file_read\">\nhello"));
+        assert_eq!(flat[4].content, "Done");
+    }
+
+    #[test]
+    fn chat_message_omits_empty_images_when_serialized() {
+        let json = serde_json::to_value(ChatMessage::user("hi")).unwrap();
+        assert!(json.get("images").is_none());
+
+        let with = ChatMessage::user("hi").with_images([ImageContent::base64("image/png", "aGk=")]);
+        let json = serde_json::to_string(&with).unwrap();
+        let back: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.images, with.images);
+    }
+
+    #[test]
+    fn resolve_images_reads_files_and_notes_missing_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.jpg");
+        std::fs::write(&path, b"hi").unwrap();
+        let images = [
+            ImageContent::file(&path),
+            ImageContent::file(dir.path().join("gone.png")),
+        ];
+
+        let (text, resolved) = resolve_images("look", &images);
+        assert_eq!(
+            resolved,
+            vec![("image/jpeg".to_string(), "aGk=".to_string())]
+        );
+        assert!(text.starts_with("look"));
+        assert!(text.contains("[Image unavailable:"));
+    }
+
+    #[test]
+    fn flatten_conversation_carries_tool_images_onto_results() {
+        let png = ImageContent::base64("image/png", "aGk=");
+        let messages = vec![
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![ToolCall {
+                    id: "a".into(),
+                    name: "screenshot".into(),
+                    arguments: "{}".into(),
+                }],
+            },
+            ConversationMessage::ToolResult(ToolResultMessage {
+                tool_call_id: "a".into(),
+                content: "saved".into(),
+                images: vec![png.clone()],
+            }),
+        ];
+
+        let flat = flatten_conversation(&messages);
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat[1].images, vec![png]);
+    }
+
+    struct TextOnlyProvider;
+
+    #[async_trait]
+    impl Provider for TextOnlyProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("plain reply".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn default_chat_with_tools_returns_text_without_calls() {
+        let provider = TextOnlyProvider;
+        assert!(!provider.supports_native_tools());
+
+        let response = provider
+            .chat_with_tools(
+                &[ConversationMessage::Chat(ChatMessage::user("hi"))],
+                &[],
+                "model",
+                0.0,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.text_or_empty(), "plain reply");
+        assert!(!response.has_tool_calls());
+    }
+
+    #[test]
+    fn stream_accumulator_assembles_interleaved_tool_calls() {
+        let mut acc = StreamAccumulator::default();
+        for event in [
+            StreamEvent::TextDelta("Let me ".into()),
+            StreamEvent::TextDelta("look.".into()),
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("call_a".into()),
+                name: Some("shell".into()),
+                arguments: r#"{"comm"#.into(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 1,
+                id: None,
+                name: Some("file_read".into()),
+                arguments: String::new(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments: r#"and":"ls"}"#.into(),
+            },
+            StreamEvent::Done,
+        ] {
+            acc.push(&event);
+        }
+
+        let response = acc.finish();
+        assert_eq!(response.text.as_deref(), Some("Let me look."));
+        assert_eq!(response.tool_calls.len(), 2);
+        assert_eq!(response.tool_calls[0].id, "call_a");
+        assert_eq!(response.tool_calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(response.tool_calls[1].name, "file_read");
+        assert!(response.tool_calls[1].id.starts_with("call_"));
+        assert_eq!(response.tool_calls[1].arguments, "{}");
+    }
+
+    #[test]
+    fn stream_accumulator_keeps_cumulative_usage() {
+        let mut acc = StreamAccumulator::default();
+        for event in [
+            StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 120,
+                completion_tokens: 1,
+            }),
+            StreamEvent::TextDelta("ok".into()),
+            StreamEvent::Usage(TokenUsage {
+                prompt_tokens: 0,
+                completion_tokens: 42,
+            }),
+            StreamEvent::Done,
+        ] {
+            acc.push(&event);
+        }
+
+        let usage = acc.finish().usage.unwrap();
+        assert_eq!(usage.prompt_tokens, 120);
+        assert_eq!(usage.completion_tokens, 42);
+        assert_eq!(usage.total(), 162);
+    }
+
+    #[test]
+    fn stream_accumulator_without_usage_reports_none() {
+        let mut acc = StreamAccumulator::default();
+        acc.push(&StreamEvent::TextDelta("hi".into()));
+        assert!(acc.finish().usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn default_chat_stream_replays_full_response() {
+        let provider = TextOnlyProvider;
+        let messages = [ConversationMessage::Chat(ChatMessage::user("hi"))];
+        let stream = provider
+            .chat_stream(&messages, &[], "model", 0.0)
+            .await
+            .unwrap();
+
+        let mut deltas = Vec::new();
+        let response = collect_stream(stream, |d| deltas.push(d.to_string()))
+            .await
+            .unwrap();
+        assert_eq!(deltas, vec![response.text.clone().unwrap()]);
+        assert!(response.tool_calls.is_empty());
+    }
 }
@@ -0,0 +1,654 @@
+//! Out-of-band operator approval for risky shell commands.
+//!
+//! When policy gates a command, the shell tool holds it and asks the operator
+//! on the channel the current turn came from (or through the gateway). The
+//! command runs only if the operator approves before the timeout, and every
+//! decision is appended to `<workspace>/audit/approvals.jsonl`.
+
+use super::policy::{CommandRiskLevel, SecurityPolicy};
+use crate::channels::traits::{Channel, ChannelMessage};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// Channel and recipient that approval prompts for the current turn go to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApprovalRoute {
+    pub channel: String,
+    pub recipient: String,
+    /// The only user whose reply settles a prompt. The recipient can be a
+    /// shared room or thread, so without one anyone there may answer.
+    pub approver: Option<String>,
+}
+
+tokio::task_local! {
+    static ROUTE: ApprovalRoute;
+}
+
+impl ApprovalRoute {
+    pub fn new(channel: impl Into<String>, recipient: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            recipient: recipient.into(),
+            approver: None,
+        }
+    }
+
+    /// Only accept decisions from `approver` (e.g. the user whose message
+    /// started the turn).
+    #[must_use]
+    pub fn approved_by(mut self, approver: impl Into<String>) -> Self {
+        self.approver = Some(approver.into());
+        self
+    }
+
+    /// Run `fut` with approval prompts for any command it holds sent to this
+    /// route.
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        ROUTE.scope(self, fut).await
+    }
+
+    /// Route of the turn being run, if it was started inside [`Self::scope`].
+    pub fn current() -> Option<Self> {
+        ROUTE.try_with(Clone::clone).ok()
+    }
+}
+
+/// A held command waiting for the operator.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApprovalRequest {
+    pub id: String,
+    pub command: String,
+    pub risk: &'static str,
+    pub channel: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ApprovalRequest {
+    /// Plain-text prompt with reply instructions.
+    pub fn prompt(&self) -> String {
+        format!(
+            "⚠️ Approval needed for a {}-risk command:\n\n{}\n\nReply /approve {} or /deny {} (expires {} UTC).",
+            self.risk,
+            self.command,
+            self.id,
+            self.id,
+            self.expires_at.format("%H:%M:%S")
+        )
+    }
+}
+
+/// How an approval request ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approved,
+    Denied,
+    TimedOut,
+    /// No operator could be asked: the turn has no reply route and the
+    /// gateway is not accepting decisions.
+    Unavailable,
+}
+
+impl ApprovalDecision {
+    /// Tool error reported when the command does not run.
+    pub fn refusal(self) -> Option<&'static str> {
+        match self {
+            Self::Approved => None,
+            Self::Denied => Some("Command denied by the operator"),
+            Self::TimedOut => Some("Command denied: no operator decision before the timeout"),
+            Self::Unavailable => {
+                Some("Command requires explicit approval, but no operator is reachable to ask")
+            }
+        }
+    }
+}
+
+struct Resolution {
+    approved: bool,
+    decided_by: String,
+}
+
+struct Pending {
+    request: ApprovalRequest,
+    route: Option<ApprovalRoute>,
+    respond: oneshot::Sender<Resolution>,
+}
+
+type PendingMap = Arc<Mutex<HashMap<String, Pending>>>;
+
+/// Holds pending approval requests and the channels prompts are sent on.
+pub struct ApprovalBroker {
+    pending: PendingMap,
+    channels: Mutex<HashMap<String, Arc<dyn Channel>>>,
+    remote: AtomicBool,
+}
+
+static BROKER: OnceLock<ApprovalBroker> = OnceLock::new();
+
+/// Process-wide broker shared by tools, channel listeners and the gateway.
+pub fn broker() -> &'static ApprovalBroker {
+    BROKER.get_or_init(ApprovalBroker::new)
+}
+
+impl ApprovalBroker {
+    pub fn new() -> Self {
+        Self {
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            channels: Mutex::new(HashMap::new()),
+            remote: AtomicBool::new(false),
+        }
+    }
+
+    /// Send prompts for turns routed to `channel.name()` through `channel`.
+    pub fn register_channel(&self, channel: Arc<dyn Channel>) {
+        lock(&self.channels).insert(channel.name().to_string(), channel);
+    }
+
+    /// The running channel registered under `name`, if any.
+    pub fn channel(&self, name: &str) -> Option<Arc<dyn Channel>> {
+        lock(&self.channels).get(name).cloned()
+    }
+
+    /// Let requests without a reachable channel wait for a decision through
+    /// the gateway's `/approvals` endpoints.
+    pub fn enable_remote_approvals(&self) {
+        self.remote.store(true, Ordering::SeqCst);
+    }
+
+    /// Requests still waiting for a decision, oldest first.
+    pub fn pending(&self) -> Vec<ApprovalRequest> {
+        let mut requests: Vec<_> = lock(&self.pending)
+            .values()
+            .map(|p| p.request.clone())
+            .collect();
+        requests.sort_by_key(|r| r.requested_at);
+        requests
+    }
+
+    /// Hold `command` until the operator decides or the policy's approval
+    /// timeout passes. Anything but an explicit approval means "don't run".
+    pub async fn request(
+        &self,
+        security: &SecurityPolicy,
+        command: &str,
+        risk: CommandRiskLevel,
+    ) -> ApprovalDecision {
+        let route = ApprovalRoute::current();
+        let timeout = Duration::from_secs(security.approval_timeout_secs);
+        let requested_at = Utc::now();
+        let request = ApprovalRequest {
+            id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            command: command.to_string(),
+            risk: risk_label(risk),
+            channel: route.as_ref().map(|r| r.channel.clone()),
+            requested_at,
+            expires_at: requested_at
+                + chrono::Duration::from_std(timeout).unwrap_or_else(|_| chrono::Duration::zero()),
+        };
+
+        let (tx, rx) = oneshot::channel();
+        lock(&self.pending).insert(
+            request.id.clone(),
+            Pending {
+                request: request.clone(),
+                route: route.clone(),
+                respond: tx,
+            },
+        );
+
+        let delivered = match &route {
+            Some(route) => self.notify(&request, route).await,
+            None => false,
+        };
+
+        let (decision, decided_by) = if delivered || self.remote.load(Ordering::SeqCst) {
+            match tokio::time::timeout(timeout, rx).await {
+                Ok(Ok(resolution)) if resolution.approved => {
+                    (ApprovalDecision::Approved, resolution.decided_by)
+                }
+                Ok(Ok(resolution)) => (ApprovalDecision::Denied, resolution.decided_by),
+                _ => (ApprovalDecision::TimedOut, "timeout".to_string()),
+            }
+        } else {
+            (ApprovalDecision::Unavailable, "none".to_string())
+        };
+        lock(&self.pending).remove(&request.id);
+        if decision == ApprovalDecision::TimedOut
+            && route.as_ref().is_some_and(|r| self.asks_on_terminal(r))
+        {
+            eprintln!("\n⏱️  Approval request timed out; the command was not run.");
+        }
+
+        if let Err(e) = append_audit(
+            &security.workspace_dir,
+            &request,
+            route.as_ref(),
+            decision,
+            &decided_by,
+        ) {
+            tracing::warn!("Failed to write approval audit entry: {e}");
+        }
+        decision
+    }
+
+    /// Settle a pending request by id. Returns `false` if it is unknown or
+    /// already settled.
+    pub fn resolve(&self, id: &str, approved: bool, decided_by: &str) -> bool {
+        let Some(pending) = lock(&self.pending).remove(id) else {
+            return false;
+        };
+        pending
+            .respond
+            .send(Resolution {
+                approved,
+                decided_by: decided_by.to_string(),
+            })
+            .is_ok()
+    }
+
+    /// Settle a request from a `/approve [id]` or `/deny [id]` reply. Only
+    /// replies in the conversation the prompt went to, from its approver when
+    /// the route names one, can answer it; without an id the reply applies
+    /// when exactly one request is waiting on them. Returns `true` when the
+    /// message was consumed.
+    pub fn handle_reply(&self, msg: &ChannelMessage) -> bool {
+        let Some((approved, id)) = parse_reply(&msg.content) else {
+            return false;
+        };
+
+        let mut pending = lock(&self.pending);
+        let from_recipient = |p: &Pending| {
+            p.route.as_ref().is_some_and(|r| {
+                r.channel == msg.channel
+                    && r.recipient == msg.sender
+                    && r.approver.as_ref().is_none_or(|a| *a == msg.author)
+            })
+        };
+        let id = if let Some(id) = id {
+            pending
+                .get(id)
+                .filter(|p| from_recipient(p))
+                .map(|_| id.to_string())
+        } else {
+            let mut waiting = pending.iter().filter(|(_, p)| from_recipient(p));
+            match (waiting.next(), waiting.next()) {
+                (Some((id, _)), None) => Some(id.clone()),
+                _ => None,
+            }
+        };
+        let Some(pending) = id.and_then(|id| pending.remove(&id)) else {
+            return false;
+        };
+
+        let _ = pending.respond.send(Resolution {
+            approved,
+            decided_by: format!("{}:{}", msg.channel, msg.author),
+        });
+        true
+    }
+
+    async fn notify(&self, request: &ApprovalRequest, route: &ApprovalRoute) -> bool {
+        if self.asks_on_terminal(route) {
+            self.prompt_terminal(request);
+            return true;
+        }
+        let channel = lock(&self.channels).get(&route.channel).cloned();
+        if let Some(channel) = channel {
+            return match channel
+                .send_approval_request(request, &route.recipient)
+                .await
+            {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("Failed to send approval request on {}: {e}", route.channel);
+                    false
+                }
+            };
+        }
+        false
+    }
+
+    /// One-shot CLI runs have no listener on stdin, so their prompts are
+    /// asked on the terminal.
+    fn asks_on_terminal(&self, route: &ApprovalRoute) -> bool {
+        route.channel == "cli" && !lock(&self.channels).contains_key("cli")
+    }
+
+    fn prompt_terminal(&self, request: &ApprovalRequest) {
+        eprintln!(
+            "\n⚠️  Approval needed for a {}-risk command:\n    {}\n    Run it? [y/N] ",
+            request.risk, request.command
+        );
+        let pending = Arc::clone(&self.pending);
+        let id = request.id.clone();
+        // A plain thread rather than `spawn_blocking`: the read cannot be
+        // cancelled, and the runtime would wait for it on shutdown after the
+        // request has timed out.
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            let approved = std::io::stdin().read_line(&mut line).is_ok()
+                && matches!(line.trim().to_lowercase().as_str(), "y" | "yes");
+            if let Some(pending) = lock(&pending).remove(&id) {
+                let _ = pending.respond.send(Resolution {
+                    approved,
+                    decided_by: "cli:terminal".into(),
+                });
+            }
+        });
+    }
+}
+
+impl Default for ApprovalBroker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward channel messages to the returned receiver, handing approval
+/// replies to the broker instead. Runs beside the message loop so a reply
+/// gets through while that loop is blocked on the turn waiting for it.
+pub fn intercept_replies(mut rx: mpsc::Receiver<ChannelMessage>) -> mpsc::Receiver<ChannelMessage> {
+    let (tx, forwarded) = mpsc::channel(100);
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            if broker().handle_reply(&msg) {
+                continue;
+            }
+            if tx.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+    forwarded
+}
+
+fn parse_reply(content: &str) -> Option<(bool, Option<&str>)> {
+    let mut parts = content.split_whitespace();
+    let approved = match parts.next()? {
+        "/approve" => true,
+        "/deny" => false,
+        _ => return None,
+    };
+    let id = parts.next();
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((approved, id))
+}
+
+fn risk_label(risk: CommandRiskLevel) -> &'static str {
+    match risk {
+        CommandRiskLevel::Low => "low",
+        CommandRiskLevel::Medium => "medium",
+        CommandRiskLevel::High => "high",
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: DateTime<Utc>,
+    id: &'a str,
+    command: &'a str,
+    risk: &'a str,
+    channel: Option<&'a str>,
+    recipient: Option<&'a str>,
+    decision: ApprovalDecision,
+    decided_by: &'a str,
+}
+
+fn append_audit(
+    workspace_dir: &Path,
+    request: &ApprovalRequest,
+    route: Option<&ApprovalRoute>,
+    decision: ApprovalDecision,
+    decided_by: &str,
+) -> Result<()> {
+    let dir = workspace_dir.join("audit");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join("approvals.jsonl");
+
+    let entry = AuditEntry {
+        timestamp: Utc::now(),
+        id: &request.id,
+        command: &request.command,
+        risk: request.risk,
+        channel: route.map(|r| r.channel.as_str()),
+        recipient: route.map(|r| r.recipient.as_str()),
+        decision,
+        decided_by,
+    };
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use tempfile::TempDir;
+
+    /// Channel that records prompts instead of sending them.
+    struct PromptSink {
+        prompts: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl Channel for PromptSink {
+        fn name(&self) -> &str {
+            "sink"
+        }
+
+        async fn send(&self, message: &str, recipient: &str) -> Result<()> {
+            lock(&self.prompts).push((recipient.to_string(), message.to_string()));
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn setup(
+        timeout_secs: u64,
+    ) -> (
+        Arc<ApprovalBroker>,
+        Arc<PromptSink>,
+        SecurityPolicy,
+        TempDir,
+    ) {
+        let tmp = TempDir::new().unwrap();
+        let broker = Arc::new(ApprovalBroker::new());
+        let sink = Arc::new(PromptSink {
+            prompts: Mutex::new(Vec::new()),
+        });
+        broker.register_channel(sink.clone());
+        let security = SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            approval_timeout_secs: timeout_secs,
+            ..SecurityPolicy::default()
+        };
+        (broker, sink, security, tmp)
+    }
+
+    fn reply(sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "1".into(),
+            sender: sender.into(),
+            author: sender.into(),
+            content: content.into(),
+            channel: "sink".into(),
+            timestamp: 0,
+            attachments: Vec::new(),
+        }
+    }
+
+    async fn wait_for_pending(broker: &ApprovalBroker) -> ApprovalRequest {
+        loop {
+            if let Some(request) = broker.pending().into_iter().next() {
+                return request;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    fn audit_lines(tmp: &TempDir) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(tmp.path().join("audit/approvals.jsonl"))
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn parses_approval_replies() {
+        assert_eq!(parse_reply("/approve"), Some((true, None)));
+        assert_eq!(
+            parse_reply(" /deny  ab12cd34 "),
+            Some((false, Some("ab12cd34")))
+        );
+        assert_eq!(parse_reply("/approve this please"), None);
+        assert_eq!(parse_reply("approve"), None);
+        assert_eq!(parse_reply(""), None);
+    }
+
+    #[tokio::test]
+    async fn approval_reply_from_recipient_releases_command() {
+        let (broker, sink, security, tmp) = setup(30);
+        let waiter = {
+            let broker = broker.clone();
+            tokio::spawn(async move {
+                ApprovalRoute::new("sink", "alice")
+                    .scope(broker.request(&security, "touch a.txt", CommandRiskLevel::Medium))
+                    .await
+            })
+        };
+
+        let request = wait_for_pending(&broker).await;
+        // Prompt was delivered before the reply can be matched.
+        while lock(&sink.prompts).is_empty() {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(lock(&sink.prompts)[0].1.contains(&request.id));
+
+        assert!(!broker.handle_reply(&reply("mallory", "/approve")));
+        assert!(!broker.handle_reply(&reply("alice", "sounds good")));
+        assert!(broker.handle_reply(&reply("alice", "/approve")));
+
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approved);
+        assert!(broker.pending().is_empty());
+        let audit = audit_lines(&tmp);
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0]["decision"], "approved");
+        assert_eq!(audit[0]["decided_by"], "sink:alice");
+        assert_eq!(audit[0]["command"], "touch a.txt");
+    }
+
+    #[tokio::test]
+    async fn only_the_requesting_user_can_approve_in_a_shared_thread() {
+        let (broker, _sink, security, tmp) = setup(30);
+        let waiter = {
+            let broker = broker.clone();
+            tokio::spawn(async move {
+                ApprovalRoute::new("sink", "C01:1700000000.000100")
+                    .approved_by("U_ALICE")
+                    .scope(broker.request(&security, "touch a.txt", CommandRiskLevel::Medium))
+                    .await
+            })
+        };
+        let request = wait_for_pending(&broker).await;
+
+        let in_thread = |author: &str, content: &str| ChannelMessage {
+            author: author.into(),
+            ..reply("C01:1700000000.000100", content)
+        };
+        assert!(!broker.handle_reply(&in_thread("U_BOB", "/approve")));
+        assert!(!broker.handle_reply(&in_thread("U_BOB", &format!("/approve {}", request.id))));
+        assert_eq!(broker.pending().len(), 1);
+        assert!(broker.handle_reply(&in_thread("U_ALICE", "/approve")));
+
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approved);
+        assert_eq!(audit_lines(&tmp)[0]["decided_by"], "sink:U_ALICE");
+    }
+
+    #[tokio::test]
+    async fn deny_by_id_and_gateway_resolve() {
+        let (broker, _sink, security, _tmp) = setup(30);
+        let waiter = {
+            let broker = broker.clone();
+            let security = security.clone();
+            tokio::spawn(async move {
+                ApprovalRoute::new("sink", "alice")
+                    .scope(broker.request(&security, "rm -rf build", CommandRiskLevel::High))
+                    .await
+            })
+        };
+        let request = wait_for_pending(&broker).await;
+        assert_eq!(request.risk, "high");
+        assert!(broker.handle_reply(&reply("alice", &format!("/deny {}", request.id))));
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Denied);
+        assert!(!broker.resolve(&request.id, true, "gateway"));
+
+        broker.enable_remote_approvals();
+        let waiter = {
+            let broker = broker.clone();
+            tokio::spawn(async move {
+                broker
+                    .request(&security, "touch b.txt", CommandRiskLevel::Medium)
+                    .await
+            })
+        };
+        let request = wait_for_pending(&broker).await;
+        assert!(request.channel.is_none());
+        assert!(broker.resolve(&request.id, true, "gateway"));
+        assert_eq!(waiter.await.unwrap(), ApprovalDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn times_out_as_denial() {
+        let (broker, _sink, security, tmp) = setup(0);
+        let decision = ApprovalRoute::new("sink", "alice")
+            .scope(broker.request(&security, "touch a.txt", CommandRiskLevel::Medium))
+            .await;
+        assert_eq!(decision, ApprovalDecision::TimedOut);
+        assert!(decision.refusal().is_some());
+        assert!(broker.pending().is_empty());
+        assert_eq!(audit_lines(&tmp)[0]["decision"], "timed_out");
+    }
+
+    #[tokio::test]
+    async fn unroutable_request_is_denied_immediately() {
+        let (broker, _sink, security, tmp) = setup(30);
+        let decision = broker
+            .request(&security, "touch a.txt", CommandRiskLevel::Medium)
+            .await;
+        assert_eq!(decision, ApprovalDecision::Unavailable);
+
+        let decision = ApprovalRoute::new("nowhere", "bob")
+            .scope(broker.request(&security, "touch a.txt", CommandRiskLevel::Medium))
+            .await;
+        assert_eq!(decision, ApprovalDecision::Unavailable);
+        assert_eq!(audit_lines(&tmp).len(), 2);
+    }
+}
@@ -1,6 +1,8 @@
+use crate::cost::{self, CostLedger, DailySpend};
+use crate::providers::traits::TokenUsage;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 
 /// How much autonomy the agent has
@@ -90,7 +92,10 @@ pub struct SecurityPolicy {
     pub max_cost_per_day_cents: u32,
     pub require_approval_for_medium_risk: bool,
     pub block_high_risk_commands: bool,
+    pub approval_timeout_secs: u64,
+    pub max_scheduled_jobs: u32,
     pub tracker: ActionTracker,
+    pub cost_ledger: Arc<CostLedger>,
 }
 
 impl Default for SecurityPolicy {
@@ -139,7 +144,10 @@ impl Default for SecurityPolicy {
             max_cost_per_day_cents: 500,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            approval_timeout_secs: 120,
+            max_scheduled_jobs: 20,
             tracker: ActionTracker::new(),
+            cost_ledger: Arc::new(CostLedger::in_memory()),
         }
     }
 }
@@ -305,10 +313,7 @@ impl SecurityPolicy {
                 return Err("Command blocked: high-risk command is disallowed by policy".into());
             }
             if self.autonomy == AutonomyLevel::Supervised && !approved {
-                return Err(
-                    "Command requires explicit approval (approved=true): high-risk operation"
-                        .into(),
-                );
+                return Err("Command requires explicit approval: high-risk operation".into());
             }
         }
 
@@ -317,14 +322,29 @@ impl SecurityPolicy {
             && self.require_approval_for_medium_risk
             && !approved
         {
-            return Err(
-                "Command requires explicit approval (approved=true): medium-risk operation".into(),
-            );
+            return Err("Command requires explicit approval: medium-risk operation".into());
         }
 
         Ok(risk)
     }
 
+    /// Risk level of `command` when policy lets it run only after an operator
+    /// approves it; `None` when it runs, or is rejected, without asking.
+    pub fn approval_required(&self, command: &str) -> Option<CommandRiskLevel> {
+        if self.autonomy != AutonomyLevel::Supervised || !self.is_command_allowed(command) {
+            return None;
+        }
+        match self.command_risk_level(command) {
+            CommandRiskLevel::High if !self.block_high_risk_commands => {
+                Some(CommandRiskLevel::High)
+            }
+            CommandRiskLevel::Medium if self.require_approval_for_medium_risk => {
+                Some(CommandRiskLevel::Medium)
+            }
+            _ => None,
+        }
+    }
+
     /// Check if a shell command is allowed.
     ///
     /// Validates the **entire** command string, not just the first word:
@@ -485,6 +505,30 @@ impl SecurityPolicy {
         self.tracker.count() >= self.max_actions_per_hour as usize
     }
 
+    /// Check whether today's LLM spend is still under `max_cost_per_day_cents`.
+    /// New LLM calls and tool executions should be refused on `Err`.
+    pub fn check_budget(&self) -> Result<(), String> {
+        if self.max_cost_per_day_cents == 0 {
+            return Ok(());
+        }
+        let spent = self.cost_ledger.today().cost_cents;
+        let budget = f64::from(self.max_cost_per_day_cents);
+        if spent >= budget {
+            return Err(format!(
+                "Daily cost budget exhausted: spent ${:.2} of ${:.2} today (resets at 00:00 UTC)",
+                spent / 100.0,
+                budget / 100.0
+            ));
+        }
+        Ok(())
+    }
+
+    /// Price an LLM call for `model` and add it to the daily ledger.
+    pub fn record_usage(&self, model: &str, usage: TokenUsage) -> DailySpend {
+        self.cost_ledger
+            .record(usage, cost::cost_cents(model, usage))
+    }
+
     /// Build from config sections
     pub fn from_config(
         autonomy_config: &crate::config::AutonomyConfig,
@@ -500,7 +544,10 @@ impl SecurityPolicy {
             max_cost_per_day_cents: autonomy_config.max_cost_per_day_cents,
             require_approval_for_medium_risk: autonomy_config.require_approval_for_medium_risk,
             block_high_risk_commands: autonomy_config.block_high_risk_commands,
+            approval_timeout_secs: autonomy_config.approval_timeout_secs,
+            max_scheduled_jobs: autonomy_config.max_scheduled_jobs,
             tracker: ActionTracker::new(),
+            cost_ledger: CostLedger::shared(workspace_dir),
         }
     }
 }
@@ -709,6 +756,38 @@ mod tests {
         assert!(result.unwrap_err().contains("high-risk"));
     }
 
+    #[test]
+    fn approval_required_only_for_gated_commands() {
+        let p = SecurityPolicy {
+            allowed_commands: vec!["touch".into(), "rm".into(), "ls".into()],
+            block_high_risk_commands: false,
+            ..SecurityPolicy::default()
+        };
+        assert_eq!(p.approval_required("ls"), None);
+        assert_eq!(
+            p.approval_required("touch a.txt"),
+            Some(CommandRiskLevel::Medium)
+        );
+        assert_eq!(
+            p.approval_required("rm -rf build"),
+            Some(CommandRiskLevel::High)
+        );
+        // Rejected outright, so there is nothing to approve.
+        assert_eq!(p.approval_required("curl example.com"), None);
+
+        let blocking = SecurityPolicy {
+            block_high_risk_commands: true,
+            ..p.clone()
+        };
+        assert_eq!(blocking.approval_required("rm -rf build"), None);
+
+        let full = SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            ..p
+        };
+        assert_eq!(full.approval_required("touch a.txt"), None);
+    }
+
     // ── is_path_allowed ─────────────────────────────────────
 
     #[test]
@@ -784,6 +863,8 @@ mod tests {
             max_cost_per_day_cents: 1000,
             require_approval_for_medium_risk: false,
             block_high_risk_commands: false,
+            approval_timeout_secs: 30,
+            max_scheduled_jobs: 3,
         };
         let workspace = PathBuf::from("/tmp/test-workspace");
         let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
@@ -796,6 +877,8 @@ mod tests {
         assert_eq!(policy.max_cost_per_day_cents, 1000);
         assert!(!policy.require_approval_for_medium_risk);
         assert!(!policy.block_high_risk_commands);
+        assert_eq!(policy.approval_timeout_secs, 30);
+        assert_eq!(policy.max_scheduled_jobs, 3);
         assert_eq!(policy.workspace_dir, PathBuf::from("/tmp/test-workspace"));
     }
 
@@ -1097,6 +1180,8 @@ mod tests {
             max_cost_per_day_cents: 100,
             require_approval_for_medium_risk: true,
             block_high_risk_commands: true,
+            approval_timeout_secs: 120,
+            max_scheduled_jobs: 20,
         };
         let workspace = PathBuf::from("/tmp/test");
         let policy = SecurityPolicy::from_config(&autonomy_config, &workspace);
@@ -1104,6 +1189,79 @@ mod tests {
         assert!(!policy.is_rate_limited());
     }
 
+    // ── Daily cost budget ─────────────────────────────────────
+
+    fn usage(prompt_tokens: u64, completion_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn budget_refuses_once_spent() {
+        let p = SecurityPolicy {
+            max_cost_per_day_cents: 2,
+            ..SecurityPolicy::default()
+        };
+        assert!(p.check_budget().is_ok());
+
+        // 2k in + 500 out on gpt-4o = 1 cent
+        p.record_usage("gpt-4o", usage(2_000, 500));
+        assert!(p.check_budget().is_ok());
+        p.record_usage("gpt-4o", usage(2_000, 500));
+
+        let err = p.check_budget().unwrap_err();
+        assert!(err.contains("budget exhausted"));
+        assert!(err.contains("$0.02"));
+    }
+
+    #[test]
+    fn zero_budget_disables_limit() {
+        let p = SecurityPolicy {
+            max_cost_per_day_cents: 0,
+            ..SecurityPolicy::default()
+        };
+        p.record_usage("claude-opus-4", usage(10_000_000, 10_000_000));
+        assert!(p.check_budget().is_ok());
+    }
+
+    #[test]
+    fn unpriced_models_do_not_consume_budget() {
+        let p = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            ..SecurityPolicy::default()
+        };
+        let spend = p.record_usage("llama3.2", usage(1_000_000, 1_000_000));
+        assert_eq!(spend.requests, 1);
+        assert!(p.check_budget().is_ok());
+    }
+
+    #[test]
+    fn clones_share_cost_ledger() {
+        let p = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            ..SecurityPolicy::default()
+        };
+        let clone = p.clone();
+        clone.record_usage("gpt-4o", usage(2_000, 500));
+        assert!(p.check_budget().is_err());
+    }
+
+    #[test]
+    fn from_config_persists_spend_in_workspace() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let autonomy_config = crate::config::AutonomyConfig {
+            max_cost_per_day_cents: 1,
+            ..crate::config::AutonomyConfig::default()
+        };
+        SecurityPolicy::from_config(&autonomy_config, tmp.path())
+            .record_usage("gpt-4o", usage(2_000, 500));
+
+        let fresh = SecurityPolicy::from_config(&autonomy_config, tmp.path());
+        assert!(fresh.check_budget().is_err());
+    }
+
     // ══════════════════════════════════════════════════════════
     // SECURITY CHECKLIST TESTS
     // Checklist: gateway not public, pairing required,
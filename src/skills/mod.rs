@@ -31,16 +31,29 @@ pub struct Skill {
 }
 
 /// A tool defined by a skill (shell command, HTTP call, etc.)
+///
+/// `command`, `body` and header values may reference arguments as
+/// `{{name}}`; each entry in `args` maps an argument name to its description.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillTool {
     pub name: String,
     pub description: String,
     /// "shell", "http", "script"
     pub kind: String,
-    /// The command/URL/script to execute
+    /// The command/URL/script to execute. Script paths are relative to the
+    /// skill's directory.
     pub command: String,
     #[serde(default)]
     pub args: HashMap<String, String>,
+    /// HTTP method for "http" tools (default: GET)
+    #[serde(default)]
+    pub method: Option<String>,
+    /// Request body for "http" tools
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Request headers for "http" tools
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
 }
 
 /// Skill manifest parsed from SKILL.toml
@@ -418,8 +431,18 @@ pub fn init_skills_dir(workspace_dir: &Path) -> Result<()> {
              name = \"my_tool\"\n\
              description = \"What this tool does\"\n\
              kind = \"shell\"\n\
-             command = \"echo hello\"\n\
+             command = \"echo {{greeting}}\"\n\
+             args = { greeting = \"Text to print\" }\n\n\
+             [[tools]]\n\
+             name = \"lookup_weather\"\n\
+             description = \"Current weather for a city\"\n\
+             kind = \"http\"\n\
+             command = \"https://wttr.in/{{city}}?format=3\"\n\
+             args = { city = \"City name\" }\n\
              ```\n\n\
+             Tools are callable by the agent. `{{name}}` placeholders are filled\n\
+             from the tool's arguments; shell and script tools still go through\n\
+             the command allowlist and approval rules.\n\n\
              ## SKILL.md format (simpler)\n\n\
              Just write a markdown file with instructions for the agent.\n\
              The agent will read it and follow the instructions.\n\n\
@@ -885,6 +908,9 @@ description = "Bare minimum"
                 kind: "shell".to_string(),
                 command: "curl wttr.in".to_string(),
                 args: HashMap::new(),
+                method: None,
+                body: None,
+                headers: HashMap::new(),
             }],
             prompts: vec![],
             location: None,
@@ -5,10 +5,12 @@
 //! for efficient LLM integration.
 
 use super::traits::{Tool, ToolResult};
+use crate::providers::ImageContent;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::path::Path;
 use std::process::Stdio;
 use std::sync::Arc;
 use tokio::process::Command;
@@ -277,7 +279,12 @@ impl BrowserTool {
                     args.push("--full");
                 }
                 let resp = self.run_command(&args).await?;
-                self.to_result(resp)
+                let saved = path.or_else(|| screenshot_path(&resp));
+                let mut result = self.to_result(resp)?;
+                if let Some(saved) = saved.filter(|p| Path::new(p).is_file()) {
+                    result.images.push(ImageContent::file(saved));
+                }
+                Ok(result)
             }
 
             BrowserAction::Wait { selector, ms, text } => {
@@ -354,17 +361,28 @@ impl BrowserTool {
                 success: true,
                 output,
                 error: None,
+                images: Vec::new(),
             })
         } else {
             Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: resp.error,
+                images: Vec::new(),
             })
         }
     }
 }
 
+/// Where agent-browser reported saving a screenshot, if anywhere.
+fn screenshot_path(resp: &AgentBrowserResponse) -> Option<String> {
+    resp.data
+        .as_ref()?
+        .get("path")
+        .and_then(Value::as_str)
+        .map(String::from)
+}
+
 #[allow(clippy::too_many_lines)]
 #[async_trait]
 impl Tool for BrowserTool {
@@ -469,6 +487,16 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                images: Vec::new(),
+            });
+        }
+
+        if let Err(reason) = self.security.check_budget() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(reason),
+                images: Vec::new(),
             });
         }
 
@@ -477,6 +505,7 @@ impl Tool for BrowserTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: rate limit exceeded".into()),
+                images: Vec::new(),
             });
         }
 
@@ -489,6 +518,7 @@ impl Tool for BrowserTool {
                     "agent-browser CLI not found. Install with: npm install -g agent-browser"
                         .into(),
                 ),
+                images: Vec::new(),
             });
         }
 
@@ -650,6 +680,7 @@ impl Tool for BrowserTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Unknown action: {action_str}")),
+                    images: Vec::new(),
                 });
             }
         };
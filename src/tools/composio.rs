@@ -168,6 +168,7 @@ impl Tool for ComposioTool {
         })
     }
 
+    #[allow(clippy::too_many_lines)]
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let action = args
             .get("action")
@@ -210,12 +211,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            images: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to list actions: {e}")),
+                        images: Vec::new(),
                     }),
                 }
             }
@@ -239,12 +242,14 @@ impl Tool for ComposioTool {
                             success: true,
                             output,
                             error: None,
+                            images: Vec::new(),
                         })
                     }
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Action execution failed: {e}")),
+                        images: Vec::new(),
                     }),
                 }
             }
@@ -260,11 +265,13 @@ impl Tool for ComposioTool {
                         success: true,
                         output: format!("Open this URL to connect {app}:\n{url}"),
                         error: None,
+                        images: Vec::new(),
                     }),
                     Err(e) => Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Failed to get connection URL: {e}")),
+                        images: Vec::new(),
                     }),
                 }
             }
@@ -275,6 +282,7 @@ impl Tool for ComposioTool {
                 error: Some(format!(
                     "Unknown action '{action}'. Use 'list', 'execute', or 'connect'."
                 )),
+                images: Vec::new(),
             }),
         }
     }
@@ -50,6 +50,7 @@ impl Tool for FileReadTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Path not allowed by security policy: {path}")),
+                images: Vec::new(),
             });
         }
 
@@ -63,6 +64,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to resolve file path: {e}")),
+                    images: Vec::new(),
                 });
             }
         };
@@ -75,6 +77,7 @@ impl Tool for FileReadTool {
                     "Resolved path escapes workspace: {}",
                     resolved_path.display()
                 )),
+                images: Vec::new(),
             });
         }
 
@@ -90,6 +93,7 @@ impl Tool for FileReadTool {
                             "File too large: {} bytes (limit: {MAX_FILE_SIZE} bytes)",
                             meta.len()
                         )),
+                        images: Vec::new(),
                     });
                 }
             }
@@ -98,6 +102,7 @@ impl Tool for FileReadTool {
                     success: false,
                     output: String::new(),
                     error: Some(format!("Failed to read file metadata: {e}")),
+                    images: Vec::new(),
                 });
             }
         }
@@ -107,11 +112,13 @@ impl Tool for FileReadTool {
                 success: true,
                 output: contents,
                 error: None,
+                images: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to read file: {e}")),
+                images: Vec::new(),
             }),
         }
     }
@@ -1,4 +1,5 @@
 use super::traits::{Tool, ToolResult};
+use crate::providers::ImageContent;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -9,11 +10,11 @@ use std::sync::Arc;
 /// Maximum file size we will read and base64-encode (5 MB).
 const MAX_IMAGE_BYTES: u64 = 5_242_880;
 
-/// Tool to read image metadata and optionally return base64-encoded data.
+/// Tool to read image metadata and optionally show the image to the model.
 ///
-/// Since providers are currently text-only, this tool extracts what it can
-/// (file size, format, dimensions from header bytes) and provides base64
-/// data for future multimodal provider support.
+/// Extracts what it can from the file (size, format, dimensions from header
+/// bytes). With `attach`, the image itself is returned as a tool image so
+/// vision-capable providers can look at it.
 pub struct ImageInfoTool {
     security: Arc<SecurityPolicy>,
 }
@@ -125,7 +126,7 @@ impl Tool for ImageInfoTool {
     }
 
     fn description(&self) -> &str {
-        "Read image file metadata (format, dimensions, size) and optionally return base64-encoded data."
+        "Read image file metadata (format, dimensions, size). Set attach to look at the image itself."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -139,6 +140,10 @@ impl Tool for ImageInfoTool {
                 "include_base64": {
                     "type": "boolean",
                     "description": "Include base64-encoded image data in output (default: false)"
+                },
+                "attach": {
+                    "type": "boolean",
+                    "description": "Attach the image so the model can view it (default: false)"
                 }
             },
             "required": ["path"]
@@ -155,6 +160,10 @@ impl Tool for ImageInfoTool {
             .get("include_base64")
             .and_then(serde_json::Value::as_bool)
             .unwrap_or(false);
+        let attach = args
+            .get("attach")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false);
 
         let path = Path::new(path_str);
 
@@ -164,6 +173,7 @@ impl Tool for ImageInfoTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Path not allowed: {path_str} (must be within workspace)")),
+                images: Vec::new(),
             });
         }
 
@@ -172,6 +182,7 @@ impl Tool for ImageInfoTool {
                 success: false,
                 output: String::new(),
                 error: Some(format!("File not found: {path_str}")),
+                images: Vec::new(),
             });
         }
 
@@ -188,6 +199,7 @@ impl Tool for ImageInfoTool {
                 error: Some(format!(
                     "Image too large: {file_size} bytes (max {MAX_IMAGE_BYTES} bytes)"
                 )),
+                images: Vec::new(),
             });
         }
 
@@ -204,24 +216,35 @@ impl Tool for ImageInfoTool {
             let _ = write!(output, "\nDimensions: {w}x{h}");
         }
 
+        let mime = match format {
+            "png" => "image/png",
+            "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "webp" => "image/webp",
+            "bmp" => "image/bmp",
+            _ => "application/octet-stream",
+        };
+
         if include_base64 {
             use base64::Engine;
             let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
-            let mime = match format {
-                "png" => "image/png",
-                "jpeg" => "image/jpeg",
-                "gif" => "image/gif",
-                "webp" => "image/webp",
-                "bmp" => "image/bmp",
-                _ => "application/octet-stream",
-            };
             let _ = write!(output, "\ndata:{mime};base64,{encoded}");
         }
 
+        let mut images = Vec::new();
+        if attach {
+            if format == "unknown" {
+                output.push_str("\nNot attached: unrecognized image format");
+            } else {
+                images.push(ImageContent::from_bytes(mime, &bytes));
+            }
+        }
+
         Ok(ToolResult {
             success: true,
             output,
             error: None,
+            images,
         })
     }
 }
@@ -488,4 +511,28 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[tokio::test]
+    async fn execute_with_attach_returns_the_image() {
+        let dir = std::env::temp_dir().join("viziclaw_image_info_attach");
+        let _ = std::fs::create_dir_all(&dir);
+        let png_path = dir.join("test_attach.png");
+        std::fs::write(
+            &png_path,
+            [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00],
+        )
+        .unwrap();
+
+        let tool = ImageInfoTool::new(test_security());
+        let result = tool
+            .execute(json!({"path": png_path.to_string_lossy(), "attach": true}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(!result.output.contains("base64"));
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].media_type, "image/png");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,172 @@
+use super::traits::{Tool, ToolResult};
+use crate::memory::ingest::{collect_documents, ingest_documents};
+use crate::memory::Memory;
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// Let the agent chunk workspace documents into long-term memory
+pub struct MemoryIngestTool {
+    memory: Arc<dyn Memory>,
+    security: Arc<SecurityPolicy>,
+    chunk_max_tokens: usize,
+}
+
+impl MemoryIngestTool {
+    pub fn new(
+        memory: Arc<dyn Memory>,
+        security: Arc<SecurityPolicy>,
+        chunk_max_tokens: usize,
+    ) -> Self {
+        Self {
+            memory,
+            security,
+            chunk_max_tokens,
+        }
+    }
+
+    fn failure(error: String) -> ToolResult {
+        ToolResult {
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            images: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Tool for MemoryIngestTool {
+    fn name(&self) -> &str {
+        "memory_ingest"
+    }
+
+    fn description(&self) -> &str {
+        "Chunk Markdown, text or source files from the workspace into long-term memory so they can be recalled later. Re-ingesting skips unchanged files and replaces the chunks of edited ones."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "File, directory or glob pattern relative to the workspace (e.g. 'docs/runbooks', 'adr/*.md')"
+                }
+            },
+            "required": ["path"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let path = args
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing 'path' parameter"))?;
+
+        if !self.security.is_path_allowed(path) {
+            return Ok(Self::failure(format!(
+                "Path not allowed by security policy: {path}"
+            )));
+        }
+
+        let target = self.security.workspace_dir.join(path);
+        let mut set = match collect_documents(&target.to_string_lossy()) {
+            Ok(set) => set,
+            Err(e) => return Ok(Self::failure(e.to_string())),
+        };
+
+        // Globs and directories may reach through symlinks out of the workspace
+        let before = set.files.len();
+        set.files
+            .retain(|file| self.security.is_resolved_path_allowed(file));
+        if set
+            .root
+            .as_ref()
+            .is_some_and(|root| !self.security.is_resolved_path_allowed(root))
+        {
+            set.root = None;
+        }
+        if set.files.is_empty() {
+            return Ok(Self::failure(format!(
+                "No ingestible documents found at {path}"
+            )));
+        }
+
+        match ingest_documents(
+            self.memory.as_ref(),
+            &self.security.workspace_dir,
+            &set,
+            self.chunk_max_tokens,
+        )
+        .await
+        {
+            Ok(report) => {
+                let mut output = format!("Ingested {path}: {}", report.summary());
+                let outside = before - set.files.len();
+                if outside > 0 {
+                    let _ = write!(
+                        output,
+                        "\nSkipped {outside} file(s) resolving outside the workspace"
+                    );
+                }
+                Ok(ToolResult {
+                    success: report.failed.is_empty(),
+                    output,
+                    error: None,
+                    images: Vec::new(),
+                })
+            }
+            Err(e) => Ok(Self::failure(format!("Failed to ingest {path}: {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Arc<dyn Memory>, MemoryIngestTool) {
+        let tmp = TempDir::new().unwrap();
+        let mem: Arc<dyn Memory> = Arc::new(SqliteMemory::new(tmp.path()).unwrap());
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        });
+        let tool = MemoryIngestTool::new(mem.clone(), security, 512);
+        (tmp, mem, tool)
+    }
+
+    #[test]
+    fn name_and_schema() {
+        let (_tmp, _mem, tool) = setup();
+        assert_eq!(tool.name(), "memory_ingest");
+        assert!(tool.parameters_schema()["properties"]["path"].is_object());
+    }
+
+    #[tokio::test]
+    async fn ingests_a_workspace_directory() {
+        let (tmp, mem, tool) = setup();
+        std::fs::create_dir_all(tmp.path().join("docs")).unwrap();
+        std::fs::write(tmp.path().join("docs/ops.md"), "# Ops\nRestart the worker.").unwrap();
+
+        let result = tool.execute(json!({"path": "docs"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.contains("1 new"));
+        assert!(mem.get("doc:docs/ops.md#0").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn rejects_paths_outside_the_workspace() {
+        let (_tmp, _mem, tool) = setup();
+        let result = tool.execute(json!({"path": "../etc"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+}
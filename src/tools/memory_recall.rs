@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::{Memory, RecallFilter};
 use async_trait::async_trait;
 use serde_json::json;
 use std::fmt::Write;
@@ -23,7 +23,7 @@ impl Tool for MemoryRecallTool {
     }
 
     fn description(&self) -> &str {
-        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance."
+        "Search long-term memory for relevant facts, preferences, or context. Returns scored results ranked by relevance; optionally filter by tags, source or creation time."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -37,6 +37,23 @@ impl Tool for MemoryRecallTool {
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only memories carrying all of these tags"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Only memories from this source or source prefix (e.g. 'agent', 'telegram')"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only memories created at or after this time (RFC 3339 or YYYY-MM-DD)"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Only memories created at or before this time (RFC 3339 or YYYY-MM-DD)"
                 }
             },
             "required": ["query"]
@@ -55,11 +72,49 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit).await {
+        let time_bound = |name: &str| {
+            args.get(name)
+                .and_then(|v| v.as_str())
+                .map(RecallFilter::parse_time)
+                .transpose()
+        };
+        let (since, until) = match (time_bound("since"), time_bound("until")) {
+            (Ok(since), Ok(until)) => (since, until),
+            (Err(e), _) | (_, Err(e)) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    images: Vec::new(),
+                })
+            }
+        };
+        let filter = RecallFilter {
+            tags: args
+                .get("tags")
+                .and_then(|v| v.as_array())
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(|t| t.as_str())
+                        .map(ToString::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            source: args
+                .get("source")
+                .and_then(|v| v.as_str())
+                .map(ToString::to_string),
+            session_id: None,
+            since,
+            until,
+        };
+
+        match self.memory.recall_filtered(query, limit, &filter).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
                 error: None,
+                images: Vec::new(),
             }),
             Ok(entries) => {
                 let mut output = format!("Found {} memories:\n", entries.len());
@@ -77,12 +132,14 @@ impl Tool for MemoryRecallTool {
                     success: true,
                     output,
                     error: None,
+                    images: Vec::new(),
                 })
             }
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Memory recall failed: {e}")),
+                images: Vec::new(),
             }),
         }
     }
@@ -91,7 +148,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::{MemoryCategory, MemoryMetadata, SqliteMemory};
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -148,6 +205,44 @@ mod tests {
         assert!(result.output.contains("Found 3"));
     }
 
+    #[tokio::test]
+    async fn recall_filters_by_tag() {
+        let (_tmp, mem) = seeded_mem();
+        mem.store_with_metadata(
+            "deploy_prod",
+            "Deploy prod with the blue-green script",
+            MemoryCategory::Core,
+            &MemoryMetadata {
+                tags: vec!["prod".into()],
+                ..MemoryMetadata::default()
+            },
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "deploy_staging",
+            "Deploy staging with make deploy",
+            MemoryCategory::Core,
+        )
+        .await
+        .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "Deploy", "tags": ["prod"]}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("deploy_prod"));
+
+        let result = tool
+            .execute(json!({"query": "Deploy", "since": "yesterday"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+    }
+
     #[tokio::test]
     async fn recall_missing_query() {
         let (_tmp, mem) = seeded_mem();
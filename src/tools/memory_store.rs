@@ -1,5 +1,6 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::{Memory, MemoryCategory};
+use crate::memory::{Memory, MemoryCategory, MemoryMetadata};
+use crate::util::parse_interval;
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -41,6 +42,19 @@ impl Tool for MemoryStoreTool {
                     "type": "string",
                     "enum": ["core", "daily", "conversation"],
                     "description": "Memory category: core (permanent), daily (session), conversation (chat)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels to filter recall by later (e.g. ['project-x', 'deadline'])"
+                },
+                "importance": {
+                    "type": "number",
+                    "description": "0.0–1.0; important memories rank higher in recall (default: 0.5)"
+                },
+                "ttl": {
+                    "type": "string",
+                    "description": "Forget the memory after this long, e.g. '30m', '12h', '7d' (default: never)"
                 }
             },
             "required": ["key", "content"]
@@ -64,16 +78,49 @@ impl Tool for MemoryStoreTool {
             _ => MemoryCategory::Core,
         };
 
-        match self.memory.store(key, content, category).await {
+        let mut metadata = MemoryMetadata::with_source("agent");
+        metadata.tags = args
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|t| t.as_str())
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        metadata.importance = args.get("importance").and_then(serde_json::Value::as_f64);
+        if let Some(ttl) = args.get("ttl").and_then(|v| v.as_str()) {
+            let Some(ttl) = parse_interval(ttl).and_then(|d| chrono::Duration::from_std(d).ok())
+            else {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!(
+                        "Invalid ttl '{ttl}': use a number followed by m, h or d"
+                    )),
+                    images: Vec::new(),
+                });
+            };
+            metadata.expires_at = Some((chrono::Utc::now() + ttl).to_rfc3339());
+        }
+
+        match self
+            .memory
+            .store_with_metadata(key, content, category, &metadata)
+            .await
+        {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
                 error: None,
+                images: Vec::new(),
             }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to store memory: {e}")),
+                images: Vec::new(),
             }),
         }
     }
@@ -128,6 +175,48 @@ mod tests {
         assert!(result.success);
     }
 
+    #[tokio::test]
+    async fn store_with_metadata() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone());
+        let result = tool
+            .execute(json!({
+                "key": "freeze",
+                "content": "Release freeze starts Friday",
+                "tags": ["release", "deadline"],
+                "importance": 0.9,
+                "ttl": "7d"
+            }))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+
+        let entry = mem.get("freeze").await.unwrap().unwrap();
+        assert_eq!(entry.tags, ["release", "deadline"]);
+        assert_eq!(entry.source.as_deref(), Some("agent"));
+        assert_eq!(entry.importance, Some(0.9));
+        assert!(entry.expires_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn store_rejects_bad_ttl_and_importance() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone());
+        let result = tool
+            .execute(json!({"key": "a", "content": "x", "ttl": "soon"}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("Invalid ttl"));
+
+        let result = tool
+            .execute(json!({"key": "b", "content": "x", "importance": 3.0}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(mem.get("b").await.unwrap().is_none());
+    }
+
     #[tokio::test]
     async fn store_missing_key() {
         let (_tmp, mem) = test_mem();
@@ -5,10 +5,14 @@ pub mod file_read;
 pub mod file_write;
 pub mod image_info;
 pub mod memory_forget;
+pub mod memory_ingest;
 pub mod memory_recall;
 pub mod memory_store;
+pub mod schedule;
 pub mod screenshot;
+pub mod send_file;
 pub mod shell;
+pub mod skill;
 pub mod traits;
 
 pub use browser::BrowserTool;
@@ -18,10 +22,14 @@ pub use file_read::FileReadTool;
 pub use file_write::FileWriteTool;
 pub use image_info::ImageInfoTool;
 pub use memory_forget::MemoryForgetTool;
+pub use memory_ingest::MemoryIngestTool;
 pub use memory_recall::MemoryRecallTool;
 pub use memory_store::MemoryStoreTool;
+pub use schedule::ScheduleTool;
 pub use screenshot::ScreenshotTool;
+pub use send_file::SendFileTool;
 pub use shell::ShellTool;
+pub use skill::SkillToolAdapter;
 pub use traits::Tool;
 #[allow(unused_imports)]
 pub use traits::{ToolResult, ToolSpec};
@@ -108,6 +116,79 @@ pub fn all_tools_with_runtime(
     tools
 }
 
+/// Tool registry for an agent run from `config`: the full registry, the
+/// agent's own `schedule`, `send_file` and `memory_ingest` tools, then the tools
+/// declared by `skills`. Skills come last so they cannot shadow a native tool.
+pub fn agent_tools(
+    config: &Arc<crate::config::Config>,
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn RuntimeAdapter>,
+    memory: Arc<dyn Memory>,
+    skills: &[crate::skills::Skill],
+) -> Vec<Box<dyn Tool>> {
+    let composio_key = if config.composio.enabled {
+        config.composio.api_key.as_deref()
+    } else {
+        None
+    };
+    let mut tools = all_tools_with_runtime(
+        security,
+        runtime.clone(),
+        memory.clone(),
+        composio_key,
+        &config.browser,
+    );
+    tools.push(Box::new(ScheduleTool::new(
+        Arc::clone(config),
+        security.clone(),
+    )));
+    tools.push(Box::new(SendFileTool::new(security.clone())));
+    tools.push(Box::new(MemoryIngestTool::new(
+        memory,
+        security.clone(),
+        config.memory.chunk_max_tokens,
+    )));
+    add_skill_tools(&mut tools, skills, security, runtime);
+    tools
+}
+
+/// Register the tools declared by `skills`. Tools with an unsupported kind,
+/// or whose name is already taken, are skipped with a warning.
+pub fn add_skill_tools(
+    tools: &mut Vec<Box<dyn Tool>>,
+    skills: &[crate::skills::Skill],
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn RuntimeAdapter>,
+) {
+    for skill in skills {
+        for tool in &skill.tools {
+            if !skill::SKILL_TOOL_KINDS.contains(&tool.kind.as_str()) {
+                tracing::warn!(
+                    "Skipping tool '{}' from skill '{}': unsupported kind '{}'",
+                    tool.name,
+                    skill.name,
+                    tool.kind
+                );
+                continue;
+            }
+            if tools.iter().any(|t| t.name() == tool.name) {
+                tracing::warn!(
+                    "Skipping tool '{}' from skill '{}': name already in use",
+                    tool.name,
+                    skill.name
+                );
+                continue;
+            }
+            tools.push(Box::new(SkillToolAdapter::new(
+                skill,
+                tool.clone(),
+                security.clone(),
+                runtime.clone(),
+            )));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +256,78 @@ mod tests {
         assert!(names.contains(&"file_write"));
     }
 
+    #[test]
+    fn skill_tools_are_registered_without_shadowing() {
+        let security = Arc::new(SecurityPolicy::default());
+        let runtime: Arc<dyn RuntimeAdapter> = Arc::new(NativeRuntime::new());
+        let tool = |name: &str, kind: &str| crate::skills::SkillTool {
+            name: name.into(),
+            description: format!("{name} tool"),
+            kind: kind.into(),
+            command: "echo hi".into(),
+            args: std::collections::HashMap::new(),
+            method: None,
+            body: None,
+            headers: std::collections::HashMap::new(),
+        };
+        let skill = crate::skills::Skill {
+            name: "demo".into(),
+            description: "Demo skill".into(),
+            version: "0.1.0".into(),
+            author: None,
+            tags: vec![],
+            tools: vec![
+                tool("greet", "shell"),
+                tool("shell", "shell"),
+                tool("mystery", "wasm"),
+            ],
+            prompts: vec![],
+            location: None,
+        };
+
+        let mut tools = default_tools(security.clone());
+        add_skill_tools(&mut tools, &[skill], &security, &runtime);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["shell", "file_read", "file_write", "greet"]);
+        assert_eq!(tools[3].description(), "greet tool");
+    }
+
+    #[test]
+    fn skill_tools_cannot_take_agent_tool_names() {
+        let tmp = TempDir::new().unwrap();
+        let config = Arc::new(crate::config::Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..crate::config::Config::default()
+        });
+        let security = Arc::new(SecurityPolicy::default());
+        let runtime: Arc<dyn RuntimeAdapter> = Arc::new(NativeRuntime::new());
+        let mem: Arc<dyn Memory> = Arc::new(crate::memory::MarkdownMemory::new(tmp.path()));
+        let skill = crate::skills::Skill {
+            name: "sneaky".into(),
+            description: "Shadows a native tool".into(),
+            version: "0.1.0".into(),
+            author: None,
+            tags: vec![],
+            tools: vec![crate::skills::SkillTool {
+                name: "schedule".into(),
+                description: "Not the real scheduler".into(),
+                kind: "shell".into(),
+                command: "echo hi".into(),
+                args: std::collections::HashMap::new(),
+                method: None,
+                body: None,
+                headers: std::collections::HashMap::new(),
+            }],
+            prompts: vec![],
+            location: None,
+        };
+
+        let tools = agent_tools(&config, &security, &runtime, mem, &[skill]);
+        let schedule: Vec<_> = tools.iter().filter(|t| t.name() == "schedule").collect();
+        assert_eq!(schedule.len(), 1);
+        assert_ne!(schedule[0].description(), "Not the real scheduler");
+    }
+
     #[test]
     fn default_tools_all_have_descriptions() {
         let security = Arc::new(SecurityPolicy::default());
@@ -225,6 +378,7 @@ mod tests {
             success: true,
             output: "hello".into(),
             error: None,
+            images: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
@@ -239,6 +393,7 @@ mod tests {
             success: false,
             output: String::new(),
             error: Some("boom".into()),
+            images: Vec::new(),
         };
         let json = serde_json::to_string(&result).unwrap();
         let parsed: ToolResult = serde_json::from_str(&json).unwrap();
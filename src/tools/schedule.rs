@@ -0,0 +1,419 @@
+use super::traits::{Tool, ToolResult};
+use crate::config::Config;
+use crate::cron::{self, schedule, CronJob, Delivery, JobKind, JobUpdate, ScheduleOptions};
+use crate::security::approval::ApprovalRoute;
+use crate::security::SecurityPolicy;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use std::sync::Arc;
+
+/// Let the agent manage its own scheduled jobs. Each job belongs to the
+/// conversation that created it, which is also where agent jobs send their
+/// answer, so "remind me in two hours" comes back to the same chat.
+pub struct ScheduleTool {
+    config: Arc<Config>,
+    security: Arc<SecurityPolicy>,
+}
+
+impl ScheduleTool {
+    pub fn new(config: Arc<Config>, security: Arc<SecurityPolicy>) -> Self {
+        Self { config, security }
+    }
+
+    /// The conversation the current turn came from. Turns run outside any
+    /// route (e.g. through the gateway) share one owner.
+    fn owner() -> Delivery {
+        let route = ApprovalRoute::current().unwrap_or_else(|| ApprovalRoute::new("agent", ""));
+        Delivery {
+            channel: route.channel,
+            recipient: route.recipient,
+        }
+    }
+
+    /// Jobs created by `owner`, soonest first.
+    fn owned_jobs(&self, owner: &Delivery) -> Result<Vec<CronJob>> {
+        Ok(cron::list_jobs(&self.config)?
+            .into_iter()
+            .filter(|job| job.owner.as_ref() == Some(owner))
+            .collect())
+    }
+
+    fn owned_job(&self, owner: &Delivery, args: &serde_json::Value) -> Result<CronJob> {
+        let id = str_arg(args, "id").ok_or_else(|| anyhow::anyhow!("Missing 'id' parameter"))?;
+        self.owned_jobs(owner)?
+            .into_iter()
+            .find(|job| job.id == id)
+            .ok_or_else(|| anyhow::anyhow!("No scheduled job '{id}' in this conversation"))
+    }
+
+    fn list(&self, owner: &Delivery) -> Result<String> {
+        let jobs = self.owned_jobs(owner)?;
+        if jobs.is_empty() {
+            return Ok("No scheduled jobs.".into());
+        }
+        let lines: Vec<String> = jobs.iter().map(describe).collect();
+        Ok(lines.join("\n"))
+    }
+
+    fn create(&self, owner: Delivery, args: &serde_json::Value) -> Result<String> {
+        let task =
+            str_arg(args, "task").ok_or_else(|| anyhow::anyhow!("Missing 'task' parameter"))?;
+        let kind = match str_arg(args, "type").unwrap_or("agent") {
+            "agent" => JobKind::Agent,
+            "shell" => JobKind::Shell,
+            other => anyhow::bail!("Unknown job type '{other}' (expected agent or shell)"),
+        };
+        if kind == JobKind::Shell {
+            self.security
+                .validate_command_execution(task, false)
+                .map_err(|e| anyhow::anyhow!(e))?;
+        }
+
+        let limit = self.security.max_scheduled_jobs as usize;
+        if self.owned_jobs(&owner)?.len() >= limit {
+            anyhow::bail!(
+                "Scheduled job limit reached for this conversation ({limit}); cancel a job first"
+            );
+        }
+
+        let timezone = str_arg(args, "timezone").map(str::to_string);
+        let expression = expression_arg(args, timezone.as_deref())?
+            .ok_or_else(|| anyhow::anyhow!("Give one of 'schedule', 'at' or 'in'"))?;
+        // Answers go back to the conversation when it is on a configured channel
+        let delivery = (kind == JobKind::Agent
+            && crate::channels::configured_channel(&self.config, &owner.channel).is_some())
+        .then(|| owner.clone());
+        let options = ScheduleOptions {
+            timezone,
+            owner: Some(owner),
+            ..ScheduleOptions::default()
+        };
+
+        let job = cron::create_job(&self.config, &expression, kind, task, delivery, &options)?;
+        Ok(format!("Scheduled {}", describe(&job)))
+    }
+
+    fn update(&self, owner: &Delivery, args: &serde_json::Value) -> Result<String> {
+        let job = self.owned_job(owner, args)?;
+        let command = str_arg(args, "task").map(str::to_string);
+        if let Some(command) = &command {
+            if job.kind == JobKind::Shell {
+                self.security
+                    .validate_command_execution(command, false)
+                    .map_err(|e| anyhow::anyhow!(e))?;
+            }
+        }
+        let timezone = str_arg(args, "timezone").map(str::to_string);
+        let update = JobUpdate {
+            expression: expression_arg(args, timezone.as_deref().or(job.timezone.as_deref()))?,
+            command,
+            timezone,
+        };
+
+        let mut job = cron::update_job(&self.config, &job.id, &update)?;
+        if let Some(paused) = args.get("paused").and_then(serde_json::Value::as_bool) {
+            cron::set_paused(&self.config, &job.id, paused)?;
+            job = cron::get_job(&self.config, &job.id)?;
+        }
+        Ok(format!("Updated {}", describe(&job)))
+    }
+
+    fn cancel(&self, owner: &Delivery, args: &serde_json::Value) -> Result<String> {
+        let job = self.owned_job(owner, args)?;
+        cron::remove_job(&self.config, &job.id)?;
+        Ok(format!("Cancelled job {}", job.id))
+    }
+}
+
+#[async_trait]
+impl Tool for ScheduleTool {
+    fn name(&self) -> &str {
+        "schedule"
+    }
+
+    fn description(&self) -> &str {
+        "List, create, update or cancel scheduled jobs for this conversation. Agent jobs run the task as a prompt at the scheduled time and send the answer back here — for a reminder, use a task like 'Remind the user to call Sam'. Shell jobs run an allowed command."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "action": {
+                    "type": "string",
+                    "enum": ["list", "create", "update", "cancel"],
+                    "description": "What to do"
+                },
+                "id": {
+                    "type": "string",
+                    "description": "Job id, for update and cancel"
+                },
+                "task": {
+                    "type": "string",
+                    "description": "Prompt for agent jobs, or command for shell jobs"
+                },
+                "type": {
+                    "type": "string",
+                    "enum": ["agent", "shell"],
+                    "description": "Job type for create (default agent)"
+                },
+                "schedule": {
+                    "type": "string",
+                    "description": "Recurring schedule: 5-field cron expression (e.g. '0 9 * * 1-5') or 'every 15m' / 'every 2h' / 'every 1d'"
+                },
+                "at": {
+                    "type": "string",
+                    "description": "Run once at this local time, e.g. '2026-11-01T09:00'"
+                },
+                "in": {
+                    "type": "string",
+                    "description": "Run once after this delay, e.g. '30m', '2h', '1d'"
+                },
+                "timezone": {
+                    "type": "string",
+                    "description": "IANA timezone for 'schedule' and 'at', e.g. 'Europe/Berlin' (default UTC)"
+                },
+                "paused": {
+                    "type": "boolean",
+                    "description": "Pause or resume the job, for update"
+                }
+            },
+            "required": ["action"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let action = str_arg(&args, "action")
+            .ok_or_else(|| anyhow::anyhow!("Missing 'action' parameter"))?;
+        let owner = Self::owner();
+
+        if action != "list" {
+            if !self.security.can_act() {
+                return Ok(failure("Action blocked: autonomy is read-only"));
+            }
+            if !self.security.record_action() {
+                return Ok(failure("Action blocked: rate limit exceeded"));
+            }
+        }
+
+        let result = match action {
+            "list" => self.list(&owner),
+            "create" => self.create(owner, &args),
+            "update" => self.update(&owner, &args),
+            "cancel" => self.cancel(&owner, &args),
+            other => Err(anyhow::anyhow!("Unknown action '{other}'")),
+        };
+        Ok(match result {
+            Ok(output) => ToolResult {
+                success: true,
+                output,
+                error: None,
+                images: Vec::new(),
+            },
+            Err(e) => failure(e.to_string()),
+        })
+    }
+}
+
+fn str_arg<'a>(args: &'a serde_json::Value, name: &str) -> Option<&'a str> {
+    args.get(name)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// The job expression from `schedule`, `at` or `in`, if one was given.
+fn expression_arg(args: &serde_json::Value, timezone: Option<&str>) -> Result<Option<String>> {
+    match (
+        str_arg(args, "schedule"),
+        str_arg(args, "at"),
+        str_arg(args, "in"),
+    ) {
+        (Some(expression), None, None) => Ok(Some(expression.to_string())),
+        (None, Some(at), None) => {
+            schedule::one_shot_at(at, schedule::parse_timezone(timezone)?).map(Some)
+        }
+        (None, None, Some(delay)) => schedule::one_shot_in(delay, Utc::now()).map(Some),
+        (None, None, None) => Ok(None),
+        _ => anyhow::bail!("Give only one of 'schedule', 'at' or 'in'"),
+    }
+}
+
+fn describe(job: &CronJob) -> String {
+    let paused = if job.paused { " [paused]" } else { "" };
+    let timezone = job
+        .timezone
+        .as_deref()
+        .map_or_else(String::new, |tz| format!(" ({tz})"));
+    format!(
+        "job {}{paused}: {} '{}' | {}{timezone} | next {}",
+        job.id,
+        job.kind.as_str(),
+        job.command,
+        job.expression,
+        job.next_run.to_rfc3339()
+    )
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+        images: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::AutonomyLevel;
+    use tempfile::TempDir;
+
+    fn test_tool(tmp: &TempDir, security: SecurityPolicy) -> ScheduleTool {
+        let config = Config {
+            workspace_dir: tmp.path().join("workspace"),
+            config_path: tmp.path().join("config.toml"),
+            ..Config::default()
+        };
+        std::fs::create_dir_all(&config.workspace_dir).unwrap();
+        ScheduleTool::new(Arc::new(config), Arc::new(security))
+    }
+
+    async fn run_as(
+        tool: &ScheduleTool,
+        channel: &str,
+        recipient: &str,
+        args: serde_json::Value,
+    ) -> ToolResult {
+        ApprovalRoute::new(channel, recipient)
+            .scope(tool.execute(args))
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn reminder_is_owned_by_the_conversation() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp, SecurityPolicy::default());
+
+        let created = run_as(
+            &tool,
+            "telegram",
+            "42",
+            json!({"action": "create", "in": "2h", "task": "Remind the user to call Sam"}),
+        )
+        .await;
+        assert!(created.success, "{:?}", created.error);
+
+        let jobs = cron::list_jobs(&tool.config).unwrap();
+        assert_eq!(jobs.len(), 1);
+        let job = &jobs[0];
+        assert_eq!(job.kind, JobKind::Agent);
+        assert!(job.expression.starts_with("at "));
+        let owner = job.owner.as_ref().unwrap();
+        assert_eq!(
+            (owner.channel.as_str(), owner.recipient.as_str()),
+            ("telegram", "42")
+        );
+
+        let mine = run_as(&tool, "telegram", "42", json!({"action": "list"})).await;
+        assert!(mine.output.contains(&job.id));
+        let theirs = run_as(&tool, "telegram", "7", json!({"action": "list"})).await;
+        assert_eq!(theirs.output, "No scheduled jobs.");
+
+        let cancel = json!({"action": "cancel", "id": job.id});
+        assert!(!run_as(&tool, "telegram", "7", cancel.clone()).await.success);
+        assert!(run_as(&tool, "telegram", "42", cancel).await.success);
+        assert!(cron::list_jobs(&tool.config).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn update_changes_schedule_and_pauses() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(&tmp, SecurityPolicy::default());
+        run_as(
+            &tool,
+            "cli",
+            "user",
+            json!({"action": "create", "schedule": "0 9 * * *", "task": "Morning briefing"}),
+        )
+        .await;
+        let id = cron::list_jobs(&tool.config).unwrap()[0].id.clone();
+
+        let result = run_as(
+            &tool,
+            "cli",
+            "user",
+            json!({
+                "action": "update",
+                "id": id,
+                "schedule": "every 30m",
+                "timezone": "Asia/Tokyo",
+                "paused": true
+            }),
+        )
+        .await;
+        assert!(result.success, "{:?}", result.error);
+
+        let job = cron::get_job(&tool.config, &id).unwrap();
+        assert_eq!(job.expression, "every 30m");
+        assert_eq!(job.timezone.as_deref(), Some("Asia/Tokyo"));
+        assert!(job.paused);
+        assert_eq!(job.command, "Morning briefing");
+    }
+
+    #[tokio::test]
+    async fn policy_limits_what_can_be_scheduled() {
+        let tmp = TempDir::new().unwrap();
+        let tool = test_tool(
+            &tmp,
+            SecurityPolicy {
+                max_scheduled_jobs: 1,
+                ..SecurityPolicy::default()
+            },
+        );
+
+        let blocked = run_as(
+            &tool,
+            "cli",
+            "user",
+            json!({"action": "create", "type": "shell", "schedule": "every 1h", "task": "curl https://example.com"}),
+        )
+        .await;
+        assert!(!blocked.success);
+        assert!(blocked.error.unwrap().contains("not allowed"));
+
+        let allowed =
+            json!({"action": "create", "type": "shell", "schedule": "every 1h", "task": "echo ok"});
+        assert!(run_as(&tool, "cli", "user", allowed.clone()).await.success);
+        let over_limit = run_as(&tool, "cli", "user", allowed.clone()).await;
+        assert!(over_limit.error.unwrap().contains("limit reached"));
+        // The limit is per conversation; other chats keep their own quota
+        assert!(run_as(&tool, "telegram", "other", allowed).await.success);
+
+        let tmp = TempDir::new().unwrap();
+        let read_only = test_tool(
+            &tmp,
+            SecurityPolicy {
+                autonomy: AutonomyLevel::ReadOnly,
+                ..SecurityPolicy::default()
+            },
+        );
+        let result = run_as(
+            &read_only,
+            "cli",
+            "user",
+            json!({"action": "create", "in": "1h", "task": "hello"}),
+        )
+        .await;
+        assert!(result.error.unwrap().contains("read-only"));
+        assert!(
+            run_as(&read_only, "cli", "user", json!({"action": "list"}))
+                .await
+                .success
+        );
+    }
+}
@@ -1,16 +1,15 @@
 use super::traits::{Tool, ToolResult};
+use crate::providers::traits::MAX_IMAGE_FILE_BYTES;
+use crate::providers::ImageContent;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
-use std::fmt::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 /// Maximum time to wait for a screenshot command to complete.
 const SCREENSHOT_TIMEOUT_SECS: u64 = 15;
-/// Maximum base64 payload size to return (2 MB of base64 ≈ 1.5 MB image).
-const MAX_BASE64_BYTES: usize = 2_097_152;
 
 /// Tool for capturing screenshots using platform-native commands.
 ///
@@ -76,6 +75,7 @@ impl ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Screenshot not supported on this platform".into()),
+                images: Vec::new(),
             });
         };
 
@@ -111,21 +111,24 @@ impl ScreenshotTool {
                                 "No screenshot tool found. Install gnome-screenshot, scrot, or ImageMagick."
                                     .into(),
                             ),
+                            images: Vec::new(),
                         });
                     }
                     return Ok(ToolResult {
                         success: false,
                         output: String::new(),
                         error: Some(format!("Screenshot command failed: {stderr}")),
+                        images: Vec::new(),
                     });
                 }
 
-                Self::read_and_encode(&output_path).await
+                Self::attach_screenshot(&output_path).await
             }
             Ok(Err(e)) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some(format!("Failed to execute screenshot command: {e}")),
+                images: Vec::new(),
             }),
             Err(_) => Ok(ToolResult {
                 success: false,
@@ -133,67 +136,39 @@ impl ScreenshotTool {
                 error: Some(format!(
                     "Screenshot timed out after {SCREENSHOT_TIMEOUT_SECS}s"
                 )),
+                images: Vec::new(),
             }),
         }
     }
 
-    /// Read the screenshot file and return base64-encoded result.
-    async fn read_and_encode(output_path: &std::path::Path) -> anyhow::Result<ToolResult> {
-        // Check file size before reading to prevent OOM on large screenshots
-        const MAX_RAW_BYTES: u64 = 1_572_864; // ~1.5 MB (base64 expands ~33%)
-        if let Ok(meta) = tokio::fs::metadata(output_path).await {
-            if meta.len() > MAX_RAW_BYTES {
-                return Ok(ToolResult {
-                    success: true,
-                    output: format!(
-                        "Screenshot saved to: {}\nSize: {} bytes (too large to base64-encode inline)",
-                        output_path.display(),
-                        meta.len(),
-                    ),
-                    error: None,
-                });
-            }
-        }
-
-        match tokio::fs::read(output_path).await {
-            Ok(bytes) => {
-                use base64::Engine;
-                let size = bytes.len();
-                let mut encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
-                let truncated = if encoded.len() > MAX_BASE64_BYTES {
-                    encoded.truncate(encoded.floor_char_boundary(MAX_BASE64_BYTES));
-                    true
-                } else {
-                    false
-                };
-
-                let mut output_msg = format!(
-                    "Screenshot saved to: {}\nSize: {size} bytes\nBase64 length: {}",
+    /// Report the saved screenshot and attach it so vision models can see it.
+    async fn attach_screenshot(output_path: &std::path::Path) -> anyhow::Result<ToolResult> {
+        match tokio::fs::metadata(output_path).await {
+            Ok(meta) if meta.len() > MAX_IMAGE_FILE_BYTES => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Screenshot saved to: {}\nSize: {} bytes (too large to attach as an image)",
                     output_path.display(),
-                    encoded.len(),
-                );
-                if truncated {
-                    output_msg.push_str(" (truncated)");
-                }
-                let mime = match output_path.extension().and_then(|e| e.to_str()) {
-                    Some("jpg" | "jpeg") => "image/jpeg",
-                    Some("bmp") => "image/bmp",
-                    Some("gif") => "image/gif",
-                    Some("webp") => "image/webp",
-                    _ => "image/png",
-                };
-                let _ = write!(output_msg, "\ndata:{mime};base64,{encoded}");
-
-                Ok(ToolResult {
-                    success: true,
-                    output: output_msg,
-                    error: None,
-                })
-            }
+                    meta.len(),
+                ),
+                error: None,
+                images: Vec::new(),
+            }),
+            Ok(meta) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Screenshot saved to: {}\nSize: {} bytes\nThe screenshot is attached as an image.",
+                    output_path.display(),
+                    meta.len(),
+                ),
+                error: None,
+                images: vec![ImageContent::file(output_path)],
+            }),
             Err(e) => Ok(ToolResult {
                 success: false,
                 output: format!("Screenshot saved to: {}", output_path.display()),
                 error: Some(format!("Failed to read screenshot file: {e}")),
+                images: Vec::new(),
             }),
         }
     }
@@ -206,7 +181,7 @@ impl Tool for ScreenshotTool {
     }
 
     fn description(&self) -> &str {
-        "Capture a screenshot of the current screen. Returns the file path and base64-encoded PNG data."
+        "Capture a screenshot of the current screen. Returns the file path and attaches the image so it can be viewed."
     }
 
     fn parameters_schema(&self) -> serde_json::Value {
@@ -231,6 +206,7 @@ impl Tool for ScreenshotTool {
                 success: false,
                 output: String::new(),
                 error: Some("Action blocked: autonomy is read-only".into()),
+                images: Vec::new(),
             });
         }
         self.capture(args).await
@@ -297,4 +273,17 @@ mod tests {
             "Command should contain the output path"
         );
     }
+
+    #[tokio::test]
+    async fn saved_screenshot_is_attached_as_an_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.png");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+
+        let result = ScreenshotTool::attach_screenshot(&path).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.contains("shot.png"));
+        assert_eq!(result.images.len(), 1);
+        assert_eq!(result.images[0].media_type, "image/png");
+    }
 }
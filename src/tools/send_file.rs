@@ -0,0 +1,271 @@
+use super::traits::{Tool, ToolResult};
+use crate::channels::traits::{Attachment, OutgoingMessage};
+use crate::security::approval::{broker, ApprovalRoute};
+use crate::security::SecurityPolicy;
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Largest file the tool will send; most chat platforms reject bigger uploads.
+const MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Send workspace files (screenshots, reports, generated artifacts) back to
+/// the conversation the current turn came from.
+pub struct SendFileTool {
+    security: Arc<SecurityPolicy>,
+}
+
+impl SendFileTool {
+    pub fn new(security: Arc<SecurityPolicy>) -> Self {
+        Self { security }
+    }
+
+    /// Resolve a workspace-relative path, refusing anything outside the
+    /// workspace or over the size limit.
+    async fn resolve(&self, path: &str) -> Result<PathBuf, String> {
+        if !self.security.is_path_allowed(path) {
+            return Err(format!("Path not allowed by security policy: {path}"));
+        }
+        let resolved = tokio::fs::canonicalize(self.security.workspace_dir.join(path))
+            .await
+            .map_err(|e| format!("Failed to resolve {path}: {e}"))?;
+        if !self.security.is_resolved_path_allowed(&resolved) {
+            return Err(format!(
+                "Resolved path escapes workspace: {}",
+                resolved.display()
+            ));
+        }
+        let meta = tokio::fs::metadata(&resolved)
+            .await
+            .map_err(|e| format!("Failed to read metadata of {path}: {e}"))?;
+        if !meta.is_file() {
+            return Err(format!("Not a file: {path}"));
+        }
+        if meta.len() > MAX_ATTACHMENT_BYTES {
+            return Err(format!(
+                "File too large: {path} is {} bytes (limit: {MAX_ATTACHMENT_BYTES} bytes)",
+                meta.len()
+            ));
+        }
+        Ok(resolved)
+    }
+}
+
+#[async_trait]
+impl Tool for SendFileTool {
+    fn name(&self) -> &str {
+        "send_file"
+    }
+
+    fn description(&self) -> &str {
+        "Send one or more workspace files (screenshots, images, reports, generated files) to the user in this conversation, with an optional message and caption."
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Relative paths of the files within the workspace"
+                },
+                "message": {
+                    "type": "string",
+                    "description": "Text sent along with the files"
+                },
+                "caption": {
+                    "type": "string",
+                    "description": "Caption for the files"
+                }
+            },
+            "required": ["paths"]
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let paths: Vec<&str> = args
+            .get("paths")
+            .and_then(|v| v.as_array())
+            .map(|paths| paths.iter().filter_map(|p| p.as_str()).collect())
+            .unwrap_or_default();
+        if paths.is_empty() {
+            anyhow::bail!("Missing 'paths' parameter");
+        }
+
+        let Some(route) = ApprovalRoute::current() else {
+            return Ok(failure(
+                "No conversation to send files to: this turn did not come from a channel",
+            ));
+        };
+        let Some(channel) = broker().channel(&route.channel) else {
+            return Ok(failure(format!(
+                "Channel '{}' is not running",
+                route.channel
+            )));
+        };
+        if !self.security.can_act() {
+            return Ok(failure("Action blocked: autonomy is read-only"));
+        }
+        if !self.security.record_action() {
+            return Ok(failure("Action blocked: rate limit exceeded"));
+        }
+
+        let caption = args.get("caption").and_then(|v| v.as_str());
+        let mut message = OutgoingMessage::text(
+            args.get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default(),
+        );
+        for path in &paths {
+            let resolved = match self.resolve(path).await {
+                Ok(resolved) => resolved,
+                Err(e) => return Ok(failure(e)),
+            };
+            let mut attachment = Attachment::from_path(resolved);
+            if let Some(caption) = caption {
+                attachment = attachment.with_caption(caption);
+            }
+            message = message.with_attachment(attachment);
+        }
+
+        match channel.send_rich(&message, &route.recipient).await {
+            Ok(()) => Ok(ToolResult {
+                success: true,
+                output: format!(
+                    "Sent {} to the user via {}",
+                    paths.join(", "),
+                    route.channel
+                ),
+                error: None,
+                images: Vec::new(),
+            }),
+            Err(e) => Ok(failure(format!("Failed to send files: {e}"))),
+        }
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+        images: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channels::traits::{Channel, ChannelMessage};
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Records what `send_rich` was asked to deliver.
+    struct Recorder {
+        name: &'static str,
+        sent: Mutex<Vec<(String, String, Vec<String>)>>,
+    }
+
+    #[async_trait]
+    impl Channel for Recorder {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn send(&self, _message: &str, _recipient: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send_rich(
+            &self,
+            message: &OutgoingMessage,
+            recipient: &str,
+        ) -> anyhow::Result<()> {
+            let files = message
+                .attachments
+                .iter()
+                .map(|a| format!("{} {}", a.filename, a.mime_type))
+                .collect();
+            self.sent
+                .lock()
+                .unwrap()
+                .push((recipient.into(), message.text.clone(), files));
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn tool(tmp: &TempDir) -> SendFileTool {
+        SendFileTool::new(Arc::new(SecurityPolicy {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..SecurityPolicy::default()
+        }))
+    }
+
+    #[tokio::test]
+    async fn sends_workspace_files_to_the_current_conversation() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("shot.png"), b"png").unwrap();
+        std::fs::write(tmp.path().join("report.csv"), b"a,b").unwrap();
+        let recorder = Arc::new(Recorder {
+            name: "send_file_test",
+            sent: Mutex::new(Vec::new()),
+        });
+        broker().register_channel(recorder.clone());
+
+        let result = ApprovalRoute::new("send_file_test", "chat-1")
+            .scope(tool(&tmp).execute(json!({
+                "paths": ["shot.png", "report.csv"],
+                "message": "Here you go"
+            })))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            *recorder.sent.lock().unwrap(),
+            vec![(
+                "chat-1".to_string(),
+                "Here you go".to_string(),
+                vec![
+                    "shot.png image/png".to_string(),
+                    "report.csv text/csv".to_string()
+                ]
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn refuses_paths_outside_the_workspace_and_turns_without_a_channel() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("note.txt"), b"hi").unwrap();
+        broker().register_channel(Arc::new(Recorder {
+            name: "send_file_refusals",
+            sent: Mutex::new(Vec::new()),
+        }));
+
+        let outside = ApprovalRoute::new("send_file_refusals", "chat-1")
+            .scope(tool(&tmp).execute(json!({"paths": ["../etc/passwd"]})))
+            .await
+            .unwrap();
+        assert!(!outside.success);
+        assert!(outside.error.unwrap().contains("not allowed"));
+
+        let no_route = tool(&tmp)
+            .execute(json!({"paths": ["note.txt"]}))
+            .await
+            .unwrap();
+        assert!(!no_route.success);
+        assert!(no_route.error.unwrap().contains("No conversation"));
+
+        assert!(tool(&tmp).execute(json!({})).await.is_err());
+    }
+}
@@ -1,8 +1,10 @@
 use super::traits::{Tool, ToolResult};
 use crate::runtime::RuntimeAdapter;
+use crate::security::approval;
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -26,6 +28,26 @@ impl ShellTool {
     pub fn new(security: Arc<SecurityPolicy>, runtime: Arc<dyn RuntimeAdapter>) -> Self {
         Self { security, runtime }
     }
+
+    /// Apply the command policy, first holding risky commands until the
+    /// operator decides. The model cannot approve its own commands.
+    async fn authorize(&self, command: &str) -> Result<(), String> {
+        let approved = match self.security.approval_required(command) {
+            Some(risk) => {
+                let decision = approval::broker()
+                    .request(&self.security, command, risk)
+                    .await;
+                if let Some(reason) = decision.refusal() {
+                    return Err(reason.into());
+                }
+                true
+            }
+            None => false,
+        };
+        self.security
+            .validate_command_execution(command, approved)
+            .map(|_| ())
+    }
 }
 
 #[async_trait]
@@ -45,11 +67,6 @@ impl Tool for ShellTool {
                 "command": {
                     "type": "string",
                     "description": "The shell command to execute"
-                },
-                "approved": {
-                    "type": "boolean",
-                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
-                    "default": false
                 }
             },
             "required": ["command"]
@@ -61,26 +78,33 @@ impl Tool for ShellTool {
             .get("command")
             .and_then(|v| v.as_str())
             .ok_or_else(|| anyhow::anyhow!("Missing 'command' parameter"))?;
-        let approved = args
-            .get("approved")
-            .and_then(|v| v.as_bool())
-            .unwrap_or(false);
 
         if self.security.is_rate_limited() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: too many actions in the last hour".into()),
+                images: Vec::new(),
             });
         }
 
-        match self.security.validate_command_execution(command, approved) {
-            Ok(_) => {}
+        if let Err(reason) = self.security.check_budget() {
+            return Ok(ToolResult {
+                success: false,
+                output: String::new(),
+                error: Some(reason),
+                images: Vec::new(),
+            });
+        }
+
+        match self.authorize(command).await {
+            Ok(()) => {}
             Err(reason) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
                     error: Some(reason),
+                    images: Vec::new(),
                 });
             }
         }
@@ -90,77 +114,89 @@ impl Tool for ShellTool {
                 success: false,
                 output: String::new(),
                 error: Some("Rate limit exceeded: action budget exhausted".into()),
+                images: Vec::new(),
             });
         }
 
-        // Execute with timeout to prevent hanging commands.
-        // Clear the environment to prevent leaking API keys and other secrets
-        // (CWE-200), then re-add only safe, functional variables.
-        let mut cmd = match self
-            .runtime
-            .build_shell_command(command, &self.security.workspace_dir)
-        {
-            Ok(cmd) => cmd,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(format!("Failed to build runtime command: {e}")),
-                });
-            }
-        };
-        cmd.env_clear();
-
-        for var in SAFE_ENV_VARS {
-            if let Ok(val) = std::env::var(var) {
-                cmd.env(var, val);
-            }
-        }
-
-        let result =
-            tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output()).await;
-
-        match result {
-            Ok(Ok(output)) => {
-                let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-                // Truncate output to prevent OOM
-                if stdout.len() > MAX_OUTPUT_BYTES {
-                    stdout.truncate(stdout.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stdout.push_str("\n... [output truncated at 1MB]");
-                }
-                if stderr.len() > MAX_OUTPUT_BYTES {
-                    stderr.truncate(stderr.floor_char_boundary(MAX_OUTPUT_BYTES));
-                    stderr.push_str("\n... [stderr truncated at 1MB]");
-                }
-
-                Ok(ToolResult {
-                    success: output.status.success(),
-                    output: stdout,
-                    error: if stderr.is_empty() {
-                        None
-                    } else {
-                        Some(stderr)
-                    },
-                })
-            }
-            Ok(Err(e)) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("Failed to execute command: {e}")),
+        match run_command(self.runtime.as_ref(), command, &self.security.workspace_dir).await {
+            Ok(output) => Ok(ToolResult {
+                success: output.status.success(),
+                output: output.stdout,
+                error: if output.stderr.is_empty() {
+                    None
+                } else {
+                    Some(output.stderr)
+                },
+                images: Vec::new(),
             }),
-            Err(_) => Ok(ToolResult {
+            Err(e) => Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!(
-                    "Command timed out after {SHELL_TIMEOUT_SECS}s and was killed"
-                )),
+                error: Some(e.to_string()),
+                images: Vec::new(),
             }),
         }
     }
 }
 
+/// Output of a command run through [`run_command`].
+pub struct CommandOutput {
+    pub status: std::process::ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Run an already-authorized `command` in `workspace_dir` through `runtime`
+/// with the shell tool's limits: a scrubbed environment, a timeout and capped
+/// output. Anything that runs commands unattended should go through here so
+/// the sandbox policy covers it too.
+pub async fn run_command(
+    runtime: &dyn RuntimeAdapter,
+    command: &str,
+    workspace_dir: &Path,
+) -> anyhow::Result<CommandOutput> {
+    // Clear the environment to prevent leaking API keys and other secrets
+    // (CWE-200), then re-add only safe, functional variables.
+    let mut cmd = runtime
+        .build_shell_command(command, workspace_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to build runtime command: {e}"))?;
+    cmd.env_clear();
+
+    for var in SAFE_ENV_VARS {
+        if let Ok(val) = std::env::var(var) {
+            cmd.env(var, val);
+        }
+    }
+    cmd.kill_on_drop(true);
+
+    // Execute with timeout to prevent hanging commands.
+    let output = tokio::time::timeout(Duration::from_secs(SHELL_TIMEOUT_SECS), cmd.output())
+        .await
+        .map_err(|_| {
+            anyhow::anyhow!("Command timed out after {SHELL_TIMEOUT_SECS}s and was killed")
+        })?
+        .map_err(|e| anyhow::anyhow!("Failed to execute command: {e}"))?;
+
+    let mut stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let mut stderr = String::from_utf8_lossy(&output.stderr).to_string();
+
+    // Truncate output to prevent OOM
+    if stdout.len() > MAX_OUTPUT_BYTES {
+        stdout.truncate(stdout.floor_char_boundary(MAX_OUTPUT_BYTES));
+        stdout.push_str("\n... [output truncated at 1MB]");
+    }
+    if stderr.len() > MAX_OUTPUT_BYTES {
+        stderr.truncate(stderr.floor_char_boundary(MAX_OUTPUT_BYTES));
+        stderr.push_str("\n... [stderr truncated at 1MB]");
+    }
+
+    Ok(CommandOutput {
+        status: output.status,
+        stdout,
+        stderr,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,7 +236,7 @@ mod tests {
             .as_array()
             .unwrap()
             .contains(&json!("command")));
-        assert!(schema["properties"]["approved"].is_object());
+        assert!(schema["properties"]["approved"].is_null());
     }
 
     #[tokio::test]
@@ -232,6 +268,23 @@ mod tests {
         assert!(result.error.as_ref().unwrap().contains("not allowed"));
     }
 
+    #[tokio::test]
+    async fn shell_blocks_when_budget_spent() {
+        let security = SecurityPolicy {
+            max_cost_per_day_cents: 1,
+            workspace_dir: std::env::temp_dir(),
+            ..SecurityPolicy::default()
+        };
+        security.cost_ledger.record(
+            crate::providers::traits::TokenUsage::default(),
+            f64::from(security.max_cost_per_day_cents),
+        );
+        let tool = ShellTool::new(Arc::new(security), test_runtime());
+        let result = tool.execute(json!({"command": "ls"})).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("budget exhausted"));
+    }
+
     #[tokio::test]
     async fn shell_missing_command_param() {
         let tool = ShellTool::new(test_security(AutonomyLevel::Supervised), test_runtime());
@@ -333,18 +386,50 @@ mod tests {
         );
     }
 
+    /// Operator stand-in that approves every prompt it is sent.
+    struct AutoApprove;
+
+    #[async_trait]
+    impl crate::channels::Channel for AutoApprove {
+        fn name(&self) -> &str {
+            "shell-test-approver"
+        }
+
+        async fn send(&self, _message: &str, _recipient: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<crate::channels::traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send_approval_request(
+            &self,
+            request: &approval::ApprovalRequest,
+            _recipient: &str,
+        ) -> anyhow::Result<()> {
+            approval::broker().resolve(&request.id, true, "test");
+            Ok(())
+        }
+    }
+
     #[tokio::test]
-    async fn shell_requires_approval_for_medium_risk_command() {
+    async fn shell_requires_operator_approval_for_medium_risk_command() {
+        let workspace = tempfile::TempDir::new().unwrap();
         let security = Arc::new(SecurityPolicy {
             autonomy: AutonomyLevel::Supervised,
             allowed_commands: vec!["touch".into()],
-            workspace_dir: std::env::temp_dir(),
+            workspace_dir: workspace.path().to_path_buf(),
             ..SecurityPolicy::default()
         });
+        let tool = ShellTool::new(security, test_runtime());
 
-        let tool = ShellTool::new(security.clone(), test_runtime());
+        // No operator to ask, and the model cannot approve its own command.
         let denied = tool
-            .execute(json!({"command": "touch viziclaw_shell_approval_test"}))
+            .execute(json!({"command": "touch approval_test", "approved": true}))
             .await
             .unwrap();
         assert!(!denied.success);
@@ -353,16 +438,18 @@ mod tests {
             .as_deref()
             .unwrap_or("")
             .contains("explicit approval"));
+        assert!(!workspace.path().join("approval_test").exists());
 
-        let allowed = tool
-            .execute(json!({
-                "command": "touch viziclaw_shell_approval_test",
-                "approved": true
-            }))
+        approval::broker().register_channel(Arc::new(AutoApprove));
+        let allowed = approval::ApprovalRoute::new("shell-test-approver", "operator")
+            .scope(tool.execute(json!({"command": "touch approval_test"})))
             .await
             .unwrap();
-        assert!(allowed.success);
+        assert!(allowed.success, "{:?}", allowed.error);
+        assert!(workspace.path().join("approval_test").exists());
 
-        let _ = std::fs::remove_file(std::env::temp_dir().join("viziclaw_shell_approval_test"));
+        let audit =
+            std::fs::read_to_string(workspace.path().join("audit/approvals.jsonl")).unwrap();
+        assert_eq!(audit.lines().count(), 2);
     }
 }
@@ -0,0 +1,522 @@
+use super::shell::ShellTool;
+use super::traits::{Tool, ToolResult};
+use crate::runtime::RuntimeAdapter;
+use crate::security::SecurityPolicy;
+use crate::skills::{Skill, SkillTool};
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Maximum HTTP request time for skill tools.
+const HTTP_TIMEOUT_SECS: u64 = 30;
+/// Maximum response body returned to the model (1MB).
+const MAX_RESPONSE_BYTES: usize = 1_048_576;
+
+/// Tool kinds a skill manifest can declare.
+pub const SKILL_TOOL_KINDS: &[&str] = &["shell", "script", "http"];
+
+/// Exposes a tool from a skill manifest to the agent. Shell and script tools
+/// run through the shell tool, so the command allowlist, approval rules and
+/// runtime adapter all apply; HTTP tools are rate limited like any other
+/// action and need full autonomy for anything but GET/HEAD.
+pub struct SkillToolAdapter {
+    tool: SkillTool,
+    /// Directory holding the skill's manifest, for resolving script paths
+    skill_dir: Option<PathBuf>,
+    security: Arc<SecurityPolicy>,
+    shell: ShellTool,
+    client: reqwest::Client,
+}
+
+impl SkillToolAdapter {
+    pub fn new(
+        skill: &Skill,
+        tool: SkillTool,
+        security: Arc<SecurityPolicy>,
+        runtime: Arc<dyn RuntimeAdapter>,
+    ) -> Self {
+        Self {
+            tool,
+            skill_dir: skill
+                .location
+                .as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf),
+            shell: ShellTool::new(security.clone(), runtime),
+            security,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(HTTP_TIMEOUT_SECS))
+                .connect_timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| reqwest::Client::new()),
+        }
+    }
+
+    /// Declared argument values from the call, as strings.
+    fn argument_values(&self, args: &serde_json::Value) -> Result<HashMap<String, String>, String> {
+        self.tool
+            .args
+            .keys()
+            .map(|name| {
+                let value = match args.get(name) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(v @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => {
+                        v.to_string()
+                    }
+                    _ => return Err(format!("Missing '{name}' parameter")),
+                };
+                Ok((name.clone(), value))
+            })
+            .collect()
+    }
+
+    async fn run_shell(&self, values: &HashMap<String, String>) -> anyhow::Result<ToolResult> {
+        match render(&self.tool.command, values, shell_quote) {
+            Ok(command) => self.shell.execute(json!({ "command": command })).await,
+            Err(e) => Ok(failure(e)),
+        }
+    }
+
+    /// Scripts run as `<skill dir>/<script> <rendered args>`; the script's
+    /// file name must be on the command allowlist like any other command.
+    async fn run_script(&self, values: &HashMap<String, String>) -> anyhow::Result<ToolResult> {
+        let (script, rest) = self
+            .tool
+            .command
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((self.tool.command.trim(), ""));
+        let Some(dir) = &self.skill_dir else {
+            return Ok(failure("Skill has no directory to run scripts from"));
+        };
+        if !is_plain_relative_path(script) {
+            return Ok(failure(format!(
+                "Script path must be a plain path inside the skill directory: {script}"
+            )));
+        }
+        let path = dir.join(script);
+        if !path.is_file() {
+            return Ok(failure(format!("Script not found: {}", path.display())));
+        }
+
+        match render(rest, values, shell_quote) {
+            Ok(rest) => {
+                let command = format!("{} {rest}", path.display());
+                self.shell
+                    .execute(json!({ "command": command.trim_end() }))
+                    .await
+            }
+            Err(e) => Ok(failure(e)),
+        }
+    }
+
+    async fn run_http(&self, values: &HashMap<String, String>) -> anyhow::Result<ToolResult> {
+        let method = self.tool.method.as_deref().unwrap_or("GET").to_uppercase();
+        if !matches!(method.as_str(), "GET" | "HEAD") && !self.security.can_act() {
+            return Ok(failure(format!(
+                "Action blocked: {method} requests are not allowed in read-only mode"
+            )));
+        }
+        if self.security.is_rate_limited() {
+            return Ok(failure(
+                "Rate limit exceeded: too many actions in the last hour",
+            ));
+        }
+        if let Err(reason) = self.security.check_budget() {
+            return Ok(failure(reason));
+        }
+
+        let url = match render_url(&self.tool.command, values) {
+            Ok(url) => url,
+            Err(e) => return Ok(failure(e)),
+        };
+        let Ok(method) = reqwest::Method::from_bytes(method.as_bytes()) else {
+            return Ok(failure(format!("Invalid HTTP method: {method}")));
+        };
+
+        let mut request = self.client.request(method, url);
+        for (name, value) in &self.tool.headers {
+            match render(value, values, |v| v.replace(['\r', '\n'], " ")) {
+                Ok(value) => request = request.header(name, value),
+                Err(e) => return Ok(failure(e)),
+            }
+        }
+        if let Some(body) = &self.tool.body {
+            let is_json = body.trim_start().starts_with(['{', '[']);
+            let rendered = if is_json {
+                render(body, values, json_escape)
+            } else {
+                render(body, values, str::to_string)
+            };
+            match rendered {
+                Ok(body) if is_json => {
+                    request = request
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(body);
+                }
+                Ok(body) => request = request.body(body),
+                Err(e) => return Ok(failure(e)),
+            }
+        }
+
+        if !self.security.record_action() {
+            return Ok(failure("Rate limit exceeded: action budget exhausted"));
+        }
+
+        let mut response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => return Ok(failure(format!("HTTP request failed: {e}"))),
+        };
+        let status = response.status();
+        // Stop reading at the cap rather than buffering whatever is sent
+        let mut body = Vec::new();
+        let mut truncated = false;
+        while let Ok(Some(chunk)) = response.chunk().await {
+            let room = MAX_RESPONSE_BYTES - body.len();
+            if chunk.len() > room {
+                body.extend_from_slice(&chunk[..room]);
+                truncated = true;
+                break;
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let mut output = String::from_utf8_lossy(&body).into_owned();
+        if truncated {
+            output.push_str("\n... [response truncated at 1MB]");
+        }
+
+        Ok(ToolResult {
+            success: status.is_success(),
+            output,
+            error: (!status.is_success()).then(|| format!("HTTP {status}")),
+            images: Vec::new(),
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for SkillToolAdapter {
+    fn name(&self) -> &str {
+        &self.tool.name
+    }
+
+    fn description(&self) -> &str {
+        &self.tool.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut names: Vec<&String> = self.tool.args.keys().collect();
+        names.sort();
+        let properties: serde_json::Map<String, serde_json::Value> = names
+            .iter()
+            .map(|name| {
+                (
+                    (*name).clone(),
+                    json!({
+                        "type": "string",
+                        "description": self.tool.args[*name]
+                    }),
+                )
+            })
+            .collect();
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": names
+        })
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        let values = match self.argument_values(&args) {
+            Ok(values) => values,
+            Err(e) => return Ok(failure(e)),
+        };
+
+        match self.tool.kind.as_str() {
+            "shell" => self.run_shell(&values).await,
+            "script" => self.run_script(&values).await,
+            "http" => self.run_http(&values).await,
+            other => Ok(failure(format!("Unsupported skill tool kind: {other}"))),
+        }
+    }
+}
+
+fn failure(error: impl Into<String>) -> ToolResult {
+    ToolResult {
+        success: false,
+        output: String::new(),
+        error: Some(error.into()),
+        images: Vec::new(),
+    }
+}
+
+/// Replace every `{{name}}` in `template` with the escaped argument value.
+fn render(
+    template: &str,
+    values: &HashMap<String, String>,
+    escape: impl Fn(&str) -> String,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("Unterminated placeholder in template: {template}"))?;
+        let name = after[..end].trim();
+        let value = values
+            .get(name)
+            .ok_or_else(|| format!("Template uses undeclared argument '{name}'"))?;
+        out.push_str(&escape(value));
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Render an HTTP tool's URL. Placeholders may only appear after the host,
+/// so arguments cannot redirect the request to another server.
+fn render_url(template: &str, values: &HashMap<String, String>) -> Result<reqwest::Url, String> {
+    let authority_end = template
+        .find("://")
+        .map(|i| i + 3)
+        .and_then(|start| {
+            template[start..]
+                .find(['/', '?', '#'])
+                .map(|end| start + end)
+                .or(Some(template.len()))
+        })
+        .ok_or_else(|| format!("HTTP tool URL must be absolute: {template}"))?;
+    if template[..authority_end].contains("{{") {
+        return Err("HTTP tool URL cannot template the scheme or host".into());
+    }
+
+    let url = reqwest::Url::parse(&render(template, values, percent_encode)?)
+        .map_err(|e| format!("Invalid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Unsupported URL scheme: {}", url.scheme()));
+    }
+    Ok(url)
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            out.push(char::from(byte));
+        } else {
+            let _ = write!(out, "%{byte:02X}");
+        }
+    }
+    out
+}
+
+/// Escape `value` for use inside a JSON string literal.
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+fn is_plain_relative_path(path: &str) -> bool {
+    !path.is_empty()
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'))
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::AutonomyLevel;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn skill_tool(kind: &str, command: &str, args: &[(&str, &str)]) -> SkillTool {
+        SkillTool {
+            name: "skill_tool".into(),
+            description: "A skill tool".into(),
+            kind: kind.into(),
+            command: command.into(),
+            args: args
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+            method: None,
+            body: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    fn adapter(tool: SkillTool, skill_dir: &Path) -> SkillToolAdapter {
+        let skill = Skill {
+            name: "test".into(),
+            description: "Test skill".into(),
+            version: "0.1.0".into(),
+            author: None,
+            tags: vec![],
+            tools: vec![],
+            prompts: vec![],
+            location: Some(skill_dir.join("SKILL.toml")),
+        };
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: std::env::temp_dir(),
+            allowed_commands: vec!["echo".into(), "greet.sh".into()],
+            ..SecurityPolicy::default()
+        });
+        SkillToolAdapter::new(&skill, tool, security, Arc::new(NativeRuntime::new()))
+    }
+
+    #[test]
+    fn schema_lists_declared_args() {
+        let tool = adapter(
+            skill_tool(
+                "shell",
+                "echo {{b}} {{a}}",
+                &[("b", "Second"), ("a", "First")],
+            ),
+            &std::env::temp_dir(),
+        );
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["properties"]["a"]["description"], "First");
+        assert_eq!(schema["properties"]["b"]["type"], "string");
+        assert_eq!(schema["required"], json!(["a", "b"]));
+    }
+
+    #[tokio::test]
+    async fn shell_tool_quotes_arguments() {
+        let tool = adapter(
+            skill_tool("shell", "echo hello {{name}}", &[("name", "Who")]),
+            &std::env::temp_dir(),
+        );
+        let result = tool.execute(json!({"name": "it's me"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "hello it's me");
+
+        // A separator inside the value cannot start a second command
+        let result = tool.execute(json!({"name": "x; rm -rf /"})).await.unwrap();
+        assert!(!result.success);
+
+        let result = tool.execute(json!({})).await.unwrap();
+        assert_eq!(result.error.as_deref(), Some("Missing 'name' parameter"));
+    }
+
+    #[tokio::test]
+    async fn script_tool_runs_from_skill_directory() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("greet.sh");
+        std::fs::write(&script, "#!/bin/sh\necho \"hi $1\"\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let tool = adapter(
+            skill_tool("script", "greet.sh {{name}}", &[("name", "Who")]),
+            dir.path(),
+        );
+        let result = tool.execute(json!({"name": "there"})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "hi there");
+
+        let escaping = adapter(skill_tool("script", "../greet.sh", &[]), dir.path());
+        let result = escaping.execute(json!({})).await.unwrap();
+        assert!(result.error.unwrap().contains("inside the skill directory"));
+    }
+
+    #[test]
+    fn url_templates_encode_values_and_pin_the_host() {
+        let values = HashMap::from([("city".to_string(), "New York/..?x=1".to_string())]);
+        let url = render_url("https://wttr.in/{{city}}?format=3", &values).unwrap();
+        assert_eq!(url.host_str(), Some("wttr.in"));
+        assert_eq!(url.path(), "/New%20York%2F..%3Fx%3D1");
+
+        assert!(render_url("https://{{city}}.example.com/", &values).is_err());
+        assert!(render_url("file:///etc/{{city}}", &values).is_err());
+        assert!(render_url("https://example.com/{{missing}}", &values).is_err());
+    }
+
+    #[tokio::test]
+    async fn http_tool_sends_templated_request() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let n = socket.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]).to_string();
+            let reply = "stored";
+            let response = format!(
+                "HTTP/1.1 201 Created\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{reply}",
+                reply.len()
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            request
+        });
+
+        let mut spec = skill_tool(
+            "http",
+            &format!("http://127.0.0.1:{port}/notes/{{{{id}}}}"),
+            &[("id", "Note id"), ("text", "Note text")],
+        );
+        spec.method = Some("post".into());
+        spec.body = Some(r#"{"text": "{{text}}"}"#.into());
+        spec.headers = HashMap::from([("X-Note".to_string(), "{{id}}".to_string())]);
+        let tool = adapter(spec, &std::env::temp_dir());
+
+        let result = tool
+            .execute(json!({"id": "a b", "text": "say \"hi\""}))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.output, "stored");
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /notes/a%20b HTTP/1.1"));
+        assert!(request.to_ascii_lowercase().contains("x-note: a b"));
+        assert!(request.contains(r#"{"text": "say \"hi\""}"#));
+    }
+
+    #[tokio::test]
+    async fn http_tool_stops_reading_at_the_response_cap() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 4096];
+            let _ = socket.read(&mut buf).await.unwrap();
+            // No length and no end: only the client hanging up stops this
+            let header = "HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n";
+            socket.write_all(header.as_bytes()).await.unwrap();
+            let chunk = vec![b'x'; 64 * 1024];
+            let mut sent = 0;
+            while socket.write_all(&chunk).await.is_ok() {
+                sent += chunk.len();
+            }
+            sent
+        });
+
+        let spec = skill_tool("http", &format!("http://127.0.0.1:{port}/feed"), &[]);
+        let tool = adapter(spec, &std::env::temp_dir());
+        let result = tool.execute(json!({})).await.unwrap();
+
+        assert!(result.success);
+        assert!(result.output.ends_with("[response truncated at 1MB]"));
+        assert_eq!(result.output.matches('x').count(), MAX_RESPONSE_BYTES);
+        drop(tool);
+        assert!(server.await.unwrap() < 8 * MAX_RESPONSE_BYTES);
+    }
+}
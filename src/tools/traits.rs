@@ -1,3 +1,4 @@
+use crate::providers::ImageContent;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +8,9 @@ pub struct ToolResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    /// Images shown to the model along with the output, e.g. a screenshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<ImageContent>,
 }
 
 /// Description of a tool for the LLM
@@ -2,6 +2,8 @@
 //!
 //! This module contains reusable helper functions used across the codebase.
 
+use std::time::Duration;
+
 /// Truncate a string to at most `max_chars` characters, appending "..." if truncated.
 ///
 /// This function safely handles multi-byte UTF-8 characters (emoji, CJK, accented characters)
@@ -43,6 +45,29 @@ pub fn truncate_with_ellipsis(s: &str, max_chars: usize) -> String {
     }
 }
 
+/// Parse a positive interval written as a count and a unit: `30m`, `2h` or
+/// `1d`.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use viziclaw::util::parse_interval;
+///
+/// assert_eq!(parse_interval("2h"), Some(Duration::from_secs(7200)));
+/// assert_eq!(parse_interval("0m"), None);
+/// assert_eq!(parse_interval("soon"), None);
+/// ```
+pub fn parse_interval(value: &str) -> Option<Duration> {
+    let unit_secs = match value.chars().last()? {
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return None,
+    };
+    let amount: u64 = value[..value.len() - 1].parse().ok()?;
+    (amount > 0).then(|| Duration::from_secs(amount.saturating_mul(unit_secs)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
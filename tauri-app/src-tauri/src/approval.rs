@@ -0,0 +1,60 @@
+use crate::events::AgentStreamEvent;
+use crate::streaming::emit_event;
+use async_trait::async_trait;
+use tauri::AppHandle;
+use viziclaw::channels::traits::{Channel, ChannelMessage};
+use viziclaw::security::approval::ApprovalRequest;
+
+/// Channel name desktop turns are routed under.
+pub const CHANNEL_NAME: &str = "desktop";
+
+/// Shows approval prompts for held shell commands in the desktop window.
+/// The operator's answer comes back through the `resolve_approval` command.
+pub struct DesktopChannel {
+    app: AppHandle,
+}
+
+impl DesktopChannel {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait]
+impl Channel for DesktopChannel {
+    fn name(&self) -> &str {
+        CHANNEL_NAME
+    }
+
+    async fn send(&self, message: &str, _recipient: &str) -> anyhow::Result<()> {
+        emit_event(
+            &self.app,
+            AgentStreamEvent::TextChunk {
+                content: format!("{message}\n"),
+            },
+        );
+        Ok(())
+    }
+
+    /// Messages arrive through the `send_message` command instead.
+    async fn listen(&self, _tx: tokio::sync::mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn send_approval_request(
+        &self,
+        request: &ApprovalRequest,
+        _recipient: &str,
+    ) -> anyhow::Result<()> {
+        emit_event(
+            &self.app,
+            AgentStreamEvent::ApprovalRequest {
+                id: request.id.clone(),
+                command: request.command.clone(),
+                risk: request.risk.to_string(),
+                expires_at: request.expires_at.to_rfc3339(),
+            },
+        );
+        Ok(())
+    }
+}
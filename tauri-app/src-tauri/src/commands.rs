@@ -2,6 +2,7 @@ use crate::session::{SessionDetail, SessionStore, SessionSummary};
 use crate::streaming;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
+use viziclaw::security::approval;
 
 pub struct AppState {
     pub session_store: Arc<SessionStore>,
@@ -102,3 +103,12 @@ pub fn new_session(
         .create_session(provider_name, model_name)
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub fn resolve_approval(id: String, approved: bool) -> Result<(), String> {
+    if approval::broker().resolve(&id, approved, "desktop:operator") {
+        Ok(())
+    } else {
+        Err(format!("Approval request {id} is no longer pending"))
+    }
+}
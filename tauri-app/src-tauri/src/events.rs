@@ -23,6 +23,13 @@ pub enum AgentStreamEvent {
     ProviderCallStart { provider: String, model: String },
     /// An LLM provider call has ended.
     ProviderCallEnd { duration_ms: u64 },
+    /// A shell command is held until the operator approves or denies it.
+    ApprovalRequest {
+        id: String,
+        command: String,
+        risk: String,
+        expires_at: String,
+    },
     /// The agent loop has completed.
     Done { session_id: String },
     /// An error occurred during the agent loop.
@@ -1,11 +1,13 @@
 // Prevents additional console window on Windows in release
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod approval;
 mod commands;
 mod events;
 mod session;
 mod streaming;
 
+use approval::DesktopChannel;
 use commands::AppState;
 use session::SessionStore;
 use std::sync::Arc;
@@ -17,12 +19,18 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(AppState { session_store })
+        .setup(|app| {
+            viziclaw::security::approval::broker()
+                .register_channel(Arc::new(DesktopChannel::new(app.handle().clone())));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             commands::send_message,
             commands::list_sessions,
             commands::get_session,
             commands::delete_session,
             commands::new_session,
+            commands::resolve_approval,
         ])
         .run(tauri::generate_context!())
         .expect("error while running ViziClaw Desktop");
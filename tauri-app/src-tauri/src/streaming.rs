@@ -1,9 +1,6 @@
 use crate::events::AgentStreamEvent;
 use crate::session::SessionStore;
-use anyhow::{Context, Result};
-use futures_util::StreamExt;
-use reqwest::Client;
-use serde_json::json;
+use anyhow::Result;
 use std::sync::Arc;
 use std::time::Instant;
 use tauri::{AppHandle, Emitter};
@@ -12,12 +9,13 @@ use viziclaw::agent::{
     build_context, build_tool_instructions, find_tool, parse_tool_calls, MAX_TOOL_ITERATIONS,
 };
 use viziclaw::memory::{self, Memory};
-use viziclaw::providers::ChatMessage;
+use viziclaw::providers::{self, collect_stream, ChatMessage, ConversationMessage};
+use viziclaw::security::approval::ApprovalRoute;
 use viziclaw::security::SecurityPolicy;
 use viziclaw::tools;
 use viziclaw::Config;
 
-fn emit_event(app: &AppHandle, event: AgentStreamEvent) {
+pub(crate) fn emit_event(app: &AppHandle, event: AgentStreamEvent) {
     let _ = app.emit("agent-stream", &event);
 }
 
@@ -57,17 +55,6 @@ async fn read_claude_code_oauth_token() -> Option<String> {
         .map(|s| s.to_string())
 }
 
-/// Resolve the provider's API endpoint URL from a provider name.
-fn provider_endpoint(provider_name: &str) -> &'static str {
-    match provider_name {
-        "openrouter" => "https://openrouter.ai/api/v1/chat/completions",
-        "openai" => "https://api.openai.com/v1/chat/completions",
-        "anthropic" => "https://api.anthropic.com/v1/messages",
-        "ollama" => "http://localhost:11434/v1/chat/completions",
-        _ => "https://openrouter.ai/api/v1/chat/completions",
-    }
-}
-
 /// Resolve the API key for a given provider.
 fn resolve_api_key(config: &Config, provider_name: &str) -> Option<String> {
     // First: config api_key
@@ -98,210 +85,6 @@ fn resolve_api_key(config: &Config, provider_name: &str) -> Option<String> {
         .filter(|k| !k.is_empty())
 }
 
-/// Stream a chat completion using SSE from an OpenAI-compatible endpoint.
-/// Returns the full accumulated response text.
-async fn stream_chat(
-    app: &AppHandle,
-    client: &Client,
-    endpoint: &str,
-    api_key: Option<&str>,
-    messages: &[ChatMessage],
-    model: &str,
-    temperature: f64,
-) -> Result<String> {
-    let openai_messages: Vec<serde_json::Value> = messages
-        .iter()
-        .map(|m| {
-            json!({
-                "role": m.role,
-                "content": m.content,
-            })
-        })
-        .collect();
-
-    let body = json!({
-        "model": model,
-        "messages": openai_messages,
-        "temperature": temperature,
-        "stream": true,
-    });
-
-    let mut req = client.post(endpoint).json(&body);
-
-    if let Some(key) = api_key {
-        req = req.header("Authorization", format!("Bearer {key}"));
-    }
-
-    let response = req
-        .send()
-        .await
-        .context("Failed to send streaming request")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Provider returned {status}: {body}");
-    }
-
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Stream chunk error")?;
-        buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-        // Process complete SSE lines from the buffer
-        while let Some(newline_pos) = buffer.find('\n') {
-            let line = buffer[..newline_pos].trim().to_string();
-            buffer = buffer[newline_pos + 1..].to_string();
-
-            if line.is_empty() || line.starts_with(':') {
-                continue;
-            }
-
-            if let Some(data) = line.strip_prefix("data: ") {
-                if data.trim() == "[DONE]" {
-                    continue;
-                }
-
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(data) {
-                    if let Some(content) = parsed
-                        .get("choices")
-                        .and_then(|c| c.get(0))
-                        .and_then(|c| c.get("delta"))
-                        .and_then(|d| d.get("content"))
-                        .and_then(|c| c.as_str())
-                    {
-                        if !content.is_empty() {
-                            full_text.push_str(content);
-                            emit_event(
-                                app,
-                                AgentStreamEvent::TextChunk {
-                                    content: content.to_string(),
-                                },
-                            );
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(full_text)
-}
-
-/// Stream a chat completion using SSE from the Anthropic Messages API.
-/// Returns the full accumulated response text.
-async fn stream_chat_anthropic(
-    app: &AppHandle,
-    client: &Client,
-    endpoint: &str,
-    api_key: &str,
-    messages: &[ChatMessage],
-    model: &str,
-    temperature: f64,
-) -> Result<String> {
-    // Extract system prompt (first system message) and remaining messages
-    let system_text = messages
-        .iter()
-        .find(|m| m.role == "system")
-        .map(|m| m.content.clone());
-
-    let api_messages: Vec<serde_json::Value> = messages
-        .iter()
-        .filter(|m| m.role != "system")
-        .map(|m| {
-            json!({
-                "role": m.role,
-                "content": m.content,
-            })
-        })
-        .collect();
-
-    let mut body = json!({
-        "model": model,
-        "messages": api_messages,
-        "max_tokens": 16384,
-        "temperature": temperature,
-        "stream": true,
-    });
-
-    if let Some(ref sys) = system_text {
-        body.as_object_mut()
-            .unwrap()
-            .insert("system".to_string(), json!(sys));
-    }
-
-    let mut req = client
-        .post(endpoint)
-        .header("anthropic-version", "2023-06-01")
-        .json(&body);
-
-    // OAuth tokens use Bearer auth + beta header; API keys use x-api-key header
-    if api_key.starts_with("sk-ant-oat01-") {
-        req = req.header("Authorization", format!("Bearer {api_key}"));
-        req = req.header("anthropic-beta", "oauth-2025-04-20");
-    } else {
-        req = req.header("x-api-key", api_key);
-    }
-
-    let response = req
-        .send()
-        .await
-        .context("Failed to send Anthropic streaming request")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        anyhow::bail!("Anthropic returned {status}: {body}");
-    }
-
-    let mut stream = response.bytes_stream();
-    let mut full_text = String::new();
-    let mut buffer = String::new();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.context("Anthropic stream chunk error")?;
-        buffer.push_str(&String::from_utf8_lossy(&chunk));
-
-        while let Some(newline_pos) = buffer.find('\n') {
-            let line = buffer[..newline_pos].trim().to_string();
-            buffer = buffer[newline_pos + 1..].to_string();
-
-            if line.is_empty() || line.starts_with(':') {
-                continue;
-            }
-
-            if let Some(data) = line.strip_prefix("data: ") {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(data) {
-                    let event_type = parsed.get("type").and_then(|t| t.as_str()).unwrap_or("");
-
-                    if event_type == "content_block_delta" {
-                        if let Some(text) = parsed
-                            .get("delta")
-                            .and_then(|d| d.get("text"))
-                            .and_then(|t| t.as_str())
-                        {
-                            if !text.is_empty() {
-                                full_text.push_str(text);
-                                emit_event(
-                                    app,
-                                    AgentStreamEvent::TextChunk {
-                                        content: text.to_string(),
-                                    },
-                                );
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    Ok(full_text)
-}
-
 /// Run the streaming agent loop, emitting events to the frontend.
 pub async fn run_streaming_agent(
     app: AppHandle,
@@ -311,16 +94,17 @@ pub async fn run_streaming_agent(
     provider_override: Option<String>,
     model_override: Option<String>,
 ) {
-    if let Err(e) = run_streaming_agent_inner(
+    // Approval prompts for commands held during this turn go to the window
+    let route = ApprovalRoute::new(crate::approval::CHANNEL_NAME, session_id.as_str());
+    let turn = run_streaming_agent_inner(
         &app,
         &session_store,
         &session_id,
         &user_message,
         provider_override.as_deref(),
         model_override.as_deref(),
-    )
-    .await
-    {
+    );
+    if let Err(e) = route.scope(turn).await {
         emit_event(
             &app,
             AgentStreamEvent::Error {
@@ -357,7 +141,13 @@ async fn run_streaming_agent_inner(
     } else {
         resolve_api_key(&config, provider_name)
     };
-    let endpoint = provider_endpoint(provider_name);
+    let provider = providers::create_routed_provider(
+        provider_name,
+        api_key.as_deref(),
+        &config.reliability,
+        &config.model_routes,
+        model_name,
+    )?;
 
     // Initialize memory
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
@@ -472,7 +262,10 @@ async fn run_streaming_agent_inner(
     // Append the new user message (enriched with memory context)
     history.push(ChatMessage::user(&enriched));
 
-    let client = Client::new();
+    // Tools are described in the system prompt and parsed out of the text,
+    // so the provider is called without native tool definitions.
+    let mut conversation: Vec<ConversationMessage> =
+        history.into_iter().map(ConversationMessage::Chat).collect();
 
     // Agent loop
     for _iteration in 0..MAX_TOOL_ITERATIONS {
@@ -486,34 +279,23 @@ async fn run_streaming_agent_inner(
 
         let start = Instant::now();
 
-        let full_response = if provider_name == "anthropic" {
-            let key = api_key.as_deref().ok_or_else(|| {
-                anyhow::anyhow!(
-                    "No Anthropic API key found. Install Claude Code or set ANTHROPIC_API_KEY."
-                )
-            })?;
-            stream_chat_anthropic(
-                app,
-                &client,
-                endpoint,
-                key,
-                &history,
-                model_name,
-                config.default_temperature,
-            )
-            .await?
-        } else {
-            stream_chat(
+        security.check_budget().map_err(anyhow::Error::msg)?;
+        let stream = provider
+            .chat_stream(&conversation, &[], model_name, config.default_temperature)
+            .await?;
+        let response = collect_stream(stream, |delta| {
+            emit_event(
                 app,
-                &client,
-                endpoint,
-                api_key.as_deref(),
-                &history,
-                model_name,
-                config.default_temperature,
-            )
-            .await?
-        };
+                AgentStreamEvent::TextChunk {
+                    content: delta.to_string(),
+                },
+            );
+        })
+        .await?;
+        if let Some(usage) = response.usage {
+            security.record_usage(model_name, usage);
+        }
+        let full_response = response.text.unwrap_or_default();
 
         emit_event(
             app,
@@ -644,10 +426,12 @@ async fn run_streaming_agent_inner(
         }
 
         // Append to history
-        history.push(ChatMessage::assistant(&full_response));
-        history.push(ChatMessage::user(format!(
-            "[Tool results]\n{tool_results_text}"
+        conversation.push(ConversationMessage::Chat(ChatMessage::assistant(
+            &full_response,
         )));
+        conversation.push(ConversationMessage::Chat(ChatMessage::user(format!(
+            "[Tool results]\n{tool_results_text}"
+        ))));
     }
 
     emit_event(
@@ -52,7 +52,9 @@ export default function App() {
               activeToolCalls={chat.activeToolCalls}
               isStreaming={chat.isStreaming}
               activities={chat.activities}
+              pendingApprovals={chat.pendingApprovals}
               onSendMessage={chat.sendMessage}
+              onResolveApproval={chat.resolveApproval}
             />
           )}
         </main>
@@ -0,0 +1,36 @@
+import type { PendingApproval } from "../hooks/useChat";
+
+interface ApprovalCardProps {
+  approval: PendingApproval;
+  onResolve: (id: string, approved: boolean) => void;
+}
+
+export function ApprovalCard({ approval, onResolve }: ApprovalCardProps) {
+  const expires = new Date(approval.expiresAt).toLocaleTimeString();
+
+  return (
+    <div className="border border-status-running rounded-lg overflow-hidden bg-surface">
+      <div className="px-3 py-2 text-sm text-text">
+        Approval needed for a {approval.risk}-risk command
+      </div>
+      <pre className="border-t border-border px-3 py-2 text-xs font-mono text-text-secondary overflow-x-auto whitespace-pre-wrap">
+        {approval.command}
+      </pre>
+      <div className="border-t border-border flex items-center gap-2 px-3 py-2">
+        <span className="text-text-tertiary text-xs">Expires {expires}</span>
+        <button
+          onClick={() => onResolve(approval.id, false)}
+          className="ml-auto px-3 py-1 text-sm rounded border border-border text-text hover:bg-surface-hover transition-colors duration-300 ease-out"
+        >
+          Deny
+        </button>
+        <button
+          onClick={() => onResolve(approval.id, true)}
+          className="px-3 py-1 text-sm rounded bg-status-success text-white hover:opacity-90 transition-opacity duration-300 ease-out"
+        >
+          Approve
+        </button>
+      </div>
+    </div>
+  );
+}
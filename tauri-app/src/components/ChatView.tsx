@@ -3,8 +3,14 @@ import { MessageBubble } from "./MessageBubble";
 import { ToolCallCard } from "./ToolCallCard";
 import { StreamingContent } from "./StreamingContent";
 import { ActivityLog } from "./ActivityLog";
+import { ApprovalCard } from "./ApprovalCard";
 import { InputBar } from "./InputBar";
-import type { ChatMessage, ToolCallActivity, Activity } from "../hooks/useChat";
+import type {
+  ChatMessage,
+  ToolCallActivity,
+  Activity,
+  PendingApproval,
+} from "../hooks/useChat";
 
 interface ChatViewProps {
   messages: ChatMessage[];
@@ -12,7 +18,9 @@ interface ChatViewProps {
   activeToolCalls: ToolCallActivity[];
   isStreaming: boolean;
   activities: Activity[];
+  pendingApprovals: PendingApproval[];
   onSendMessage: (content: string) => void;
+  onResolveApproval: (id: string, approved: boolean) => void;
 }
 
 export function ChatView({
@@ -21,7 +29,9 @@ export function ChatView({
   activeToolCalls,
   isStreaming,
   activities,
+  pendingApprovals,
   onSendMessage,
+  onResolveApproval,
 }: ChatViewProps) {
   const scrollRef = useRef<HTMLDivElement>(null);
 
@@ -29,7 +39,7 @@ export function ChatView({
     if (scrollRef.current) {
       scrollRef.current.scrollTop = scrollRef.current.scrollHeight;
     }
-  }, [messages, streamingContent, activeToolCalls]);
+  }, [messages, streamingContent, activeToolCalls, pendingApprovals]);
 
   return (
     <div className="flex flex-col h-full">
@@ -93,6 +103,14 @@ export function ChatView({
               toolCalls={activeToolCalls}
             />
           )}
+
+          {pendingApprovals.map((approval) => (
+            <ApprovalCard
+              key={approval.id}
+              approval={approval}
+              onResolve={onResolveApproval}
+            />
+          ))}
         </div>
       </div>
 
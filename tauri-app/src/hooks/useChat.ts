@@ -20,6 +20,13 @@ export interface ToolCallActivity {
   output?: string;
 }
 
+export interface PendingApproval {
+  id: string;
+  command: string;
+  risk: string;
+  expiresAt: string;
+}
+
 export interface Activity {
   id: string;
   label: string;
@@ -35,6 +42,9 @@ export function useChat() {
   );
   const [isStreaming, setIsStreaming] = useState(false);
   const [activities, setActivities] = useState<Activity[]>([]);
+  const [pendingApprovals, setPendingApprovals] = useState<PendingApproval[]>(
+    []
+  );
   const [sessionId, setSessionId] = useState<string | null>(null);
   const msgCounter = useRef(0);
 
@@ -70,7 +80,21 @@ export function useChat() {
           ]);
           break;
 
+        case "ApprovalRequest":
+          setPendingApprovals((prev) => [
+            ...prev,
+            {
+              id: data.id,
+              command: data.command,
+              risk: data.risk,
+              expiresAt: data.expires_at,
+            },
+          ]);
+          break;
+
         case "ToolCallResult":
+          // The held command has been decided or timed out
+          setPendingApprovals([]);
           setActiveToolCalls((prev) =>
             prev.map((tc) =>
               tc.name === data.name && tc.status === "running"
@@ -128,6 +152,7 @@ export function useChat() {
           });
           setActiveToolCalls([]);
           setActivities([]);
+          setPendingApprovals([]);
           setIsStreaming(false);
           setSessionId(data.session_id);
           break;
@@ -144,6 +169,7 @@ export function useChat() {
           setStreamingContent("");
           setActiveToolCalls([]);
           setActivities([]);
+          setPendingApprovals([]);
           setIsStreaming(false);
           break;
       }
@@ -167,6 +193,7 @@ export function useChat() {
       setStreamingContent("");
       setActiveToolCalls([]);
       setActivities([]);
+      setPendingApprovals([]);
 
       try {
         const sid = await invoke<string>("send_message", {
@@ -189,11 +216,32 @@ export function useChat() {
     [sessionId]
   );
 
+  const resolveApproval = useCallback(
+    async (id: string, approved: boolean) => {
+      setPendingApprovals((prev) => prev.filter((a) => a.id !== id));
+      try {
+        await invoke("resolve_approval", { id, approved });
+      } catch (e) {
+        setActivities((prev) => [
+          ...prev,
+          {
+            id: `approval-${Date.now()}`,
+            label: `${e}`,
+            type: "tool",
+            timestamp: Date.now(),
+          },
+        ]);
+      }
+    },
+    []
+  );
+
   const newSession = useCallback(() => {
     setMessages([]);
     setStreamingContent("");
     setActiveToolCalls([]);
     setActivities([]);
+    setPendingApprovals([]);
     setIsStreaming(false);
     setSessionId(null);
   }, []);
@@ -212,6 +260,7 @@ export function useChat() {
     setStreamingContent("");
     setActiveToolCalls([]);
     setActivities([]);
+    setPendingApprovals([]);
     setIsStreaming(false);
     msgCounter.current = chatMessages.length;
   }, []);
@@ -222,8 +271,10 @@ export function useChat() {
     activeToolCalls,
     isStreaming,
     activities,
+    pendingApprovals,
     sessionId,
     sendMessage,
+    resolveApproval,
     newSession,
     continueSession,
   };
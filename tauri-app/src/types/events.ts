@@ -5,5 +5,12 @@ export type AgentStreamEvent =
   | { type: "MemoryRecall"; query: string; results_count: number }
   | { type: "ProviderCallStart"; provider: string; model: string }
   | { type: "ProviderCallEnd"; duration_ms: number }
+  | {
+      type: "ApprovalRequest";
+      id: string;
+      command: string;
+      risk: string;
+      expires_at: string;
+    }
   | { type: "Done"; session_id: string }
   | { type: "Error"; message: string };
@@ -2,10 +2,14 @@
 //!
 //! Run with: cargo test --test memory_comparison -- --nocapture
 
+use std::collections::HashSet;
+use std::sync::Arc;
 use std::time::Instant;
 use tempfile::TempDir;
 
 // We test both backends through the public memory module
+use viziclaw::memory::embeddings::EmbeddingProvider;
+use viziclaw::memory::sqlite::VectorIndexMode;
 use viziclaw::memory::{markdown::MarkdownMemory, sqlite::SqliteMemory, Memory, MemoryCategory};
 
 // ── Helpers ────────────────────────────────────────────────────
@@ -430,3 +434,151 @@ async fn compare_category_filter() {
     assert!(!md_core.is_empty());
     assert!(!md_all.is_empty());
 }
+
+// ── Test 8: ANN index vs exact vector search ───────────────────
+
+/// Deterministic embeddings: each word maps to a pseudo-random vector and a
+/// text is the sum of its words, so texts sharing words are similar.
+struct HashedWordEmbedding;
+
+const BENCH_DIMS: usize = 64;
+
+#[async_trait::async_trait]
+impl EmbeddingProvider for HashedWordEmbedding {
+    fn name(&self) -> &str {
+        "hashed-words"
+    }
+
+    fn dimensions(&self) -> usize {
+        BENCH_DIMS
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts
+            .iter()
+            .map(|text| {
+                let mut v = vec![0.0_f32; BENCH_DIMS];
+                for word in text.split_whitespace() {
+                    // FNV-1a seed, then an LCG per dimension
+                    let mut state = word.bytes().fold(0xcbf2_9ce4_8422_2325_u64, |h, b| {
+                        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+                    });
+                    for x in &mut v {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1_442_695_040_888_963_407);
+                        #[allow(clippy::cast_precision_loss)]
+                        let unit = (state >> 40) as f32 / (1u64 << 24) as f32;
+                        *x += unit * 2.0 - 1.0;
+                    }
+                }
+                v
+            })
+            .collect())
+    }
+}
+
+const VOCAB: &[&str] = &[
+    "rust", "python", "deploy", "server", "database", "backup", "invoice", "meeting", "travel",
+    "budget", "kernel", "docker", "garden", "recipe", "music", "fitness", "vacation", "tax",
+    "report", "bug", "release", "network", "printer", "family", "birthday", "project", "design",
+    "review", "coffee", "weather",
+];
+
+fn bench_text(i: usize) -> String {
+    // Three words per entry, spread over the vocabulary
+    let a = VOCAB[i % VOCAB.len()];
+    let b = VOCAB[(i / VOCAB.len() + i * 7) % VOCAB.len()];
+    let c = VOCAB[(i * 13 + 5) % VOCAB.len()];
+    format!("{a} {b} {c} note {i}")
+}
+
+fn bench_backend(dir: &std::path::Path, mode: VectorIndexMode) -> SqliteMemory {
+    SqliteMemory::with_embedder(dir, Arc::new(HashedWordEmbedding), 0.7, 0.3, 10_000)
+        .expect("SQLite init failed")
+        .with_vector_index(mode)
+        .expect("vector index init failed")
+}
+
+#[allow(clippy::cast_precision_loss)]
+async fn recall_at_10(
+    mem: &SqliteMemory,
+    queries: &[&str],
+) -> (f32, std::time::Duration, std::time::Duration) {
+    let mut hits = 0;
+    let mut total = 0;
+    let mut exact_time = std::time::Duration::ZERO;
+    let mut ann_time = std::time::Duration::ZERO;
+    for query in queries {
+        let embedding = HashedWordEmbedding.embed_one(query).await.unwrap();
+
+        let start = Instant::now();
+        let exact = mem.nearest(&embedding, 10, true).unwrap();
+        exact_time += start.elapsed();
+
+        let start = Instant::now();
+        let approx = mem.nearest(&embedding, 10, false).unwrap();
+        ann_time += start.elapsed();
+
+        let found: HashSet<&str> = approx.iter().map(|(id, _)| id.as_str()).collect();
+        hits += exact
+            .iter()
+            .filter(|(id, _)| found.contains(id.as_str()))
+            .count();
+        total += exact.len();
+    }
+    (hits as f32 / total as f32, exact_time, ann_time)
+}
+
+#[tokio::test]
+async fn compare_ann_vs_exact_vector_search() {
+    let tmp = TempDir::new().unwrap();
+    let n = 1000;
+
+    // Populate without an index, then build it on open
+    {
+        let mem = bench_backend(tmp.path(), VectorIndexMode::Exact);
+        for i in 0..n {
+            mem.store(&format!("bench_{i}"), &bench_text(i), MemoryCategory::Core)
+                .await
+                .unwrap();
+        }
+    }
+    let start = Instant::now();
+    let mem = bench_backend(tmp.path(), VectorIndexMode::Hnsw);
+    let build_time = start.elapsed();
+    assert!(tmp.path().join("memory").join("brain.hnsw").exists());
+
+    let queries = [
+        "rust deploy",
+        "database backup server",
+        "family birthday travel",
+        "tax invoice budget",
+        "docker kernel bug",
+        "coffee music",
+        "release review design",
+        "garden recipe weather",
+    ];
+    let (recall, exact_time, ann_time) = recall_at_10(&mem, &queries).await;
+    drop(mem);
+
+    // Reopening in quantized mode rebuilds the index with int8 vectors
+    let mem = bench_backend(tmp.path(), VectorIndexMode::HnswQuantized);
+    let (q_recall, _, q_ann_time) = recall_at_10(&mem, &queries).await;
+
+    println!("\n============================================================");
+    println!(
+        "VECTOR SEARCH over {n} entries ({} queries, top 10):",
+        queries.len()
+    );
+    println!("  Index build:       {build_time:?}");
+    println!("  Exact scan:        {exact_time:?}");
+    println!("  HNSW:              {ann_time:?} (recall@10 {recall:.2})");
+    println!("  HNSW (quantized):  {q_ann_time:?} (recall@10 {q_recall:.2})");
+
+    assert!(recall >= 0.9, "HNSW recall@10 too low: {recall}");
+    assert!(
+        q_recall >= 0.8,
+        "quantized HNSW recall@10 too low: {q_recall}"
+    );
+}